@@ -0,0 +1,192 @@
+//! Implements actively corrupted virtual machines.
+//!
+//! The protocols in this library assume by default that every party follows
+//! the specification. To observe what happens when that is not the case, a
+//! virtual machine can be created with a cheating [`Behavior`] (see
+//! [`VirtualMachine::new_cheating`]), which makes it deviate from the protocol
+//! at specific points:
+//! - When it stores a share, in any of the share memories.
+//! - When it sends its share to open a value, in the reconstruction of
+//!   [`mpc`](crate::mpc), [`shamir`](crate::shamir), [`spdz`](crate::spdz) and
+//!   [`bdoz`](crate::bdoz).
+//! - When it sends a value to the other parties in a
+//!   [`broadcast`](crate::broadcast), or its shares as a dealer in the
+//!   [`dkg_protocol`](crate::threshold::dkg_protocol).
+//!
+//! The passive protocols give a wrong result without noticing the deviation,
+//! while the MAC checks and the echo broadcast detect it and abort, and the
+//! distributed key generation excludes the dealer.
+//!
+//! A passively corrupted party, in turn, follows the protocol, but the
+//! adversary learns everything it sees: its inputs, its random tape and the
+//! messages it receives. A protocol is secure against a semi-honest adversary
+//! if this [`PassiveView`] can be simulated from the inputs and outputs of the
+//! corrupted parties alone. The parties of an
+//! [`MpcContext`](crate::context::MpcContext) are corrupted with
+//! [`MpcContext::corrupt`](crate::context::MpcContext::corrupt), and the views
+//! of all of them are collected in an [`AdversaryView`].
+//!
+//! # Example
+//!
+//! Here, Bob reports a wrong share when opening an authenticated value, and the
+//! MAC check catches him.
+//!
+//! ```should_panic
+//! use smol_mpc::adversary::Behavior;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::spdz;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! let mut prg = Prg::new(None);
+//! let mut alice: VirtualMachine<Mersenne61> = VirtualMachine::new("alice");
+//! let mut bob = VirtualMachine::new_cheating("bob", Behavior::WrongOpening(Mersenne61::new(1)));
+//! let mut parties = vec![&mut alice, &mut bob];
+//!
+//! spdz::setup(&mut parties, &mut prg);
+//! spdz::simulate_auth_dist("x", &mut parties, &Mersenne61::new(10), &mut prg);
+//! spdz::reconstruct_share(&mut parties, "x", &mut prg);
+//! ```
+//!
+//! [`VirtualMachine::new_cheating`]: crate::vm::VirtualMachine::new_cheating
+
+use crate::math::mersenne::MersenneField;
+use crate::network::{Record, Simulator};
+use crate::utils::prg::{Prg, PseudoRandomGenerator};
+use crate::vm::VirtualMachine;
+
+/// Defines how a virtual machine takes part in the protocols.
+#[derive(Clone)]
+pub enum Behavior<T: MersenneField> {
+    /// Follows the protocol.
+    Honest,
+
+    /// Follows the protocol, but is passively corrupted, so the adversary
+    /// learns its view.
+    Passive,
+
+    /// Adds the provided error to every share that the machine stores.
+    ShareError(T),
+
+    /// Adds the provided error to its share every time it opens a value.
+    WrongOpening(T),
+
+    /// Sends inconsistent values when broadcasting: the $i$-th party receives
+    /// the value plus $i$ times the provided error.
+    Inconsistent(T),
+}
+
+impl<T: MersenneField> Behavior<T> {
+    /// Returns `true` if the machine is corrupted, either passively or
+    /// actively.
+    pub fn is_corrupted(&self) -> bool {
+        !matches!(self, Behavior::Honest)
+    }
+
+    /// Returns the value of a share as stored by the machine.
+    pub fn stored_share(&self, value: T) -> T {
+        match self {
+            Behavior::ShareError(error) => value.add(error),
+            _ => value,
+        }
+    }
+
+    /// Returns the value of a share as sent by the machine to open it.
+    pub fn opened_share(&self, value: &T) -> T {
+        match self {
+            Behavior::WrongOpening(error) => value.add(error),
+            _ => value.clone(),
+        }
+    }
+
+    /// Returns the value that the machine sends to the $i$-th party when it
+    /// broadcasts a value.
+    pub fn sent_value(&self, value: &T, receiver: usize) -> T {
+        match self {
+            Behavior::Inconsistent(error) => value.add(&error.multiply(&T::new(receiver as u64))),
+            _ => value.clone(),
+        }
+    }
+}
+
+/// Contains everything that a passively corrupted party saw during an
+/// execution.
+#[derive(Clone, Debug)]
+pub struct PassiveView<T> {
+    /// ID of the party.
+    pub id: String,
+
+    /// Private values of the party, which include its inputs, ordered by ID.
+    pub inputs: Vec<(String, T)>,
+
+    /// Random bytes drawn from the PRG of the party since it was corrupted,
+    /// rounded up to the whole blocks generated by the PRG.
+    pub randomness: Vec<u8>,
+
+    /// Shares in the memory of the party, ordered by ID.
+    pub shares: Vec<(String, T)>,
+
+    /// Messages received by the party since the communication counters were
+    /// reset, in the order in which they were delivered.
+    pub received: Vec<Record<T>>,
+
+    /// Values opened by the party since the communication counters were reset.
+    pub opened: Vec<T>,
+}
+
+impl<T: MersenneField> PassiveView<T> {
+    /// Collects the view of the party with ID `id`, given the state `tape` of
+    /// its PRG when it was corrupted.
+    pub(crate) fn collect(parties: &[&mut VirtualMachine<T>], id: &str, tape: &Prg) -> Self {
+        let view = Simulator::new(parties).view(id);
+        let party = parties.iter().find(|party| party.id == id).unwrap();
+        // The PRG produces one block for each step of its counter.
+        let n_blocks = party.prg.counter().saturating_sub(tape.counter()) as usize;
+
+        Self {
+            id: view.id,
+            inputs: party
+                .priv_values()
+                .into_iter()
+                .map(|(id, value)| (id.to_string(), value.clone()))
+                .collect(),
+            randomness: tape.clone().next(tape.block_len() * n_blocks),
+            shares: view.shares,
+            received: view.received,
+            opened: view.opened,
+        }
+    }
+}
+
+/// Contains the views of all the passively corrupted parties, which is what
+/// the adversary learns from an execution.
+#[derive(Clone, Debug)]
+pub struct AdversaryView<T> {
+    /// Views of the corrupted parties, in the order in which they were
+    /// corrupted.
+    pub parties: Vec<PassiveView<T>>,
+}
+
+impl<T> AdversaryView<T> {
+    /// Returns the IDs of the corrupted parties.
+    pub fn ids(&self) -> Vec<&str> {
+        self.parties.iter().map(|view| view.id.as_str()).collect()
+    }
+
+    /// Returns the view of the corrupted party with the provided ID, and
+    /// panics if the party is not corrupted.
+    pub fn party(&self, id: &str) -> &PassiveView<T> {
+        self.parties
+            .iter()
+            .find(|view| view.id == id)
+            .unwrap_or_else(|| panic!("The party {} is not corrupted.", id))
+    }
+
+    /// Returns the messages received by any of the corrupted parties.
+    pub fn received(&self) -> Vec<&Record<T>> {
+        self.parties
+            .iter()
+            .flat_map(|view| view.received.iter())
+            .collect()
+    }
+}
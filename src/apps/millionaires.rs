@@ -0,0 +1,119 @@
+//! Solves Yao's millionaires' problem.
+//!
+//! Two millionaires, Alice and Bob, want to know which of them is richer
+//! without revealing their wealth to each other. This is the problem with
+//! which Yao introduced secure computation (Yao82), and it only needs a
+//! comparison of two private values:
+//! 1. Each millionaire secret-shares its wealth with the other (see
+//!    [`distribute_shares`](crate::mpc::distribute_shares)).
+//! 2. The parties compute shares of the bits $\[a < b\]$ and $\[b < a\]$ with
+//!    the comparison based on function secret sharing (see
+//!    [`less_than_fss_protocol`](crate::mpc::less_than_fss_protocol)), whose
+//!    keys are provided by a trusted dealer with a secret seed (see
+//!    [`dealer`]).
+//! 3. The parties open the two bits, which give the order of the values and
+//!    nothing else.
+//!
+//! The comparison interprets the values as signed integers in the range
+//! $(-p / 4, p / 4)$, so the wealth of each millionaire must be smaller than
+//! $p / 4$.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::cmp::Ordering;
+//! use smol_mpc::apps::millionaires;
+//!
+//! assert_eq!(millionaires::run(1_000_000, 2_500_000), Ordering::Less);
+//! assert_eq!(millionaires::run(7_000_000, 7_000_000), Ordering::Equal);
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::math::mersenne::{Mersenne61, MersenneField};
+use crate::mpc;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+/// Compares the wealth of Alice and Bob, returning the order of Alice's wealth
+/// with respect to Bob's.
+///
+/// This function creates the virtual machines of both millionaires in the
+/// field [`Mersenne61`] and runs the whole computation with
+/// [`millionaires_protocol`]. It panics if any of the values is not smaller
+/// than $p / 4$.
+pub fn run(alice_value: u64, bob_value: u64) -> Ordering {
+    let bound = Mersenne61::ORDER / 4;
+    if alice_value >= bound || bob_value >= bound {
+        panic!(
+            "The wealth of a millionaire must be smaller than {}.",
+            bound
+        );
+    }
+
+    let mut alice: VirtualMachine<Mersenne61> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Mersenne61> = VirtualMachine::new("bob");
+    alice.insert_priv_value("alice_wealth", Mersenne61::new(alice_value));
+    bob.insert_priv_value("bob_wealth", Mersenne61::new(bob_value));
+
+    mpc::distribute_shares("alice_wealth", "alice", vec![&mut alice, &mut bob]);
+    mpc::distribute_shares("bob_wealth", "bob", vec![&mut alice, &mut bob]);
+
+    let mut dealer = dealer();
+    millionaires_protocol(
+        &mut [&mut alice, &mut bob],
+        "alice_wealth",
+        "bob_wealth",
+        &mut dealer,
+    )
+}
+
+/// Creates the PRG of the trusted dealer used by [`run`], seeded from the
+/// randomness of the operating system.
+///
+/// The comparison keys contain the masks that hide the inputs when they are
+/// opened, so the dealer must not use a fixed seed, which would let anyone
+/// compute the masks and unmask the inputs.
+pub fn dealer() -> Prg {
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    Prg::new(Some(seed.to_vec()))
+}
+
+/// Compares two secret-shared values between two parties, returning the order
+/// of the value with ID `id_a` with respect to the value with ID `id_b`.
+///
+/// The comparison keys are generated with the PRG of the trusted dealer
+/// `dealer`, and the shares of the two comparison bits are removed from the
+/// memory of the parties after opening them. The function panics if there are
+/// not exactly two parties.
+pub fn millionaires_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: &str,
+    id_b: &str,
+    dealer: &mut Prg,
+) -> Ordering
+where
+    T: MersenneField,
+{
+    mpc::generate_comparison_keys(parties, 2, dealer);
+    let id_less = mpc::temp_id(parties, "less");
+    let id_greater = mpc::temp_id(parties, "greater");
+    mpc::less_than_fss_protocol(parties, id_a, id_b, &id_less);
+    mpc::less_than_fss_protocol(parties, id_b, id_a, &id_greater);
+
+    let less = mpc::reconstruct_share(parties, &id_less).value() == 1;
+    let greater = mpc::reconstruct_share(parties, &id_greater).value() == 1;
+    for party in parties.iter_mut() {
+        party.remove_share(&id_less);
+        party.remove_share(&id_greater);
+    }
+
+    match (less, greater) {
+        (true, _) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => Ordering::Equal,
+    }
+}
@@ -0,0 +1,9 @@
+//! Defines complete applications built on top of the protocols of the
+//! library.
+//!
+//! In contrast with the rest of the library, which exposes the building blocks
+//! of MPC one protocol at a time, this module contains end-to-end computations
+//! that are often used as first examples of what MPC can do.
+pub mod millionaires;
+#[cfg(feature = "sha2")]
+pub mod psi;
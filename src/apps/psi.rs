@@ -0,0 +1,208 @@
+//! Implements private set intersection (PSI) between two parties.
+//!
+//! In PSI, a receiver with a set $X$ and a sender with a set $Y$ compute the
+//! intersection $X \cap Y$, which only the receiver learns, without either of
+//! them learning anything else about the set of the other, except its size.
+//! The protocol here is the classic one based on an [`oprf`](crate::oprf):
+//! 1. The sender samples a fresh key $k$ and sends $F_k(y)$ for every $y \in Y$,
+//!    in an order that does not depend on $Y$.
+//! 2. The receiver obtains $F_k(x)$ for every $x \in X$ with the OPRF.
+//! 3. The receiver outputs the elements $x$ such that $F_k(x)$ is among the
+//!    values sent by the sender.
+//!
+//! Since $F_k$ is pseudo-random, the values of the elements of $Y$ that are
+//! not in $X$ look random to the receiver, and the sender learns nothing
+//! because the OPRF hides the inputs of the receiver. The size of the
+//! intersection alone can be computed with [`psi_cardinality_protocol`], in
+//! which the sender shuffles the evaluations on the elements of the receiver so
+//! that the receiver cannot tell which of them are in the intersection. As the OPRF, this module
+//! is only available with the `sha2` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::apps::psi;
+//! use smol_mpc::math::group::Group;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let group: Group<Fp> = Group::generate(128, &mut prg);
+//!
+//! // Alice and Bob want to know which contacts they have in common.
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! for (id, phone) in [("a0", 5550101), ("a1", 5550102), ("a2", 5550103)] {
+//!     alice.insert_priv_value(id, Fp::new(phone));
+//! }
+//! for (id, phone) in [("b0", 5550103), ("b1", 5550104), ("b2", 5550101), ("b3", 5550105)] {
+//!     bob.insert_priv_value(id, Fp::new(phone));
+//! }
+//!
+//! let ids_alice = ["a0", "a1", "a2"];
+//! let ids_bob = ["b0", "b1", "b2", "b3"];
+//! let intersection = psi::psi_protocol(&mut alice, &mut bob, &group, &ids_alice, &ids_bob);
+//! let values: Vec<u64> = intersection.iter().map(|value| value.value()).collect();
+//! assert_eq!(values, vec![5550101, 5550103]);
+//! ```
+
+use std::collections::HashSet;
+
+use crate::math::group::{Group, GroupElement};
+use crate::math::mersenne::MersenneField;
+use crate::mpc;
+use crate::network::{Payload, Simulator};
+use crate::oprf;
+use crate::vm::VirtualMachine;
+
+/// Computes the intersection of the sets of two parties, which is output to
+/// the receiver.
+///
+/// The receiver holds its elements in its private memory under the IDs
+/// `ids_receiver`, and the sender under the IDs `ids_sender`. The function
+/// returns the elements of the receiver that are also held by the sender, in
+/// the order of `ids_receiver`. The key of the sender is only stored during
+/// the execution.
+pub fn psi_protocol<T>(
+    receiver: &mut VirtualMachine<T>,
+    sender: &mut VirtualMachine<T>,
+    group: &Group<T>,
+    ids_receiver: &[&str],
+    ids_sender: &[&str],
+) -> Vec<T>
+where
+    T: MersenneField,
+{
+    let id_key = sender.fresh_id("psi_key");
+    let key = T::random(&mut sender.prg);
+    sender.insert_priv_value(&id_key, key.clone());
+
+    // The sender evaluates the function on its own set and sends the values
+    // sorted, so that their order does not depend on the set.
+    let mut evaluated: Vec<Vec<u8>> = ids_sender
+        .iter()
+        .map(|id| oprf::evaluate(group, &key, sender.get_priv_value(id)))
+        .collect();
+    evaluated.sort();
+    let sent: HashSet<Vec<u8>> = {
+        let parties = [&mut *receiver, &mut *sender];
+        let network = Simulator::new(&parties).with_tag("psi");
+        network.send(1, 0, Payload::Bits(mpc::bytes_to_bits(&evaluated.concat())));
+        network.deliver();
+        let bytes = mpc::bits_to_bytes(&network.recv_from(0, 1).payload.bits());
+        bytes.chunks(oprf::OUTPUT_LEN).map(<[u8]>::to_vec).collect()
+    };
+
+    let outputs = oprf::oprf_batch_protocol(receiver, sender, group, ids_receiver, &id_key);
+    sender.remove_priv_value(&id_key);
+
+    ids_receiver
+        .iter()
+        .zip(outputs)
+        .filter(|(_, output)| sent.contains(output))
+        .map(|(id, _)| receiver.get_priv_value(id).clone())
+        .collect()
+}
+
+/// Computes the size of the intersection of the sets of two parties, which is
+/// output to the receiver without the intersection itself.
+///
+/// The receiver blinds the hashes $H(x)$ of its elements with a single random
+/// exponent $r$ and sends them to the sender, which raises them to a fresh key
+/// $k$ and sends them back in a random order, together with the sorted values
+/// $H(y)^k$ of its own elements. The receiver removes the blinding to obtain
+/// $H(x)^k$ and counts how many of them are among the values of the sender, but
+/// the shuffle hides which of its elements they belong to. The messages go
+/// through the simulated network with the tag `"psi_ca"`, and the sender
+/// learns nothing because the blinded hashes are uniformly distributed.
+pub fn psi_cardinality_protocol<T>(
+    receiver: &mut VirtualMachine<T>,
+    sender: &mut VirtualMachine<T>,
+    group: &Group<T>,
+    ids_receiver: &[&str],
+    ids_sender: &[&str],
+) -> usize
+where
+    T: MersenneField,
+{
+    let blind = loop {
+        let r = T::random(&mut receiver.prg);
+        if r.value() != 0 {
+            break r;
+        }
+    };
+    let blinded: Vec<GroupElement> = ids_receiver
+        .iter()
+        .map(|id| {
+            group.exp(
+                &oprf::hash_to_group(group, receiver.get_priv_value(id)),
+                &blind,
+            )
+        })
+        .collect();
+
+    let key = loop {
+        let k = T::random(&mut sender.prg);
+        if k.value() != 0 {
+            break k;
+        }
+    };
+    let mut evaluated: Vec<GroupElement> = ids_sender
+        .iter()
+        .map(|id| group.exp(&oprf::hash_to_group(group, sender.get_priv_value(id)), &key))
+        .collect();
+    evaluated.sort_by(|a, b| a.0.cmp(&b.0));
+    // Random indices for the Fisher-Yates shuffle of the answers.
+    let swaps: Vec<usize> = (1..ids_receiver.len())
+        .rev()
+        .map(|i| {
+            let bytes = sender.prg.next(8);
+            (u64::from_le_bytes(bytes.try_into().unwrap()) % (i as u64 + 1)) as usize
+        })
+        .collect();
+
+    let parties = [&mut *receiver, &mut *sender];
+    let network = Simulator::new(&parties).with_tag("psi_ca");
+    network.send(
+        0,
+        1,
+        Payload::Bits(mpc::bytes_to_bits(&group.encode(&blinded))),
+    );
+    network.deliver();
+
+    // The sender raises the blinded hashes to its key and shuffles them.
+    let received = group.decode(&mpc::bits_to_bytes(&network.recv_from(1, 0).payload.bits()));
+    let mut answered: Vec<GroupElement> = received
+        .iter()
+        .map(|element| group.exp(element, &key))
+        .collect();
+    for (i, j) in (1..answered.len()).rev().zip(swaps) {
+        answered.swap(i, j);
+    }
+    network.send(
+        1,
+        0,
+        Payload::Bits(mpc::bytes_to_bits(&group.encode(&answered))),
+    );
+    network.send(
+        1,
+        0,
+        Payload::Bits(mpc::bytes_to_bits(&group.encode(&evaluated))),
+    );
+    network.deliver();
+
+    let answered = group.decode(&mpc::bits_to_bytes(&network.recv_from(0, 1).payload.bits()));
+    let evaluated: HashSet<Vec<u8>> = group
+        .decode(&mpc::bits_to_bytes(&network.recv_from(0, 1).payload.bits()))
+        .iter()
+        .map(|element| element.0.to_bytes_le())
+        .collect();
+    let unblind = blind.inverse();
+    answered
+        .iter()
+        .filter(|element| evaluated.contains(&group.exp(element, &unblind).0.to_bytes_le()))
+        .count()
+}
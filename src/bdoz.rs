@@ -0,0 +1,330 @@
+//! Implements authenticated secret-sharing with pairwise MACs, in the style of
+//! BDOZ.
+//!
+//! In contrast with the global MAC key of [`spdz`](crate::spdz), in BDOZ every
+//! pair of parties authenticates the shares with its own keys. Each party $j$
+//! holds a private key $\alpha_j$ and, for each share $x_i$ of every other party
+//! $i$, a local key $\beta_{j, i}$. The party $i$ holds the MAC
+//!    $$m_{i, j} = \alpha_j x_i + \beta_{j, i}$$
+//! of its share for every verifier $j$. When the share $x_i$ is revealed, the
+//! party $i$ sends it together with $m_{i, j}$ to each party $j$, which checks
+//! the MAC with its keys. MACs and keys are linear, so the linear operations
+//! are local, and every opening is checked immediately, without the batched
+//! check of SPDZ. The price is that the size of the authenticated shares grows
+//! linearly with the number of parties.
+//!
+//! The parties are identified by their position in the vector of parties, so
+//! the same order must be used in every call. As in [`spdz`](crate::spdz), the
+//! preprocessing material is generated by a trusted party.
+
+use crate::math::mersenne::MersenneField;
+use crate::mpc::additive_shares;
+use crate::network::{Payload, Simulator};
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+use std::collections::HashMap;
+
+/// Defines a share authenticated with pairwise MACs.
+#[derive(Clone)]
+pub struct BdozShare<T: MersenneField> {
+    /// Share of the value.
+    pub value: T,
+
+    /// MACs of the share, one for each verifier.
+    pub macs: Vec<T>,
+
+    /// Local keys to verify the shares of the other parties, one for each of
+    /// them.
+    pub keys: Vec<T>,
+}
+
+/// Defines the BDOZ state of a virtual machine.
+pub struct BdozState<T: MersenneField> {
+    /// Private MAC key of the virtual machine, available after [`setup`].
+    pub alpha: Option<T>,
+
+    /// Memory for authenticated shares.
+    pub shares: HashMap<String, BdozShare<T>>,
+}
+
+impl<T: MersenneField> BdozState<T> {
+    /// Creates an empty state without a MAC key.
+    pub fn new() -> Self {
+        Self {
+            alpha: None,
+            shares: HashMap::new(),
+        }
+    }
+
+    /// Returns the authenticated share with the provided ID.
+    pub fn get_share(&self, id: &str) -> &BdozShare<T> {
+        self.shares.get(id).unwrap_or_else(|| {
+            panic!("The id `{}` is not registered in the virtual machine.", id);
+        })
+    }
+
+    fn alpha(&self) -> &T {
+        self.alpha.as_ref().unwrap_or_else(|| {
+            panic!("The BDOZ setup has not been executed.");
+        })
+    }
+}
+
+impl<T: MersenneField> Default for BdozState<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Samples a private MAC key for each party.
+pub fn setup<T>(parties: &mut [&mut VirtualMachine<T>], prg: &mut Prg)
+where
+    T: MersenneField,
+{
+    for party in parties.iter_mut() {
+        party.bdoz.alpha = Some(T::random(&mut *prg));
+    }
+}
+
+/// Simulates the distribution of authenticated shares of a value.
+///
+/// A trusted party, which knows the keys $\alpha_j$ of all the parties,
+/// computes additive shares of the value, samples the local keys, and computes
+/// the MACs of each share. The authenticated shares are stored in the BDOZ
+/// memory of each party under the provided ID.
+pub fn simulate_auth_dist<T>(
+    id: &str,
+    parties: &mut [&mut VirtualMachine<T>],
+    value: &T,
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let n_parties = parties.len();
+    let alphas: Vec<T> = parties
+        .iter()
+        .map(|party| party.bdoz.alpha().clone())
+        .collect();
+    let values = additive_shares(value, n_parties, prg);
+
+    // keys[j][i] is the key of the party j for the share of the party i.
+    let keys: Vec<Vec<T>> = (0..n_parties)
+        .map(|_| (0..n_parties).map(|_| T::random(&mut *prg)).collect())
+        .collect();
+
+    for (i, (party, value)) in parties.iter_mut().zip(values).enumerate() {
+        let macs = (0..n_parties)
+            .map(|j| alphas[j].multiply(&value).add(&keys[j][i]))
+            .collect();
+        let share = BdozShare {
+            value,
+            macs,
+            keys: keys[i].clone(),
+        };
+        insert_bdoz_share(party, id, share);
+    }
+}
+
+/// Distributes authenticated shares of a value stored in the private memory of
+/// the party with ID `id_owner`.
+///
+/// The authentication of the input is simulated by a trusted party. The
+/// shares are stored in the BDOZ memory of each party with the same ID that
+/// the owner has in its private memory.
+pub fn distribute_shares<T>(
+    id_var: &str,
+    id_owner: &str,
+    parties: &mut [&mut VirtualMachine<T>],
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let value = parties
+        .iter()
+        .find(|party| party.id == id_owner)
+        .map(|party| party.get_priv_value(id_var).clone())
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+
+    simulate_auth_dist(id_var, parties, &value, prg);
+}
+
+/// Creates and distributes authenticated shares of a multiplication triple
+/// among a set of parties.
+pub fn generate_triple<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_triple: (&str, &str, &str),
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let a = T::random(&mut *prg);
+    let b = T::random(&mut *prg);
+    let c = a.multiply(&b);
+
+    simulate_auth_dist(id_triple.0, parties, &a, prg);
+    simulate_auth_dist(id_triple.1, parties, &b, prg);
+    simulate_auth_dist(id_triple.2, parties, &c, prg);
+}
+
+/// Adds two authenticated values locally.
+pub fn add_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: &str,
+    id_b: &str,
+    id_result: &str,
+) where
+    T: MersenneField,
+{
+    for party in parties.iter_mut() {
+        let a = party.bdoz.get_share(id_a);
+        let b = party.bdoz.get_share(id_b);
+        let sum = combine(&[(T::new(1), a), (T::new(1), b)]);
+        insert_bdoz_share(party, id_result, sum);
+    }
+}
+
+/// Multiplicates two authenticated values using an authenticated triple.
+///
+/// The values $\epsilon = x - a$ and $\delta = y - b$ are opened and their
+/// MACs are checked immediately. The parties compute locally the authenticated
+/// shares of $c + \epsilon b + \delta a + \epsilon \delta$. The public term is
+/// added to the share of the first party, so every verifier $j$ subtracts
+/// $\alpha_j \epsilon \delta$ from its key for that share to keep the MAC
+/// consistent. The triple is removed from the memory once consumed.
+pub fn mult_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_x: &str,
+    id_y: &str,
+    id_result: &str,
+    id_triple: (&str, &str, &str),
+) where
+    T: MersenneField,
+{
+    let minus_one = T::new(1).negate();
+    let epsilon_shares: Vec<BdozShare<T>> = parties
+        .iter()
+        .map(|party| {
+            let x = party.bdoz.get_share(id_x);
+            let a = party.bdoz.get_share(id_triple.0);
+            combine(&[(T::new(1), x), (minus_one.clone(), a)])
+        })
+        .collect();
+    let delta_shares: Vec<BdozShare<T>> = parties
+        .iter()
+        .map(|party| {
+            let y = party.bdoz.get_share(id_y);
+            let b = party.bdoz.get_share(id_triple.1);
+            combine(&[(T::new(1), y), (minus_one.clone(), b)])
+        })
+        .collect();
+    let epsilon = checked_open(parties, &epsilon_shares);
+    let delta = checked_open(parties, &delta_shares);
+
+    let epsilon_delta = epsilon.multiply(&delta);
+    for (j, party) in parties.iter_mut().enumerate() {
+        let a = party.bdoz.get_share(id_triple.0);
+        let b = party.bdoz.get_share(id_triple.1);
+        let c = party.bdoz.get_share(id_triple.2);
+        let mut result = combine(&[(T::new(1), c), (epsilon.clone(), b), (delta.clone(), a)]);
+
+        // The constant is added to the share of the first party.
+        if j == 0 {
+            result.value = result.value.add(&epsilon_delta);
+        }
+        let correction = party.bdoz.alpha().multiply(&epsilon_delta);
+        result.keys[0] = result.keys[0].subtract(&correction);
+
+        for id in [id_triple.0, id_triple.1, id_triple.2] {
+            party.bdoz.shares.remove(id);
+        }
+        insert_bdoz_share(party, id_result, result);
+    }
+}
+
+/// Opens an authenticated value, checking the MACs of every share.
+///
+/// The function panics if a MAC is not valid, which means that some party has
+/// tampered with its share.
+pub fn reconstruct_share<T>(parties: &[&mut VirtualMachine<T>], id: &str) -> T
+where
+    T: MersenneField,
+{
+    let shares: Vec<BdozShare<T>> = parties
+        .iter()
+        .map(|party| party.bdoz.get_share(id).clone())
+        .collect();
+    checked_open(parties, &shares)
+}
+
+/// Opens a value from its authenticated shares, where every party sends its
+/// share and the MAC for the receiver through the network, and checks the
+/// MACs of the shares received from the other parties.
+fn checked_open<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    shares: &[BdozShare<T>],
+) -> T {
+    let network = Simulator::new(parties).with_tag("bdoz_open");
+    for (i, (party, share)) in parties.iter().zip(shares).enumerate() {
+        let value = party.behavior.opened_share(&share.value);
+        for j in (0..parties.len()).filter(|j| *j != i) {
+            network.send(
+                i,
+                j,
+                Payload::Elements(vec![value.clone(), share.macs[j].clone()]),
+            );
+        }
+    }
+    network.deliver();
+
+    let mut opened = T::new(0);
+    for (j, verifier) in parties.iter().enumerate() {
+        let alpha = verifier.bdoz.alpha();
+        let mut value = verifier.behavior.opened_share(&shares[j].value);
+        for i in (0..parties.len()).filter(|i| *i != j) {
+            let received = network.recv_from(j, i).payload.elements();
+            let expected = alpha.multiply(&received[0]).add(&shares[j].keys[i]);
+            if expected.value() != received[1].value() {
+                panic!("The MAC check failed, some party has cheated.");
+            }
+            value = value.add(&received[0]);
+        }
+        opened = value;
+    }
+    opened
+}
+
+/// Computes locally a linear combination of authenticated shares.
+fn combine<T: MersenneField>(terms: &[(T, &BdozShare<T>)]) -> BdozShare<T> {
+    let n_parties = terms[0].1.macs.len();
+    let mut result = BdozShare {
+        value: T::new(0),
+        macs: (0..n_parties).map(|_| T::new(0)).collect(),
+        keys: (0..n_parties).map(|_| T::new(0)).collect(),
+    };
+    for (coeff, share) in terms {
+        result.value = result.value.add(&coeff.multiply(&share.value));
+        for j in 0..n_parties {
+            result.macs[j] = result.macs[j].add(&coeff.multiply(&share.macs[j]));
+            result.keys[j] = result.keys[j].add(&coeff.multiply(&share.keys[j]));
+        }
+    }
+    result
+}
+
+/// Inserts an authenticated share in the BDOZ memory of a party.
+fn insert_bdoz_share<T: MersenneField>(
+    party: &mut VirtualMachine<T>,
+    id: &str,
+    share: BdozShare<T>,
+) {
+    if party.bdoz.shares.contains_key(id) {
+        panic!("There exists an authenticated share with this id.");
+    }
+    let share = BdozShare {
+        value: party.behavior.stored_share(share.value),
+        ..share
+    };
+    party.bdoz.shares.insert(id.to_string(), share);
+}
@@ -0,0 +1,123 @@
+//! Implements a simulated broadcast channel with consistency checking.
+//!
+//! The virtual machines communicate through point-to-point channels, so a
+//! value that a party "broadcasts" is in fact sent separately to each party.
+//! A corrupted sender may then equivocate, that is, send different values to
+//! different parties. Echo broadcast prevents this: after receiving the value
+//! from the sender, every party echoes it to all the other parties, and each
+//! party checks that all the echoes agree with the value it received. If the
+//! sender equivocated, some honest party sees two different values and aborts.
+//! Echo broadcast does not guarantee termination, but it guarantees that the
+//! honest parties that do not abort agree on the same value.
+//!
+//! # Example
+//!
+//! Here, a cheating sender equivocates by sending a different value to the
+//! third party, and the parties detect it when they compare the echoes.
+//!
+//! ```should_panic
+//! use smol_mpc::broadcast;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//!
+//! let received = vec![Mersenne61::new(7), Mersenne61::new(7), Mersenne61::new(8)];
+//! broadcast::echo_broadcast(&received);
+//! ```
+
+use crate::dispute::Dispute;
+use crate::math::mersenne::MersenneField;
+use crate::vm::VirtualMachine;
+
+/// Runs the echo phase of a broadcast, where `received[j]` is the value that
+/// the $j$-th party received from the sender.
+///
+/// Every party echoes its value to all the other parties and compares the
+/// echoes with it. The function returns the value agreed by all the parties,
+/// or panics if the sender sent inconsistent values.
+pub fn echo_broadcast<T: MersenneField>(received: &[T]) -> T {
+    for (j, value) in received.iter().enumerate() {
+        // The party j compares the echoes of the other parties.
+        let consistent = received
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != j)
+            .all(|(_, echo)| echo.value() == value.value());
+        if !consistent {
+            panic!("The sender sent inconsistent values in the broadcast.");
+        }
+    }
+
+    received[0].clone()
+}
+
+/// Broadcasts the value with ID `id` stored in the private memory of the party
+/// with ID `id_sender`.
+///
+/// The sender sends the value to every party through the point-to-point
+/// channels, and the consistency is checked with [`echo_broadcast`]. At the
+/// end, every other party stores the value in its private memory under the
+/// same ID.
+pub fn broadcast_protocol<T>(parties: &mut [&mut VirtualMachine<T>], id_sender: &str, id: &str)
+where
+    T: MersenneField,
+{
+    let sender = parties
+        .iter()
+        .find(|party| party.id == id_sender)
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+    let value = sender.get_priv_value(id);
+
+    let received: Vec<T> = (0..parties.len())
+        .map(|i| sender.behavior.sent_value(value, i))
+        .collect();
+    let value = echo_broadcast(&received);
+
+    for party in parties.iter_mut().filter(|party| party.id != id_sender) {
+        party.insert_priv_value(id, value.clone());
+    }
+}
+
+/// Broadcasts the value with ID `id` stored in the private memory of the party
+/// with ID `id_sender`, reporting the equivocations as a dispute instead of
+/// aborting.
+///
+/// The sender announces the value it sent, and the first party that received a
+/// different value disputes with the sender (see [`dispute`](crate::dispute)).
+/// If all the values agree, every other party stores the value in its private
+/// memory under the same ID, as in [`broadcast_protocol`].
+pub fn try_broadcast_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_sender: &str,
+    id: &str,
+) -> Result<(), Dispute>
+where
+    T: MersenneField,
+{
+    let sender = parties
+        .iter()
+        .find(|party| party.id == id_sender)
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+    let value = sender.get_priv_value(id).clone();
+
+    for (i, party) in parties
+        .iter()
+        .enumerate()
+        .filter(|(_, party)| party.id != id_sender)
+    {
+        if sender.behavior.sent_value(&value, i).value() != value.value() {
+            return Err(Dispute::new(
+                &party.id,
+                id_sender,
+                "received a different value in the broadcast",
+            ));
+        }
+    }
+
+    for party in parties.iter_mut().filter(|party| party.id != id_sender) {
+        party.insert_priv_value(id, value.clone());
+    }
+    Ok(())
+}
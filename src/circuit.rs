@@ -0,0 +1,579 @@
+//! Implements a circuit representation shared by all the protocol families.
+//!
+//! The protocols of this library compute one operation at a time, so a larger
+//! computation is a sequence of protocol calls. A [`Circuit`] describes such a
+//! computation once, as a list of gates over numbered wires, and each backend
+//! evaluates it with its own protocols:
+//!
+//! - the secret-sharing protocols evaluate all the gates over the field (see
+//!   [`mpc::evaluate_circuit`](crate::mpc::evaluate_circuit));
+//! - the GMW protocol (see [`gmw::evaluate_circuit`](crate::mpc::gmw::evaluate_circuit))
+//!   evaluates boolean circuits, with XOR, AND and NOT gates over bits;
+//! - Yao's protocol evaluates boolean circuits after converting them with
+//!   [`gc::Circuit::from_ir`](crate::gc::Circuit::from_ir).
+//!
+//! Circuits are built with a [`CircuitBuilder`], which allocates a new wire for
+//! each input and each gate, so the gates are always in topological order.
+//! Before running a circuit, its size and depth can be inspected, and the cost
+//! of evaluating it with each backend can be estimated with
+//! [`Circuit::estimate_cost`].
+//!
+//! The wires of a [`CircuitBuilder`] are plain indices, so nothing prevents
+//! feeding a boolean wire to an arithmetic gate. A [`TypedCircuitBuilder`]
+//! returns an [`ArithWire`] or a [`BoolWire`] instead, and its gates only accept
+//! wires of the right type, so such mistakes are caught at compile time.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::circuit::CircuitBuilder;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//!
+//! type Fp = Mersenne61;
+//!
+//! // Builds a circuit that computes x * y + 3.
+//! let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+//! let x = builder.input();
+//! let y = builder.input();
+//! let product = builder.mul(x, y);
+//! let three = builder.constant(Fp::new(3));
+//! let result = builder.add(product, three);
+//! builder.output(result);
+//! let circuit = builder.build();
+//!
+//! let output = circuit.evaluate(&[Fp::new(4), Fp::new(5)]);
+//! assert_eq!(output[0].value(), 23);
+//! ```
+//!
+//! The same circuit with typed wires:
+//!
+//! ```rust
+//! # use smol_mpc::circuit::TypedCircuitBuilder;
+//! # use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! # type Fp = Mersenne61;
+//! let mut builder: TypedCircuitBuilder<Fp> = TypedCircuitBuilder::new();
+//! let x = builder.input();
+//! let y = builder.input();
+//! let product = builder.mul(x, y);
+//! let three = builder.constant(Fp::new(3));
+//! let result = builder.add(product, three);
+//! builder.output(result);
+//! let circuit = builder.build();
+//! assert_eq!(circuit.evaluate(&[Fp::new(4), Fp::new(5)])[0].value(), 23);
+//! ```
+//!
+//! whereas mixing the types of the wires does not compile:
+//!
+//! ```compile_fail
+//! # use smol_mpc::circuit::TypedCircuitBuilder;
+//! # use smol_mpc::math::mersenne::Mersenne61;
+//! let mut builder: TypedCircuitBuilder<Mersenne61> = TypedCircuitBuilder::new();
+//! let x = builder.input();
+//! let bit = builder.bool_input();
+//! builder.mul(x, bit);
+//! ```
+
+use crate::gc::{GarblingScheme, LABEL_LEN};
+use crate::math::mersenne::MersenneField;
+
+/// Identifies a wire of a circuit.
+pub type Wire = usize;
+
+/// Defines a gate of a circuit.
+#[derive(Clone, Debug)]
+pub enum Gate<T> {
+    /// Addition over the field of the wires `.0` and `.1` into the wire `.2`.
+    Add(Wire, Wire, Wire),
+
+    /// Multiplication over the field of the wires `.0` and `.1` into the wire
+    /// `.2`.
+    Mul(Wire, Wire, Wire),
+
+    /// Public constant `.0` assigned to the wire `.1`.
+    Const(T, Wire),
+
+    /// XOR of the bits in the wires `.0` and `.1` into the wire `.2`.
+    Xor(Wire, Wire, Wire),
+
+    /// AND of the bits in the wires `.0` and `.1` into the wire `.2`.
+    And(Wire, Wire, Wire),
+
+    /// Negation of the bit in the wire `.0` into the wire `.1`.
+    Inv(Wire, Wire),
+}
+
+impl<T> Gate<T> {
+    /// Returns whether the gate operates on bits.
+    pub fn is_boolean(&self) -> bool {
+        matches!(self, Gate::Xor(..) | Gate::And(..) | Gate::Inv(..))
+    }
+
+    /// Returns the input wires of the gate.
+    pub fn inputs(&self) -> Vec<Wire> {
+        match *self {
+            Gate::Add(a, b, _) | Gate::Mul(a, b, _) | Gate::Xor(a, b, _) | Gate::And(a, b, _) => {
+                vec![a, b]
+            }
+            Gate::Inv(a, _) => vec![a],
+            Gate::Const(..) => Vec::new(),
+        }
+    }
+
+    /// Returns the output wire of the gate.
+    pub fn output(&self) -> Wire {
+        match *self {
+            Gate::Add(_, _, c) | Gate::Mul(_, _, c) | Gate::Xor(_, _, c) | Gate::And(_, _, c) => c,
+            Gate::Const(_, c) | Gate::Inv(_, c) => c,
+        }
+    }
+}
+
+/// Counts the gates of each kind in a circuit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GateCounts {
+    /// Number of addition gates.
+    pub add: usize,
+
+    /// Number of multiplication gates.
+    pub mul: usize,
+
+    /// Number of constants.
+    pub constant: usize,
+
+    /// Number of XOR gates.
+    pub xor: usize,
+
+    /// Number of AND gates.
+    pub and: usize,
+
+    /// Number of NOT gates.
+    pub inv: usize,
+}
+
+/// Defines the backends that can evaluate a circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Additive secret-sharing with Beaver triples (see
+    /// [`mpc::evaluate_circuit`](crate::mpc::evaluate_circuit)).
+    SecretSharing,
+
+    /// The GMW protocol (see
+    /// [`gmw::evaluate_circuit`](crate::mpc::gmw::evaluate_circuit)).
+    Gmw,
+
+    /// Yao's protocol with the provided garbling scheme, where the garbler
+    /// holds the first `n_garbler_inputs` inputs (see
+    /// [`gc::yao_protocol`](crate::gc::yao_protocol)).
+    Yao {
+        /// Scheme used to garble the circuit.
+        scheme: GarblingScheme,
+
+        /// Number of inputs of the garbler.
+        n_garbler_inputs: usize,
+    },
+}
+
+/// Defines the estimated cost of evaluating a circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// Number of communication rounds.
+    pub rounds: usize,
+
+    /// Total number of bits sent by all the parties.
+    pub bits: usize,
+}
+
+/// Defines a circuit as a list of gates in topological order.
+#[derive(Clone, Debug)]
+pub struct Circuit<T> {
+    /// Total number of wires of the circuit.
+    pub n_wires: usize,
+
+    /// Input wires of the circuit, in the order in which the inputs are
+    /// provided.
+    pub inputs: Vec<Wire>,
+
+    /// Output wires of the circuit.
+    pub outputs: Vec<Wire>,
+
+    /// Gates of the circuit in topological order.
+    pub gates: Vec<Gate<T>>,
+}
+
+impl<T: MersenneField> Circuit<T> {
+    /// Returns whether all the gates of the circuit operate on bits.
+    pub fn is_boolean(&self) -> bool {
+        self.gates.iter().all(|gate| gate.is_boolean())
+    }
+
+    /// Returns whether all the gates of the circuit operate on field elements.
+    pub fn is_arithmetic(&self) -> bool {
+        self.gates.iter().all(|gate| !gate.is_boolean())
+    }
+
+    /// Returns the number of gates of each kind.
+    pub fn gate_counts(&self) -> GateCounts {
+        let mut counts = GateCounts::default();
+        for gate in &self.gates {
+            match gate {
+                Gate::Add(..) => counts.add += 1,
+                Gate::Mul(..) => counts.mul += 1,
+                Gate::Const(..) => counts.constant += 1,
+                Gate::Xor(..) => counts.xor += 1,
+                Gate::And(..) => counts.and += 1,
+                Gate::Inv(..) => counts.inv += 1,
+            }
+        }
+        counts
+    }
+
+    /// Returns the multiplicative depth of the circuit, that is, the largest
+    /// number of multiplication and AND gates in a path from an input to an
+    /// output.
+    pub fn multiplicative_depth(&self) -> usize {
+        self.layers(|gate| matches!(gate, Gate::Mul(..) | Gate::And(..)))
+            .len()
+    }
+
+    /// Returns the multiplicative width of the circuit, that is, the largest
+    /// number of multiplication and AND gates at the same multiplicative
+    /// depth, which can be computed in the same round.
+    pub fn multiplicative_width(&self) -> usize {
+        self.layers(|gate| matches!(gate, Gate::Mul(..) | Gate::And(..)))
+            .iter()
+            .map(|layer| layer.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Estimates the cost of evaluating the circuit with a backend among
+    /// `n_parties` parties, not counting the distribution of the inputs nor
+    /// the preprocessing.
+    ///
+    /// - With secret-sharing, every multiplication opens two field elements,
+    ///   which every party sends to the other parties, and the gates that can
+    ///   be computed in parallel share the round. Since the bits are field
+    ///   elements, AND and XOR gates cost one multiplication each.
+    /// - With GMW, every AND gate opens two bits in the same way, and XOR and
+    ///   NOT gates are free.
+    /// - With Yao's protocol, the garbler sends the garbled tables and the
+    ///   labels of its inputs, the evaluator receives the labels of its inputs
+    ///   with one OT each, where the garbler sends both labels, and the
+    ///   evaluator sends the output back. This takes two rounds regardless of
+    ///   the circuit.
+    pub fn estimate_cost(&self, backend: Backend, n_parties: usize) -> CostEstimate {
+        let links = n_parties * (n_parties - 1);
+        match backend {
+            Backend::SecretSharing => {
+                let layers = self
+                    .layers(|gate| matches!(gate, Gate::Mul(..) | Gate::And(..) | Gate::Xor(..)));
+                let n_mults: usize = layers.iter().map(|layer| layer.len()).sum();
+                CostEstimate {
+                    rounds: layers.len(),
+                    bits: 2 * n_mults * links * T::POWER as usize,
+                }
+            }
+            Backend::Gmw => {
+                let counts = self.gate_counts();
+                if counts.add + counts.mul + counts.constant > 0 {
+                    panic!("The GMW protocol supports only boolean gates.");
+                }
+                CostEstimate {
+                    rounds: self.multiplicative_depth(),
+                    bits: 2 * counts.and * links,
+                }
+            }
+            Backend::Yao {
+                scheme,
+                n_garbler_inputs,
+            } => {
+                let counts = self.gate_counts();
+                if counts.add + counts.mul + counts.constant > 0 {
+                    panic!("Garbled circuits support only boolean gates.");
+                }
+                let label_bits = 8 * LABEL_LEN;
+                let table_bits = match scheme {
+                    GarblingScheme::Textbook => 4 * 2 * label_bits * (counts.and + counts.xor),
+                    GarblingScheme::PointAndPermute => 4 * label_bits * (counts.and + counts.xor),
+                    GarblingScheme::FreeXor => 4 * label_bits * counts.and,
+                    GarblingScheme::HalfGates => 2 * label_bits * counts.and,
+                };
+                let n_evaluator_inputs = self.inputs.len() - n_garbler_inputs;
+                let input_bits = label_bits * (n_garbler_inputs + 2 * n_evaluator_inputs);
+                let output_bits = (label_bits + 1) * self.outputs.len();
+                CostEstimate {
+                    rounds: 2,
+                    bits: table_bits + input_bits + output_bits,
+                }
+            }
+        }
+    }
+
+    /// Groups the gates selected by `is_nonlinear` by their depth, counted in
+    /// selected gates, so `layers[d]` holds the selected gates that need `d`
+    /// selected gates before them.
+    fn layers(&self, is_nonlinear: impl Fn(&Gate<T>) -> bool) -> Vec<Vec<&Gate<T>>> {
+        let mut depths = vec![0; self.n_wires];
+        let mut layers: Vec<Vec<&Gate<T>>> = Vec::new();
+        for gate in &self.gates {
+            let depth = gate
+                .inputs()
+                .iter()
+                .map(|wire| depths[*wire])
+                .max()
+                .unwrap_or(0);
+            if is_nonlinear(gate) {
+                if layers.len() <= depth {
+                    layers.resize(depth + 1, Vec::new());
+                }
+                layers[depth].push(gate);
+                depths[gate.output()] = depth + 1;
+            } else {
+                depths[gate.output()] = depth;
+            }
+        }
+        layers
+    }
+
+    /// Evaluates the circuit in the clear and returns the values of the output
+    /// wires.
+    ///
+    /// The bits of the boolean gates are represented by the field elements zero
+    /// and one, so $u \oplus v = u + v - 2uv$ and $\lnot u = 1 - u$.
+    pub fn evaluate(&self, inputs: &[T]) -> Vec<T> {
+        if inputs.len() != self.inputs.len() {
+            panic!(
+                "The circuit has {} inputs but {} values were provided.",
+                self.inputs.len(),
+                inputs.len()
+            );
+        }
+
+        let mut wires: Vec<Option<T>> = vec![None; self.n_wires];
+        for (wire, value) in self.inputs.iter().zip(inputs) {
+            wires[*wire] = Some(value.clone());
+        }
+
+        let get = |wires: &[Option<T>], wire: Wire| -> T {
+            wires[wire]
+                .clone()
+                .unwrap_or_else(|| panic!("The wire {} has not been evaluated.", wire))
+        };
+        for gate in &self.gates {
+            let value = match gate {
+                Gate::Add(a, b, _) => get(&wires, *a).add(&get(&wires, *b)),
+                Gate::Mul(a, b, _) | Gate::And(a, b, _) => {
+                    get(&wires, *a).multiply(&get(&wires, *b))
+                }
+                Gate::Const(value, _) => value.clone(),
+                Gate::Xor(a, b, _) => {
+                    let (u, v) = (get(&wires, *a), get(&wires, *b));
+                    let uv = u.multiply(&v);
+                    u.add(&v).subtract(&uv.add(&uv))
+                }
+                Gate::Inv(a, _) => T::new(1).subtract(&get(&wires, *a)),
+            };
+            wires[gate.output()] = Some(value);
+        }
+
+        self.outputs.iter().map(|wire| get(&wires, *wire)).collect()
+    }
+}
+
+/// Builds a [`Circuit`] gate by gate.
+///
+/// Each method that adds an input or a gate returns the wire that holds its
+/// value, which can be used as an input of the next gates.
+pub struct CircuitBuilder<T> {
+    circuit: Circuit<T>,
+}
+
+impl<T: MersenneField> CircuitBuilder<T> {
+    /// Creates a builder for an empty circuit.
+    pub fn new() -> Self {
+        Self {
+            circuit: Circuit {
+                n_wires: 0,
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                gates: Vec::new(),
+            },
+        }
+    }
+
+    /// Adds an input to the circuit.
+    pub fn input(&mut self) -> Wire {
+        let wire = self.new_wire();
+        self.circuit.inputs.push(wire);
+        wire
+    }
+
+    /// Adds an addition gate.
+    pub fn add(&mut self, a: Wire, b: Wire) -> Wire {
+        let c = self.new_wire();
+        self.circuit.gates.push(Gate::Add(a, b, c));
+        c
+    }
+
+    /// Adds a multiplication gate.
+    pub fn mul(&mut self, a: Wire, b: Wire) -> Wire {
+        let c = self.new_wire();
+        self.circuit.gates.push(Gate::Mul(a, b, c));
+        c
+    }
+
+    /// Adds a public constant.
+    pub fn constant(&mut self, value: T) -> Wire {
+        let c = self.new_wire();
+        self.circuit.gates.push(Gate::Const(value, c));
+        c
+    }
+
+    /// Adds a XOR gate.
+    pub fn xor(&mut self, a: Wire, b: Wire) -> Wire {
+        let c = self.new_wire();
+        self.circuit.gates.push(Gate::Xor(a, b, c));
+        c
+    }
+
+    /// Adds an AND gate.
+    pub fn and(&mut self, a: Wire, b: Wire) -> Wire {
+        let c = self.new_wire();
+        self.circuit.gates.push(Gate::And(a, b, c));
+        c
+    }
+
+    /// Adds a NOT gate.
+    pub fn inv(&mut self, a: Wire) -> Wire {
+        let c = self.new_wire();
+        self.circuit.gates.push(Gate::Inv(a, c));
+        c
+    }
+
+    /// Marks a wire as an output of the circuit.
+    pub fn output(&mut self, wire: Wire) {
+        if wire >= self.circuit.n_wires {
+            panic!("The wire {} does not exist.", wire);
+        }
+        self.circuit.outputs.push(wire);
+    }
+
+    /// Returns the circuit built so far.
+    pub fn build(self) -> Circuit<T> {
+        self.circuit
+    }
+
+    /// Allocates a new wire.
+    fn new_wire(&mut self) -> Wire {
+        self.circuit.n_wires += 1;
+        self.circuit.n_wires - 1
+    }
+}
+
+impl<T: MersenneField> Default for CircuitBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identifies a wire of a circuit that holds a field element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArithWire(Wire);
+
+/// Identifies a wire of a circuit that holds a bit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoolWire(Wire);
+
+impl ArithWire {
+    /// Returns the index of the wire in the circuit.
+    pub fn wire(self) -> Wire {
+        self.0
+    }
+}
+
+impl BoolWire {
+    /// Returns the index of the wire in the circuit.
+    pub fn wire(self) -> Wire {
+        self.0
+    }
+}
+
+/// Builds a [`Circuit`] gate by gate, keeping track of the type of each wire.
+///
+/// The arithmetic gates receive and return [`ArithWire`]s and the boolean gates
+/// [`BoolWire`]s, so the arithmetic and the boolean parts of a circuit cannot be
+/// mixed by mistake. The resulting circuit is the same as the one built with a
+/// [`CircuitBuilder`], and it can be evaluated by any backend.
+pub struct TypedCircuitBuilder<T> {
+    builder: CircuitBuilder<T>,
+}
+
+impl<T: MersenneField> TypedCircuitBuilder<T> {
+    /// Creates a builder for an empty circuit.
+    pub fn new() -> Self {
+        Self {
+            builder: CircuitBuilder::new(),
+        }
+    }
+
+    /// Adds an input that holds a field element.
+    pub fn input(&mut self) -> ArithWire {
+        ArithWire(self.builder.input())
+    }
+
+    /// Adds an input that holds a bit.
+    pub fn bool_input(&mut self) -> BoolWire {
+        BoolWire(self.builder.input())
+    }
+
+    /// Adds an addition gate.
+    pub fn add(&mut self, a: ArithWire, b: ArithWire) -> ArithWire {
+        ArithWire(self.builder.add(a.0, b.0))
+    }
+
+    /// Adds a multiplication gate.
+    pub fn mul(&mut self, a: ArithWire, b: ArithWire) -> ArithWire {
+        ArithWire(self.builder.mul(a.0, b.0))
+    }
+
+    /// Adds a public constant.
+    pub fn constant(&mut self, value: T) -> ArithWire {
+        ArithWire(self.builder.constant(value))
+    }
+
+    /// Adds a XOR gate.
+    pub fn xor(&mut self, a: BoolWire, b: BoolWire) -> BoolWire {
+        BoolWire(self.builder.xor(a.0, b.0))
+    }
+
+    /// Adds an AND gate.
+    pub fn and(&mut self, a: BoolWire, b: BoolWire) -> BoolWire {
+        BoolWire(self.builder.and(a.0, b.0))
+    }
+
+    /// Adds a NOT gate.
+    pub fn inv(&mut self, a: BoolWire) -> BoolWire {
+        BoolWire(self.builder.inv(a.0))
+    }
+
+    /// Marks an arithmetic wire as an output of the circuit.
+    pub fn output(&mut self, wire: ArithWire) {
+        self.builder.output(wire.0);
+    }
+
+    /// Marks a boolean wire as an output of the circuit.
+    pub fn bool_output(&mut self, wire: BoolWire) {
+        self.builder.output(wire.0);
+    }
+
+    /// Returns the circuit built so far.
+    pub fn build(self) -> Circuit<T> {
+        self.builder.build()
+    }
+}
+
+impl<T: MersenneField> Default for TypedCircuitBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
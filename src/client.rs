@@ -0,0 +1,107 @@
+//! Implements the roles of external clients in an MPC deployment.
+//!
+//! In many real-world deployments, the parties that hold the inputs or receive
+//! the outputs are not the same ones that run the computation. Instead, a set of
+//! clients delegate the computation to a committee of servers, represented here
+//! as virtual machines. The clients in this module do not have a share memory
+//! and never take part in the protocols executed by the committee; they only
+//! provide inputs to it and receive outputs from it.
+
+use crate::math::mersenne::MersenneField;
+use crate::mpc;
+use crate::vm::VirtualMachine;
+use std::collections::HashMap;
+
+/// Defines a client that provides private inputs to the committee.
+///
+/// Inputs are provided using the masked-input technique. The committee holds
+/// shares of a random mask $r$ that is opened only towards the client. Then,
+/// the client publishes $c = x - r$ for its input $x$, and the parties compute
+/// locally their shares of $x = c + r$. Since $r$ is uniformly random, the
+/// value $c$ reveals nothing about the input.
+pub struct InputClient<T: MersenneField> {
+    /// ID of the client.
+    pub id: String,
+
+    /// Memory for the inputs of the client.
+    pub inputs: HashMap<String, T>,
+}
+
+impl<T: MersenneField> InputClient<T> {
+    /// Creates a new input client with the provided ID.
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            inputs: HashMap::new(),
+        }
+    }
+
+    /// Inserts an input in the memory of the client using a provided ID.
+    pub fn insert_input(&mut self, id: &str, value: T) {
+        self.inputs.insert(id.to_string(), value);
+    }
+
+    /// Secret-shares the input with ID `id_input` among the committee.
+    ///
+    /// The parties must hold shares of a random mask under the ID `id_mask`,
+    /// for example generated with [`VirtualMachine::prss_random_share`]. Each
+    /// party sends its share of the mask to the client, which reconstructs the
+    /// mask and publishes the masked input. At the end, the parties hold shares
+    /// of the input under the ID `id_input` and the mask is removed from their
+    /// share memory, so it is never used twice.
+    pub fn share_input(
+        &self,
+        parties: &mut [&mut VirtualMachine<T>],
+        id_input: &str,
+        id_mask: &str,
+    ) {
+        let input = self.inputs.get(id_input).unwrap_or_else(|| {
+            panic!("The id is not registered in the input client.");
+        });
+
+        let mask = mpc::reconstruct_share(parties, id_mask);
+        let masked_input = input.subtract(&mask);
+        mpc::add_const_protocol(parties, &masked_input, id_mask, id_input);
+
+        for party in parties {
+            party.remove_share(id_mask);
+        }
+    }
+}
+
+/// Defines a client that receives outputs from the committee.
+///
+/// Each party sends its share of the output only to the client, so the value
+/// is learned by the client and remains hidden from the parties.
+pub struct OutputClient<T: MersenneField> {
+    /// ID of the client.
+    pub id: String,
+
+    /// Memory for the outputs received by the client.
+    pub outputs: HashMap<String, T>,
+}
+
+impl<T: MersenneField> OutputClient<T> {
+    /// Creates a new output client with the provided ID.
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            outputs: HashMap::new(),
+        }
+    }
+
+    /// Receives the shares of the value with ID `id` from the parties and
+    /// stores the reconstructed value in the memory of the client under the
+    /// same ID.
+    pub fn receive_output(&mut self, parties: &[&mut VirtualMachine<T>], id: &str) {
+        let value = mpc::reconstruct_share(parties, id);
+        self.outputs.insert(id.to_string(), value);
+    }
+
+    /// Returns an output previously received by the client.
+    pub fn get_output(&self, id: &str) -> &T {
+        self.outputs.get(id).unwrap_or_else(|| {
+            panic!("The id is not registered in the output client.");
+        })
+    }
+}
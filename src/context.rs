@@ -0,0 +1,539 @@
+//! Bundles the parties of a computation with the randomness it needs.
+//!
+//! The functions of [`mpc`] receive the list of parties and, when they need
+//! correlated randomness, a trusted dealer, which must be passed again in every
+//! call. An [`MpcContext`] owns the list of parties and a trusted [`Dealer`] for
+//! the preprocessing, so a protocol can be written as a sequence of operations
+//! on the context, or as a straight-line program with the [`mpc!`](crate::mpc!)
+//! macro.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::context::MpcContext;
+//! use smol_mpc::dealer::Dealer;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! alice.insert_priv_value("a", Fp::new(4));
+//! bob.insert_priv_value("b", Fp::new(2));
+//!
+//! let dealer = Dealer::new(Prg::new(Some(vec![3, 4])));
+//! let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], dealer);
+//! ctx.input("a", "alice");
+//! ctx.input("b", "bob");
+//! ctx.add("a", "b", "c");
+//! ctx.mult("c", "b", "d");
+//! assert_eq!(ctx.open("d").value(), 12);
+//! ```
+//!
+//! The context also counts the operations performed since it was created (see
+//! [`counter`](crate::counter)), for example to check how many triples a
+//! computation consumed:
+//!
+//! ```rust
+//! # use smol_mpc::context::MpcContext;
+//! # use smol_mpc::dealer::Dealer;
+//! # use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! # use smol_mpc::utils::prg::Prg;
+//! # use smol_mpc::vm::VirtualMachine;
+//! # type Fp = Mersenne61;
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! alice.insert_priv_value("a", Fp::new(4));
+//!
+//! let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], Dealer::new(Prg::new(None)));
+//! ctx.input("a", "alice");
+//! ctx.reset_counts();
+//! ctx.mult("a", "a", "b");
+//! ctx.mult("b", "a", "c");
+//! assert_eq!(ctx.counts().triples, 4);
+//! ```
+//!
+//! # Corruption
+//!
+//! A party can be passively corrupted with [`MpcContext::corrupt`]: it keeps
+//! following the protocol, but the adversary collects its inputs, its random
+//! tape and the messages it receives in an
+//! [`AdversaryView`](crate::adversary::AdversaryView).
+//!
+//! ```rust
+//! # use smol_mpc::context::MpcContext;
+//! # use smol_mpc::dealer::Dealer;
+//! # use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! # use smol_mpc::utils::prg::Prg;
+//! # use smol_mpc::vm::VirtualMachine;
+//! # type Fp = Mersenne61;
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! alice.insert_priv_value("a", Fp::new(4));
+//! bob.insert_priv_value("b", Fp::new(2));
+//!
+//! let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], Dealer::new(Prg::new(None)));
+//! ctx.corrupt("bob");
+//! ctx.input("a", "alice");
+//! ctx.input("b", "bob");
+//! ctx.mult("a", "b", "c");
+//! ctx.open("c");
+//!
+//! let view = ctx.adversary_view();
+//! assert_eq!(view.party("bob").inputs[0].1.value(), 2);
+//! assert_eq!(view.party("bob").opened.last().unwrap().value(), 8);
+//! ```
+//!
+//! # Sessions
+//!
+//! Several instances of a protocol can run over the same parties if each one
+//! is labelled with its own session ID, as the executions of a functionality
+//! are labelled in the Universal Composability framework. Inside a session
+//! `sid`, the ID `x` refers to the entry `sid:x` of the memory of the parties
+//! and the messages are tagged as `sid:tag`, so two sessions can use the same
+//! IDs without colliding. The sub-sessions of `sid` are derived as
+//! `sid/label` (see [`MpcContext::sub_session`]).
+//!
+//! ```rust
+//! # use smol_mpc::context::MpcContext;
+//! # use smol_mpc::dealer::Dealer;
+//! # use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! # use smol_mpc::utils::prg::Prg;
+//! # use smol_mpc::vm::VirtualMachine;
+//! # type Fp = Mersenne61;
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! alice.insert_priv_value("x", Fp::new(4));
+//!
+//! let dealer = Dealer::new(Prg::new(None));
+//! let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], dealer).with_session("main");
+//! let left = ctx.sub_session("left");
+//! let right = ctx.sub_session("right");
+//! ctx.in_session(&left, |ctx| ctx.input("x", "alice"));
+//! ctx.in_session(&right, |ctx| ctx.input("x", "alice"));
+//! ctx.in_session(&left, |ctx| ctx.add("x", "x", "y"));
+//! assert_eq!(ctx.in_session(&left, |ctx| ctx.open("y")).value(), 8);
+//! assert_eq!(ctx.in_session(&right, |ctx| ctx.open("x")).value(), 4);
+//! ```
+
+use crate::adversary::{AdversaryView, Behavior, PassiveView};
+use crate::counter::{self, OpCounts};
+use crate::dealer::Dealer;
+use crate::handle::{SecretHandle, SecretRef};
+use crate::math::mersenne::MersenneField;
+use crate::mpc;
+use crate::preprocessing::TripleHandle;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+/// Defines the context of a computation: the parties, the dealer of the
+/// preprocessing and the current session.
+pub struct MpcContext<'v, T: MersenneField> {
+    parties: Vec<&'v mut VirtualMachine<T>>,
+    dealer: Dealer,
+    session: Option<String>,
+    start: OpCounts,
+    corrupted: Vec<(String, Prg)>,
+}
+
+impl<'v, T: MersenneField> MpcContext<'v, T> {
+    /// Creates a new context for the provided parties, outside of any session.
+    pub fn new(parties: Vec<&'v mut VirtualMachine<T>>, dealer: Dealer) -> Self {
+        Self {
+            parties,
+            dealer,
+            session: None,
+            start: counter::current(),
+            corrupted: Vec::new(),
+        }
+    }
+
+    /// Runs the operations of the context in the session with the provided ID.
+    pub fn with_session(mut self, session: &str) -> Self {
+        self.session = Some(session.to_string());
+        self
+    }
+
+    /// Returns the ID of the current session, if any.
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    /// Derives the ID of a sub-session of the current session, `sid/label`,
+    /// or `label` outside of any session.
+    pub fn sub_session(&self, label: &str) -> String {
+        match &self.session {
+            Some(session) => format!("{}/{}", session, label),
+            None => label.to_string(),
+        }
+    }
+
+    /// Runs the provided operations in the session with the provided ID, and
+    /// goes back to the current session afterwards.
+    pub fn in_session<R, F>(&mut self, session: &str, operations: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let previous = self.session.replace(session.to_string());
+        let output = operations(self);
+        self.session = previous;
+        output
+    }
+
+    /// Returns the entry of the memory that the ID refers to in the current
+    /// session.
+    pub fn qualify(&self, id: &str) -> String {
+        match &self.session {
+            Some(session) => format!("{}:{}", session, id),
+            None => id.to_string(),
+        }
+    }
+
+    /// Returns a fresh ID for a temporary value, with the form `prefix#n`, that
+    /// is not used by any of the parties in the current session (see
+    /// [`mpc::temp_id`]).
+    pub fn temp_id(&mut self, prefix: &str) -> String {
+        loop {
+            let id = mpc::temp_id(&mut self.parties, prefix);
+            let qualified = self.qualify(&id);
+            if self
+                .parties
+                .iter()
+                .all(|party| !party.contains_id(&qualified))
+            {
+                return id;
+            }
+        }
+    }
+
+    /// Runs a protocol over the parties, tagging the messages with the current
+    /// session.
+    fn run<R, F>(&mut self, protocol: F) -> R
+    where
+        F: FnOnce(&mut Vec<&'v mut VirtualMachine<T>>) -> R,
+    {
+        self.mark_session(self.session.as_deref());
+        let output = protocol(&mut self.parties);
+        self.mark_session(None);
+        output
+    }
+
+    fn mark_session(&self, session: Option<&str>) {
+        for party in self.parties.iter() {
+            party.mailbox.set_session(session);
+        }
+    }
+
+    /// Returns the operations performed in the current thread since the
+    /// context was created or its counts were reset. The counts are summed over
+    /// all the parties, so a multiplication between two parties consumes two
+    /// triples.
+    pub fn counts(&self) -> OpCounts {
+        counter::current() - self.start
+    }
+
+    /// Starts counting the operations again from zero.
+    pub fn reset_counts(&mut self) {
+        self.start = counter::current();
+    }
+
+    /// Corrupts passively the party with the provided ID, which keeps following
+    /// the protocol while the adversary collects its view. A party that already
+    /// deviates from the protocol keeps its behavior. The function panics if
+    /// the party is not in the context.
+    pub fn corrupt(&mut self, id: &str) {
+        let party = self
+            .parties
+            .iter_mut()
+            .find(|party| party.id == id)
+            .unwrap_or_else(|| panic!("Party with that id does not exist."));
+        if !party.behavior.is_corrupted() {
+            party.behavior = Behavior::Passive;
+        }
+        if !self.corrupted.iter().any(|(corrupted, _)| corrupted == id) {
+            self.corrupted.push((id.to_string(), party.prg.clone()));
+        }
+    }
+
+    /// Returns the IDs of the corrupted parties, in the order in which they
+    /// were corrupted.
+    pub fn corrupted(&self) -> Vec<&str> {
+        self.corrupted.iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    /// Collects the views of the corrupted parties up to this point of the
+    /// execution.
+    pub fn adversary_view(&self) -> AdversaryView<T> {
+        AdversaryView {
+            parties: self
+                .corrupted
+                .iter()
+                .map(|(id, tape)| PassiveView::collect(&self.parties, id, tape))
+                .collect(),
+        }
+    }
+
+    /// Returns the parties, to run protocols of [`mpc`] that have no method in
+    /// the context.
+    pub fn parties(&mut self) -> &mut Vec<&'v mut VirtualMachine<T>> {
+        &mut self.parties
+    }
+
+    /// Returns the dealer of the context.
+    pub fn dealer(&mut self) -> &mut Dealer {
+        &mut self.dealer
+    }
+
+    /// Distributes shares of the private value with ID `id_var` held by the
+    /// party `id_owner` (see [`mpc::distribute_shares`]). The private value is
+    /// read outside of the session, and the shares are stored in the session.
+    pub fn input(&mut self, id_var: &str, id_owner: &str) -> SecretHandle<T> {
+        let id_shares = self.qualify(id_var);
+        let in_session = self.session.is_some();
+        if in_session {
+            // The owner keeps a copy of the value under the ID of the session
+            // only while the shares are distributed.
+            if let Some(owner) = self.parties.iter_mut().find(|party| party.id == id_owner) {
+                let value = owner.get_priv_value(id_var).clone();
+                owner.insert_priv_value(&id_shares, value);
+            }
+        }
+
+        self.run(|parties| {
+            let parties = parties.iter_mut().map(|party| &mut **party).collect();
+            mpc::distribute_shares(&id_shares, id_owner, parties);
+        });
+        if in_session {
+            for party in self.parties.iter_mut() {
+                party.remove_priv_value(&id_shares);
+            }
+        }
+        SecretHandle::new(id_var)
+    }
+
+    /// Deals the provided number of multiplication triples to the parties.
+    pub fn preprocess(&mut self, count: usize) -> Vec<TripleHandle> {
+        self.dealer.deal_triples(&mut self.parties, count)
+    }
+
+    /// Computes shares of $a + b$.
+    pub fn add(
+        &mut self,
+        id_a: impl SecretRef<T>,
+        id_b: impl SecretRef<T>,
+        id_result: &str,
+    ) -> SecretHandle<T> {
+        let (id_a, id_b) = (self.qualify(id_a.id()), self.qualify(id_b.id()));
+        let result = self.qualify(id_result);
+        self.run(|parties| mpc::add_protocol(parties, &id_a, &id_b, &result));
+        SecretHandle::new(id_result)
+    }
+
+    /// Computes shares of $a - b$.
+    pub fn sub(
+        &mut self,
+        id_a: impl SecretRef<T>,
+        id_b: impl SecretRef<T>,
+        id_result: &str,
+    ) -> SecretHandle<T> {
+        let (id_a, id_b) = (self.qualify(id_a.id()), self.qualify(id_b.id()));
+        let result = self.qualify(id_result);
+        self.run(|parties| mpc::subtract_protocol(parties, &id_a, &id_b, &result));
+        SecretHandle::new(id_result)
+    }
+
+    /// Computes shares of $x \cdot y$ with a Beaver triple. If the parties have
+    /// no triple left, the dealer deals a new one first.
+    pub fn mult(
+        &mut self,
+        id_x: impl SecretRef<T>,
+        id_y: impl SecretRef<T>,
+        id_result: &str,
+    ) -> SecretHandle<T> {
+        if self.parties[0].preprocessing.remaining_triples() == 0 {
+            self.preprocess(1);
+        }
+        let (id_x, id_y) = (self.qualify(id_x.id()), self.qualify(id_y.id()));
+        let result = self.qualify(id_result);
+        self.run(|parties| mpc::mult_protocol(parties, &id_x, &id_y, &result));
+        SecretHandle::new(id_result)
+    }
+
+    /// Computes shares of $c \cdot x$ for a public constant $c$.
+    pub fn mult_const(
+        &mut self,
+        value: &T,
+        id: impl SecretRef<T>,
+        id_result: &str,
+    ) -> SecretHandle<T> {
+        let id = self.qualify(id.id());
+        let result = self.qualify(id_result);
+        self.run(|parties| mpc::multiply_by_const_protocol(parties, value, &id, &result));
+        SecretHandle::new(id_result)
+    }
+
+    /// Computes shares of $x + c$ for a public constant $c$.
+    pub fn add_const(
+        &mut self,
+        value: &T,
+        id: impl SecretRef<T>,
+        id_result: &str,
+    ) -> SecretHandle<T> {
+        let id = self.qualify(id.id());
+        let result = self.qualify(id_result);
+        self.run(|parties| mpc::add_const_protocol(parties, value, &id, &result));
+        SecretHandle::new(id_result)
+    }
+
+    /// Opens the secret-shared value to all the parties.
+    pub fn open(&self, id: impl SecretRef<T>) -> T {
+        self.mark_session(self.session.as_deref());
+        let value = mpc::reconstruct_share(&self.parties, &self.qualify(id.id()));
+        self.mark_session(None);
+        value
+    }
+
+    /// Opens the secret-shared values with the provided IDs to all the
+    /// parties, in order.
+    pub fn open_all(&self, ids: &[&str]) -> Vec<T> {
+        ids.iter().map(|id| self.open(id)).collect()
+    }
+
+    /// Opens the secret-shared value only to the party `receiver_id`, which
+    /// stores it in its private memory with the ID of the session.
+    pub fn open_to(&mut self, id: &str, receiver_id: &str) {
+        let id = self.qualify(id);
+        self.run(|parties| mpc::reconstruct_to(parties, &id, receiver_id));
+    }
+}
+
+/// Runs a straight-line program over an [`MpcContext`], so the IDs of the
+/// values do not have to be written as strings.
+///
+/// The macro receives the context followed by a list of statements:
+/// - `input x from alice;` distributes the private value `x` of the party
+///   `alice`.
+/// - `c = a + b;`, `c = a - b;` and `c = a * b;` compute an operation on two
+///   secret-shared values. One of the operands of `+`, `-` and `*` can be an
+///   integer literal, which is taken as a public constant.
+/// - `reveal c;` opens the value `c` to all the parties.
+///
+/// Each expression has a single operator, and operations can be nested with
+/// parentheses, as in `d = (a + b) * c;`. The nested results are stored with
+/// fresh temporary IDs (see [`MpcContext::temp_id`]). The macro returns the
+/// revealed values, in the order of the `reveal` statements, which are opened
+/// after all the other statements.
+///
+/// # Example
+///
+/// ```rust
+/// use smol_mpc::context::MpcContext;
+/// use smol_mpc::dealer::Dealer;
+/// use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+/// use smol_mpc::mpc;
+/// use smol_mpc::utils::prg::Prg;
+/// use smol_mpc::vm::VirtualMachine;
+///
+/// type Fp = Mersenne61;
+///
+/// let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+/// let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+/// alice.insert_priv_value("a", Fp::new(3));
+/// bob.insert_priv_value("b", Fp::new(4));
+///
+/// let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], Dealer::new(Prg::new(None)));
+/// let revealed = mpc! { ctx:
+///     input a from alice;
+///     input b from bob;
+///     c = a + b;
+///     d = (c * b) - 1;
+///     reveal c;
+///     reveal d;
+/// };
+/// assert_eq!(revealed[0].value(), 7);
+/// assert_eq!(revealed[1].value(), 27);
+/// ```
+#[macro_export]
+macro_rules! mpc {
+    ($ctx:ident : $($body:tt)*) => {{
+        let mut revealed: ::std::vec::Vec<&str> = ::std::vec::Vec::new();
+        $crate::mpc!(@statements $ctx, revealed; $($body)*);
+        $ctx.open_all(&revealed)
+    }};
+
+    (@statements $ctx:ident, $revealed:ident;) => {};
+    (@statements $ctx:ident, $revealed:ident; input $id:ident from $owner:ident; $($rest:tt)*) => {
+        $ctx.input(stringify!($id), stringify!($owner));
+        $crate::mpc!(@statements $ctx, $revealed; $($rest)*);
+    };
+    (@statements $ctx:ident, $revealed:ident; reveal $id:ident; $($rest:tt)*) => {
+        $revealed.push(stringify!($id));
+        $crate::mpc!(@statements $ctx, $revealed; $($rest)*);
+    };
+    (@statements $ctx:ident, $revealed:ident; $id:ident = $($expr:tt)+) => {
+        $crate::mpc!(@split $ctx, $revealed, $id; []; $($expr)+);
+    };
+
+    // Moves the tokens of the expression up to the semicolon into brackets.
+    (@split $ctx:ident, $revealed:ident, $id:ident; [$($expr:tt)*]; ; $($rest:tt)*) => {
+        $crate::mpc!(@assign $ctx, stringify!($id); $($expr)*);
+        $crate::mpc!(@statements $ctx, $revealed; $($rest)*);
+    };
+    (@split $ctx:ident, $revealed:ident, $id:ident; [$($expr:tt)*]; $next:tt $($rest:tt)*) => {
+        $crate::mpc!(@split $ctx, $revealed, $id; [$($expr)* $next]; $($rest)*);
+    };
+
+    (@assign $ctx:ident, $result:expr; $constant:literal + $b:tt) => {
+        let b = $crate::mpc!(@operand $ctx; $b);
+        $ctx.add_const(&$crate::math::mersenne::MersenneField::new($constant), &b, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $a:tt + $constant:literal) => {
+        let a = $crate::mpc!(@operand $ctx; $a);
+        $ctx.add_const(&$crate::math::mersenne::MersenneField::new($constant), &a, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $a:tt - $constant:literal) => {
+        let a = $crate::mpc!(@operand $ctx; $a);
+        let constant = $crate::math::mersenne::MersenneField::new($constant);
+        $ctx.add_const(&$crate::math::mersenne::MersenneField::negate(&constant), &a, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $constant:literal - $b:tt) => {
+        let b = $crate::mpc!(@operand $ctx; $b);
+        let negated = $ctx.temp_id("mpc");
+        let minus_one = $crate::math::mersenne::MersenneField::negate(
+            &<_ as $crate::math::mersenne::MersenneField>::new(1),
+        );
+        $ctx.mult_const(&minus_one, &b, &negated);
+        $ctx.add_const(&$crate::math::mersenne::MersenneField::new($constant), &negated, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $constant:literal * $b:tt) => {
+        let b = $crate::mpc!(@operand $ctx; $b);
+        $ctx.mult_const(&$crate::math::mersenne::MersenneField::new($constant), &b, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $a:tt * $constant:literal) => {
+        let a = $crate::mpc!(@operand $ctx; $a);
+        $ctx.mult_const(&$crate::math::mersenne::MersenneField::new($constant), &a, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $a:tt + $b:tt) => {
+        let (a, b) = ($crate::mpc!(@operand $ctx; $a), $crate::mpc!(@operand $ctx; $b));
+        $ctx.add(&a, &b, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $a:tt - $b:tt) => {
+        let (a, b) = ($crate::mpc!(@operand $ctx; $a), $crate::mpc!(@operand $ctx; $b));
+        $ctx.sub(&a, &b, $result);
+    };
+    (@assign $ctx:ident, $result:expr; $a:tt * $b:tt) => {
+        let (a, b) = ($crate::mpc!(@operand $ctx; $a), $crate::mpc!(@operand $ctx; $b));
+        $ctx.mult(&a, &b, $result);
+    };
+
+    (@operand $ctx:ident; $id:ident) => {
+        ::std::string::String::from(stringify!($id))
+    };
+    (@operand $ctx:ident; ($($expr:tt)+)) => {{
+        let temp = $ctx.temp_id("mpc");
+        $crate::mpc!(@assign $ctx, &temp; $($expr)+);
+        temp
+    }};
+}
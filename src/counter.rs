@@ -0,0 +1,114 @@
+//! Implements counters of the operations performed during an execution.
+//!
+//! Every field addition, multiplication and inversion, every byte produced by a
+//! [`Prg`](crate::utils::prg::Prg), every opened value and every consumed
+//! multiplication triple is counted. This allows to compare the cost of
+//! protocols, for example the number of triples consumed by a circuit, without
+//! an external profiler.
+//!
+//! The counters are kept per thread, and since the parties are simulated in the
+//! same thread, they are summed over all the parties: if two parties consume one
+//! triple each for a multiplication, two triples are counted. The
+//! operations of a [`Dealer`](crate::dealer::Dealer) running in the same thread
+//! are counted as well. Subtractions are counted as additions, and the
+//! multiplications performed by [`MersenneField::pow`] are counted one by one.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::counter;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//!
+//! type Fp = Mersenne61;
+//!
+//! let (_, counts) = counter::measure(|| Fp::new(3).multiply(&Fp::new(4)).add(&Fp::new(1)));
+//! assert_eq!(counts.mults, 1);
+//! assert_eq!(counts.adds, 1);
+//! ```
+//!
+//! [`MersenneField::pow`]: crate::math::mersenne::MersenneField::pow
+
+use std::cell::Cell;
+use std::fmt;
+use std::ops::Sub;
+
+/// Defines the number of operations of each kind performed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct OpCounts {
+    /// Number of field multiplications.
+    pub mults: u64,
+
+    /// Number of field additions and subtractions.
+    pub adds: u64,
+
+    /// Number of field inversions.
+    pub inversions: u64,
+
+    /// Number of pseudo-random bytes generated.
+    pub prg_bytes: u64,
+
+    /// Number of values opened, counted once for each party that learns them.
+    pub openings: u64,
+
+    /// Number of multiplication triples consumed from the preprocessing.
+    pub triples: u64,
+}
+
+/// Subtracts the counts of each kind, saturating at zero, so that the
+/// difference between two readings of the counters is never negative even if
+/// they were reset in between.
+impl Sub for OpCounts {
+    type Output = OpCounts;
+
+    fn sub(self, other: OpCounts) -> OpCounts {
+        OpCounts {
+            mults: self.mults.saturating_sub(other.mults),
+            adds: self.adds.saturating_sub(other.adds),
+            inversions: self.inversions.saturating_sub(other.inversions),
+            prg_bytes: self.prg_bytes.saturating_sub(other.prg_bytes),
+            openings: self.openings.saturating_sub(other.openings),
+            triples: self.triples.saturating_sub(other.triples),
+        }
+    }
+}
+
+impl fmt::Display for OpCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} mults, {} adds, {} inversions, {} PRG bytes, {} openings, {} triples",
+            self.mults, self.adds, self.inversions, self.prg_bytes, self.openings, self.triples
+        )
+    }
+}
+
+thread_local! {
+    static COUNTS: Cell<OpCounts> = Cell::new(OpCounts::default());
+}
+
+/// Returns the operations counted in the current thread.
+pub fn current() -> OpCounts {
+    COUNTS.with(|counts| counts.get())
+}
+
+/// Sets all the counters of the current thread to zero.
+pub fn reset() {
+    COUNTS.with(|counts| counts.set(OpCounts::default()));
+}
+
+/// Runs the provided function and returns its output together with the
+/// operations it performed.
+pub fn measure<R, F: FnOnce() -> R>(function: F) -> (R, OpCounts) {
+    let start = current();
+    let output = function();
+    (output, current() - start)
+}
+
+/// Updates the counters of the current thread.
+pub(crate) fn record<F: FnOnce(&mut OpCounts)>(update: F) {
+    COUNTS.with(|counts| {
+        let mut value = counts.get();
+        update(&mut value);
+        counts.set(value);
+    });
+}
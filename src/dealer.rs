@@ -0,0 +1,173 @@
+//! Implements a trusted dealer for the generation of correlated randomness.
+//!
+//! Most of the protocols in this library split the computation in two phases.
+//! In the preprocessing phase, the parties obtain shares of random values with
+//! a certain structure, such as Beaver triples or random bits, which do not
+//! depend on the inputs. In the online phase, such values are consumed to
+//! evaluate the function on the actual inputs. In this library, the
+//! preprocessing phase is simulated by a trusted dealer: an entity that is not a
+//! computing party, that samples the correlated randomness in the clear and
+//! sends shares of it to each party. The dealer must be trusted to not reveal
+//! the values it generates and to not collude with any party.
+
+use crate::math::matrix::Matrix;
+use crate::math::mersenne::MersenneField;
+use crate::mpc;
+use crate::preprocessing::{
+    ComparisonKeyHandle, DaBitHandle, EdaBitHandle, SquareHandle, TripleHandle,
+};
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+/// Defines a trusted dealer.
+///
+/// The dealer owns the pseudo-random generator used to sample all the values
+/// it deals, so the parties never have access to it.
+pub struct Dealer {
+    prg: Prg,
+}
+
+impl Dealer {
+    /// Creates a new dealer that samples its values with the provided
+    /// pseudo-random generator.
+    pub fn new(prg: Prg) -> Self {
+        Self { prg }
+    }
+
+    /// Sends shares of the provided value to the parties, which store them in
+    /// the share memory under the provided ID.
+    pub fn deal_value<T>(&mut self, parties: &mut [&mut VirtualMachine<T>], id: &str, value: &T)
+    where
+        T: MersenneField,
+    {
+        mpc::simulate_random_dist(id, parties, value, &mut self.prg);
+    }
+
+    /// Sends shares of a uniformly random value to the parties, which store
+    /// them in the share memory under the provided ID.
+    pub fn deal_random<T>(&mut self, parties: &mut [&mut VirtualMachine<T>], id: &str)
+    where
+        T: MersenneField,
+    {
+        let value = T::random(&mut self.prg);
+        mpc::simulate_random_dist(id, parties, &value, &mut self.prg);
+    }
+
+    /// Sends shares of a multiplication triple to the parties, which store
+    /// them in the share memory under the provided IDs.
+    pub fn deal_triple<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        id_triple: (&str, &str, &str),
+    ) where
+        T: MersenneField,
+    {
+        mpc::generate_triple(parties, id_triple, &mut self.prg);
+    }
+
+    /// Sends shares of several multiplication triples to the parties, which
+    /// register them in their preprocessing store. The handles of the triples
+    /// are returned in the order in which they will be consumed.
+    pub fn deal_triples<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        count: usize,
+    ) -> Vec<TripleHandle>
+    where
+        T: MersenneField,
+    {
+        mpc::generate_triples(parties, count, &mut self.prg)
+    }
+
+    /// Sends shares of several square pairs to the parties, which register
+    /// them in their preprocessing store. The handles of the pairs are returned
+    /// in the order in which they will be consumed.
+    pub fn deal_square_pairs<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        count: usize,
+    ) -> Vec<SquareHandle>
+    where
+        T: MersenneField,
+    {
+        mpc::generate_square_pairs(parties, count, &mut self.prg)
+    }
+
+    /// Sends shares of several daBits to the parties, which register them in
+    /// their preprocessing store. The handles of the daBits are returned in the
+    /// order in which they will be consumed.
+    pub fn deal_dabits<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        count: usize,
+    ) -> Vec<DaBitHandle>
+    where
+        T: MersenneField,
+    {
+        mpc::generate_dabits(parties, count, &mut self.prg)
+    }
+
+    /// Sends shares of several edaBits of `n_bits` bits to the parties, which
+    /// register them in their preprocessing store. The handles of the edaBits
+    /// are returned in the order in which they will be consumed.
+    pub fn deal_edabits<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        count: usize,
+        n_bits: usize,
+    ) -> Vec<EdaBitHandle>
+    where
+        T: MersenneField,
+    {
+        mpc::generate_edabits(parties, count, n_bits, &mut self.prg)
+    }
+
+    /// Sends several keys for the comparison based on function secret sharing
+    /// to two parties, which register them in their preprocessing store. The
+    /// handles of the keys are returned in the order in which they will be
+    /// consumed.
+    pub fn deal_comparison_keys<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        count: usize,
+    ) -> Vec<ComparisonKeyHandle>
+    where
+        T: MersenneField,
+    {
+        mpc::generate_comparison_keys(parties, count, &mut self.prg)
+    }
+
+    /// Sends shares of a matrix multiplication triple to the parties. See
+    /// [`mpc::generate_matrix_triple`] for the layout of the IDs.
+    pub fn deal_matrix_triple<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        ids_triple: (&Matrix<&str>, &Matrix<&str>, &Matrix<&str>),
+    ) where
+        T: MersenneField,
+    {
+        mpc::generate_matrix_triple(parties, ids_triple, &mut self.prg);
+    }
+
+    /// Sends shares of a uniformly random bit to the parties, which store them
+    /// in the share memory under the provided ID.
+    pub fn deal_random_bit<T>(&mut self, parties: &mut [&mut VirtualMachine<T>], id: &str)
+    where
+        T: MersenneField,
+    {
+        mpc::generate_random_bit(parties, id, &mut self.prg);
+    }
+
+    /// Sends shares of a random pair for the truncation protocol to the
+    /// parties. See [`mpc::generate_trunc_pair`] for the details.
+    pub fn deal_trunc_pair<T>(
+        &mut self,
+        parties: &mut [&mut VirtualMachine<T>],
+        frac_bits: u32,
+        ids_pair: (&str, &str),
+    ) where
+        T: MersenneField,
+    {
+        mpc::generate_trunc_pair(parties, frac_bits, ids_pair, &mut self.prg);
+    }
+}
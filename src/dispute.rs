@@ -0,0 +1,202 @@
+//! Implements player elimination based on disputes.
+//!
+//! With an honest majority, that is, $n \geq 2t + 1$, an actively secure
+//! protocol does not need to abort when a check fails. In the player
+//! elimination technique of Hirt, Maurer and Przydatek, a failed check is
+//! traced back to a [`Dispute`]: a pair of parties that disagree on what one of
+//! them sent to the other. The parties cannot tell which one is lying, but at
+//! least one of them is corrupted, so both are eliminated. The remaining $n - 2$
+//! parties tolerate $t - 1$ corruptions, which keeps the honest majority, and
+//! the computation restarts with them. Since each elimination removes a
+//! corrupted party, the protocol succeeds after at most $t$ restarts.
+//!
+//! A protocol takes part in the elimination by returning its failed checks as
+//! a [`Dispute`], as done by
+//! [`try_broadcast_protocol`](crate::broadcast::try_broadcast_protocol), and
+//! [`run_with_elimination`] restarts it until it succeeds.
+//!
+//! # Example
+//!
+//! Here, Bob sends inconsistent values when broadcasting his input, so Charlie
+//! disputes with him. Both are eliminated and the broadcast is repeated among
+//! Alice, Dave and Eve.
+//!
+//! ```rust
+//! use smol_mpc::adversary::Behavior;
+//! use smol_mpc::broadcast;
+//! use smol_mpc::dispute;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::party::PartySet;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob = VirtualMachine::new_cheating("bob", Behavior::Inconsistent(Fp::new(1)));
+//! let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+//! let mut dave: VirtualMachine<Fp> = VirtualMachine::new("dave");
+//! let mut eve: VirtualMachine<Fp> = VirtualMachine::new("eve");
+//! for party in [&mut alice, &mut bob, &mut charlie, &mut dave, &mut eve] {
+//!     party.insert_priv_value(&format!("{}_input", party.id), Fp::new(1));
+//! }
+//!
+//! let parties = vec![&mut alice, &mut bob, &mut charlie, &mut dave, &mut eve];
+//! let mut parties = PartySet::new(parties).with_threshold(2);
+//! let disputes = dispute::run_with_elimination(&mut parties, |parties| {
+//!     for id in parties.ids() {
+//!         broadcast::try_broadcast_protocol(parties.parties_mut(), &id, &format!("{}_input", id))?;
+//!     }
+//!     Ok(())
+//! })
+//! .unwrap()
+//! .1;
+//!
+//! assert_eq!(disputes[0].accused, "bob");
+//! assert_eq!(parties.ids(), vec!["alice", "dave", "eve"]);
+//! assert_eq!(parties.threshold(), Some(1));
+//! ```
+
+use crate::math::mersenne::MersenneField;
+use crate::party::PartySet;
+use crate::vm::InsertPolicy;
+use std::fmt;
+
+/// Defines a dispute between two parties, at least one of which is corrupted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dispute {
+    /// ID of the party that detected the deviation.
+    pub accuser: String,
+
+    /// ID of the party accused of deviating from the protocol.
+    pub accused: String,
+
+    /// Description of the failed check.
+    pub reason: String,
+}
+
+impl Dispute {
+    /// Creates a dispute between the provided parties.
+    pub fn new(accuser: &str, accused: &str, reason: &str) -> Self {
+        Self {
+            accuser: accuser.to_string(),
+            accused: accused.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Dispute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} accuses {}: {}",
+            self.accuser, self.accused, self.reason
+        )
+    }
+}
+
+/// Reports that a dispute cannot be resolved by eliminating its parties,
+/// because the remaining parties could not tolerate a corrupted party less.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TooFewParties {
+    /// Dispute that could not be resolved.
+    pub dispute: Dispute,
+
+    /// Number of parties that would remain after the elimination.
+    pub remaining: usize,
+}
+
+impl fmt::Display for TooFewParties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Eliminating {} and {} leaves only {} parties.",
+            self.dispute.accuser, self.dispute.accused, self.remaining
+        )
+    }
+}
+
+impl std::error::Error for TooFewParties {}
+
+/// Removes both parties of the dispute from the set, and lowers its threshold
+/// by one, since at least one of them is corrupted. The function panics if the
+/// set has a threshold of zero, because then no party can be corrupted and the
+/// dispute cannot happen.
+///
+/// The function returns an error and leaves the set unchanged if no party
+/// would remain, or if the remaining $n - 2$ parties would not be more than
+/// the new threshold $t - 1$, as with $n = 2$ and $t = 1$.
+pub fn eliminate<T: MersenneField>(
+    parties: &mut PartySet<'_, T>,
+    dispute: &Dispute,
+) -> Result<(), TooFewParties> {
+    let threshold = parties.threshold();
+    if threshold == Some(0) {
+        panic!(
+            "There is a dispute between {} and {} with no corrupted parties.",
+            dispute.accuser, dispute.accused
+        );
+    }
+
+    let remaining = parties.len().saturating_sub(2);
+    if remaining == 0 || threshold.is_some_and(|threshold| threshold > remaining) {
+        return Err(TooFewParties {
+            dispute: dispute.clone(),
+            remaining,
+        });
+    }
+
+    parties.remove(&dispute.accuser);
+    parties.remove(&dispute.accused);
+    if let Some(threshold) = threshold {
+        parties.set_threshold(threshold - 1);
+    }
+    Ok(())
+}
+
+/// Runs a protocol until it finishes without disputes.
+///
+/// After each failed execution, both parties of the dispute are eliminated
+/// (see [`eliminate`]) and the protocol is restarted from scratch with the
+/// remaining parties, so it must not rely on values computed by the previous
+/// executions. The restarts store their values again under the same IDs, so
+/// the parties use the [`InsertPolicy::Overwrite`] policy during them and
+/// recover their own policies at the end. The function returns the output of
+/// the successful execution together with the disputes, in the order in which
+/// they were found, or an error if a dispute cannot be resolved because too
+/// few parties remain.
+pub fn run_with_elimination<'v, T, R, F>(
+    parties: &mut PartySet<'v, T>,
+    mut protocol: F,
+) -> Result<(R, Vec<Dispute>), TooFewParties>
+where
+    T: MersenneField,
+    F: FnMut(&mut PartySet<'v, T>) -> Result<R, Dispute>,
+{
+    let policies: Vec<(String, InsertPolicy)> = parties
+        .iter()
+        .map(|party| (party.id.clone(), party.insert_policy))
+        .collect();
+    let restore = |parties: &mut PartySet<'v, T>| {
+        for party in parties.parties_mut() {
+            party.insert_policy = policies.iter().find(|(id, _)| *id == party.id).unwrap().1;
+        }
+    };
+
+    let mut disputes = Vec::new();
+    let output = loop {
+        match protocol(parties) {
+            Ok(output) => break output,
+            Err(dispute) => {
+                restore(parties);
+                eliminate(parties, &dispute)?;
+                disputes.push(dispute);
+                for party in parties.parties_mut() {
+                    party.insert_policy = InsertPolicy::Overwrite;
+                }
+            }
+        }
+    };
+    restore(parties);
+    Ok((output, disputes))
+}
@@ -0,0 +1,92 @@
+//! Implements the events emitted during the execution of the protocols.
+//!
+//! A virtual machine reports what happens to it through events: a share is
+//! inserted in its memory, it sends a message, it opens some values, or it
+//! starts and finishes a protocol. Hooks registered with
+//! [`VirtualMachine::add_hook`](crate::vm::VirtualMachine::add_hook) receive the
+//! ID of the party and each of its events, so an execution can be observed, for
+//! example by a visualizer, without modifying the code of the protocols. With
+//! the `tracing` feature, the events are also emitted as
+//! [`tracing`](https://docs.rs/tracing) events at the debug level.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::event::Event;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::mpc;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//! use std::sync::{Arc, Mutex};
+//!
+//! type Fp = Mersenne61;
+//!
+//! let log = Arc::new(Mutex::new(Vec::new()));
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! let alice_log = log.clone();
+//! alice.add_hook(move |party, event: &Event<Fp>| {
+//!     alice_log.lock().unwrap().push(format!("{}: {}", party, event))
+//! });
+//!
+//! let mut parties = vec![&mut alice, &mut bob];
+//! mpc::simulate_random_dist("x", &mut parties, &Fp::new(3), &mut Prg::new(None));
+//! mpc::reconstruct_share(&parties, "x");
+//! assert_eq!(log.lock().unwrap()[0], "alice: share x inserted");
+//! ```
+
+use crate::math::mersenne::MersenneField;
+use crate::network::{Message, Payload};
+use std::fmt;
+
+/// Defines the events emitted by a virtual machine.
+#[derive(Clone, Debug)]
+pub enum Event<T> {
+    /// A share was inserted in the share memory under the ID.
+    ShareInserted { id: String },
+
+    /// A message was sent by the party.
+    MessageSent(Message<T>),
+
+    /// Some values were opened by the party in a step with the tag.
+    Opened { tag: String, payload: Payload<T> },
+
+    /// The party started the protocol or sub-protocol with the name.
+    ProtocolStarted { name: String },
+
+    /// The party finished the protocol or sub-protocol with the name.
+    ProtocolFinished { name: String },
+}
+
+/// Defines a function that observes the events of a virtual machine, given the
+/// ID of the party.
+pub type Hook<T> = Box<dyn Fn(&str, &Event<T>) + Send>;
+
+impl<T: MersenneField> fmt::Display for Event<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::ShareInserted { id } => write!(f, "share {} inserted", id),
+            Event::MessageSent(message) => write!(
+                f,
+                "sent {} bytes to {} [{}]",
+                message.payload.n_bytes(),
+                message.to,
+                message.tag
+            ),
+            Event::Opened { tag, payload } => {
+                let values: Vec<String> = match payload {
+                    Payload::Elements(elements) => elements
+                        .iter()
+                        .map(|value| value.value().to_string())
+                        .collect(),
+                    Payload::Bits(bits) => {
+                        bits.iter().map(|bit| (*bit as u8).to_string()).collect()
+                    }
+                };
+                write!(f, "opened [{}] [{}]", values.join(", "), tag)
+            }
+            Event::ProtocolStarted { name } => write!(f, "started {}", name),
+            Event::ProtocolFinished { name } => write!(f, "finished {}", name),
+        }
+    }
+}
@@ -0,0 +1,443 @@
+//! Implements function secret sharing (FSS).
+//!
+//! A function secret-sharing scheme splits a function $f$ into two keys $k_0$
+//! and $k_1$, such that each key hides $f$ and, for every input $x$,
+//!    $$\textsf{Eval}(k_0, x) + \textsf{Eval}(k_1, x) = f(x).$$
+//! In contrast with the secret sharing of values, each party can evaluate its
+//! share of the function at any input locally, without interaction.
+//!
+//! A distributed point function (DPF) shares the point function $f_{\alpha,
+//! \beta}$, which is $\beta$ at $x = \alpha$ and zero elsewhere. The module
+//! implements the construction of Boyle, Gilboa and Ishai over a
+//! [GGM tree](crate::utils::ggm) of depth $d$, whose keys have $O(d)$ seeds.
+//! Both parties walk down the tree from their own root seed along the path of
+//! $x$, carrying a control bit. Off the path of $\alpha$, the nodes of both
+//! parties are equal and their outputs cancel out; on the path, the control
+//! bits differ, and the party whose bit is set applies the correction words of
+//! the keys, which keep the nodes apart down to the leaf $\alpha$, where a last
+//! correction turns them into shares of $\beta$.
+//!
+//! A distributed comparison function (DCF) shares the comparison function
+//! $f^<_{\alpha, \beta}$, which is $\beta$ at every $x < \alpha$ and zero
+//! elsewhere. The module implements the construction of Boyle, Chandran,
+//! Gilboa, Gupta, Ishai, Kumar and Rathee, which extends the DPF with a value
+//! at each node: when the path of $x$ leaves the path of $\alpha$ to the left,
+//! the shares of the values picked up on the way add up to $\beta$, and
+//! otherwise they cancel out. A DCF is the core of the comparison between
+//! secret-shared values of
+//! [`less_than_fss_protocol`](crate::mpc::less_than_fss_protocol).
+//!
+//! As an application, [`pir_protocol`] retrieves a record from a database
+//! replicated by two servers, without revealing to any of them which record
+//! was retrieved.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::fss;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::utils::prg::Prg;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let (key_0, key_1) = fss::gen_dpf(5, &Fp::new(42), 4, &mut prg);
+//!
+//! assert_eq!(key_0.eval(5).add(&key_1.eval(5)).value(), 42);
+//! assert_eq!(key_0.eval(6).add(&key_1.eval(6)).value(), 0);
+//!
+//! let (key_0, key_1) = fss::gen_dcf(5, &Fp::new(42), 4, &mut prg);
+//! assert_eq!(key_0.eval(4).add(&key_1.eval(4)).value(), 42);
+//! assert_eq!(key_0.eval(5).add(&key_1.eval(5)).value(), 0);
+//! ```
+
+use crate::math::mersenne::MersenneField;
+use crate::utils::ggm::{self, Seed, MAX_DEPTH, SEED_LEN};
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+/// Expands a seed into the seeds and the control bits of its two children. The
+/// control bit of a child is the least significant bit of its seed, which is
+/// then cleared.
+fn expand(seed: &Seed) -> ([Seed; 2], [bool; 2]) {
+    let mut children = ggm::expand(seed);
+    let mut bits = [false; 2];
+    for (child, bit) in children.iter_mut().zip(bits.iter_mut()) {
+        *bit = child[0] & 1 == 1;
+        child[0] &= !1;
+    }
+    (children, bits)
+}
+
+/// Computes the bitwise XOR of two seeds.
+fn xor(a: &Seed, b: &Seed) -> Seed {
+    let mut result = [0; SEED_LEN];
+    for (byte, (a, b)) in result.iter_mut().zip(a.iter().zip(b)) {
+        *byte = a ^ b;
+    }
+    result
+}
+
+/// Maps a seed to a pseudo-random field element.
+fn convert<T: MersenneField>(seed: &Seed) -> T {
+    T::random(&mut Prg::new(Some(seed.to_vec())))
+}
+
+/// Expands a seed into the pseudo-random values of its two children, which are
+/// used by the DCF. They are drawn from a stream independent of the one that
+/// produces the seeds of the children.
+fn expand_values<T: MersenneField>(seed: &Seed) -> [T; 2] {
+    let mut prg = Prg::with_nonce(Some(seed.to_vec()), 1);
+    [T::random(&mut prg), T::random(&mut prg)]
+}
+
+/// Negates a value if the control bit of the second party is set, which is
+/// the sign $(-1)^{t_1}$ of the corrections.
+fn signed<T: MersenneField>(value: T, control: bool) -> T {
+    if control {
+        value.negate()
+    } else {
+        value
+    }
+}
+
+/// Returns the bit of the input that selects the child at the provided level,
+/// where the level 0 is the root.
+fn bit(x: u64, depth: usize, level: usize) -> usize {
+    ((x >> (depth - level - 1)) & 1) as usize
+}
+
+/// Checks the depth of a domain and that an input belongs to it.
+fn check_input(x: u64, depth: usize) {
+    if depth > MAX_DEPTH {
+        panic!("The depth of the domain can be at most {}.", MAX_DEPTH);
+    }
+    if x >> depth != 0 {
+        panic!("The input {} is outside the domain of the function.", x);
+    }
+}
+
+/// Defines the correction word of a level of the tree, which is applied to
+/// the children of a node whose control bit is set.
+#[derive(Clone)]
+struct CorrectionWord {
+    seed: Seed,
+    bits: [bool; 2],
+}
+
+impl CorrectionWord {
+    /// Expands a node and applies the correction word if its control bit is
+    /// set.
+    fn expand(&self, seed: &Seed, control: bool) -> ([Seed; 2], [bool; 2]) {
+        let (mut children, mut bits) = expand(seed);
+        if control {
+            for side in 0..2 {
+                children[side] = xor(&children[side], &self.seed);
+                bits[side] ^= self.bits[side];
+            }
+        }
+        (children, bits)
+    }
+}
+
+/// Defines the key of a party in a distributed point function.
+#[derive(Clone)]
+pub struct DpfKey<T: MersenneField> {
+    party: usize,
+    seed: Seed,
+    corrections: Vec<CorrectionWord>,
+    output_correction: T,
+}
+
+/// Generates the keys of a distributed point function that is $\beta$ at
+/// $\alpha$ and zero elsewhere, over the domain of the inputs of `depth` bits.
+/// The function panics if `alpha` is outside the domain or if the depth is
+/// larger than [`MAX_DEPTH`].
+pub fn gen_dpf<T: MersenneField>(
+    alpha: u64,
+    beta: &T,
+    depth: usize,
+    prg: &mut Prg,
+) -> (DpfKey<T>, DpfKey<T>) {
+    check_input(alpha, depth);
+
+    let mut roots = [[0; SEED_LEN]; 2];
+    for root in roots.iter_mut() {
+        prg.fill_bytes(root);
+    }
+
+    let mut seeds = roots;
+    let mut controls = [false, true];
+    let mut corrections = Vec::with_capacity(depth);
+    for level in 0..depth {
+        let keep = bit(alpha, depth, level);
+        let lose = 1 - keep;
+        let (children_0, bits_0) = expand(&seeds[0]);
+        let (children_1, bits_1) = expand(&seeds[1]);
+
+        // The correction makes the nodes off the path equal, and keeps the
+        // control bits on the path different.
+        let correction = CorrectionWord {
+            seed: xor(&children_0[lose], &children_1[lose]),
+            bits: [
+                bits_0[0] ^ bits_1[0] ^ (keep == 0),
+                bits_0[1] ^ bits_1[1] ^ (keep == 1),
+            ],
+        };
+        for party in 0..2 {
+            let (children, bits) = correction.expand(&seeds[party], controls[party]);
+            seeds[party] = children[keep];
+            controls[party] = bits[keep];
+        }
+        corrections.push(correction);
+    }
+
+    let mut output_correction = beta.subtract(&convert(&seeds[0])).add(&convert(&seeds[1]));
+    if controls[1] {
+        output_correction = output_correction.negate();
+    }
+
+    let key = |party: usize| DpfKey {
+        party,
+        seed: roots[party],
+        corrections: corrections.clone(),
+        output_correction: output_correction.clone(),
+    };
+    (key(0), key(1))
+}
+
+impl<T: MersenneField> DpfKey<T> {
+    /// Returns the index of the party that holds the key, which is 0 or 1.
+    pub fn party(&self) -> usize {
+        self.party
+    }
+
+    /// Returns the number of bits of the inputs of the function.
+    pub fn depth(&self) -> usize {
+        self.corrections.len()
+    }
+
+    /// Computes the output of the party for a leaf.
+    fn output(&self, seed: &Seed, control: bool) -> T {
+        let mut value: T = convert(seed);
+        if control {
+            value = value.add(&self.output_correction);
+        }
+        if self.party == 1 {
+            value.negate()
+        } else {
+            value
+        }
+    }
+
+    /// Evaluates the share of the function at the input `x`.
+    pub fn eval(&self, x: u64) -> T {
+        check_input(x, self.depth());
+
+        let mut seed = self.seed;
+        let mut control = self.party == 1;
+        for (level, correction) in self.corrections.iter().enumerate() {
+            let (children, bits) = correction.expand(&seed, control);
+            let side = bit(x, self.depth(), level);
+            seed = children[side];
+            control = bits[side];
+        }
+        self.output(&seed, control)
+    }
+
+    /// Evaluates the share of the function at every input of the domain,
+    /// expanding the tree level by level.
+    pub fn eval_all(&self) -> Vec<T> {
+        let mut nodes = vec![(self.seed, self.party == 1)];
+        for correction in &self.corrections {
+            nodes = nodes
+                .iter()
+                .flat_map(|(seed, control)| {
+                    let (children, bits) = correction.expand(seed, *control);
+                    [(children[0], bits[0]), (children[1], bits[1])]
+                })
+                .collect();
+        }
+        nodes
+            .iter()
+            .map(|(seed, control)| self.output(seed, *control))
+            .collect()
+    }
+}
+
+/// Defines the key of a party in a distributed comparison function.
+#[derive(Clone)]
+pub struct DcfKey<T: MersenneField> {
+    party: usize,
+    seed: Seed,
+    corrections: Vec<CorrectionWord>,
+    value_corrections: Vec<T>,
+    output_correction: T,
+}
+
+/// Generates the keys of a distributed comparison function that is $\beta$ at
+/// every input smaller than $\alpha$ and zero elsewhere, over the domain of the
+/// inputs of `depth` bits. The function panics if `alpha` is outside the
+/// domain or if the depth is larger than [`MAX_DEPTH`].
+pub fn gen_dcf<T: MersenneField>(
+    alpha: u64,
+    beta: &T,
+    depth: usize,
+    prg: &mut Prg,
+) -> (DcfKey<T>, DcfKey<T>) {
+    check_input(alpha, depth);
+
+    let mut roots = [[0; SEED_LEN]; 2];
+    for root in roots.iter_mut() {
+        prg.fill_bytes(root);
+    }
+
+    let mut seeds = roots;
+    let mut controls = [false, true];
+    let mut corrections = Vec::with_capacity(depth);
+    let mut value_corrections = Vec::with_capacity(depth);
+    // Difference between the values picked up by both parties on the path.
+    let mut path_value = T::new(0);
+    for level in 0..depth {
+        let keep = bit(alpha, depth, level);
+        let lose = 1 - keep;
+        let (children_0, bits_0) = expand(&seeds[0]);
+        let (children_1, bits_1) = expand(&seeds[1]);
+        let values_0: [T; 2] = expand_values(&seeds[0]);
+        let values_1: [T; 2] = expand_values(&seeds[1]);
+
+        // Leaving the path to the left means that the input is smaller than
+        // alpha, so the values must add up to beta there.
+        let mut value_correction = signed(
+            values_1[lose]
+                .subtract(&values_0[lose])
+                .subtract(&path_value),
+            controls[1],
+        );
+        if lose == 0 {
+            value_correction = value_correction.add(&signed(beta.clone(), controls[1]));
+        }
+        path_value = path_value
+            .subtract(&values_1[keep])
+            .add(&values_0[keep])
+            .add(&signed(value_correction.clone(), controls[1]));
+
+        let correction = CorrectionWord {
+            seed: xor(&children_0[lose], &children_1[lose]),
+            bits: [
+                bits_0[0] ^ bits_1[0] ^ (keep == 0),
+                bits_0[1] ^ bits_1[1] ^ (keep == 1),
+            ],
+        };
+        for party in 0..2 {
+            let (children, bits) = correction.expand(&seeds[party], controls[party]);
+            seeds[party] = children[keep];
+            controls[party] = bits[keep];
+        }
+        corrections.push(correction);
+        value_corrections.push(value_correction);
+    }
+
+    let leaf_value = convert::<T>(&seeds[1])
+        .subtract(&convert(&seeds[0]))
+        .subtract(&path_value);
+    let output_correction = signed(leaf_value, controls[1]);
+
+    let key = |party: usize| DcfKey {
+        party,
+        seed: roots[party],
+        corrections: corrections.clone(),
+        value_corrections: value_corrections.clone(),
+        output_correction: output_correction.clone(),
+    };
+    (key(0), key(1))
+}
+
+impl<T: MersenneField> DcfKey<T> {
+    /// Returns the index of the party that holds the key, which is 0 or 1.
+    pub fn party(&self) -> usize {
+        self.party
+    }
+
+    /// Returns the number of bits of the inputs of the function.
+    pub fn depth(&self) -> usize {
+        self.corrections.len()
+    }
+
+    /// Evaluates the share of the function at the input `x`.
+    pub fn eval(&self, x: u64) -> T {
+        check_input(x, self.depth());
+
+        let mut value = T::new(0);
+        let mut seed = self.seed;
+        let mut control = self.party == 1;
+        for (level, correction) in self.corrections.iter().enumerate() {
+            let (children, bits) = correction.expand(&seed, control);
+            let values: [T; 2] = expand_values(&seed);
+            let side = bit(x, self.depth(), level);
+
+            value = value.add(&values[side]);
+            if control {
+                value = value.add(&self.value_corrections[level]);
+            }
+            seed = children[side];
+            control = bits[side];
+        }
+
+        value = value.add(&convert(&seed));
+        if control {
+            value = value.add(&self.output_correction);
+        }
+        if self.party == 1 {
+            value.negate()
+        } else {
+            value
+        }
+    }
+}
+
+/// Retrieves a record from a database replicated by two servers using
+/// private information retrieval (PIR) based on a DPF.
+///
+/// Both servers hold the records of the database as private values with IDs
+/// `id_records`. The client, which uses `prg`, generates the keys of the point
+/// function that is one at `index` and sends one key to each server. Each
+/// server answers with the inner product between the records and the
+/// evaluation of its key at every index, and the client adds the answers to
+/// obtain the record. Each key hides the index, so a server that does not
+/// collude with the other learns nothing about it. The function panics if
+/// there are not exactly two servers or if the index is not the index of a
+/// record.
+pub fn pir_protocol<T>(
+    servers: &[&mut VirtualMachine<T>],
+    id_records: &[&str],
+    index: u64,
+    prg: &mut Prg,
+) -> T
+where
+    T: MersenneField,
+{
+    if servers.len() != 2 {
+        panic!("PIR based on a DPF needs exactly two servers.");
+    }
+    if index >= id_records.len() as u64 {
+        panic!("The index {} is not the index of a record.", index);
+    }
+
+    let depth = id_records.len().next_power_of_two().trailing_zeros() as usize;
+    let (key_0, key_1) = gen_dpf(index, &T::new(1), depth, prg);
+
+    let answers: Vec<T> = servers
+        .iter()
+        .zip([key_0, key_1])
+        .map(|(server, key)| {
+            key.eval_all()
+                .iter()
+                .zip(id_records)
+                .map(|(selector, id)| selector.multiply(server.get_priv_value(id)))
+                .fold(T::new(0), |answer, term| answer.add(&term))
+        })
+        .collect();
+
+    answers[0].add(&answers[1])
+}
@@ -0,0 +1,528 @@
+//! Implements Yao's garbled circuits for two parties.
+//!
+//! In Yao's protocol, one party, the garbler, encrypts a boolean circuit and
+//! the other party, the evaluator, computes it without learning anything but
+//! the output. The garbler samples two random labels $k_w^0$ and $k_w^1$ for
+//! every wire $w$ of the circuit, which represent the values zero and one. For
+//! each gate $g$ with input wires $a$ and $b$ and output wire $c$, the garbler
+//! builds a table with the four ciphertexts
+//!    $$\textsf{Enc}_{k_a^u, k_b^v}(k_c^{g(u, v)} \Vert 0^{128})$$
+//! for $u, v \in \{0, 1\}$, in random order. The encryption XORs the message
+//! with the output of a [`Prg`] seeded with both keys, so the evaluator, who
+//! holds one label of each input wire, can decrypt exactly one row of the
+//! table; the correct row is recognized by the trailing zeros. The evaluator
+//! receives the labels of the garbler inputs directly, and the labels of its
+//! own inputs through oblivious transfer (see
+//! [`iknp_extension`](crate::utils::ot::iknp_extension)), so the garbler does
+//! not learn them. At the end, the garbler reveals which label of each output
+//! wire means one. The textbook construction can be optimized with point-and-permute,
+//! free-XOR and half-gates, which reduce the work of the evaluator and the
+//! number of ciphertexts (see [`GarblingScheme`]).
+//!
+//! The circuit is given as a list of gates over numbered wires, where the
+//! first wires are the inputs of the garbler followed by the inputs of the
+//! evaluator.
+
+use crate::circuit;
+use crate::math::mersenne::MersenneField;
+use crate::utils::ot;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+/// Length of the wire labels, in bytes.
+pub const LABEL_LEN: usize = 16;
+
+/// Defines a wire label.
+pub type Label = [u8; LABEL_LEN];
+
+/// Defines a gate of a boolean circuit over numbered wires.
+#[derive(Clone, Copy, Debug)]
+pub enum Gate {
+    /// XOR gate with input wires `.0` and `.1` and output wire `.2`.
+    Xor(usize, usize, usize),
+
+    /// AND gate with input wires `.0` and `.1` and output wire `.2`.
+    And(usize, usize, usize),
+
+    /// NOT gate with input wire `.0` and output wire `.1`. The garbler swaps
+    /// the labels of the input wire, so the gate needs no table.
+    Not(usize, usize),
+}
+
+impl Gate {
+    /// Returns the input and output wires of the gate, where the input wire of
+    /// a NOT gate is repeated.
+    pub fn wires(&self) -> (usize, usize, usize) {
+        match *self {
+            Gate::Xor(a, b, c) | Gate::And(a, b, c) => (a, b, c),
+            Gate::Not(a, c) => (a, a, c),
+        }
+    }
+
+    /// Computes the gate in the clear.
+    pub fn eval(&self, u: bool, v: bool) -> bool {
+        match self {
+            Gate::Xor(..) => u ^ v,
+            Gate::And(..) => u & v,
+            Gate::Not(..) => !u,
+        }
+    }
+}
+
+/// Defines a boolean circuit to be garbled.
+#[derive(Clone, Debug)]
+pub struct Circuit {
+    /// Number of input wires of the garbler, which are the first wires of the
+    /// circuit.
+    pub n_garbler_inputs: usize,
+
+    /// Number of input wires of the evaluator, which follow the inputs of the
+    /// garbler.
+    pub n_evaluator_inputs: usize,
+
+    /// Total number of wires of the circuit.
+    pub n_wires: usize,
+
+    /// Gates of the circuit in topological order.
+    pub gates: Vec<Gate>,
+
+    /// Output wires of the circuit.
+    pub outputs: Vec<usize>,
+}
+
+impl Circuit {
+    /// Converts a boolean circuit of the [`circuit`](crate::circuit) module,
+    /// where the first `n_garbler_inputs` inputs belong to the garbler and the
+    /// rest to the evaluator.
+    ///
+    /// The wires are renumbered so the inputs come first, and the function
+    /// panics if the circuit contains arithmetic gates.
+    pub fn from_ir<T: MersenneField>(
+        circuit: &circuit::Circuit<T>,
+        n_garbler_inputs: usize,
+    ) -> Self {
+        if n_garbler_inputs > circuit.inputs.len() {
+            panic!("The circuit has only {} inputs.", circuit.inputs.len());
+        }
+
+        let mut wires: Vec<Option<usize>> = vec![None; circuit.n_wires];
+        for (index, wire) in circuit.inputs.iter().enumerate() {
+            wires[*wire] = Some(index);
+        }
+        let mut n_wires = circuit.inputs.len();
+        let mut rename = |wire: usize| -> usize {
+            *wires[wire].get_or_insert_with(|| {
+                n_wires += 1;
+                n_wires - 1
+            })
+        };
+
+        let gates = circuit
+            .gates
+            .iter()
+            .map(|gate| match *gate {
+                circuit::Gate::Xor(a, b, c) => Gate::Xor(rename(a), rename(b), rename(c)),
+                circuit::Gate::And(a, b, c) => Gate::And(rename(a), rename(b), rename(c)),
+                circuit::Gate::Inv(a, c) => Gate::Not(rename(a), rename(c)),
+                _ => panic!("Garbled circuits support only boolean gates."),
+            })
+            .collect();
+        let outputs = circuit.outputs.iter().map(|wire| rename(*wire)).collect();
+
+        Self {
+            n_garbler_inputs,
+            n_evaluator_inputs: circuit.inputs.len() - n_garbler_inputs,
+            n_wires,
+            gates,
+            outputs,
+        }
+    }
+}
+
+/// Defines the garbling schemes supported by the module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GarblingScheme {
+    /// Four ciphertexts per gate in random order, where the evaluator tries to
+    /// decrypt every row and recognizes the correct one by its padding.
+    Textbook,
+
+    /// Point-and-permute: the last bit of each label is a color bit, which is
+    /// random for the label of zero and the opposite for the label of one. The
+    /// rows are sorted by the colors of the input labels, so the evaluator
+    /// decrypts only one row, which needs no padding.
+    PointAndPermute,
+
+    /// Free-XOR on top of point-and-permute: the labels of every wire satisfy
+    /// $k_w^1 = k_w^0 \oplus \Delta$ for a global secret $\Delta$ whose color
+    /// bit is one, so XOR gates are computed by XORing the labels and need no
+    /// table.
+    FreeXor,
+
+    /// Half-gates on top of free-XOR: every AND gate is split into two half
+    /// gates, one where the garbler knows an input and one where the evaluator
+    /// knows an input, which need one ciphertext each. An AND gate then costs
+    /// two ciphertexts, and XOR gates are still free.
+    HalfGates,
+}
+
+impl GarblingScheme {
+    /// Returns whether the scheme garbles XOR gates without a table.
+    fn has_free_xor(&self) -> bool {
+        matches!(self, GarblingScheme::FreeXor | GarblingScheme::HalfGates)
+    }
+}
+
+/// Defines a garbled circuit, which is sent from the garbler to the evaluator.
+pub struct GarbledCircuit {
+    /// Scheme used to garble the circuit.
+    pub scheme: GarblingScheme,
+
+    /// Encrypted rows of the garbled table of each gate, which are empty for
+    /// the gates that need no table.
+    pub tables: Vec<Vec<Vec<u8>>>,
+
+    /// Label that represents the value one in each output wire.
+    pub output_labels: Vec<Label>,
+}
+
+impl GarbledCircuit {
+    /// Returns the total number of ciphertexts in the garbled tables.
+    pub fn n_ciphertexts(&self) -> usize {
+        self.tables.iter().map(|table| table.len()).sum()
+    }
+}
+
+/// Defines the secret state of the garbler, that is, the two labels of every
+/// wire.
+pub struct Garbler {
+    labels: Vec<[Label; 2]>,
+}
+
+impl Garbler {
+    /// Returns the label that encodes a bit on an input wire.
+    pub fn encode(&self, wire: usize, bit: bool) -> Label {
+        self.labels[wire][bit as usize]
+    }
+}
+
+/// Garbles a circuit with the provided scheme, sampling the wire labels with
+/// the provided PRG.
+pub fn garble(
+    circuit: &Circuit,
+    scheme: GarblingScheme,
+    prg: &mut Prg,
+) -> (Garbler, GarbledCircuit) {
+    let mut delta = random_label(prg);
+    delta[0] |= 1;
+
+    let mut labels: Vec<[Label; 2]> = (0..circuit.n_wires)
+        .map(|_| label_pair(scheme, &delta, prg))
+        .collect();
+
+    let mut tables = Vec::with_capacity(circuit.gates.len());
+    for (index, gate) in circuit.gates.iter().enumerate() {
+        let (a, b, c) = gate.wires();
+        if let Gate::Not(..) = gate {
+            labels[c] = [labels[a][1], labels[a][0]];
+            tables.push(Vec::new());
+            continue;
+        }
+        if scheme.has_free_xor() && matches!(gate, Gate::Xor(..)) {
+            let zero = xor_labels(&labels[a][0], &labels[b][0]);
+            labels[c] = [zero, xor_labels(&zero, &delta)];
+            tables.push(Vec::new());
+            continue;
+        }
+
+        let inputs = [(false, false), (false, true), (true, false), (true, true)];
+        let table = match scheme {
+            GarblingScheme::Textbook => {
+                let mut rows: Vec<Vec<u8>> = inputs
+                    .iter()
+                    .map(|&(u, v)| {
+                        let mut message = labels[c][gate.eval(u, v) as usize].to_vec();
+                        message.extend([0; LABEL_LEN]);
+                        xor_pad(
+                            &labels[a][u as usize],
+                            &labels[b][v as usize],
+                            index,
+                            &message,
+                        )
+                    })
+                    .collect();
+                shuffle(&mut rows, prg);
+                rows
+            }
+            GarblingScheme::PointAndPermute | GarblingScheme::FreeXor => {
+                let mut rows = vec![Vec::new(); 4];
+                for (u, v) in inputs {
+                    let (label_a, label_b) = (&labels[a][u as usize], &labels[b][v as usize]);
+                    let message = labels[c][gate.eval(u, v) as usize];
+                    rows[row_index(label_a, label_b)] = xor_pad(label_a, label_b, index, &message);
+                }
+                rows
+            }
+            GarblingScheme::HalfGates => {
+                let (zero, rows) = garble_half_gates(&labels[a], &labels[b], &delta, index);
+                labels[c] = [zero, xor_labels(&zero, &delta)];
+                rows
+            }
+        };
+        tables.push(table);
+    }
+
+    let output_labels = circuit
+        .outputs
+        .iter()
+        .map(|&wire| labels[wire][1])
+        .collect();
+    let garbled = GarbledCircuit {
+        scheme,
+        tables,
+        output_labels,
+    };
+    (Garbler { labels }, garbled)
+}
+
+/// Evaluates a garbled circuit with one label for each input wire, and returns
+/// the bits of the output wires.
+pub fn evaluate(circuit: &Circuit, garbled: &GarbledCircuit, input_labels: &[Label]) -> Vec<bool> {
+    let mut wires: Vec<Option<Label>> = vec![None; circuit.n_wires];
+    for (wire, label) in input_labels.iter().enumerate() {
+        wires[wire] = Some(*label);
+    }
+
+    for (index, (gate, table)) in circuit.gates.iter().zip(&garbled.tables).enumerate() {
+        let (a, b, c) = gate.wires();
+        let label_a = wires[a].unwrap_or_else(|| panic!("The wire {} has not been evaluated.", a));
+        let label_b = wires[b].unwrap_or_else(|| panic!("The wire {} has not been evaluated.", b));
+
+        let label_c = match garbled.scheme {
+            _ if matches!(gate, Gate::Not(..)) => label_a,
+            scheme if scheme.has_free_xor() && matches!(gate, Gate::Xor(..)) => {
+                xor_labels(&label_a, &label_b)
+            }
+            GarblingScheme::Textbook => {
+                let message = table
+                    .iter()
+                    .map(|row| xor_pad(&label_a, &label_b, index, row))
+                    .find(|message| message[LABEL_LEN..].iter().all(|byte| *byte == 0))
+                    .unwrap_or_else(|| panic!("No row of the garbled table can be decrypted."));
+                message[..LABEL_LEN].try_into().unwrap()
+            }
+            GarblingScheme::PointAndPermute | GarblingScheme::FreeXor => {
+                let row = &table[row_index(&label_a, &label_b)];
+                xor_pad(&label_a, &label_b, index, row).try_into().unwrap()
+            }
+            GarblingScheme::HalfGates => evaluate_half_gates(&label_a, &label_b, table, index),
+        };
+        wires[c] = Some(label_c);
+    }
+
+    circuit
+        .outputs
+        .iter()
+        .zip(&garbled.output_labels)
+        .map(|(&wire, one)| wires[wire].as_ref() == Some(one))
+        .collect()
+}
+
+/// Executes Yao's protocol between a garbler and an evaluator.
+///
+/// The inputs of each party are bits stored in its private memory as the field
+/// elements zero or one, under the IDs `ids_garbler_inputs` and
+/// `ids_evaluator_inputs`. The garbler garbles the circuit with the provided
+/// scheme and sends it
+/// together with the labels of its inputs, and the evaluator obtains the labels
+/// of its inputs with one OT per bit, which are run with the IKNP OT extension
+/// through the simulated network. The evaluator decodes the output and
+/// sends it back to the garbler, so both parties store the output bits in their
+/// private memory under the IDs `ids_output`.
+#[allow(clippy::too_many_arguments)]
+pub fn yao_protocol<T>(
+    garbler: &mut VirtualMachine<T>,
+    evaluator: &mut VirtualMachine<T>,
+    circuit: &Circuit,
+    ids_garbler_inputs: &[&str],
+    ids_evaluator_inputs: &[&str],
+    ids_output: &[&str],
+    scheme: GarblingScheme,
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    if ids_garbler_inputs.len() != circuit.n_garbler_inputs
+        || ids_evaluator_inputs.len() != circuit.n_evaluator_inputs
+        || ids_output.len() != circuit.outputs.len()
+    {
+        panic!("The number of IDs does not match the inputs and outputs of the circuit.");
+    }
+
+    let (garbler_state, garbled) = garble(circuit, scheme, prg);
+
+    let mut input_labels: Vec<Label> = ids_garbler_inputs
+        .iter()
+        .enumerate()
+        .map(|(wire, id)| garbler_state.encode(wire, input_bit(garbler, id)))
+        .collect();
+    let messages: Vec<ot::MessagePair> = (0..circuit.n_evaluator_inputs)
+        .map(|k| {
+            let wire = circuit.n_garbler_inputs + k;
+            let m0 = garbler_state.encode(wire, false);
+            let m1 = garbler_state.encode(wire, true);
+            (m0.to_vec(), m1.to_vec())
+        })
+        .collect();
+    let choices: Vec<bool> = ids_evaluator_inputs
+        .iter()
+        .map(|id| input_bit(evaluator, id))
+        .collect();
+    let labels = ot::iknp_extension(garbler, evaluator, &messages, &choices);
+    input_labels.extend(
+        labels
+            .into_iter()
+            .map(|label| Label::try_from(label).unwrap()),
+    );
+
+    let output = evaluate(circuit, &garbled, &input_labels);
+    for (id, bit) in ids_output.iter().zip(output) {
+        garbler.insert_priv_value(id, T::new(bit as u64));
+        evaluator.insert_priv_value(id, T::new(bit as u64));
+    }
+}
+
+/// Reads an input bit from the private memory of a party.
+fn input_bit<T: MersenneField>(party: &VirtualMachine<T>, id: &str) -> bool {
+    match party.get_priv_value(id).value() {
+        0 => false,
+        1 => true,
+        _ => panic!("The inputs of a garbled circuit must be bits."),
+    }
+}
+
+/// Encrypts or decrypts a row of a garbled table by XORing it with the output
+/// of a PRG seeded with the two input labels and the index of the gate.
+fn xor_pad(label_a: &Label, label_b: &Label, gate: usize, message: &[u8]) -> Vec<u8> {
+    let mut seed = label_a.to_vec();
+    seed.extend(label_b);
+    for (byte, tweak) in seed[LABEL_LEN..]
+        .iter_mut()
+        .zip((gate as u64).to_le_bytes())
+    {
+        *byte ^= tweak;
+    }
+
+    let pad = Prg::new(Some(seed)).next(message.len());
+    message.iter().zip(pad).map(|(m, p)| m ^ p).collect()
+}
+
+/// Garbles an AND gate with half-gates, given the labels of its input wires,
+/// and returns the label of zero of the output wire and the two ciphertexts.
+///
+/// With $p_a$ and $p_b$ the colors of $k_a^0$ and $k_b^0$, the garbler half
+/// gate is $T_G = H(k_a^0) \oplus H(k_a^1) \oplus p_b \Delta$, and the
+/// evaluator half gate is $T_E = H(k_b^0) \oplus H(k_b^1) \oplus k_a^0$. The
+/// label of zero of the output is
+///    $$H(k_a^0) \oplus p_a T_G \oplus H(k_b^0) \oplus p_b (T_E \oplus k_a^0).$$
+fn garble_half_gates(
+    labels_a: &[Label; 2],
+    labels_b: &[Label; 2],
+    delta: &Label,
+    gate: usize,
+) -> (Label, Vec<Vec<u8>>) {
+    let (color_a, color_b) = (color(&labels_a[0]) == 1, color(&labels_b[0]) == 1);
+    let hash_a = labels_a.map(|label| hash_label(&label, 2 * gate));
+    let hash_b = labels_b.map(|label| hash_label(&label, 2 * gate + 1));
+
+    let mut table_g = xor_labels(&hash_a[0], &hash_a[1]);
+    if color_b {
+        table_g = xor_labels(&table_g, delta);
+    }
+    let mut zero_g = hash_a[0];
+    if color_a {
+        zero_g = xor_labels(&zero_g, &table_g);
+    }
+
+    let table_e = xor_labels(&xor_labels(&hash_b[0], &hash_b[1]), &labels_a[0]);
+    let mut zero_e = hash_b[0];
+    if color_b {
+        zero_e = xor_labels(&zero_e, &xor_labels(&table_e, &labels_a[0]));
+    }
+
+    (
+        xor_labels(&zero_g, &zero_e),
+        vec![table_g.to_vec(), table_e.to_vec()],
+    )
+}
+
+/// Evaluates an AND gate garbled with half-gates, given one label of each
+/// input wire.
+fn evaluate_half_gates(label_a: &Label, label_b: &Label, table: &[Vec<u8>], gate: usize) -> Label {
+    let table_g: Label = table[0].as_slice().try_into().unwrap();
+    let table_e: Label = table[1].as_slice().try_into().unwrap();
+
+    let mut label_g = hash_label(label_a, 2 * gate);
+    if color(label_a) == 1 {
+        label_g = xor_labels(&label_g, &table_g);
+    }
+    let mut label_e = hash_label(label_b, 2 * gate + 1);
+    if color(label_b) == 1 {
+        label_e = xor_labels(&label_e, &xor_labels(&table_e, label_a));
+    }
+    xor_labels(&label_g, &label_e)
+}
+
+/// Hashes a label with a tweak, using the output of a [`Prg`] seeded with
+/// both.
+fn hash_label(label: &Label, tweak: usize) -> Label {
+    let mut seed = label.to_vec();
+    seed.extend((tweak as u64).to_le_bytes());
+    Prg::new(Some(seed)).next(LABEL_LEN).try_into().unwrap()
+}
+
+/// Samples a random wire label.
+fn random_label(prg: &mut Prg) -> Label {
+    prg.next(LABEL_LEN).try_into().unwrap()
+}
+
+/// Samples the labels of zero and one of a wire for the provided scheme.
+fn label_pair(scheme: GarblingScheme, delta: &Label, prg: &mut Prg) -> [Label; 2] {
+    let zero = random_label(prg);
+    match scheme {
+        GarblingScheme::Textbook => [zero, random_label(prg)],
+        GarblingScheme::PointAndPermute => {
+            let mut one = random_label(prg);
+            one[0] = (one[0] & !1) | (!zero[0] & 1);
+            [zero, one]
+        }
+        GarblingScheme::FreeXor | GarblingScheme::HalfGates => [zero, xor_labels(&zero, delta)],
+    }
+}
+
+/// Returns the color bit of a label.
+fn color(label: &Label) -> usize {
+    (label[0] & 1) as usize
+}
+
+/// Returns the row of a point-and-permute table given by the colors of the
+/// input labels.
+fn row_index(label_a: &Label, label_b: &Label) -> usize {
+    2 * color(label_a) + color(label_b)
+}
+
+/// Computes the XOR of two labels.
+fn xor_labels(a: &Label, b: &Label) -> Label {
+    let mut result = *a;
+    for (x, y) in result.iter_mut().zip(b) {
+        *x ^= y;
+    }
+    result
+}
+
+/// Shuffles the rows of a garbled table with the Fisher-Yates algorithm.
+fn shuffle(rows: &mut [Vec<u8>], prg: &mut Prg) {
+    for i in (1..rows.len()).rev() {
+        let j = prg.next(1)[0] as usize % (i + 1);
+        rows.swap(i, j);
+    }
+}
@@ -0,0 +1,135 @@
+//! Implements typed handles to secret-shared values.
+//!
+//! The protocols refer to the values in the memory of the virtual machines by
+//! their IDs, so a mistyped ID is only noticed when a party fails to find it
+//! in its memory, deep inside a protocol execution. Besides, an ID does not
+//! tell whether it refers to arithmetic shares over a field or to boolean
+//! shares, which live in different memories. The handles defined here solve
+//! both problems: the protocols that create a secret-shared value return a
+//! handle to it, and the protocols accept either a plain ID or a handle of
+//! the right kind, so passing a handle to boolean shares to an arithmetic
+//! protocol, or a handle over another field, does not compile.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::mpc;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! let mut parties = vec![&mut alice, &mut bob];
+//!
+//! let a = mpc::simulate_random_dist("a", &mut parties, &Fp::new(3), &mut prg);
+//! let b = mpc::simulate_random_dist("b", &mut parties, &Fp::new(4), &mut prg);
+//! let sum = mpc::add_protocol(&mut parties, &a, &b, "sum");
+//! assert_eq!(mpc::reconstruct_share(&parties, &sum).value(), 7);
+//! ```
+//!
+//! A handle to boolean shares is rejected by the arithmetic protocols.
+//!
+//! ```compile_fail
+//! use smol_mpc::math::mersenne::Mersenne61;
+//! use smol_mpc::mpc;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! let mut prg = Prg::new(None);
+//! let mut alice: VirtualMachine<Mersenne61> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Mersenne61> = VirtualMachine::new("bob");
+//! let mut parties = vec![&mut alice, &mut bob];
+//!
+//! let bit = mpc::simulate_bool_dist("bit", &mut parties, true, &mut prg);
+//! mpc::reconstruct_share(&parties, &bit);
+//! ```
+
+use std::marker::PhantomData;
+
+/// Refers to a value secret-shared over the field `T` with additive
+/// secret-sharing.
+pub struct SecretHandle<T> {
+    id: String,
+    field: PhantomData<T>,
+}
+
+impl<T> SecretHandle<T> {
+    /// Creates a handle to the shares stored under the provided ID.
+    pub(crate) fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            field: PhantomData,
+        }
+    }
+
+    /// Returns the ID of the shares in the memory of the parties.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl<T> Clone for SecretHandle<T> {
+    fn clone(&self) -> Self {
+        Self::new(&self.id)
+    }
+}
+
+/// Refers to a bit secret-shared with boolean shares.
+#[derive(Clone, Debug)]
+pub struct BoolHandle {
+    id: String,
+}
+
+impl BoolHandle {
+    /// Creates a handle to the boolean shares stored under the provided ID.
+    pub(crate) fn new(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
+
+    /// Returns the ID of the boolean shares in the memory of the parties.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Defines the references to a value secret-shared over the field `T` that
+/// the protocols accept, that is, its ID or its handle.
+pub trait SecretRef<T> {
+    /// Returns the ID of the shares in the memory of the parties.
+    fn id(&self) -> &str;
+}
+
+impl<T, S: AsRef<str> + ?Sized> SecretRef<T> for &S {
+    fn id(&self) -> &str {
+        (*self).as_ref()
+    }
+}
+
+impl<T> SecretRef<T> for &SecretHandle<T> {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Defines the references to a boolean-shared bit that the protocols accept,
+/// that is, its ID or its handle.
+pub trait BoolRef {
+    /// Returns the ID of the boolean shares in the memory of the parties.
+    fn id(&self) -> &str;
+}
+
+impl<S: AsRef<str> + ?Sized> BoolRef for &S {
+    fn id(&self) -> &str {
+        (*self).as_ref()
+    }
+}
+
+impl BoolRef for &BoolHandle {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -5,7 +5,8 @@
 //! of an MPC protocol. Instead, we aim for a simpler representation in which
 //! all of these tasks are done locally and the communication is simulated by
 //! sending information between virtual machines that represent nodes in the 
-//! network.
+//! network. Still, the `net` feature provides a thin TCP backend so the
+//! parties can also run on different machines.
 //! 
 //! The idea of the library is to represent each node in the network as a
 //! as a small virtual machine (see [`VirtualMachine`](crate::vm::VirtualMachine)). 
@@ -13,16 +14,52 @@
 //! will store all the that are known by the virtual machine. The shared memory 
 //! will store the shares distributed in a protocol execution. Both types of 
 //! memory are implemented in an ID-value structure, which means that all the 
-//! values will be retrieved and stored by a user-defined ID of type [`&str`]. 
-//! So sending a value from one machine to the other corresponds to retrieving a 
-//! value from the first party using the ID, and storing it in the memory of 
-//! the other party using the same ID.
+//! values will be retrieved and stored by a user-defined ID. IDs are passed as
+//! [`&str`] and the memory keeps its own copy, so they can also be built at
+//! runtime, for example with [`format!`]. The basic protocols also return
+//! typed handles to the values they create, which can be passed instead of the
+//! IDs (see [`handle`](crate::handle)).
+//! The protocols never read the memory of other virtual machines: the values
+//! that a party sends are put in its outbox and delivered to the inbox of the
+//! receiver by a simulated network (see [`network`](crate::network)). The
+//! basic protocols are also available as round-based state machines, one for
+//! each party, which make the rounds explicit (see
+//! [`protocol`](crate::protocol)).
+//! To avoid passing the parties and the pseudo-random generator to every
+//! protocol, they can be bundled in an [`MpcContext`](crate::context::MpcContext).
+//! The parties can also be grouped in a [`PartySet`](crate::party::PartySet),
+//! which validates their IDs and carries the threshold of the access
+//! structure. The executions can be observed with hooks that receive the
+//! events of each party (see [`event`](crate::event)), and the field
+//! operations, random bytes, openings and triples they use are counted (see
+//! [`counter`](crate::counter)).
 //! 
 //! At the time of writing, we have implemented a passive protocol based on additive
 //! secret-sharing that performs multiplications using beaver triples. Such 
-//! Beaver triples **are not** generated using a protocol, instead, its 
-//! generation is simulated. In future work, we will work on implementing a 
-//! protocol to illustrate the generation of Beaver triples.
+//! Beaver triples are usually sent to the parties by a trusted
+//! [`Dealer`](crate::dealer::Dealer). For two parties, they can also be
+//! generated with a protocol based on oblivious transfer (see
+//! [`ot_triple_protocol`](crate::mpc::ot_triple_protocol)).
+//! For active security, the [`spdz`](crate::spdz) module authenticates the
+//! shares with information-theoretic MACs, so tampering is detected when the
+//! values are opened. The [`bdoz`](crate::bdoz) module offers the same
+//! operations using pairwise MACs instead of a global MAC key.
+//! With an honest majority, a failed check can also be traced to a pair of
+//! parties in dispute, which are eliminated before restarting the computation
+//! (see [`dispute`](crate::dispute)).
+//! Secret keys can be Shamir-shared, so that the parties decrypt together
+//! without reconstructing them (see [`threshold`](crate::threshold)).
+//! A client can also evaluate a pseudo-random function keyed by a server
+//! without either of them learning the input of the other (see
+//! [`oprf`](crate::oprf)), which is the basis of the private set intersection
+//! in [`apps`](crate::apps), together with other complete applications such
+//! as Yao's millionaires' problem.
+//! Functions can also be secret-shared, so that each party evaluates its share
+//! locally at any input (see [`fss`](crate::fss)).
+//! Computations with many operations can be described once as a
+//! [`Circuit`](crate::circuit::Circuit) and evaluated by the different
+//! protocol families, for example the [`mimc`](crate::mimc) cipher, which
+//! evaluates a pseudo-random function on shared inputs.
 //!   
 //! # Examples
 //! 
@@ -38,19 +75,16 @@
 //! ```rust
 //! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
 //! use smol_mpc::mpc;
-//! use smol_mpc::utils::prg::Prg;
 //! use smol_mpc::vm::VirtualMachine;
 //! 
 //! type Fp = Mersenne61;
 //! 
 //! fn main() {
-//!     // Creates a new pseudo-random generator with a default seed.
-//!     let mut prg = Prg::new(None);
-//!
 //!     // Creates two virtual machines that represent the nodes involved in the
 //!     // computation. The ID for each virtual machine is provided in the
 //!     // constructor to identify each virtual machine during the protocol
-//!     // executions.
+//!     // executions. Each virtual machine owns a pseudo-random generator,
+//!     // from which it draws the randomness of the shares it distributes.
 //!     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
 //!     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 //!    
@@ -63,7 +97,7 @@
 //!     // contains the parties Alice and Bob. At the end of the execution, both
 //!     // of them will have a share of the value 4 stored in their share memory
 //!     // and identified with ID "a".
-//!     mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+//!     mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 //!
 //!     // Bob stores in his private memory a value with ID "b".
 //!     bob.insert_priv_value("b", Fp::new(2));
@@ -71,7 +105,7 @@
 //!     // Bob distributes shares of its private value "b" among him and Alice.
 //!     // At the end, both will have a share of the value 2 in their share
 //!     // memory stored with id "b".
-//!     mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob], &mut prg);
+//!     mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob]);
 //!
 //!     // Alice and Bob engage in an addition protocol to securely add "a" and
 //!     // "b". The result of this protocol will be shares of the sum of both
@@ -95,6 +129,7 @@
 //! secure multiplication providing their private values.
 //! 
 //! ```rust
+//! use smol_mpc::dealer::Dealer;
 //! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
 //! use smol_mpc::mpc;
 //! use smol_mpc::utils::prg::Prg;
@@ -103,9 +138,6 @@
 //! type Fp = Mersenne61;
 //! 
 //! fn main () {
-//!     // Creates a new pseudo-random generator.
-//!     let mut prg = Prg::new(Some(vec![1, 2]));
-//! 
 //!     // These two lines creates two virtual machines, one for Alice and the
 //!     // other for Bob.
 //!     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
@@ -114,35 +146,29 @@
 //!     // Alice distributes a private value. Here, Alice and Bob obtain shares
 //!     // of a value stored with ID "a".
 //!     alice.insert_priv_value("a", Fp::new(4));
-//!     mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+//!     mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 //! 
 //!     // Bob distributes a private value. Here, Alice and Bob obtain shares
 //!     // of a value stored with ID "b"
 //!     bob.insert_priv_value("b", Fp::new(2));
-//!     mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob], &mut prg);
+//!     mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob]);
 //! 
-//!     // Here, Alice and Bob receive shares of a Beaver triple (x1, x2, x3),
-//!     // where x3 = x1 * x2. Such shares are stored in the memory of alice and
-//!     // Bob with IDs "x1", "x2" and "x3" respectively.
-//!     mpc::generate_triple(
-//!         &mut vec![&mut alice, &mut bob],
-//!         ("x1", "x2", "x3"),
-//!         &mut prg,
-//!     );
+//!     // Here, a trusted dealer sends to Alice and Bob shares of a Beaver
+//!     // triple (x1, x2, x3), where x3 = x1 * x2. Such shares are stored in the
+//!     // memory of alice and Bob with IDs "x1", "x2" and "x3" respectively.
+//!     // The triple is registered in the preprocessing store of both parties,
+//!     // so it can be used automatically by the multiplication protocol.
+//!     let mut dealer = Dealer::new(Prg::new(Some(vec![3, 4])));
+//!     dealer.deal_triple(&mut vec![&mut alice, &mut bob], ("x1", "x2", "x3"));
+//!     mpc::register_triple(&mut vec![&mut alice, &mut bob], ("x1", "x2", "x3"));
 //! 
 //!     // Alice and Bob engage in a multiplication protocol to compute securely
-//!     // the product of "a" with "b", using the triple whose ID's are "x1",
-//!     // "x2" and "x3" (the same ones that were created in the previous)
-//!     // instruction. At the end of the computation, Alice and Bob will obtain
+//!     // the product of "a" with "b", consuming the next unused triple of
+//!     // their preprocessing store (the one registered in the previous
+//!     // instruction). At the end of the computation, Alice and Bob will obtain
 //!     // shares of the product of "a" and "b", and such share will be stored
 //!     // in the memory using the id "prod".
-//!     mpc::mult_protocol(
-//!         &mut vec![&mut alice, &mut bob],
-//!         "a",
-//!         "b",
-//!         "prod",
-//!         ("x1", "x2", "x3"),
-//!     );
+//!     mpc::mult_protocol(&mut vec![&mut alice, &mut bob], "a", "b", "prod");
 //!    
 //!     // Alice and Bob engage in a protocol to reconstruct the value of "prod".
 //!     let mult_reconst = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "prod");
@@ -161,7 +187,31 @@
 //! [TinySMPC]: https://github.com/kennysong/tinysmpc
 //! [SCL]: https://github.com/anderspkd/secure-computation-library
 
+pub mod adversary;
+pub mod apps;
+pub mod bdoz;
+pub mod broadcast;
+pub mod circuit;
+pub mod client;
+pub mod context;
+pub mod counter;
+pub mod dealer;
+pub mod dispute;
+pub mod event;
+pub mod fss;
+pub mod gc;
+pub mod handle;
 pub mod math;
+pub mod mimc;
 pub mod mpc;
+pub mod network;
+#[cfg(feature = "sha2")]
+pub mod oprf;
+pub mod party;
+pub mod preprocessing;
+pub mod protocol;
+pub mod shamir;
+pub mod spdz;
+pub mod threshold;
 pub mod utils;
 pub mod vm;
@@ -0,0 +1,33 @@
+//! Implements a fixed-point encoding of real numbers into field elements.
+//!
+//! A real number $x$ is encoded as the field element $\lfloor x \cdot 2^f
+//! \rceil$, where $f$ is the number of fractional bits. Negative numbers are
+//! encoded using their additive inverse in the field, so an element is decoded
+//! as negative if it is greater than $p / 2$. Additions of encoded values can
+//! be done directly in the field, but the product of two encoded values has
+//! $2f$ fractional bits, which must be truncated afterwards (see
+//! [`trunc_protocol`](crate::mpc::trunc_protocol)).
+
+use crate::math::mersenne::MersenneField;
+
+/// Encodes a real number as a field element using `frac_bits` fractional bits.
+pub fn encode<T: MersenneField>(x: f64, frac_bits: u32) -> T {
+    let scaled = (x * (1u64 << frac_bits) as f64).round() as i64;
+    if scaled < 0 {
+        T::new(scaled.unsigned_abs()).negate()
+    } else {
+        T::new(scaled as u64)
+    }
+}
+
+/// Decodes a field element as a real number with `frac_bits` fractional bits.
+pub fn decode<T: MersenneField>(x: &T, frac_bits: u32) -> f64 {
+    let value = x.value();
+    let signed = if value > T::ORDER / 2 {
+        -((T::ORDER - value) as f64)
+    } else {
+        value as f64
+    };
+
+    signed / (1u64 << frac_bits) as f64
+}
@@ -0,0 +1,134 @@
+//! Implements a cyclic group of prime order for discrete-logarithm protocols.
+//!
+//! The group is the subgroup of order $q$ of the multiplicative group
+//! $\mathbb{Z}_p^*$, where $q$ is the order of a Mersenne field and $p = kq + 1$
+//! is a prime. Since the order of the group is the same as the order of the
+//! field, the field elements are used directly as exponents, so that
+//! $g^a g^b = g^{a + b}$ for any $a, b \in \mathbb{F}_q$. The modulus $p$ is
+//! generated with the Miller-Rabin test using the library PRG.
+//!
+//! The discrete logarithm in a group of order $2^{61} - 1$ can be computed with
+//! about $2^{31}$ operations, so this group is only suitable for examples.
+
+use crate::math::mersenne::MersenneField;
+use crate::utils::paillier::{is_probable_prime, random_below, random_bits, BigUint};
+use crate::utils::prg::Prg;
+use std::marker::PhantomData;
+
+/// Defines an element of a [`Group`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupElement(pub BigUint);
+
+/// Defines a subgroup of $\mathbb{Z}_p^*$ whose order is the order of the
+/// Mersenne field `T`.
+#[derive(Clone)]
+pub struct Group<T: MersenneField> {
+    p: BigUint,
+    cofactor: BigUint,
+    generator: GroupElement,
+    _field: PhantomData<T>,
+}
+
+impl<T: MersenneField> Group<T> {
+    /// Generates a group whose modulus $p$ has about `bits` bits, together with
+    /// a random generator.
+    pub fn generate(bits: u64, prg: &mut Prg) -> Self {
+        if bits <= T::POWER + 1 {
+            panic!("The modulus must be larger than the order of the field.");
+        }
+
+        let q = BigUint::from(T::ORDER);
+        let one = BigUint::from(1u32);
+        let (p, cofactor) = loop {
+            // The cofactor must be even for p = kq + 1 to be odd.
+            let cofactor = random_bits(bits - T::POWER - 1, prg) << 1;
+            let p = &cofactor * &q + &one;
+            if is_probable_prime(&p, prg) {
+                break (p, cofactor);
+            }
+        };
+
+        let mut group = Self {
+            p,
+            cofactor,
+            generator: GroupElement(one),
+            _field: PhantomData,
+        };
+        group.generator = group.random_element(prg);
+        group
+    }
+
+    /// Returns the modulus $p$ of the group.
+    pub fn modulus(&self) -> &BigUint {
+        &self.p
+    }
+
+    /// Returns the generator $g$ of the group.
+    pub fn generator(&self) -> &GroupElement {
+        &self.generator
+    }
+
+    /// Returns the identity element of the group.
+    pub fn identity(&self) -> GroupElement {
+        GroupElement(BigUint::from(1u32))
+    }
+
+    /// Samples a random element of the group different from the identity,
+    /// which is also a generator because the order of the group is prime.
+    ///
+    /// The element is computed as $x^k$ for a random $x \in \mathbb{Z}_p^*$,
+    /// so its discrete logarithm with respect to the other generators is
+    /// unknown.
+    pub fn random_element(&self, prg: &mut Prg) -> GroupElement {
+        loop {
+            let x = random_below(&self.p, prg);
+            let element = x.modpow(&self.cofactor, &self.p);
+            if element > BigUint::from(1u32) {
+                return GroupElement(element);
+            }
+        }
+    }
+
+    /// Computes the product of two elements of the group.
+    pub fn mul(&self, a: &GroupElement, b: &GroupElement) -> GroupElement {
+        GroupElement(&a.0 * &b.0 % &self.p)
+    }
+
+    /// Computes the power of an element of the group to a field element.
+    pub fn exp(&self, base: &GroupElement, exponent: &T) -> GroupElement {
+        GroupElement(base.0.modpow(&BigUint::from(exponent.value()), &self.p))
+    }
+
+    /// Computes the inverse of an element of the group.
+    pub fn inverse(&self, a: &GroupElement) -> GroupElement {
+        // a^(q - 1) is the inverse of a because a^q = 1.
+        GroupElement(a.0.modpow(&BigUint::from(T::ORDER - 1), &self.p))
+    }
+
+    /// Encodes elements of the group as bytes, writing each of them in little
+    /// endian with the number of bytes of the modulus.
+    pub fn encode(&self, elements: &[GroupElement]) -> Vec<u8> {
+        let len = self.element_len();
+        elements
+            .iter()
+            .flat_map(|element| {
+                let mut bytes = element.0.to_bytes_le();
+                bytes.resize(len, 0);
+                bytes
+            })
+            .collect()
+    }
+
+    /// Decodes the elements of the group encoded with [`Group::encode`].
+    pub fn decode(&self, bytes: &[u8]) -> Vec<GroupElement> {
+        bytes
+            .chunks(self.element_len())
+            .map(|chunk| GroupElement(BigUint::from_bytes_le(chunk)))
+            .collect()
+    }
+
+    /// Returns the number of bytes of the modulus.
+    fn element_len(&self) -> usize {
+        (self.p.bits() as usize).div_ceil(8)
+    }
+}
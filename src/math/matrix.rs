@@ -0,0 +1,183 @@
+//! Implements a basic matrix type.
+//!
+//! Matrices are used in this library for two purposes. On the one hand, a
+//! matrix of field elements allows to perform linear algebra in the clear. On
+//! the other hand, a matrix of IDs allows to refer to a matrix whose entries
+//! have been secret-shared among a set of parties, where each entry is stored in
+//! the share memory of the parties using the corresponding ID.
+
+use crate::math::mersenne::MersenneField;
+use crate::utils::prg::PseudoRandomGenerator;
+
+/// Defines a matrix stored in row-major order.
+pub struct Matrix<E> {
+    rows: usize,
+    cols: usize,
+    data: Vec<E>,
+}
+
+impl<E> Matrix<E> {
+    /// Creates a matrix from a vector of rows.
+    ///
+    /// All the rows must have the same length.
+    pub fn from_rows(rows: Vec<Vec<E>>) -> Self {
+        let n_rows = rows.len();
+        let n_cols = rows.first().map_or(0, |row| row.len());
+
+        let mut data = Vec::with_capacity(n_rows * n_cols);
+        for row in rows {
+            if row.len() != n_cols {
+                panic!("All the rows of a matrix must have the same length.");
+            }
+            data.extend(row);
+        }
+
+        Self {
+            rows: n_rows,
+            cols: n_cols,
+            data,
+        }
+    }
+
+    /// Returns the number of rows of the matrix.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns of the matrix.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the entry in the row `i` and column `j`.
+    pub fn get(&self, i: usize, j: usize) -> &E {
+        if i >= self.rows || j >= self.cols {
+            panic!("The index ({}, {}) is out of the matrix bounds.", i, j);
+        }
+
+        &self.data[i * self.cols + j]
+    }
+
+    /// Returns an iterator over the entries of the matrix in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, E> {
+        self.data.iter()
+    }
+
+    /// Creates a new matrix with the same dimensions by applying a function to
+    /// each entry.
+    pub fn map<U, F>(&self, f: F) -> Matrix<U>
+    where
+        F: FnMut(&E) -> U,
+    {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<E: Clone> Matrix<E> {
+    /// Returns the row `i` of the matrix.
+    pub fn row(&self, i: usize) -> &[E] {
+        if i >= self.rows {
+            panic!("The row {} is out of the matrix bounds.", i);
+        }
+
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// Computes the transpose of the matrix.
+    pub fn transpose(&self) -> Self {
+        let data = (0..self.cols)
+            .flat_map(|j| (0..self.rows).map(move |i| (i, j)))
+            .map(|(i, j)| self.data[i * self.cols + j].clone())
+            .collect();
+
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            data,
+        }
+    }
+}
+
+impl<T: MersenneField> Matrix<T> {
+    /// Creates a matrix filled with zeros.
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: (0..rows * cols).map(|_| T::new(0)).collect(),
+        }
+    }
+
+    /// Creates a matrix with random entries using the provided pseudo-random
+    /// generator.
+    pub fn random<G: PseudoRandomGenerator + ?Sized>(
+        rows: usize,
+        cols: usize,
+        prg: &mut G,
+    ) -> Self {
+        Self {
+            rows,
+            cols,
+            data: (0..rows * cols).map(|_| T::random(prg)).collect(),
+        }
+    }
+
+    /// Computes the entry-wise sum of two matrices.
+    pub fn add(&self, other: &Self) -> Self {
+        self.check_same_dimensions(other);
+        Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(a, b)| a.add(b))
+                .collect(),
+        }
+    }
+
+    /// Computes the entry-wise subtraction of two matrices.
+    pub fn subtract(&self, other: &Self) -> Self {
+        self.check_same_dimensions(other);
+        Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(a, b)| a.subtract(b))
+                .collect(),
+        }
+    }
+
+    /// Computes the product of two matrices.
+    pub fn multiply(&self, other: &Self) -> Self {
+        if self.cols != other.rows {
+            panic!("The dimensions of the matrices do not allow the product.");
+        }
+
+        let mut result = Self::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for j in 0..other.cols {
+                let mut value = T::new(0);
+                for k in 0..self.cols {
+                    value = value.add(&self.get(i, k).multiply(other.get(k, j)));
+                }
+                result.data[i * other.cols + j] = value;
+            }
+        }
+        result
+    }
+
+    fn check_same_dimensions(&self, other: &Self) {
+        if self.rows != other.rows || self.cols != other.cols {
+            panic!("The matrices must have the same dimensions.");
+        }
+    }
+}
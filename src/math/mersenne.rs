@@ -9,7 +9,8 @@
 //!
 //! [Secure Computation Library]: https://github.com/anderspkd/secure-computation-library/blob/master/src/scl/math/mersenne61.cc
 
-use crate::utils::prg::Prg;
+use crate::counter;
+use crate::utils::prg::PseudoRandomGenerator;
 
 /// Defines an element in a Mersenne field $\mathbb{F}_p$ with $p = 2 ^ {61} - 1$.
 #[derive(Clone)]
@@ -19,7 +20,7 @@ pub struct Mersenne61 {
 }
 
 /// Defines the operations over Mersenne fields elements.
-pub trait MersenneField {
+pub trait MersenneField: Clone {
     /// Power of the Mersenne field. Mersenne fields are of the form
     /// $\mathbb{F}_p$ with $p = 2^n - 1$. This variable represents $n$.
     const POWER: u64;
@@ -47,10 +48,23 @@ pub trait MersenneField {
 
     /// Generates a random element in the Mersenne field provided a
     /// pseudo-random generator.
-    fn random(prg: &mut Prg) -> Self;
+    fn random<G: PseudoRandomGenerator + ?Sized>(prg: &mut G) -> Self;
 
     /// Returns the value of the element in the Mersenne field.
     fn value(&self) -> u64;
+
+    /// Given a field element $a \in \mathbb{F}_p$, returns $a^e$ for a public
+    /// exponent $e$ using the square-and-multiply algorithm.
+    fn pow(&self, exp: u64) -> Self {
+        let mut result = Self::new(1);
+        for bit in (0..u64::BITS).rev() {
+            result = result.multiply(&result);
+            if (exp >> bit) & 1 == 1 {
+                result = result.multiply(self);
+            }
+        }
+        result
+    }
 }
 
 impl MersenneField for Mersenne61 {
@@ -74,24 +88,20 @@ impl MersenneField for Mersenne61 {
     }
 
     fn add(&self, other: &Self) -> Self {
-        let sum = self.value + other.value;
-        if sum >= Self::ORDER {
-            Self {
-                value: sum - Self::ORDER,
-            }
-        } else {
-            Self { value: sum }
-        }
+        counter::record(|counts| counts.adds += 1);
+        self.sum(other)
     }
 
     fn subtract(&self, other: &Self) -> Self {
-        self.add(&other.negate())
+        counter::record(|counts| counts.adds += 1);
+        self.sum(&other.negate())
     }
 
     fn inverse(&self) -> Self {
         if self.value == 0 {
             panic!("You can not invert the zero element of a field.");
         }
+        counter::record(|counts| counts.inversions += 1);
 
         let mut k: i64 = 0;
         let mut new_k: i64 = 1;
@@ -114,6 +124,7 @@ impl MersenneField for Mersenne61 {
     }
 
     fn multiply(&self, other: &Self) -> Self {
+        counter::record(|counts| counts.mults += 1);
         let mult: u128 = (self.value as u128) * (other.value as u128);
         let mut a = mult >> Self::POWER;
         let mut b: u64 = mult as u64;
@@ -124,7 +135,7 @@ impl MersenneField for Mersenne61 {
         let a_wrap = Self { value: a as u64 };
         let b_wrap = Self { value: b };
 
-        a_wrap.add(&b_wrap)
+        a_wrap.sum(&b_wrap)
     }
 
     fn negate(&self) -> Self {
@@ -137,15 +148,26 @@ impl MersenneField for Mersenne61 {
         }
     }
 
-    fn random(prg: &mut Prg) -> Self {
-        let random_bytes = prg.next((u64::BITS / 8) as usize);
-        let random_value = u64::from_ne_bytes(
-            random_bytes
-                .try_into()
-                .expect("Expected a vector with 8 bytes"),
-        );
+    fn random<G: PseudoRandomGenerator + ?Sized>(prg: &mut G) -> Self {
+        let mut random_bytes = [0; (u64::BITS / 8) as usize];
+        prg.fill_bytes(&mut random_bytes);
 
-        Self::new(random_value)
+        Self::new(u64::from_ne_bytes(random_bytes))
+    }
+}
+
+impl Mersenne61 {
+    /// Adds two elements without counting the operation, so that the
+    /// additions performed inside other operations are not counted.
+    fn sum(&self, other: &Self) -> Self {
+        let sum = self.value + other.value;
+        if sum >= Self::ORDER {
+            Self {
+                value: sum - Self::ORDER,
+            }
+        } else {
+            Self { value: sum }
+        }
     }
 }
 
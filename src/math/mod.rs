@@ -4,4 +4,7 @@
 //! This module contains all the implementation of all the algebraic structures
 //! and math tools that the library uses to compute the protocols.
 
+pub mod fixed;
+pub mod group;
+pub mod matrix;
 pub mod mersenne;
@@ -0,0 +1,170 @@
+//! Implements the MiMC block cipher, which is cheap to evaluate in MPC.
+//!
+//! MiMC (AGR+16) works directly over the field: each of its $r$ rounds adds
+//! the key $k$ and a public round constant $c_i$ to the state and raises the
+//! result to a small power $d$,
+//!    $$x_{i+1} = (x_i + k + c_i)^d,$$
+//! starting from the message $x_0$, and the ciphertext is $x_r + k$. The power
+//! must be a permutation of the field, that is, $\gcd(d, p - 1) = 1$, and the
+//! number of rounds is $\lceil \log_d p \rceil$. In contrast with ciphers such
+//! as AES, which are built from bit operations, the only non-linear operation
+//! is the power, so the cipher only needs a few multiplications per round.
+//! This makes it a realistic workload for the protocols of this library: a
+//! pseudo-random function evaluated with a secret-shared key on a
+//! secret-shared input, as needed for example to derive shared randomness or
+//! to produce tags inside MPC.
+//!
+//! The cipher is available in the clear ([`Mimc::encrypt`]) and as a
+//! [`Circuit`] ([`Mimc::circuit`]), which is evaluated on shared inputs with
+//! [`mimc_protocol`]. The circuit is also a convenient benchmark, as its size
+//! and depth can be inspected and its cost estimated with
+//! [`Circuit::estimate_cost`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::mimc::{self, Mimc};
+//! use smol_mpc::mpc;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let cipher: Mimc<Fp> = Mimc::new(&mut prg);
+//!
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! let mut parties = vec![&mut alice, &mut bob];
+//!
+//! mpc::simulate_random_dist("key", &mut parties, &Fp::new(1234), &mut prg);
+//! mpc::simulate_random_dist("x", &mut parties, &Fp::new(42), &mut prg);
+//! mimc::mimc_protocol(&mut parties, &cipher, "key", "x", "y", Some(&mut prg));
+//!
+//! let expected = cipher.encrypt(&Fp::new(1234), &Fp::new(42));
+//! assert_eq!(mpc::reconstruct_share(&parties, "y").value(), expected.value());
+//! ```
+
+use crate::circuit::{Circuit, CircuitBuilder, Wire};
+use crate::math::mersenne::MersenneField;
+use crate::mpc;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+/// Returns the smallest exponent $d \geq 3$ such that $x^d$ is a permutation
+/// of the field.
+fn exponent<T: MersenneField>() -> u64 {
+    let gcd = |mut a: u64, mut b: u64| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    (3..).find(|&d| gcd(d, T::ORDER - 1) == 1).unwrap()
+}
+
+/// Defines an instance of MiMC, given by its round constants.
+#[derive(Clone)]
+pub struct Mimc<T: MersenneField> {
+    exponent: u64,
+    constants: Vec<T>,
+}
+
+impl<T: MersenneField> Mimc<T> {
+    /// Creates an instance with random round constants, except the first one,
+    /// which is zero.
+    pub fn new(prg: &mut Prg) -> Self {
+        let exponent = exponent::<T>();
+        let n_rounds = (T::POWER as f64 / (exponent as f64).log2()).ceil() as usize;
+        let constants = std::iter::once(T::new(0))
+            .chain((1..n_rounds).map(|_| T::random(&mut *prg)))
+            .collect();
+        Self {
+            exponent,
+            constants,
+        }
+    }
+
+    /// Returns the exponent $d$ of the rounds.
+    pub fn exponent(&self) -> u64 {
+        self.exponent
+    }
+
+    /// Returns the number of rounds.
+    pub fn n_rounds(&self) -> usize {
+        self.constants.len()
+    }
+
+    /// Returns the round constants.
+    pub fn constants(&self) -> &[T] {
+        &self.constants
+    }
+
+    /// Encrypts a message with the provided key.
+    pub fn encrypt(&self, key: &T, message: &T) -> T {
+        self.constants
+            .iter()
+            .fold(message.clone(), |state, constant| {
+                state.add(key).add(constant).pow(self.exponent)
+            })
+            .add(key)
+    }
+
+    /// Returns the circuit of the cipher, whose first input is the key and
+    /// whose second input is the message. Each power is computed with square
+    /// and multiply, so a round needs $\lfloor \log_2 d \rfloor$ squarings and
+    /// one multiplication for each other bit set in $d$.
+    pub fn circuit(&self) -> Circuit<T> {
+        let mut builder = CircuitBuilder::new();
+        let key = builder.input();
+        let mut state = builder.input();
+        for constant in &self.constants {
+            let constant = builder.constant(constant.clone());
+            let shifted = builder.add(key, constant);
+            let base = builder.add(state, shifted);
+            state = pow(&mut builder, base, self.exponent);
+        }
+        let result = builder.add(state, key);
+        builder.output(result);
+        builder.build()
+    }
+}
+
+/// Adds the gates that raise a wire to a power to a circuit.
+fn pow<T: MersenneField>(builder: &mut CircuitBuilder<T>, base: Wire, exponent: u64) -> Wire {
+    let mut result = base;
+    for bit in (0..u64::BITS - 1 - exponent.leading_zeros()).rev() {
+        result = builder.mul(result, result);
+        if (exponent >> bit) & 1 == 1 {
+            result = builder.mul(result, base);
+        }
+    }
+    result
+}
+
+/// Encrypts a secret-shared message with a secret-shared key.
+///
+/// The parties evaluate the circuit of the cipher with
+/// [`mpc::evaluate_circuit`] on the key with ID `id_key` and the message with
+/// ID `id_message`, and hold the shares of the ciphertext under the ID
+/// `id_result`. The triples are generated first if `preprocessing` contains a
+/// PRG; otherwise, they must have been registered beforehand.
+pub fn mimc_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    cipher: &Mimc<T>,
+    id_key: &str,
+    id_message: &str,
+    id_result: &str,
+    preprocessing: Option<&mut Prg>,
+) where
+    T: MersenneField,
+{
+    mpc::evaluate_circuit(
+        parties,
+        &cipher.circuit(),
+        &[id_key, id_message],
+        &[id_result],
+        preprocessing,
+    );
+}
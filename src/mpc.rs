@@ -8,266 +8,3572 @@
 //! - A protocol to distribute shares of a value.
 //! - A protocol to reconstruct a value from its shares.
 //! - A protocol to generate correlated randomness needed in the execution of
-//! the protocol.
+//!   the protocol.
 //!
 //! At the time of writting, we only support one protocol based on additive
 //! secret-sharing schemes using Beaver triples for multiplications with passive
 //! security. From the list presented above, we only cover the first four
 //! elements. The generation or correlated randomness via secure protocols is
-//! not implemented yet. Those functionalities are emulated using PRGs.
+//! not implemented yet. Those functionalities are emulated using PRGs, except
+//! for random shared values, which can be generated without interaction using
+//! pseudo-random secret-sharing (see [`prss_setup`]).
 
+pub mod gmw;
+
+use crate::circuit::{Circuit, Gate, Wire};
+use crate::event::Event;
+use crate::fss;
+use crate::handle::{BoolHandle, BoolRef, SecretHandle, SecretRef};
+use crate::math::fixed;
+use crate::math::matrix::Matrix;
 use crate::math::mersenne::MersenneField;
+use crate::network::{MissingParty, Payload, Simulator};
+use crate::preprocessing::{
+    ComparisonKey, ComparisonKeyHandle, DaBitHandle, EdaBit, EdaBitHandle, SquareHandle,
+    TripleHandle,
+};
+use crate::utils::ot;
+use crate::utils::paillier::{self, BigUint};
 use crate::utils::prg::Prg;
-use crate::vm::VirtualMachine;
+use crate::vm::{Snapshot, VirtualMachine};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Maximum number of bits of the values, in their signed representation, that
+/// can be truncated with [`trunc_protocol`].
+pub const TRUNC_INPUT_BITS: u32 = 40;
+
+/// Statistical security parameter, in bits, of [`trunc_protocol`].
+pub const TRUNC_STAT_SECURITY: u32 = 20;
+
+/// Statistical security parameter, in bits, of the mask used in the HE-based
+/// triple generation (see [`he_triple_protocol`]).
+pub const HE_TRIPLE_STAT_SECURITY: u64 = 40;
+
+/// Represents an additive share of a private element in certain algebraic
+/// structure.
+///
+/// We need to make clear that the `value` field **do not** represent the value
+/// that the share is trying to hide. On the contrary, this field stores the
+/// value that a party holds once the shares of a private element have been
+/// computed and distributed.
+#[derive(Clone)]
+pub struct Share<T: MersenneField> {
+    /// ID of the share in memory.
+    pub id: String,
+
+    /// Value that the share holds.
+    pub value: T,
+}
+
+impl<T: MersenneField> Share<T> {
+    /// Creates a new share with a given value.
+    pub(crate) fn new(id: &str, value: T) -> Self {
+        Self {
+            id: id.to_string(),
+            value,
+        }
+    }
+}
+
+/// Represents a key used in pseudo-random secret-sharing (PRSS).
+///
+/// The key is known by all the parties in `subset`, and it is used to derive a
+/// stream of pseudo-random elements that is the same for all of them.
+#[derive(Clone)]
+pub struct PrssKey {
+    /// IDs of the parties that know the key.
+    pub subset: Vec<String>,
+
+    /// Pseudo-random generator seeded with the key.
+    pub prg: Prg,
+}
+
+/// Executes the setup phase of pseudo-random secret-sharing (PRSS).
+///
+/// For each subset of $n - t$ parties, where $n$ is the number of parties and
+/// $t$ is the provided threshold, the first party of the subset samples a
+/// random key with its own PRG and sends it to all the parties in the subset.
+/// Any coalition of at most $t$ parties misses the key of the subset formed by
+/// the remaining parties, so it can not predict the random values generated
+/// afterwards with [`VirtualMachine::prss_random_share`].
+pub fn prss_setup<T>(parties: &mut [&mut VirtualMachine<T>], threshold: usize)
+where
+    T: MersenneField,
+{
+    if threshold >= parties.len() {
+        panic!("The threshold must be smaller than the number of parties.");
+    }
+
+    let ids: Vec<String> = parties.iter().map(|party| party.id.clone()).collect();
+    for subset in subsets(parties.len(), parties.len() - threshold) {
+        let seed = parties[subset[0]].prg.next(32);
+        let subset_ids: Vec<String> = subset.iter().map(|&i| ids[i].clone()).collect();
+        for i in subset {
+            parties[i].prss_keys.push(PrssKey {
+                subset: subset_ids.clone(),
+                prg: Prg::new(Some(seed.clone())),
+            });
+        }
+    }
+}
+
+/// Sets up a PRG shared by each pair of parties.
+///
+/// For each pair of parties, the first one samples a seed with its own PRG and
+/// sends it to the second one, so both can derive the same pseudo-random values
+/// locally afterwards (see [`VirtualMachine::pairwise_prg`]). For instance, the
+/// parties $i < j$ can obtain shares of zero if $i$ adds and $j$ subtracts the
+/// same value.
+pub fn pairwise_setup<T>(parties: &mut [&mut VirtualMachine<T>])
+where
+    T: MersenneField,
+{
+    for i in 0..parties.len() {
+        for j in i + 1..parties.len() {
+            let seed = parties[i].prg.next(32);
+            let (id_i, id_j) = (parties[i].id.clone(), parties[j].id.clone());
+            parties[i].insert_pairwise_prg(&id_j, Prg::new(Some(seed.clone())));
+            parties[j].insert_pairwise_prg(&id_i, Prg::new(Some(seed)));
+        }
+    }
+}
+
+/// Returns all the subsets of `{0, ..., n - 1}` of size `k` in lexicographic
+/// order.
+pub(crate) fn subsets(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+
+    let mut result = Vec::new();
+    for first in 0..n {
+        for rest in subsets(n - first - 1, k - 1) {
+            let mut subset = vec![first];
+            subset.extend(rest.iter().map(|i| i + first + 1));
+            result.push(subset);
+        }
+    }
+    result
+}
+
+/// Distributes a share among a set of parties.
+///
+/// This function distributes shares of a value stored in the private memory of
+/// a party with ID `id_owner`. The distribution of shares is done among the
+/// parties provided in the parameter `parties`. The shares computed and
+/// distributed will be stored in the share memory of each parties with the ID
+/// `id_var` (i.e. with the same ID that the owner has in its private memory).
+/// The randomness of the shares is drawn from the PRG of the owner.
+pub fn distribute_shares<T>(
+    id_var: &str,
+    id_owner: &str,
+    mut parties: Vec<&mut VirtualMachine<T>>,
+) -> SecretHandle<T>
+where
+    T: MersenneField,
+{
+    let owner = parties
+        .iter()
+        .position(|party| party.id == id_owner)
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+    let value = parties[owner].get_priv_value(id_var).clone();
+
+    let n_parties = parties.len();
+    let shares = additive_shares(&value, n_parties, &mut parties[owner].prg);
+    let received = scatter(
+        &parties,
+        owner,
+        shares.into_iter().map(|share| vec![share]).collect(),
+    );
+    for (party, mut values) in parties.into_iter().zip(received) {
+        party.insert_share(id_var, Share::new(id_var, values.remove(0)));
+    }
+
+    SecretHandle::new(id_var)
+}
+
+/// Distributes shares of several values among a set of parties.
+///
+/// This is the batched version of [`distribute_shares`]: every value with an ID
+/// in `ids_var` stored in the private memory of the party with ID `id_owner` is
+/// split into shares, which are stored in the share memory of each party under
+/// the same ID. The owner is looked up only once for the whole batch.
+pub fn distribute_shares_many<T>(
+    ids_var: &[&str],
+    id_owner: &str,
+    mut parties: Vec<&mut VirtualMachine<T>>,
+) where
+    T: MersenneField,
+{
+    let owner = parties
+        .iter()
+        .position(|party| party.id == id_owner)
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+    let values: Vec<T> = ids_var
+        .iter()
+        .map(|id| parties[owner].get_priv_value(id).clone())
+        .collect();
+
+    let n_parties = parties.len();
+    let prg = &mut parties[owner].prg;
+    let mut shares: Vec<Vec<T>> = (0..n_parties - 1)
+        .map(|_| values.iter().map(|_| T::random(&mut *prg)).collect())
+        .collect();
+    let last_shares = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            shares.iter().fold(value.clone(), |acc, party_shares| {
+                acc.subtract(&party_shares[i])
+            })
+        })
+        .collect();
+    shares.push(last_shares);
+
+    let received = scatter(&parties, owner, shares);
+    for (party, party_shares) in parties.into_iter().zip(received) {
+        for (id, value) in ids_var.iter().zip(party_shares) {
+            party.insert_share(id, Share::new(id, value));
+        }
+    }
+}
+
+/// Multiplicates two secret-shared values distributed among a set of parties.
+///
+/// This protocol executes the multiplication between two secret-shared values
+/// whose shares has been distributed and stored in the memory of the parties
+/// involved in the protocol. The multiplication is executed using the next
+/// unused multiplication triple $(a, b, c)$ of the preprocessing store of the
+/// parties (see [`register_triple`] and [`generate_triples`]). The parties open
+/// $\epsilon = x - a$ and $\delta = y - b$, and compute locally the shares of
+/// $c + \epsilon b + \delta a + \epsilon \delta$, where the last term is added
+/// only by the first party. At the end of the execution of the protocol, the
+/// parties will end up with the shares of the product under the ID `id_result`
+/// stored in the share memory.
+pub fn mult_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_x: impl SecretRef<T>,
+    id_y: impl SecretRef<T>,
+    id_result: &str,
+) -> SecretHandle<T>
+where
+    T: MersenneField,
+{
+    let triples: Vec<Vec<(T, T, T)>> = next_stored_triples(parties)
+        .into_iter()
+        .map(|triple| vec![triple])
+        .collect();
+    let x: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, &[id_x.id()]))
+        .collect();
+    let y: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, &[id_y.id()]))
+        .collect();
+
+    let products = beaver_mult(parties, &x, &y, &triples);
+    for (party, mut values) in parties.iter_mut().zip(products) {
+        party.insert_share(id_result, Share::new(id_result, values.remove(0)));
+    }
+
+    SecretHandle::new(id_result)
+}
+
+/// Squares a secret-shared value distributed among a set of parties.
+///
+/// The protocol consumes the next unused square pair $(r, r^2)$ of the
+/// preprocessing store of the parties (see [`generate_square_pairs`]). The
+/// parties open $e = x - r$ and compute locally the shares of
+/// $x^2 = r^2 + 2 e r + e^2$, where the last term is added only by the first
+/// party. Compared to [`mult_protocol`], only one value is opened and the
+/// preprocessing material is smaller. At the end of the execution of the
+/// protocol, the parties will end up with the shares of the square under the
+/// ID `id_result`.
+pub fn square_protocol<T>(parties: &mut [&mut VirtualMachine<T>], id_x: &str, id_result: &str)
+where
+    T: MersenneField,
+{
+    let x = local_column(parties, id_x);
+    let square = square_local(parties, &x);
+    for (party, value) in parties.iter_mut().zip(square) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Computes the local shares of the square of a secret-shared value given by
+/// the local shares of each party, consuming the next stored square pair.
+fn square_local<T: MersenneField>(parties: &mut [&mut VirtualMachine<T>], x: &[T]) -> Vec<T> {
+    let mut handles = Vec::new();
+    let mut pairs = Vec::new();
+    for party in parties.iter_mut() {
+        let (handle, pair) = party.preprocessing.next_square().unwrap_or_else(|| {
+            panic!("Not enough square pairs to compute the square.");
+        });
+        handles.push(handle);
+        pairs.push(pair);
+    }
+    if handles.iter().any(|handle| *handle != handles[0]) {
+        panic!("The parties do not agree on the next square pair.");
+    }
+
+    let e = open_single(
+        parties,
+        x.iter()
+            .zip(&pairs)
+            .map(|(share, (r, _))| share.subtract(r))
+            .collect(),
+    );
+
+    let two_e = e.add(&e);
+    pairs
+        .into_iter()
+        .enumerate()
+        .map(|(j, (r, r_square))| {
+            let value = r_square.add(&r.multiply(&two_e));
+            if j == 0 {
+                value.add(&e.multiply(&e))
+            } else {
+                value
+            }
+        })
+        .collect()
+}
+
+/// Converts a boolean-shared bit into an arithmetic sharing of the same bit.
+///
+/// The protocol consumes the next unused daBit $(r, r)$ of the preprocessing
+/// store of the parties (see [`generate_dabits`]). The parties open the
+/// boolean value $c = x \oplus r$, and compute locally the arithmetic shares
+/// of $x = c + r - 2 c r$, that is, $r$ if $c = 0$ and $1 - r$ otherwise. At
+/// the end of the execution of the protocol, the parties will end up with the
+/// arithmetic shares of the bit under the ID `id_result` in the share memory.
+pub fn b2a_protocol<T>(parties: &mut [&mut VirtualMachine<T>], id_bool: &str, id_result: &str)
+where
+    T: MersenneField,
+{
+    let dabits = next_stored_dabits(parties);
+    let masked: Vec<Vec<bool>> = parties
+        .iter()
+        .zip(&dabits)
+        .map(|(party, (_, r))| vec![party.get_bool_share(id_bool) ^ r])
+        .collect();
+    let c = Simulator::new(parties).with_tag("b2a").open_bits(&masked)[0];
+
+    for (j, (party, (r, _))) in parties.iter_mut().zip(dabits).enumerate() {
+        let value = match (c, j) {
+            (false, _) => r,
+            (true, 0) => T::new(1).subtract(&r),
+            (true, _) => r.negate(),
+        };
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Converts an arithmetic sharing of a bit into a boolean sharing of the same
+/// bit.
+///
+/// The protocol consumes the next unused daBit $(r, r)$ and the next unused
+/// multiplication triple of the preprocessing store of the parties. The
+/// parties compute the arithmetic shares of $x \oplus r = x + r - 2 x r$ with
+/// the triple, open it as $c$, and set the boolean shares of $x = c \oplus r$,
+/// where $c$ is added only by the first party. The value with ID `id` must be
+/// zero or one. At the end of the execution of the protocol, the parties will
+/// end up with the boolean shares of the bit under the ID `id_result` in the
+/// boolean share memory.
+pub fn a2b_protocol<T>(parties: &mut [&mut VirtualMachine<T>], id: &str, id_result: &str)
+where
+    T: MersenneField,
+{
+    let dabits = next_stored_dabits(parties);
+    let triples: Vec<Vec<(T, T, T)>> = next_stored_triples(parties)
+        .into_iter()
+        .map(|triple| vec![triple])
+        .collect();
+
+    let x: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, &[id]))
+        .collect();
+    let r: Vec<Vec<T>> = dabits.iter().map(|(r, _)| vec![r.clone()]).collect();
+    let xr = beaver_mult(parties, &x, &r, &triples);
+    let c = open_single(
+        parties,
+        (0..parties.len())
+            .map(|j| x[j][0].add(&r[j][0]).subtract(&xr[j][0].add(&xr[j][0])))
+            .collect(),
+    );
+
+    for (j, (party, (_, r_bool))) in parties.iter_mut().zip(dabits).enumerate() {
+        let share = if j == 0 {
+            (c.value() == 1) ^ r_bool
+        } else {
+            r_bool
+        };
+        party.insert_bool_share(id_result, share);
+    }
+}
+
+/// Pulls the next unused edaBit from the preprocessing store of every party,
+/// checking that it has the expected number of bits.
+fn next_stored_edabits<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+    n_bits: usize,
+) -> Vec<EdaBit<T>> {
+    let mut handles = Vec::new();
+    let mut edabits = Vec::new();
+    for party in parties.iter_mut() {
+        let (handle, edabit) = party.preprocessing.next_edabit().unwrap_or_else(|| {
+            panic!("Not enough edaBits to run the protocol.");
+        });
+        if edabit.bits.len() != n_bits {
+            panic!("The next edaBit does not have {} bits.", n_bits);
+        }
+        handles.push(handle);
+        edabits.push(edabit);
+    }
+
+    if handles.iter().any(|handle| *handle != handles[0]) {
+        panic!("The parties do not agree on the next edaBit.");
+    }
+    edabits
+}
+
+/// Pulls the next unused comparison key from the preprocessing store of every
+/// party.
+fn next_stored_comparison_keys<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+) -> Vec<ComparisonKey<T>> {
+    let mut handles = Vec::new();
+    let mut keys = Vec::new();
+    for party in parties.iter_mut() {
+        let (handle, key) = party
+            .preprocessing
+            .next_comparison_key()
+            .unwrap_or_else(|| {
+                panic!("Not enough comparison keys to run the protocol.");
+            });
+        handles.push(handle);
+        keys.push(key);
+    }
+
+    if handles.iter().any(|handle| *handle != handles[0]) {
+        panic!("The parties do not agree on the next comparison key.");
+    }
+    keys
+}
+
+/// Pulls the next unused daBit from the preprocessing store of every party.
+fn next_stored_dabits<T: MersenneField>(parties: &mut [&mut VirtualMachine<T>]) -> Vec<(T, bool)> {
+    let mut handles = Vec::new();
+    let mut dabits = Vec::new();
+    for party in parties.iter_mut() {
+        let (handle, dabit) = party.preprocessing.next_dabit().unwrap_or_else(|| {
+            panic!("Not enough daBits to convert the sharing.");
+        });
+        handles.push(handle);
+        dabits.push(dabit);
+    }
+
+    if handles.iter().any(|handle| *handle != handles[0]) {
+        panic!("The parties do not agree on the next daBit.");
+    }
+    dabits
+}
+
+/// Registers a multiplication triple in the preprocessing store of the
+/// parties.
+///
+/// The shares of the triple must be stored in the share memory of the parties
+/// under the provided IDs, for example using [`generate_triple`]. The shares
+/// are moved from the share memory to the store, and the triples are consumed
+/// by [`mult_protocol`] in the order in which they were registered.
+pub fn register_triple<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_triple: (&str, &str, &str),
+) -> TripleHandle
+where
+    T: MersenneField,
+{
+    let handles: Vec<TripleHandle> = parties
+        .iter_mut()
+        .map(|party| {
+            let triple = local_triples(party, &[id_triple]).remove(0);
+            for id in [id_triple.0, id_triple.1, id_triple.2] {
+                party.remove_share(id);
+            }
+            party.preprocessing.add_triple(triple)
+        })
+        .collect();
+
+    common_handle(&handles)
+}
+
+/// Pulls the next unused triple from the preprocessing store of every party.
+fn next_stored_triples<T: MersenneField>(parties: &mut [&mut VirtualMachine<T>]) -> Vec<(T, T, T)> {
+    let mut handles = Vec::new();
+    let mut triples = Vec::new();
+    for party in parties.iter_mut() {
+        let (handle, triple) = party.preprocessing.next_triple().unwrap_or_else(|| {
+            panic!("Not enough triples to compute the multiplication.");
+        });
+        handles.push(handle);
+        triples.push(triple);
+    }
+
+    common_handle(&handles);
+    triples
+}
+
+/// Checks that all the parties refer to the same preprocessed material.
+fn common_handle(handles: &[TripleHandle]) -> TripleHandle {
+    if handles.iter().any(|handle| *handle != handles[0]) {
+        panic!("The parties do not agree on the next triple.");
+    }
+    handles[0]
+}
+
+/// Multiplicates several pairs of secret-shared values in a single round.
+///
+/// The $i$-th value with ID in `ids_x` is multiplied with the $i$-th value with
+/// ID in `ids_y`, consuming the $i$-th triple in `triples`. All the values
+/// $\epsilon_i$ and $\delta_i$ are opened together, and no intermediate values
+/// are stored in the memory of the parties. At the end of the execution of the
+/// protocol, the parties will end up with the shares of the $i$-th product
+/// under the $i$-th ID in `ids_result`.
+pub fn mult_many<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_x: &[&str],
+    ids_y: &[&str],
+    ids_result: &[&str],
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    if ids_x.len() != ids_y.len() || ids_x.len() != ids_result.len() {
+        panic!("The number of IDs of the operands and the results must match.");
+    }
+    if triples.len() < ids_x.len() {
+        panic!("Not enough triples to compute the products.");
+    }
+
+    let x: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, ids_x))
+        .collect();
+    let y: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, ids_y))
+        .collect();
+    let local_triples: Vec<_> = parties
+        .iter()
+        .map(|party| local_triples(party, &triples[..ids_x.len()]))
+        .collect();
+
+    let products = beaver_mult(parties, &x, &y, &local_triples);
+    for (party, values) in parties.iter_mut().zip(products) {
+        for (id, value) in ids_result.iter().zip(values) {
+            party.insert_share(id, Share::new(id, value));
+        }
+    }
+}
+
+/// Computes the inner product of two secret-shared vectors.
+///
+/// The vectors are given as the IDs of their secret-shared entries, and one
+/// multiplication triple is needed for each entry. In contrast with calling
+/// [`mult_protocol`] once for each entry, all the values $\epsilon_i$ and
+/// $\delta_i$ are opened together in a single round, and the products are
+/// added locally afterwards. At the end of the protocol, the parties will end up
+/// with shares of the inner product under the ID `id_result` stored in the
+/// share memory.
+pub fn inner_product_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_x: &[&str],
+    ids_y: &[&str],
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    if ids_x.len() != ids_y.len() || ids_x.len() != triples.len() {
+        panic!("The vectors and the triples must have the same length.");
+    }
+
+    let x: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, ids_x))
+        .collect();
+    let y: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, ids_y))
+        .collect();
+    let local_triples: Vec<_> = parties
+        .iter()
+        .map(|party| local_triples(party, triples))
+        .collect();
+
+    // All the products are computed with a single opening round and added
+    // locally.
+    let products = beaver_mult(parties, &x, &y, &local_triples);
+    for (party, values) in parties.iter_mut().zip(products) {
+        let value = values.iter().fold(T::new(0), |acc, elem| acc.add(elem));
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Multiplicates two secret-shared matrices distributed among a set of
+/// parties.
+///
+/// A secret-shared matrix is represented as a [`Matrix`] of IDs, where each
+/// entry is stored in the share memory of the parties with the corresponding
+/// ID. The multiplication is executed using a matrix triple $(A, B, C)$ with
+/// $C = AB$ (see [`generate_matrix_triple`]), which plays the same role as a
+/// Beaver triple: the parties open $E = X - A$ and $D = Y - B$, and compute
+/// locally $C + EB + AD + ED$. At the end of the execution of the protocol, the
+/// parties will end up with the shares of the product stored under the IDs
+/// of `ids_result`.
+pub fn matmul_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_x: &Matrix<&str>,
+    ids_y: &Matrix<&str>,
+    ids_result: &Matrix<&str>,
+    triple_ids: (&Matrix<&str>, &Matrix<&str>, &Matrix<&str>),
+) where
+    T: MersenneField,
+{
+    if ids_result.rows() != ids_x.rows() || ids_result.cols() != ids_y.cols() {
+        panic!("The result matrix does not have the dimensions of the product.");
+    }
+
+    // Computing E and D, which are opened together in a single round.
+    let masked: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| {
+            let share_e = local_matrix(party, ids_x).subtract(&local_matrix(party, triple_ids.0));
+            let share_d = local_matrix(party, ids_y).subtract(&local_matrix(party, triple_ids.1));
+            share_e.iter().chain(share_d.iter()).cloned().collect()
+        })
+        .collect();
+    let mut opened = Simulator::new(parties).with_tag("matmul").open(&masked);
+    let opened_d = opened.split_off(ids_x.rows() * ids_x.cols());
+    let e = to_matrix(opened, ids_x.cols());
+    let d = to_matrix(opened_d, ids_y.cols());
+
+    for (j, party) in parties.iter_mut().enumerate() {
+        let share_a = local_matrix(party, triple_ids.0);
+        let share_b = local_matrix(party, triple_ids.1);
+        let share_c = local_matrix(party, triple_ids.2);
+
+        let mut share_z = share_c
+            .add(&e.multiply(&share_b))
+            .add(&share_a.multiply(&d));
+        if j == 0 {
+            share_z = share_z.add(&e.multiply(&d));
+        }
+
+        for (id, value) in ids_result.iter().zip(share_z.iter()) {
+            party.insert_share(id, Share::new(id, value.clone()));
+        }
+    }
+}
+
+/// Multiplicates many secret-shared values distributed among a set of parties.
+///
+/// The product of the $k$ values with IDs `ids` is computed as a balanced tree
+/// of multiplications: in each level, the values are multiplied in pairs using
+/// one Beaver triple per pair, and all the pairs of the level are opened in the
+/// same round. Hence, the protocol needs $k - 1$ triples and $\lceil \log_2 k
+/// \rceil$ rounds. The intermediate products are not stored in the memory of
+/// the parties. At the end of the execution of the protocol, the parties will
+/// end up with the shares of the product under the ID `id_result`.
+pub fn product_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids: &[&str],
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    if ids.is_empty() {
+        panic!("At least one value is needed to compute a product.");
+    }
+    if triples.len() < ids.len() - 1 {
+        panic!("Not enough triples to compute the product.");
+    }
+
+    let mut level: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| local_values(party, ids))
+        .collect();
+
+    let mut remaining_triples = triples.iter();
+    while level[0].len() > 1 {
+        let n_pairs = level[0].len() / 2;
+        let level_triples: Vec<_> = remaining_triples.by_ref().take(n_pairs).copied().collect();
+
+        let x: Vec<Vec<T>> = level
+            .iter()
+            .map(|values| values.iter().step_by(2).take(n_pairs).cloned().collect())
+            .collect();
+        let y: Vec<Vec<T>> = level
+            .iter()
+            .map(|values| values.iter().skip(1).step_by(2).cloned().collect())
+            .collect();
+        let local_triples: Vec<_> = parties
+            .iter()
+            .map(|party| local_triples(party, &level_triples))
+            .collect();
+
+        let mut next_level = beaver_mult(parties, &x, &y, &local_triples);
+
+        // The last value is moved to the next level if it has no pair.
+        if level[0].len() % 2 == 1 {
+            for (next_values, values) in next_level.iter_mut().zip(&level) {
+                next_values.push(values[values.len() - 1].clone());
+            }
+        }
+        level = next_level;
+    }
+
+    for (party, values) in parties.iter_mut().zip(level) {
+        party.insert_share(id_result, Share::new(id_result, values[0].clone()));
+    }
+}
+
+/// Raises a secret-shared value to a public exponent.
+///
+/// The power is computed with the square-and-multiply algorithm over the
+/// shares, where each squaring and each multiplication consumes one Beaver
+/// triple from `triples`. For an exponent $e \geq 1$ with $\ell$ bits, of which
+/// $w$ are ones, the protocol needs $(\ell - 1) + (w - 1)$ triples. If $e = 0$,
+/// the result is a sharing of one and no triple is used. The intermediate
+/// powers are not stored in the memory of the parties. At the end of the
+/// execution of the protocol, the parties will end up with the shares of the
+/// power under the ID `id_result`.
+pub fn pow_const_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_x: &str,
+    exp: u64,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    let base = local_column(parties, id_x);
+
+    let result = pow_local(parties, &base, exp, triples);
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Computes the local shares of a power of a secret-shared value given by the
+/// local shares of each party.
+fn pow_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    base: &[T],
+    exp: u64,
+    triples: &[(&str, &str, &str)],
+) -> Vec<T> {
+    if exp == 0 {
+        return public_local(parties, T::new(1));
+    }
+
+    let highest_bit = u64::BITS - 1 - exp.leading_zeros();
+    let needed_triples = (highest_bit + exp.count_ones() - 1) as usize;
+    if triples.len() < needed_triples {
+        panic!("Not enough triples to compute the power.");
+    }
+
+    let mut remaining_triples = triples.iter();
+    let mut result = base.to_vec();
+    for bit in (0..highest_bit).rev() {
+        let triple = remaining_triples.next().unwrap();
+        result = beaver_mult_single(parties, &result, &result, triple);
+        if (exp >> bit) & 1 == 1 {
+            let triple = remaining_triples.next().unwrap();
+            result = beaver_mult_single(parties, &result, base, triple);
+        }
+    }
+    result
+}
+
+/// Tests if two secret-shared values are equal.
+///
+/// The protocol relies on Fermat's little theorem: for $d = a - b$, the value
+/// $d^{p - 1}$ is one if $d \neq 0$ and zero otherwise. Hence, the parties
+/// compute $1 - d^{p - 1}$ using [`pow_const_protocol`], obtaining shares of a
+/// bit that is one if and only if both values are equal. The exponentiation
+/// consumes the triples in `triples`; for $p = 2^{61} - 1$, 119 triples are
+/// needed. At the end of the execution of the protocol, the parties will end up
+/// with the shares of the bit under the ID `id_result`.
+pub fn equals_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: &str,
+    id_b: &str,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    let diff: Vec<T> = parties
+        .iter()
+        .map(|party| {
+            party
+                .get_share(id_a)
+                .value
+                .subtract(&party.get_share(id_b).value)
+        })
+        .collect();
+
+    let is_nonzero = pow_local(parties, &diff, T::ORDER - 1, triples);
+    for (j, (party, value)) in parties.iter_mut().zip(is_nonzero).enumerate() {
+        let bit = if j == 0 {
+            T::new(1).subtract(&value)
+        } else {
+            value.negate()
+        };
+        party.insert_share(id_result, Share::new(id_result, bit));
+    }
+}
+
+/// Computes the inverse of a secret-shared value.
+///
+/// The protocol uses the masking trick with the provided Beaver triple
+/// $(a, b, c)$: the mask is $r = b$, and the parties open $\epsilon = x - a$ to
+/// compute shares of $x \cdot r = c + \epsilon \cdot b$, which they open as
+/// well. Since $a$ and $r$ are uniformly random, the opened values reveal
+/// nothing about $x$. The parties then invert $x \cdot r$ publicly and multiply
+/// it by their shares of $r$, obtaining shares of $x^{-1}$ under the ID
+/// `id_result`.
+///
+/// If the opened value is zero, the parties open the mask as well. If the mask
+/// is zero, which happens with negligible probability, they retry with the next
+/// triple of the preprocessing store (see [`generate_triples`]). Otherwise, the
+/// shared value is zero and the function returns [`NotInvertible`].
+pub fn inverse_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_x: &str,
+    id_result: &str,
+    triple_id: (&str, &str, &str),
+) -> Result<(), NotInvertible>
+where
+    T: MersenneField,
+{
+    let x = local_column(parties, id_x);
+
+    let inverse = masked_inverse(parties, &x, &triple_id)?;
+    for (party, value) in parties.iter_mut().zip(inverse) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+    Ok(())
+}
+
+/// Divides two secret-shared values in the field.
+///
+/// The protocol computes shares of the inverse of the denominator with the
+/// same masking trick used in [`inverse_protocol`], and then multiplies it by
+/// the numerator. Hence, it consumes two Beaver triples: the first one is used
+/// for the inversion and the second one for the multiplication. At the end of
+/// the execution of the protocol, the parties will end up with the shares of
+/// the quotient under the ID `id_result`.
+///
+/// The function returns [`NotInvertible`] if the denominator is zero.
+pub fn div_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_num: &str,
+    id_den: &str,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) -> Result<(), NotInvertible>
+where
+    T: MersenneField,
+{
+    if triples.len() < 2 {
+        panic!("The division needs two triples.");
+    }
+
+    let num = local_column(parties, id_num);
+    let den = local_column(parties, id_den);
+
+    let den_inverse = masked_inverse(parties, &den, &triples[0])?;
+    let quotient = beaver_mult_single(parties, &num, &den_inverse, &triples[1]);
+    for (party, value) in parties.iter_mut().zip(quotient) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+    Ok(())
+}
+
+/// Reports that a secret-shared value could not be inverted because it is
+/// zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotInvertible;
+
+impl fmt::Display for NotInvertible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The shared value is zero and can not be inverted.")
+    }
+}
+
+impl std::error::Error for NotInvertible {}
+
+/// Computes the local shares of the inverse of a secret-shared value given by
+/// the local shares of each party.
+fn masked_inverse<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+    x: &[T],
+    triple_id: &(&str, &str, &str),
+) -> Result<Vec<T>, NotInvertible> {
+    let mut triples: Vec<(T, T, T)> = parties
+        .iter()
+        .map(|party| local_triples(party, std::slice::from_ref(triple_id)).remove(0))
+        .collect();
+
+    loop {
+        let x_minus_a: Vec<T> = x
+            .iter()
+            .zip(&triples)
+            .map(|(share_x, (a, _, _))| share_x.subtract(a))
+            .collect();
+        let epsilon = open_single(parties, x_minus_a);
+        let xr: Vec<T> = triples
+            .iter()
+            .map(|(_, b, c)| c.add(&epsilon.multiply(b)))
+            .collect();
+        let xr = open_single(parties, xr);
+
+        let r: Vec<T> = triples.iter().map(|(_, b, _)| b.clone()).collect();
+        if xr.value() != 0 {
+            let xr_inv = xr.inverse();
+            return Ok(r.iter().map(|share_r| share_r.multiply(&xr_inv)).collect());
+        }
+        // The mask is discarded, so opening it reveals only whether x is zero.
+        if open_single(parties, r).value() != 0 {
+            return Err(NotInvertible);
+        }
+        triples = next_stored_triples(parties);
+    }
+}
+
+/// Selects obliviously one of two secret-shared values.
+///
+/// Given shares of a bit $b$, which may come from the output of a comparison or
+/// an equality test, the parties compute shares of $b \cdot x + (1 - b) \cdot
+/// y$, that is, $x$ if $b = 1$ and $y$ if $b = 0$. The value is computed as
+/// $y + b \cdot (x - y)$, which needs a single multiplication using the
+/// provided Beaver triple. No party learns which value was selected. At the end
+/// of the execution of the protocol, the parties will end up with the shares of
+/// the selected value under the ID `id_result`.
+pub fn select_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_bit: &str,
+    id_x: &str,
+    id_y: &str,
+    id_result: &str,
+    triple_id: (&str, &str, &str),
+) where
+    T: MersenneField,
+{
+    let bit = local_column(parties, id_bit);
+    let x = local_column(parties, id_x);
+    let y = local_column(parties, id_y);
+
+    let result = select_local(parties, &bit, &x, &y, &triple_id);
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Computes the local shares of the oblivious selection between two
+/// secret-shared values given by the local shares of each party.
+fn select_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    bit: &[T],
+    x: &[T],
+    y: &[T],
+    triple_id: &(&str, &str, &str),
+) -> Vec<T> {
+    let diff: Vec<T> = x.iter().zip(y).map(|(a, b)| a.subtract(b)).collect();
+    beaver_mult_single(parties, bit, &diff, triple_id)
+        .iter()
+        .zip(y)
+        .map(|(product, share_y)| product.add(share_y))
+        .collect()
+}
+
+/// Compares two secret-shared values.
+///
+/// The parties obtain shares of a bit that is one if and only if $a < b$, where
+/// both values are interpreted as signed integers in the range
+/// $(-p / 4, p / 4)$. The protocol uses the fact that $a < b$ if and only if
+/// $2(a - b)$, interpreted as an element of $[0, p)$, is odd. The least
+/// significant bit of $x = 2(a - b)$ is extracted by masking $x$ with a random
+/// value $r$ whose bits $r_i$ are secret-shared, opening $c = x + r$, and
+/// computing $x_0 = c_0 \oplus r_0 \oplus \[c < r\]$, where the comparison
+/// $\[c < r\]$ is computed bitwise using a logarithmic-round prefix-OR.
+///
+/// The protocol consumes the random bits with IDs in `bits` (see
+/// [`generate_random_bit`]) and the Beaver triples in `triples`. The number of
+/// each of them needed is given by [`less_than_cost`]. At the end of the
+/// execution of the protocol, the parties will end up with the shares of the
+/// bit under the ID `id_result`. For two parties, [`less_than_fss_protocol`]
+/// computes the same bit with function secret sharing instead.
+pub fn less_than_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: &str,
+    id_b: &str,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+    bits: &[&str],
+) where
+    T: MersenneField,
+{
+    let a = local_column(parties, id_a);
+    let b = local_column(parties, id_b);
+
+    let mut preproc = Preproc::new(triples, bits);
+    let result = less_than_local(parties, &a, &b, &mut preproc);
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Returns the number of Beaver triples and the number of random bits, in that
+/// order, needed by [`less_than_protocol`] to compare two values.
+pub fn less_than_cost<T: MersenneField>() -> (usize, usize) {
+    lsb_cost::<T>()
+}
+
+/// Compares two secret-shared values using an edaBit.
+///
+/// This protocol is the same as [`less_than_protocol`], but the mask $r$ and
+/// its bits are taken from the next unused edaBit of the preprocessing store of
+/// the parties, which must have as many bits as the field modulus (see
+/// [`generate_edabits`]), instead of being composed from independent random
+/// bits. The Beaver triples needed for the bitwise comparison are taken from
+/// `triples`; their number is the first value returned by [`less_than_cost`].
+/// At the end of the execution of the protocol, the parties will end up with
+/// the shares of the bit under the ID `id_result`.
+pub fn less_than_edabit_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: &str,
+    id_b: &str,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    let edabits = next_stored_edabits(parties, T::POWER as usize);
+    let r_bits = transpose(
+        &edabits
+            .into_iter()
+            .map(|edabit| edabit.bits)
+            .collect::<Vec<_>>(),
+    );
+
+    let double_diff: Vec<T> = local_column(parties, id_a)
+        .iter()
+        .zip(local_column(parties, id_b))
+        .map(|(share_a, share_b)| {
+            let diff = share_a.subtract(&share_b);
+            diff.add(&diff)
+        })
+        .collect();
+
+    let mut preproc = Preproc::new(triples, &[]);
+    let result = lsb_masked_local(parties, &double_diff, &r_bits, &mut preproc);
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Compares two secret-shared values using function secret sharing.
+///
+/// This protocol computes the same bit as [`less_than_protocol`], but the
+/// least significant bit of $x = 2(a - b)$ is extracted with the next unused
+/// comparison key of the preprocessing store of the parties (see
+/// [`generate_comparison_keys`]). The parties open $c = x + r$ for the mask $r$
+/// of the key and evaluate their keys of the distributed comparison function
+/// at $c$, which gives shares of $r_0 \oplus \[c < r\]$ without interaction. Then,
+/// $x_0 = c_0 \oplus r_0 \oplus \[c < r\]$ is computed locally. In contrast with
+/// the bitwise comparison, the protocol takes a single round and consumes no
+/// Beaver triples, but the keys have the size of $O(\log p)$ seeds and the
+/// protocol only works for two parties. At the end of the execution of the
+/// protocol, the parties will end up with the shares of the bit under the ID
+/// `id_result`. The function panics if there are not exactly two parties.
+pub fn less_than_fss_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: &str,
+    id_b: &str,
+    id_result: &str,
+) where
+    T: MersenneField,
+{
+    if parties.len() != 2 {
+        panic!("The comparison based on FSS needs exactly two parties.");
+    }
+
+    let keys = next_stored_comparison_keys(parties);
+    let masked: Vec<T> = local_column(parties, id_a)
+        .iter()
+        .zip(local_column(parties, id_b))
+        .zip(&keys)
+        .map(|((share_a, share_b), key)| {
+            let diff = share_a.subtract(&share_b);
+            diff.add(&diff).add(&key.mask)
+        })
+        .collect();
+    let c = open_single(parties, masked).value();
+
+    let r0_xor_less: Vec<T> = keys
+        .iter()
+        .map(|key| key.mask_lsb.add(&key.dcf.eval(c)))
+        .collect();
+    let result = if c & 1 == 1 {
+        one_minus_local(&r0_xor_less)
+    } else {
+        r0_xor_less
+    };
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Extracts the least significant bit of a secret-shared value.
+///
+/// The parties mask the value $x$ with a random value $r$ whose bits $r_i$ are
+/// secret-shared, open $c = x + r$, and compute the bit as $c_0 \oplus r_0
+/// \oplus \[c < r\]$: since $p$ is odd, the reduction modulo $p$ of $x + r$
+/// flips the parity exactly when $c < r$. The comparison between the public
+/// value $c$ and the bits of $r$ uses a logarithmic-round prefix-OR. This is
+/// much cheaper than a full bit decomposition of $x$.
+///
+/// The protocol consumes the random bits with IDs in `bits` and the Beaver
+/// triples in `triples`; the number of each of them is given by [`lsb_cost`].
+/// At the end of the execution of the protocol, the parties will end up with
+/// the shares of the bit under the ID `id_result`.
+pub fn lsb_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id: &str,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+    bits: &[&str],
+) where
+    T: MersenneField,
+{
+    let x = local_column(parties, id);
+
+    let mut preproc = Preproc::new(triples, bits);
+    let result = lsb_local(parties, &x, &mut preproc);
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Returns the number of Beaver triples and the number of random bits, in that
+/// order, needed by [`lsb_protocol`].
+pub fn lsb_cost<T: MersenneField>() -> (usize, usize) {
+    let n_bits = T::POWER as usize;
+    (prefix_mults(n_bits) + 1, n_bits)
+}
+
+/// Computes the maximum of a list of secret-shared values.
+///
+/// The values are compared in a balanced tree: in each level, the values are
+/// compared in pairs using [`less_than_protocol`], and the greater value of
+/// each pair is selected obliviously using [`select_protocol`]. Hence, for $k$
+/// values, the protocol executes $k - 1$ comparisons and $k - 1$ selections,
+/// consuming $k - 1$ times the triples and bits given by [`less_than_cost`]
+/// plus $k - 1$ additional triples. At the end of the execution of the
+/// protocol, the parties will end up with the shares of the maximum under the
+/// ID `id_result`.
+pub fn max_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids: &[&str],
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+    bits: &[&str],
+) where
+    T: MersenneField,
+{
+    let mut preproc = Preproc::new(triples, bits);
+    let (max, _) = max_local(parties, ids, false, &mut preproc);
+    for (party, value) in parties.iter_mut().zip(max) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Computes the position of the maximum of a list of secret-shared values.
+///
+/// The protocol works like [`max_protocol`], but the parties also select
+/// obliviously the index of the greater value of each pair, so it consumes
+/// $k - 1$ additional triples for $k$ values. At the end of the execution of the
+/// protocol, the parties will end up with the shares of the index of the
+/// maximum, as an integer starting from zero, under the ID `id_result`. If the
+/// maximum appears more than once, the first index is returned.
+pub fn argmax_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids: &[&str],
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+    bits: &[&str],
+) where
+    T: MersenneField,
+{
+    let mut preproc = Preproc::new(triples, bits);
+    let (_, index) = max_local(parties, ids, true, &mut preproc);
+    for (party, value) in parties.iter_mut().zip(index) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Sorts obliviously a list of secret-shared values.
+///
+/// The values are sorted in ascending order using Batcher's odd-even merge
+/// sorting network. The sequence of compare-exchange operations of the network
+/// depends only on the number of values, so the parties do not learn anything
+/// about the order of the values. Each compare-exchange computes
+/// $b = \[y < x\]$ with [`less_than_protocol`], the minimum $y b + x (1 - b)$
+/// with one multiplication, and the maximum as $x + y$ minus the minimum. See
+/// [`sort_cost`] for the number of triples and random bits consumed. At the end
+/// of the execution of the protocol, the parties will end up with the shares of
+/// the sorted values under the IDs `ids_out`.
+pub fn sort_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_in: &[&str],
+    ids_out: &[&str],
+    triples: &[(&str, &str, &str)],
+    bits: &[&str],
+) where
+    T: MersenneField,
+{
+    if ids_in.len() != ids_out.len() {
+        panic!("The input and output lists must have the same length.");
+    }
+
+    let mut preproc = Preproc::new(triples, bits);
+    let mut values: Vec<Vec<T>> = ids_in.iter().map(|id| local_column(parties, id)).collect();
+    for (i, j) in batcher_network(ids_in.len()) {
+        let swap = less_than_local(parties, &values[j], &values[i], &mut preproc);
+        let min = select_local(
+            parties,
+            &swap,
+            &values[j],
+            &values[i],
+            preproc.next_triple(),
+        );
+        let max: Vec<T> = values[i]
+            .iter()
+            .zip(&values[j])
+            .zip(&min)
+            .map(|((share_x, share_y), share_min)| share_x.add(share_y).subtract(share_min))
+            .collect();
+
+        values[i] = min;
+        values[j] = max;
+    }
+
+    for (id, value) in ids_out.iter().zip(values) {
+        for (party, share) in parties.iter_mut().zip(value) {
+            party.insert_share(id, Share::new(id, share));
+        }
+    }
+}
+
+/// Returns the number of Beaver triples and the number of random bits, in that
+/// order, needed by [`sort_protocol`] to sort `n` values.
+pub fn sort_cost<T: MersenneField>(n: usize) -> (usize, usize) {
+    let (triples, bits) = less_than_cost::<T>();
+    let comparators = batcher_network(n).len();
+    (comparators * (triples + 1), comparators * bits)
+}
+
+/// Returns the compare-exchange operations of Batcher's odd-even merge sorting
+/// network for `n` values.
+///
+/// The network for the next power of two is truncated, which is equivalent to
+/// padding the input with values greater than all the others.
+fn batcher_network(n: usize) -> Vec<(usize, usize)> {
+    let mut comparators = Vec::new();
+    let mut p = 1;
+    while p < n {
+        let mut k = p;
+        while k >= 1 {
+            let mut j = k % p;
+            while j + k < n {
+                for i in 0..k.min(n - j - k) {
+                    if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                        comparators.push((i + j, i + j + k));
+                    }
+                }
+                j += 2 * k;
+            }
+            k /= 2;
+        }
+        p *= 2;
+    }
+    comparators
+}
+
+/// Computes the prefix-OR of a list of secret-shared bits.
+///
+/// The parties obtain shares of $y_i = x_0 \lor \dots \lor x_i$ for each
+/// position $i$, stored under the IDs `ids_out`. The protocol needs
+/// $\lceil \log_2 n \rceil$ rounds for $n$ bits, and the number of Beaver
+/// triples consumed is given by [`prefix_cost`].
+pub fn prefix_or_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_in: &[&str],
+    ids_out: &[&str],
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    prefix_protocol(parties, ids_in, ids_out, triples, PrefixOp::Or);
+}
+
+/// Computes the prefix-AND of a list of secret-shared bits.
+///
+/// The parties obtain shares of $y_i = x_0 \land \dots \land x_i$ for each
+/// position $i$, stored under the IDs `ids_out`. Since the AND of two bits is
+/// their product, this is the same as [`prefix_product_protocol`].
+pub fn prefix_and_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_in: &[&str],
+    ids_out: &[&str],
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    prefix_protocol(parties, ids_in, ids_out, triples, PrefixOp::Product);
+}
+
+/// Computes the prefix-products of a list of secret-shared values.
+///
+/// The parties obtain shares of $y_i = x_0 \cdot \dots \cdot x_i$ for each
+/// position $i$, stored under the IDs `ids_out`. The protocol needs
+/// $\lceil \log_2 n \rceil$ rounds for $n$ values, and the number of Beaver
+/// triples consumed is given by [`prefix_cost`].
+pub fn prefix_product_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_in: &[&str],
+    ids_out: &[&str],
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    prefix_protocol(parties, ids_in, ids_out, triples, PrefixOp::Product);
+}
+
+/// Returns the number of Beaver triples needed by the prefix protocols for a
+/// list of `n` values.
+pub fn prefix_cost(n: usize) -> usize {
+    prefix_mults(n)
+}
+
+fn prefix_protocol<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_in: &[&str],
+    ids_out: &[&str],
+    triples: &[(&str, &str, &str)],
+    op: PrefixOp,
+) {
+    if ids_in.len() != ids_out.len() {
+        panic!("The input and output lists must have the same length.");
+    }
+
+    let mut preproc = Preproc::new(triples, &[]);
+    let values: Vec<Vec<T>> = ids_in.iter().map(|id| local_column(parties, id)).collect();
+    let prefixes = prefix_local(parties, values, op, &mut preproc);
+    for (id, value) in ids_out.iter().zip(prefixes) {
+        for (party, share) in parties.iter_mut().zip(value) {
+            party.insert_share(id, Share::new(id, share));
+        }
+    }
+}
+
+/// Evaluates a public table at a secret-shared index.
+///
+/// The index $i$ must be in the range $[0, n)$, where $n$ is the length of the
+/// table. The parties first compute shares of the powers $i, i^2, \dots,
+/// i^{n - 1}$ with the prefix-product protocol, in $\lceil \log_2 (n - 1)
+/// \rceil$ rounds. Then, they compute locally the one-hot encoding of the
+/// index: the $k$-th entry is the Lagrange basis polynomial
+/// $L_k(X) = \prod_{m \neq k} (X - m) / (k - m)$ evaluated at $i$, which is
+/// one if $i = k$ and zero for the rest of the positions in the range. Since the
+/// polynomials are public, evaluating them on the powers is a linear operation.
+/// Finally, the inner product of the one-hot encoding with the table is also
+/// computed locally. The number of Beaver triples consumed is given by
+/// [`lookup_cost`]. At the end of the execution of the protocol, the parties
+/// will end up with the shares of `table[i]` under the ID `id_result`.
+pub fn lookup_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    table: &[T],
+    id_index: &str,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    if table.is_empty() {
+        panic!("The table must have at least one entry.");
+    }
+
+    let index = local_column(parties, id_index);
+    let mut preproc = Preproc::new(triples, &[]);
+
+    let mut powers = vec![public_local(parties, T::new(1))];
+    let copies: Vec<Vec<T>> = (1..table.len()).map(|_| index.clone()).collect();
+    powers.extend(prefix_local(
+        parties,
+        copies,
+        PrefixOp::Product,
+        &mut preproc,
+    ));
+
+    let basis = lagrange_basis::<T>(table.len());
+    let mut result: Vec<T> = (0..parties.len()).map(|_| T::new(0)).collect();
+    for (entry, coefficients) in table.iter().zip(basis) {
+        for (power, coefficient) in powers.iter().zip(coefficients) {
+            let scale = coefficient.multiply(entry);
+            for (share, share_power) in result.iter_mut().zip(power) {
+                *share = share.add(&share_power.multiply(&scale));
+            }
+        }
+    }
+
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Returns the number of Beaver triples needed by [`lookup_protocol`] for a
+/// table with `n` entries.
+pub fn lookup_cost(n: usize) -> usize {
+    prefix_mults(n.saturating_sub(1))
+}
+
+/// Evaluates a public polynomial at a secret-shared point.
+///
+/// The coefficients are given from the constant term upwards, that is,
+/// `coeffs[i]` multiplies $x^i$. The polynomial is evaluated with Horner's
+/// rule, $c_0 + x (c_1 + x (c_2 + \dots + x c_d))$. The innermost product is
+/// computed locally because $c_d$ is public, and each of the remaining
+/// products consumes one Beaver triple from `triples`, so a polynomial of
+/// degree $d \geq 1$ needs $d - 1$ triples and $d - 1$ rounds. At the end of the
+/// execution of the protocol, the parties will end up with the shares of the
+/// evaluation under the ID `id_result`.
+pub fn poly_eval_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    coeffs: &[T],
+    id_x: &str,
+    id_result: &str,
+    triples: &[(&str, &str, &str)],
+) where
+    T: MersenneField,
+{
+    let x = local_column(parties, id_x);
+
+    let result = match coeffs.split_last() {
+        None => public_local(parties, T::new(0)),
+        Some((leading, [])) => public_local(parties, leading.clone()),
+        Some((leading, rest)) => {
+            if triples.len() < rest.len() - 1 {
+                panic!("Not enough triples to evaluate the polynomial.");
+            }
+
+            let mut acc: Vec<T> = x.iter().map(|share| share.multiply(leading)).collect();
+            let mut remaining_triples = triples.iter();
+            for (i, coeff) in rest.iter().enumerate().rev() {
+                if i < rest.len() - 1 {
+                    let triple = remaining_triples.next().unwrap();
+                    acc = beaver_mult_single(parties, &acc, &x, triple);
+                }
+                acc[0] = acc[0].add(coeff);
+            }
+            acc
+        }
+    };
+
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Evaluates a circuit on secret-shared inputs.
+///
+/// The $i$-th input of the circuit is the value shared under the $i$-th ID in
+/// `ids_inputs`. Additions and constants are computed locally, and the
+/// multiplications whose inputs are ready are computed together in a single
+/// round as in [`mult_many`], so the number of rounds is the multiplicative
+/// depth of the circuit. Boolean gates are computed on bits shared over the
+/// field as in [`Circuit::evaluate`], so AND and XOR gates need one
+/// multiplication each and NOT gates are free. The intermediate wires are kept
+/// as local shares, so no temporary IDs are stored in the memory of the
+/// parties.
+///
+/// Every multiplication consumes the next triple of the preprocessing store.
+/// If `preprocessing` contains a PRG, the triples needed by the circuit are
+/// first generated with [`generate_triples`]; otherwise, they must have been
+/// registered beforehand. At the end, the parties hold the shares of the
+/// $i$-th output under the $i$-th ID in `ids_outputs`.
+pub fn evaluate_circuit<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    circuit: &Circuit<T>,
+    ids_inputs: &[&str],
+    ids_outputs: &[&str],
+    preprocessing: Option<&mut Prg>,
+) where
+    T: MersenneField,
+{
+    if ids_inputs.len() != circuit.inputs.len() || ids_outputs.len() != circuit.outputs.len() {
+        panic!("The number of IDs does not match the inputs and outputs of the circuit.");
+    }
+
+    if let Some(prg) = preprocessing {
+        let n_mults = circuit
+            .gates
+            .iter()
+            .filter(|gate| matches!(gate, Gate::Mul(..) | Gate::And(..) | Gate::Xor(..)))
+            .count();
+        generate_triples(parties, n_mults, prg);
+    }
+
+    // wires[w][j] holds the share of the wire w held by the party j.
+    let mut wires: Vec<Option<Vec<T>>> = vec![None; circuit.n_wires];
+    for (wire, id) in circuit.inputs.iter().zip(ids_inputs) {
+        wires[*wire] = Some(local_column(parties, id));
+    }
+
+    let mut pending: Vec<&Gate<T>> = circuit.gates.iter().collect();
+    while !pending.is_empty() {
+        // Computes the local gates that are ready, and collects the
+        // multiplications that are ready to be computed in this round.
+        let mut mults = Vec::new();
+        let mut blocked = Vec::new();
+        for gate in pending {
+            if gate.inputs().iter().any(|wire| wires[*wire].is_none()) {
+                blocked.push(gate);
+                continue;
+            }
+
+            let get = |wire: &Wire| wires[*wire].clone().unwrap();
+            let shares = match gate {
+                Gate::Add(a, b, _) => get(a).iter().zip(get(b)).map(|(u, v)| u.add(&v)).collect(),
+                Gate::Const(value, _) => public_local(parties, value.clone()),
+                Gate::Inv(a, _) => {
+                    let one = public_local(parties, T::new(1));
+                    one.iter()
+                        .zip(get(a))
+                        .map(|(u, v)| u.subtract(&v))
+                        .collect()
+                }
+                Gate::Mul(..) | Gate::And(..) | Gate::Xor(..) => {
+                    mults.push(gate);
+                    continue;
+                }
+            };
+            wires[gate.output()] = Some(shares);
+        }
+
+        if mults.is_empty() {
+            if let Some(gate) = blocked.first() {
+                panic!(
+                    "The wire {} depends on wires that are never evaluated.",
+                    gate.output()
+                );
+            }
+            break;
+        }
+
+        let triples: Vec<Vec<(T, T, T)>> = (0..mults.len())
+            .map(|_| next_stored_triples(parties))
+            .collect();
+        let operand = |k: usize| -> Vec<Vec<T>> {
+            let values: Vec<Vec<T>> = mults
+                .iter()
+                .map(|gate| wires[gate.inputs()[k]].clone().unwrap())
+                .collect();
+            transpose(&values)
+        };
+        let triples = transpose(&triples);
+        let products = transpose(&beaver_mult(parties, &operand(0), &operand(1), &triples));
+        for (gate, product) in mults.iter().zip(products) {
+            let shares = match gate {
+                Gate::Xor(a, b, _) => {
+                    let (u, v) = (wires[*a].as_ref().unwrap(), wires[*b].as_ref().unwrap());
+                    u.iter()
+                        .zip(v)
+                        .zip(product)
+                        .map(|((u, v), uv)| u.add(v).subtract(&uv.add(&uv)))
+                        .collect()
+                }
+                _ => product,
+            };
+            wires[gate.output()] = Some(shares);
+        }
+
+        pending = blocked;
+    }
+
+    for (wire, id) in circuit.outputs.iter().zip(ids_outputs) {
+        let shares = wires[*wire]
+            .clone()
+            .unwrap_or_else(|| panic!("The wire {} has not been evaluated.", wire));
+        for (party, value) in parties.iter_mut().zip(shares) {
+            party.insert_share(id, Share::new(id, value));
+        }
+    }
+}
+
+/// Returns the coefficients, from the constant one, of the Lagrange basis
+/// polynomials for the points $0, \dots, n - 1$.
+fn lagrange_basis<T: MersenneField>(n: usize) -> Vec<Vec<T>> {
+    (0..n)
+        .map(|k| {
+            let mut coefficients = vec![T::new(1)];
+            let mut denominator = T::new(1);
+            for m in (0..n).filter(|&m| m != k) {
+                // Multiplication by (X - m).
+                let point = T::new(m as u64);
+                let mut next = vec![T::new(0); coefficients.len() + 1];
+                for (d, coefficient) in coefficients.iter().enumerate() {
+                    next[d + 1] = next[d + 1].add(coefficient);
+                    next[d] = next[d].subtract(&coefficient.multiply(&point));
+                }
+                coefficients = next;
+                denominator = denominator.multiply(&T::new(k as u64).subtract(&point));
+            }
+
+            let inverse = denominator.inverse();
+            coefficients
+                .iter()
+                .map(|coefficient| coefficient.multiply(&inverse))
+                .collect()
+        })
+        .collect()
+}
+
+/// Preprocessed material consumed by the protocols that need more than one
+/// kind of correlated randomness.
+struct Preproc<'p> {
+    triples: std::slice::Iter<'p, (&'p str, &'p str, &'p str)>,
+    bits: std::slice::Iter<'p, &'p str>,
+}
+
+impl<'p> Preproc<'p> {
+    fn new(triples: &'p [(&'p str, &'p str, &'p str)], bits: &'p [&'p str]) -> Self {
+        Self {
+            triples: triples.iter(),
+            bits: bits.iter(),
+        }
+    }
+
+    fn next_triple(&mut self) -> &'p (&'p str, &'p str, &'p str) {
+        self.triples
+            .next()
+            .unwrap_or_else(|| panic!("Not enough triples to execute the protocol."))
+    }
+
+    fn next_bit(&mut self) -> &'p str {
+        self.bits
+            .next()
+            .unwrap_or_else(|| panic!("Not enough random bits to execute the protocol."))
+    }
+}
+
+/// Computes the local shares of the maximum of the secret-shared values with the
+/// given IDs, and optionally of its index.
+fn max_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    ids: &[&str],
+    with_index: bool,
+    preproc: &mut Preproc,
+) -> (Vec<T>, Vec<T>) {
+    if ids.is_empty() {
+        panic!("At least one value is needed to compute the maximum.");
+    }
+
+    // Each candidate is a pair with the local shares of the value and of its
+    // index.
+    let mut level: Vec<(Vec<T>, Vec<T>)> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            (
+                local_column(parties, id),
+                public_local(parties, T::new(i as u64)),
+            )
+        })
+        .collect();
+
+    while level.len() > 1 {
+        let mut next_level = Vec::new();
+        for pair in level.chunks(2) {
+            if let [(x, index_x), (y, index_y)] = pair {
+                let x_less = less_than_local(parties, x, y, preproc);
+                let max = select_local(parties, &x_less, y, x, preproc.next_triple());
+                let index = if with_index {
+                    select_local(parties, &x_less, index_y, index_x, preproc.next_triple())
+                } else {
+                    index_x.clone()
+                };
+                next_level.push((max, index));
+            } else {
+                next_level.push(pair[0].clone());
+            }
+        }
+        level = next_level;
+    }
+
+    level.remove(0)
+}
+
+/// Computes the local shares of the comparison between two secret-shared
+/// values given by the local shares of each party.
+fn less_than_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    a: &[T],
+    b: &[T],
+    preproc: &mut Preproc,
+) -> Vec<T> {
+    let double_diff: Vec<T> = a
+        .iter()
+        .zip(b)
+        .map(|(share_a, share_b)| {
+            let diff = share_a.subtract(share_b);
+            diff.add(&diff)
+        })
+        .collect();
+
+    lsb_local(parties, &double_diff, preproc)
+}
+
+/// Computes the local shares of the least significant bit of a secret-shared
+/// value given by the local shares of each party.
+fn lsb_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    x: &[T],
+    preproc: &mut Preproc,
+) -> Vec<T> {
+    // The bits of the mask, from the least significant one.
+    let r_bits: Vec<Vec<T>> = (0..T::POWER)
+        .map(|_| local_column(parties, preproc.next_bit()))
+        .collect();
+
+    lsb_masked_local(parties, x, &r_bits, preproc)
+}
+
+/// Computes the local shares of the least significant bit of a secret-shared
+/// value using a mask whose bits are given by their local shares, starting from
+/// the least significant one.
+fn lsb_masked_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    x: &[T],
+    r_bits: &[Vec<T>],
+    preproc: &mut Preproc,
+) -> Vec<T> {
+    let mut r: Vec<T> = (0..parties.len()).map(|_| T::new(0)).collect();
+    for (i, bit) in r_bits.iter().enumerate() {
+        let power = T::new(1 << i);
+        for (share_r, share_bit) in r.iter_mut().zip(bit) {
+            *share_r = share_r.add(&share_bit.multiply(&power));
+        }
+    }
+
+    let c = open_single(
+        parties,
+        x.iter()
+            .zip(&r)
+            .map(|(share_x, share_r)| share_x.add(share_r))
+            .collect(),
+    )
+    .value();
+
+    let c_less = bit_less_than_local(parties, c, r_bits, preproc);
+    let r0_xor_less = xor_local(parties, &r_bits[0], &c_less, preproc);
+    if c & 1 == 1 {
+        one_minus_local(&r0_xor_less)
+    } else {
+        r0_xor_less
+    }
+}
+
+/// Computes the local shares of the bit $\[c < r\]$, where $c$ is a public value
+/// and the bits of $r$ are secret-shared, starting from the least significant
+/// one.
+fn bit_less_than_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    c: u64,
+    r_bits: &[Vec<T>],
+    preproc: &mut Preproc,
+) -> Vec<T> {
+    // The bits where c and r differ.
+    let diff_bits: Vec<Vec<T>> = r_bits
+        .iter()
+        .enumerate()
+        .map(|(i, bit)| {
+            if (c >> i) & 1 == 1 {
+                one_minus_local(bit)
+            } else {
+                bit.clone()
+            }
+        })
+        .collect();
+
+    // Prefix-OR from the most significant bit: or_bits[i] is one if c and r
+    // differ in some position greater or equal than i.
+    let reversed_bits: Vec<Vec<T>> = diff_bits.into_iter().rev().collect();
+    let mut or_bits = prefix_local(parties, reversed_bits, PrefixOp::Or, preproc);
+    or_bits.reverse();
+
+    // The first position in which they differ is the only one in which the
+    // prefix-OR changes. There, c < r if the bit of c is zero.
+    let mut result: Vec<T> = (0..parties.len()).map(|_| T::new(0)).collect();
+    for i in 0..r_bits.len() {
+        if (c >> i) & 1 == 0 {
+            for (j, share) in result.iter_mut().enumerate() {
+                let mut first_diff = or_bits[i][j].clone();
+                if i + 1 < r_bits.len() {
+                    first_diff = first_diff.subtract(&or_bits[i + 1][j]);
+                }
+                *share = share.add(&first_diff);
+            }
+        }
+    }
+    result
+}
+
+/// Computes the local shares of the XOR of two secret-shared bits.
+fn xor_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    x: &[T],
+    y: &[T],
+    preproc: &mut Preproc,
+) -> Vec<T> {
+    let product = beaver_mult_single(parties, x, y, preproc.next_triple());
+    x.iter()
+        .zip(y)
+        .zip(product)
+        .map(|((share_x, share_y), share_xy)| {
+            share_x.add(share_y).subtract(&share_xy.add(&share_xy))
+        })
+        .collect()
+}
+
+/// Operation applied by [`prefix_local`].
+#[derive(Clone, Copy)]
+enum PrefixOp {
+    Or,
+    Product,
+}
+
+/// Computes the local shares of the prefixes of a list of secret-shared values
+/// given by the local shares of each party, that is, `values[i][j]` is the share
+/// of the $i$-th value held by the $j$-th party.
+///
+/// The prefixes are computed with the parallel prefix algorithm of Hillis and
+/// Steele: in the round with distance $d$, the value in position $i \geq d$ is
+/// combined with the value in position $i - d$. This needs $\lceil \log_2 n
+/// \rceil$ rounds, in which all the multiplications are executed in parallel.
+fn prefix_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    mut values: Vec<Vec<T>>,
+    op: PrefixOp,
+    preproc: &mut Preproc,
+) -> Vec<Vec<T>> {
+    let mut distance = 1;
+    while distance < values.len() {
+        let products = mult_batch_local(
+            parties,
+            &values[..values.len() - distance],
+            &values[distance..],
+            preproc,
+        );
+
+        for (i, product) in (distance..values.len()).zip(products) {
+            values[i] = match op {
+                PrefixOp::Product => product,
+                PrefixOp::Or => values[i]
+                    .iter()
+                    .zip(&values[i - distance])
+                    .zip(product)
+                    .map(|((x, y), xy)| x.add(y).subtract(&xy))
+                    .collect(),
+            };
+        }
+        distance *= 2;
+    }
+    values
+}
+
+/// Returns the number of multiplications executed by [`prefix_local`] for a
+/// list of `n` values.
+fn prefix_mults(n: usize) -> usize {
+    let mut mults = 0;
+    let mut distance = 1;
+    while distance < n {
+        mults += n - distance;
+        distance *= 2;
+    }
+    mults
+}
+
+/// Multiplicates in parallel pairs of secret-shared values given by the local
+/// shares of each party, that is, `x[i][j]` is the share of the $i$-th value
+/// held by the $j$-th party. The products have the same layout.
+fn mult_batch_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    x: &[Vec<T>],
+    y: &[Vec<T>],
+    preproc: &mut Preproc,
+) -> Vec<Vec<T>> {
+    if x.is_empty() {
+        return Vec::new();
+    }
+
+    let triple_ids: Vec<_> = (0..x.len()).map(|_| *preproc.next_triple()).collect();
+    let local_triples: Vec<_> = parties
+        .iter()
+        .map(|party| local_triples(party, &triple_ids))
+        .collect();
+
+    transpose(&beaver_mult(
+        parties,
+        &transpose(x),
+        &transpose(y),
+        &local_triples,
+    ))
+}
+
+/// Transposes a matrix given as a vector of rows.
+fn transpose<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    if rows.is_empty() {
+        return Vec::new();
+    }
+
+    (0..rows[0].len())
+        .map(|j| rows.iter().map(|row| row[j].clone()).collect())
+        .collect()
+}
+
+/// Computes the local shares of $1 - x$ for a secret-shared value $x$.
+fn one_minus_local<T: MersenneField>(x: &[T]) -> Vec<T> {
+    x.iter()
+        .enumerate()
+        .map(|(j, share)| {
+            if j == 0 {
+                T::new(1).subtract(share)
+            } else {
+                share.negate()
+            }
+        })
+        .collect()
+}
+
+/// Returns the local shares of a public value, where the first party holds the
+/// value and the rest of the parties hold zero.
+fn public_local<T: MersenneField>(parties: &[&mut VirtualMachine<T>], value: T) -> Vec<T> {
+    (0..parties.len())
+        .map(|j| if j == 0 { value.clone() } else { T::new(0) })
+        .collect()
+}
+
+/// Returns the share of the value with the given ID held by each party.
+fn local_column<T: MersenneField>(parties: &[&mut VirtualMachine<T>], id: &str) -> Vec<T> {
+    parties
+        .iter()
+        .map(|party| party.get_share(id).value.clone())
+        .collect()
+}
+
+/// Truncates a secret-shared fixed-point value.
+///
+/// The protocol implements the probabilistic truncation of Catrina and Saxena.
+/// Given shares of a value $a$ of at most [`TRUNC_INPUT_BITS`] bits (in its
+/// signed representation) and a random pair $(\[r\], \[r'\])$ generated with
+/// [`generate_trunc_pair`], where $r'$ holds the $m$ = `frac_bits` least
+/// significant bits of $r$, the parties open $c = 2^{k - 1} + a + r$. Then, they
+/// compute locally $\[a'\] = (c \bmod 2^m) - \[r'\]$ and
+/// $\[d\] = (\[a\] - \[a'\]) \cdot 2^{-m}$. The result $d$ is
+/// $\lfloor a / 2^m \rfloor$ or $\lfloor a / 2^m \rfloor + 1$, and it is closer
+/// to $a / 2^m$ with higher probability. The opened value $c$ hides $a$
+/// statistically with [`TRUNC_STAT_SECURITY`] bits of security. At the end of
+/// the execution of the protocol, the parties will end up with the shares of
+/// $d$ under the ID `id_result`.
+pub fn trunc_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id: &str,
+    frac_bits: u32,
+    id_result: &str,
+    mask_ids: (&str, &str),
+) where
+    T: MersenneField,
+{
+    let a = local_column(parties, id);
+
+    let r = local_column(parties, mask_ids.0);
+    let r_low = local_column(parties, mask_ids.1);
+    let result = trunc_local(parties, &a, frac_bits, &r, &r_low);
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Truncates a secret-shared fixed-point value using an edaBit.
+///
+/// This protocol is the same as [`trunc_protocol`], but the random pair
+/// $(\[r\], \[r'\])$ is obtained locally from the next unused edaBit of the
+/// preprocessing store of the parties, which must have [`TRUNC_INPUT_BITS`] +
+/// [`TRUNC_STAT_SECURITY`] bits (see [`generate_edabits`]): $r$ is the integer
+/// of the edaBit and $r'$ is the sum of its `frac_bits` least significant bits
+/// weighted by their powers of two. Hence, the same preprocessed material can
+/// be used for any number of fractional bits. At the end of the execution of
+/// the protocol, the parties will end up with the shares of the truncated value
+/// under the ID `id_result`.
+pub fn trunc_edabit_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id: &str,
+    frac_bits: u32,
+    id_result: &str,
+) where
+    T: MersenneField,
+{
+    let edabits = next_stored_edabits(parties, (TRUNC_INPUT_BITS + TRUNC_STAT_SECURITY) as usize);
+    let a = local_column(parties, id);
+    let r: Vec<T> = edabits.iter().map(|edabit| edabit.value.clone()).collect();
+    let r_low: Vec<T> = edabits
+        .iter()
+        .map(|edabit| {
+            edabit.bits[..frac_bits as usize]
+                .iter()
+                .enumerate()
+                .fold(T::new(0), |acc, (i, bit)| {
+                    acc.add(&bit.multiply(&T::new(1 << i)))
+                })
+        })
+        .collect();
+
+    let result = trunc_local(parties, &a, frac_bits, &r, &r_low);
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Computes the local shares of the truncation of a secret-shared value given
+/// by the local shares of each party.
+fn trunc_local<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    a: &[T],
+    frac_bits: u32,
+    r: &[T],
+    r_low: &[T],
+) -> Vec<T> {
+    let shift = T::new(1 << (TRUNC_INPUT_BITS - 1));
+    let c = open_single(
+        parties,
+        r.iter()
+            .zip(a)
+            .enumerate()
+            .map(|(j, (share_r, share_a))| {
+                let masked = share_a.add(share_r);
+                if j == 0 {
+                    masked.add(&shift)
+                } else {
+                    masked
+                }
+            })
+            .collect(),
+    );
+
+    let c_low = T::new(c.value() % (1 << frac_bits));
+    let scale = T::new(1 << frac_bits).inverse();
+    r_low
+        .iter()
+        .zip(a)
+        .enumerate()
+        .map(|(j, (share_r_low, share_a))| {
+            let share_a_low = if j == 0 {
+                c_low.subtract(share_r_low)
+            } else {
+                share_r_low.negate()
+            };
+            share_a.subtract(&share_a_low).multiply(&scale)
+        })
+        .collect()
+}
+
+/// Divides two secret-shared fixed-point values.
+///
+/// The protocol implements Goldschmidt's iterative division. The denominator
+/// $b$ must be in the interval $[0.5, 1)$, so that the initial approximation
+/// $w_0 = 2.9142 - 2b$ of $1 / b$ has a relative error of at most $0.0858$.
+/// Then, the parties compute $n_0 = a w_0$ and $d_0 = b w_0$, and in each
+/// iteration they update $n_{i + 1} = n_i (2 - d_i)$ and
+/// $d_{i + 1} = d_i (2 - d_i)$, so $d_i$ converges quadratically to one and
+/// $n_i$ converges to $a / b$. The number of iterations is chosen to achieve
+/// `frac_bits` bits of precision.
+///
+/// Each multiplication consumes one Beaver triple from `triples` and one
+/// truncation pair from `trunc_masks`; see [`fixed_div_cost`] to obtain how
+/// many of each are needed. At the end of the execution of the protocol, the
+/// parties will end up with the shares of the quotient under the ID
+/// `id_result`.
+pub fn fixed_div_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_num: &str,
+    id_den: &str,
+    id_result: &str,
+    frac_bits: u32,
+    triples: &[(&str, &str, &str)],
+    trunc_masks: &[(&str, &str)],
+) where
+    T: MersenneField,
+{
+    let cost = fixed_div_cost(frac_bits);
+    if triples.len() < cost || trunc_masks.len() < cost {
+        panic!("Not enough preprocessed material to compute the division.");
+    }
+
+    let num = local_column(parties, id_num);
+    let den = local_column(parties, id_den);
+
+    let mut step = 0;
+    let mut fixed_mult = |x: &[T], y: &[T]| {
+        let product = beaver_mult_single(parties, x, y, &triples[step]);
+        let r = local_column(parties, trunc_masks[step].0);
+        let r_low = local_column(parties, trunc_masks[step].1);
+        let result = trunc_local(parties, &product, frac_bits, &r, &r_low);
+        step += 1;
+        result
+    };
+
+    // Computes a public constant minus a secret-shared value locally.
+    let const_minus = |constant: f64, x: &[T]| -> Vec<T> {
+        x.iter()
+            .enumerate()
+            .map(|(j, share)| {
+                if j == 0 {
+                    fixed::encode::<T>(constant, frac_bits).subtract(share)
+                } else {
+                    share.negate()
+                }
+            })
+            .collect()
+    };
+
+    let twice_den: Vec<T> = den.iter().map(|share| share.add(share)).collect();
+    let w = const_minus(2.9142, &twice_den);
+    let mut n = fixed_mult(&num, &w);
+    let mut d = fixed_mult(&den, &w);
+    for _ in 0..(cost - 2) / 2 {
+        let f = const_minus(2.0, &d);
+        n = fixed_mult(&n, &f);
+        d = fixed_mult(&d, &f);
+    }
+
+    for (party, value) in parties.iter_mut().zip(n) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Returns the number of Beaver triples, which is the same as the number of
+/// truncation pairs, needed by [`fixed_div_protocol`] for the given number of
+/// fractional bits.
+pub fn fixed_div_cost(frac_bits: u32) -> usize {
+    // The initial approximation has about 3.5 bits of precision, and each
+    // iteration doubles the number of correct bits.
+    let mut iterations = 0;
+    let mut precision = 3.5;
+    while precision < frac_bits as f64 {
+        precision *= 2.0;
+        iterations += 1;
+    }
+
+    2 + 2 * iterations
+}
+
+/// Multiplicates one pair of secret-shared values given by the local shares of
+/// each party using the Beaver triple with the provided IDs.
+fn beaver_mult_single<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    x: &[T],
+    y: &[T],
+    triple_id: &(&str, &str, &str),
+) -> Vec<T> {
+    let x: Vec<Vec<T>> = x.iter().map(|value| vec![value.clone()]).collect();
+    let y: Vec<Vec<T>> = y.iter().map(|value| vec![value.clone()]).collect();
+    let local_triples: Vec<_> = parties
+        .iter()
+        .map(|party| local_triples(party, std::slice::from_ref(triple_id)))
+        .collect();
+
+    beaver_mult(parties, &x, &y, &local_triples)
+        .into_iter()
+        .map(|mut values| values.remove(0))
+        .collect()
+}
+
+/// Multiplicates pairs of secret-shared values using Beaver triples.
+///
+/// The values are given as the local shares of each party, that is, `x[j][i]`
+/// is the share of the $i$-th value held by the $j$-th party, and the same
+/// holds for `y` and `triples`. All the values $\epsilon_i$ and $\delta_i$ are
+/// opened in a single round through the simulated network. The result
+/// contains the local shares of the products with the same layout.
+fn beaver_mult<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    x: &[Vec<T>],
+    y: &[Vec<T>],
+    triples: &[Vec<(T, T, T)>],
+) -> Vec<Vec<T>> {
+    let n_values = x[0].len();
+    let masked: Vec<Vec<T>> = (0..x.len())
+        .map(|j| {
+            let epsilons = (0..n_values).map(|i| x[j][i].subtract(&triples[j][i].0));
+            let deltas = (0..n_values).map(|i| y[j][i].subtract(&triples[j][i].1));
+            epsilons.chain(deltas).collect()
+        })
+        .collect();
+    let mut opened = Simulator::new(parties).with_tag("beaver").open(&masked);
+    let deltas = opened.split_off(n_values);
+    let epsilons = opened;
+
+    triples
+        .iter()
+        .enumerate()
+        .map(|(j, party_triples)| {
+            party_triples
+                .iter()
+                .enumerate()
+                .map(|(i, (a, b, c))| {
+                    let mut value = c.add(&epsilons[i].multiply(b)).add(&deltas[i].multiply(a));
+                    if j == 0 {
+                        value = value.add(&epsilons[i].multiply(&deltas[i]));
+                    }
+                    value
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Sends to each party $j$ the values `values[j]` from the party in position
+/// `from`, and returns the values received by each party, in one round.
+fn scatter<T: MersenneField>(
+    parties: &[&mut VirtualMachine<T>],
+    from: usize,
+    values: Vec<Vec<T>>,
+) -> Vec<Vec<T>> {
+    let network = Simulator::new(parties).with_tag("input");
+    let mut values = values.into_iter();
+    let own_values: Vec<Vec<T>> = (0..parties.len())
+        .map(|to| {
+            let party_values = values.next().unwrap();
+            if to == from {
+                party_values
+            } else {
+                network.send(from, to, Payload::Elements(party_values));
+                Vec::new()
+            }
+        })
+        .collect();
+    network.deliver();
+
+    own_values
+        .into_iter()
+        .enumerate()
+        .map(|(to, party_values)| {
+            if to == from {
+                party_values
+            } else {
+                network.recv_from(to, from).payload.elements()
+            }
+        })
+        .collect()
+}
+
+/// Opens one secret-shared value given by the local share of each party.
+fn open_single<T: MersenneField>(parties: &[&mut VirtualMachine<T>], shares: Vec<T>) -> T {
+    let shares: Vec<Vec<T>> = shares.into_iter().map(|share| vec![share]).collect();
+    Simulator::new(parties)
+        .with_tag("open")
+        .open(&shares)
+        .remove(0)
+}
+
+/// Builds a matrix with `cols` columns from its entries in row-major order.
+fn to_matrix<T: MersenneField>(entries: Vec<T>, cols: usize) -> Matrix<T> {
+    Matrix::from_rows(entries.chunks(cols).map(|row| row.to_vec()).collect())
+}
+
+/// Returns the shares that a party holds for the values with the given IDs.
+fn local_values<T: MersenneField>(party: &VirtualMachine<T>, ids: &[&str]) -> Vec<T> {
+    ids.iter()
+        .map(|id| party.get_share(id).value.clone())
+        .collect()
+}
+
+/// Returns the shares that a party holds for the triples with the given IDs.
+fn local_triples<T: MersenneField>(
+    party: &VirtualMachine<T>,
+    triple_ids: &[(&str, &str, &str)],
+) -> Vec<(T, T, T)> {
+    triple_ids
+        .iter()
+        .map(|(a, b, c)| {
+            (
+                party.get_share(a).value.clone(),
+                party.get_share(b).value.clone(),
+                party.get_share(c).value.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the matrix of shares that a party holds for a secret-shared matrix.
+fn local_matrix<T: MersenneField>(party: &VirtualMachine<T>, ids: &Matrix<&str>) -> Matrix<T> {
+    ids.map(|id| party.get_share(id).value.clone())
+}
+
+/// Distributes shares of a publicly known value.
+///
+/// This method distributes shares among a set of parties of a publicly known
+/// value. The shares are stored in the share memory of each party using the
+/// provided ID.
+pub fn distribute_pub_value<T>(value: &T, id: &str, parties: &mut [&mut VirtualMachine<T>])
+where
+    T: MersenneField,
+{
+    parties[0].insert_share(id, Share::new(id, T::new(value.value())));
+    for party in parties.iter_mut().skip(1) {
+        party.insert_share(id, Share::new(id, T::new(0)));
+    }
+}
+
+/// Computes the secure multiplication of a publicly known value with a
+/// previously secret-shared value.
+///
+/// The function multiplies a public value with the shares with a private value
+/// that has been already secret-shared among the provided set of parties. The
+/// result of this computation will be shares of the result stored in the
+/// memory of each party under ID `id_result`.
+pub fn multiply_by_const_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    value: &T,
+    id: impl SecretRef<T>,
+    id_result: &str,
+) -> SecretHandle<T>
+where
+    T: MersenneField,
+{
+    for party in parties {
+        let share = party.get_share(id.id());
+        let value_mult = share.value.multiply(value);
+
+        let share_mult = Share::new(id_result, value_mult);
+        party.insert_share(id_result, share_mult);
+    }
+
+    SecretHandle::new(id_result)
+}
+
+/// Adds a publicly known value to a previously secret-shared value.
+///
+/// Only the first party adds the public value to its share, so the shares of
+/// the rest of the parties are copied unchanged. The result will be stored in
+/// the memory of each party under ID `id_result`.
+pub fn add_const_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    value: &T,
+    id: impl SecretRef<T>,
+    id_result: &str,
+) -> SecretHandle<T>
+where
+    T: MersenneField,
+{
+    affine_protocol(parties, &T::new(1), id.id(), value, id_result);
+    SecretHandle::new(id_result)
+}
+
+/// Applies a public affine transformation to a previously secret-shared value.
+///
+/// The parties compute locally the shares of $a x + b$, where $a$ and $b$ are
+/// public and $x$ is the value with ID `id`. The constant $b$ is added only by
+/// the first party. The result will be stored in the memory of each party under
+/// ID `id_result`.
+pub fn affine_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    a: &T,
+    id: &str,
+    b: &T,
+    id_result: &str,
+) where
+    T: MersenneField,
+{
+    for (i, party) in parties.iter_mut().enumerate() {
+        let mut value = party.get_share(id).value.multiply(a);
+        if i == 0 {
+            value = value.add(b);
+        }
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Runs a protocol inside a new scope of the memory of all the parties.
+///
+/// The scope named `name` is opened in every party before calling `protocol`
+/// and closed afterwards (see
+/// [`VirtualMachine::enter_scope`](crate::vm::VirtualMachine::enter_scope)), so
+/// all the values that the protocol stores are erased, and none of them
+/// collides with the IDs used outside. The protocol can still read the values
+/// of the outer scopes, and its results should be returned instead of being
+/// stored.
+pub fn scoped<T, R, F>(parties: &mut [&mut VirtualMachine<T>], name: &str, protocol: F) -> R
+where
+    T: MersenneField,
+    F: FnOnce(&mut [&mut VirtualMachine<T>]) -> R,
+{
+    for party in parties.iter_mut() {
+        party.enter_scope(name);
+        party.emit(|| Event::ProtocolStarted {
+            name: name.to_string(),
+        });
+    }
+    // The scope is closed even if the protocol panics, so a failed execution
+    // does not leave its intermediate values behind.
+    let output = panic::catch_unwind(AssertUnwindSafe(|| protocol(&mut *parties)));
+    for party in parties.iter_mut() {
+        party.exit_scope();
+        party.emit(|| Event::ProtocolFinished {
+            name: name.to_string(),
+        });
+    }
+    output.unwrap_or_else(|error| panic::resume_unwind(error))
+}
+
+/// Captures the state of all the parties (see [`VirtualMachine::snapshot`]).
+pub fn snapshot<T>(parties: &[&mut VirtualMachine<T>]) -> Vec<Snapshot<T>>
+where
+    T: MersenneField,
+{
+    parties.iter().map(|party| party.snapshot()).collect()
+}
+
+/// Rewinds all the parties to the states captured by [`snapshot`], which must
+/// be given in the same order as the parties.
+pub fn restore<T>(parties: &mut [&mut VirtualMachine<T>], snapshots: &[Snapshot<T>])
+where
+    T: MersenneField,
+{
+    if parties.len() != snapshots.len() {
+        panic!("The number of snapshots must match the number of parties.");
+    }
+    for (party, snapshot) in parties.iter_mut().zip(snapshots) {
+        party.restore(snapshot);
+    }
+}
+
+/// Returns a fresh ID for a temporary value, with the form `prefix#n`, that
+/// is not used in the memory of any of the parties.
+///
+/// Protocols use this function for their intermediate values instead of
+/// hard-coded IDs, so several executions of the same protocol, nested or one
+/// after the other, never collide with each other or with the IDs of the
+/// caller.
+pub fn temp_id<T>(parties: &mut [&mut VirtualMachine<T>], prefix: &str) -> String
+where
+    T: MersenneField,
+{
+    loop {
+        let id = parties[0].fresh_id(prefix);
+        if parties.iter().all(|party| !party.contains_id(&id)) {
+            return id;
+        }
+    }
+}
+
+/// Computes the secure subtraction between two secret shared values.
+///
+/// Computes the secure subraction between the shared value stored with ID
+/// `id_a` with the value store with ID `id_b`. The result of this function will
+/// be be the result of the operation stored as shares under the ID `id_result`.
+pub fn subtract_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: impl SecretRef<T>,
+    id_b: impl SecretRef<T>,
+    id_result: &str,
+) -> SecretHandle<T>
+where
+    T: MersenneField,
+{
+    // The intermediate value lives in its own scope, so it cannot collide with
+    // the IDs of the caller. The shares of `id_a` are read before, since the
+    // intermediate value would shadow them if they had the same ID.
+    let a = local_column(parties, id_a.id());
+    let negated = scoped(parties, "subtract", |parties| {
+        let id_negated = temp_id(parties, "subtraction");
+        multiply_by_const_protocol(&mut *parties, &T::new(1).negate(), id_b, &id_negated);
+        local_column(parties, &id_negated)
+    });
+
+    for ((party, share_a), share_negated) in parties.iter_mut().zip(a).zip(negated) {
+        let value = share_a.add(&share_negated);
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+
+    SecretHandle::new(id_result)
+}
+
+/// Adds two secret-shared values distributed among a set of parties.
+///
+/// This protocol executes the addition between two secret-shared values
+/// whose shares has been distributed and stored in the memory of the parties
+/// involved in the protocol. The addition is executed locally by the parties.
+/// At the end of the execution of the protocol, the parties will end up with
+/// the shares of the addition under the ID `id_result` stored in the share
+/// memory.
+pub fn add_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: impl SecretRef<T>,
+    id_b: impl SecretRef<T>,
+    id_result: &str,
+) -> SecretHandle<T>
+where
+    T: MersenneField,
+{
+    for party in parties {
+        let share_a = party.get_share(id_a.id());
+        let share_b = party.get_share(id_b.id());
+
+        let value_sum = share_a.value.add(&share_b.value);
+        let share_sum = Share::new(id_result, value_sum);
+        party.insert_share(id_result, share_sum);
+    }
+
+    SecretHandle::new(id_result)
+}
+
+/// Adds several pairs of secret-shared values distributed among a set of
+/// parties.
+///
+/// The $i$-th value with ID in `ids_a` is added to the $i$-th value with ID in
+/// `ids_b`, and the shares of the sum are stored under the $i$-th ID in
+/// `ids_result`. The additions are executed locally by the parties.
+pub fn add_many<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_a: &[&str],
+    ids_b: &[&str],
+    ids_result: &[&str],
+) where
+    T: MersenneField,
+{
+    if ids_a.len() != ids_b.len() || ids_a.len() != ids_result.len() {
+        panic!("The number of IDs of the operands and the results must match.");
+    }
+
+    for party in parties {
+        for ((id_a, id_b), id_result) in ids_a.iter().zip(ids_b).zip(ids_result) {
+            let value_sum = party
+                .get_share(id_a)
+                .value
+                .add(&party.get_share(id_b).value);
+            party.insert_share(id_result, Share::new(id_result, value_sum));
+        }
+    }
+}
+
+/// Computes a linear combination of secret-shared values with public
+/// coefficients.
+///
+/// Each term of `terms` is a pair with a public coefficient $c_i$ and the ID of
+/// a secret-shared value $x_i$. The parties compute locally the shares of
+/// $\sum_i c_i x_i + c$, where the public constant $c$ is added only by the
+/// first party. No intermediate values are stored in the memory of the parties.
+/// At the end of the execution of the protocol, the parties will end up with
+/// the shares of the linear combination under the ID `id_result`.
+pub fn linear_combination_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    terms: &[(T, &str)],
+    constant: T,
+    id_result: &str,
+) where
+    T: MersenneField,
+{
+    let mut result = public_local(parties, constant);
+    for (coeff, id) in terms {
+        for (value, share) in result.iter_mut().zip(local_column(parties, id)) {
+            *value = value.add(&share.multiply(coeff));
+        }
+    }
+
+    for (party, value) in parties.iter_mut().zip(result) {
+        party.insert_share(id_result, Share::new(id_result, value));
+    }
+}
+
+/// Reconstructs a previously shared value among a set of parties.
+///
+/// The method reconstructs a shared value among the provided set of parties and
+/// identified with the provided ID.
+pub fn reconstruct_share<T>(parties: &[&mut VirtualMachine<T>], id: impl SecretRef<T>) -> T
+where
+    T: MersenneField,
+{
+    let shares = parties
+        .iter()
+        .map(|party| party.behavior.opened_share(&party.get_share(id.id()).value))
+        .collect();
+
+    open_single(parties, shares)
+}
+
+/// Reconstructs a previously shared value, or returns an error if any of the
+/// parties is offline.
+///
+/// An additive sharing can only be reconstructed with the shares of all the
+/// parties, so a single party that drops out makes the value unrecoverable,
+/// while [`shamir::try_reconstruct_share`](crate::shamir::try_reconstruct_share)
+/// tolerates up to $n - t - 1$ missing parties.
+pub fn try_reconstruct_share<T>(
+    parties: &[&mut VirtualMachine<T>],
+    id: impl SecretRef<T>,
+) -> Result<T, MissingParty>
+where
+    T: MersenneField,
+{
+    match parties.iter().find(|party| party.mailbox.is_offline()) {
+        Some(party) => Err(MissingParty::new(&party.id)),
+        None => Ok(reconstruct_share(parties, id)),
+    }
+}
 
-/// Represents an additive share of a private element in certain algebraic
-/// structure.
+/// Reconstructs a previously shared value through a designated king party.
 ///
-/// We need to make clear that the `value` field **do not** represent the value
-/// that the share is trying to hide. On the contrary, this field stores the
-/// value that a party holds once the shares of a private element have been
-/// computed and distributed.
-pub struct Share<'a, T: MersenneField> {
-    /// ID of the share in memory.
-    pub id: &'a str,
+/// Instead of sending its share to every other party, as in
+/// [`reconstruct_share`], each party sends it only to the party with ID
+/// `king_id`, which reconstructs the value and sends it back to everyone. This
+/// star topology uses $2(n - 1)$ messages instead of $n(n - 1)$, but takes two
+/// rounds instead of one (see [`Simulator::open_via_king`]).
+pub fn reconstruct_via_king<T>(
+    parties: &[&mut VirtualMachine<T>],
+    id: impl SecretRef<T>,
+    king_id: &str,
+) -> T
+where
+    T: MersenneField,
+{
+    let king = parties
+        .iter()
+        .position(|party| party.id == king_id)
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+    let shares: Vec<Vec<T>> = parties
+        .iter()
+        .map(|party| vec![party.behavior.opened_share(&party.get_share(id.id()).value)])
+        .collect();
 
-    /// Value that the share holds.
-    pub value: T,
+    Simulator::new(parties)
+        .with_tag("open")
+        .open_via_king(&shares, king)
+        .remove(0)
 }
 
-impl<'a, T: MersenneField> Share<'a, T> {
-    /// Creates a new share with a given value.
-    fn new(id: &'a str, value: T) -> Self {
-        Self { id, value }
+/// Reconstructs a previously shared value towards a single party.
+///
+/// Every party sends its share of the value with ID `id` only to the party with
+/// ID `receiver_id`, which adds them up and stores the value in its private
+/// memory under the same ID. Since a virtual machine cannot hold a share and a
+/// private value with the same ID, the receiver drops its share of the value.
+/// The rest of the parties learn nothing about the value, so this allows to
+/// model protocols with private outputs.
+pub fn reconstruct_to<T>(parties: &mut [&mut VirtualMachine<T>], id: &str, receiver_id: &str)
+where
+    T: MersenneField,
+{
+    let receiver = parties
+        .iter()
+        .position(|party| party.id == receiver_id)
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+
+    let network = Simulator::new(parties).with_tag("output");
+    for (from, party) in parties
+        .iter()
+        .enumerate()
+        .filter(|(from, _)| *from != receiver)
+    {
+        let share = party.behavior.opened_share(&party.get_share(id).value);
+        network.send(from, receiver, Payload::Elements(vec![share]));
     }
+    network.deliver();
+
+    let own_share = parties[receiver]
+        .behavior
+        .opened_share(&parties[receiver].get_share(id).value);
+    let value = (0..parties.len())
+        .filter(|from| *from != receiver)
+        .fold(own_share, |acc, from| {
+            acc.add(&network.recv_from(receiver, from).payload.elements()[0])
+        });
+
+    parties[receiver].remove_share(id);
+    parties[receiver].insert_priv_value(id, value);
 }
 
-/// Distributes a share among a set of parties.
+/// Transfers a secret-shared value from one set of parties to a new set of
+/// parties.
 ///
-/// This function distributes shares of a value stored in the private memory of
-/// a party with ID `id_owner`. The distribution of shares is done among the
-/// parties provided in the parameter `parties`. The shares computed and
-/// distributed will be stored in the share memory of each parties with the ID
-/// `id_var` (i.e. with the same ID that the owner has in its private memory).
-pub fn distribute_shares<'a, 'b, T>(
-    id_var: &'a str,
-    id_owner: &'a str,
-    parties: Vec<&'b mut VirtualMachine<'a, T>>,
-    prg: &mut Prg,
+/// Each party in `old_parties` splits its own share of the value with ID `id`
+/// into additive sub-shares, one for each party in `new_parties`, and sends
+/// them through a network that connects both committees. Every new party adds
+/// up the sub-shares it receives, obtaining a fresh share of the same value.
+/// Both committees may have different sizes. Once the resharing finishes, the
+/// old parties erase their shares, so the value is held only by the new
+/// committee under the same ID.
+pub fn reshare<T>(
+    old_parties: &mut [&mut VirtualMachine<T>],
+    new_parties: &mut [&mut VirtualMachine<T>],
+    id: &str,
 ) where
     T: MersenneField,
-    'a: 'b,
 {
-    let mut shares: Vec<Share<T>> = Vec::new();
-    let mut sum = T::new(0);
-    for _ in 0..parties.len() - 1 {
-        let random_elem = T::random(prg);
-        sum = sum.add(&random_elem);
-        let share_random = Share::new(id_var, random_elem);
-        shares.push(share_random);
-    }
+    let n_old = old_parties.len();
+    let n_new = new_parties.len();
+    let sub_shares: Vec<Vec<T>> = old_parties
+        .iter_mut()
+        .map(|party| {
+            let value = party.get_share(id).value.clone();
+            additive_shares(&value, n_new, &mut party.prg)
+        })
+        .collect();
 
-    let mut value_search = None;
-    for party in &parties {
-        if party.id == id_owner {
-            value_search = Some(party.get_priv_value(id_var));
+    let mut committees: Vec<&mut VirtualMachine<T>> = old_parties
+        .iter_mut()
+        .map(|party| &mut **party)
+        .chain(new_parties.iter_mut().map(|party| &mut **party))
+        .collect();
+    let network = Simulator::new(&committees).with_tag("reshare");
+    for (from, party_sub_shares) in sub_shares.into_iter().enumerate() {
+        for (to, sub_share) in party_sub_shares.into_iter().enumerate() {
+            network.send(from, n_old + to, Payload::Elements(vec![sub_share]));
         }
     }
+    network.deliver();
+    let new_values: Vec<T> = (0..n_new)
+        .map(|to| {
+            (0..n_old).fold(T::new(0), |acc, from| {
+                acc.add(&network.recv_from(n_old + to, from).payload.elements()[0])
+            })
+        })
+        .collect();
 
-    let value = value_search.unwrap_or_else(|| {
-        panic!("Party with that id does not exist.");
-    });
+    for party in committees[..n_old].iter_mut() {
+        party.remove_share(id);
+    }
+    for (party, value) in committees[n_old..].iter_mut().zip(new_values) {
+        party.insert_share(id, Share::new(id, value));
+    }
+}
 
-    let last_value = value.subtract(&sum);
-    let share_last_value = Share::new(id_var, last_value);
-    shares.push(share_last_value);
+/// Samples a public random field element that all the parties agree on.
+///
+/// Each party samples a random contribution $r_j$, and the contributions are
+/// opened with a commit-then-open protocol (see [`open_committed`]): every
+/// party broadcasts a hash commitment to its contribution (see
+/// [`commit`](crate::utils::commit)) in a first round, and the openings in a
+/// second round, which every party checks against the commitments it received
+/// before. The result $r = \sum_j r_j$ is uniformly random as long as one
+/// party is honest, since every contribution is fixed before any other is
+/// revealed. A corrupted party with
+/// [`Behavior::WrongOpening`](crate::adversary::Behavior) reveals a different
+/// contribution from the one it committed to. The function panics if an
+/// opening does not match its commitment. At the end, the value is stored in
+/// the private memory of every party under the provided ID, and it is also
+/// returned.
+#[cfg(feature = "sha2")]
+pub fn coin_toss<T>(parties: &mut [&mut VirtualMachine<T>], id: &str) -> T
+where
+    T: MersenneField,
+{
+    let contributions: Vec<T> = parties
+        .iter_mut()
+        .map(|party| T::random(&mut party.prg))
+        .collect();
+    let value = open_committed(parties, &contributions, "coin_toss");
 
-    for party in parties {
-        party.insert_share(id_var, shares.remove(0));
+    for party in parties.iter_mut() {
+        party.insert_priv_value(id, value.clone());
     }
+    value
 }
 
-/// Multiplicates two secret-shared values distributed among a set of parties.
+/// Opens the sum of one value held by each party with a commit-then-open
+/// protocol.
 ///
-/// This protocol executes the multiplication between two secret-shared values
-/// whose shares has been distributed and stored in the memory of the parties
-/// involved in the protocol. The multiplication is executed using a
-/// multiplication triple whose shares have been distributed among the parties.
-/// At the end of the execution of the protocol, the parties will end up with
-/// the shares of the product under the ID `id_result` stored in the share
-/// memory.
-pub fn mult_protocol<'a, 'b, T>(
-    parties: &mut Vec<&'b mut VirtualMachine<'a, T>>,
-    id_x: &'a str,
-    id_y: &'a str,
-    id_result: &'a str,
-    triple_id: (&'a str, &'a str, &'a str),
+/// Each party commits to its value with randomness drawn from its own PRG and
+/// broadcasts the commitment in a first round. Once all the commitments are
+/// received, the parties broadcast the openings in a second round, and every
+/// party checks the openings it receives against the commitments it received
+/// before, so no party can choose its value after seeing the others. The
+/// revealed values go through the behavior of each party. The function panics
+/// if an opening does not match its commitment, and otherwise returns the sum
+/// of the values.
+#[cfg(feature = "sha2")]
+pub(crate) fn open_committed<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+    values: &[T],
+    tag: &str,
+) -> T {
+    use crate::utils::commit::{self, Commitment, Opening};
+
+    let (commitments, openings): (Vec<_>, Vec<_>) = parties
+        .iter_mut()
+        .zip(values)
+        .map(|(party, value)| commit::commit(&value.value().to_le_bytes(), &party.prg.next(32)))
+        .unzip();
+    let n_parties = parties.len();
+    let network = Simulator::new(parties).with_tag(tag);
+
+    // First round: every party broadcasts its commitment.
+    for (from, commitment) in commitments.iter().enumerate() {
+        network.send_to_all(from, Payload::Bits(bytes_to_bits(&commitment.0)));
+    }
+    network.deliver();
+    let received_commitments: Vec<Vec<Commitment>> = (0..n_parties)
+        .map(|to| {
+            (0..n_parties)
+                .map(|from| {
+                    if from == to {
+                        return commitments[to].clone();
+                    }
+                    let bytes = bits_to_bytes(&network.recv_from(to, from).payload.bits());
+                    Commitment(bytes.try_into().unwrap())
+                })
+                .collect()
+        })
+        .collect();
+
+    // Second round: every party broadcasts the opening of its value.
+    for (from, (party, opening)) in parties.iter().zip(&openings).enumerate() {
+        let bytes: [u8; 8] = opening.value.as_slice().try_into().unwrap();
+        let revealed = party
+            .behavior
+            .opened_share(&T::new(u64::from_le_bytes(bytes)));
+        let message = [
+            revealed.value().to_le_bytes().as_slice(),
+            &opening.randomness,
+        ]
+        .concat();
+        network.send_to_all(from, Payload::Bits(bytes_to_bits(&message)));
+    }
+    network.deliver();
+
+    let mut sum = T::new(0);
+    for (to, commitments) in received_commitments.iter().enumerate() {
+        sum = T::new(0);
+        for (from, commitment) in commitments.iter().enumerate() {
+            let opening = if from == to {
+                openings[to].clone()
+            } else {
+                let bytes = bits_to_bytes(&network.recv_from(to, from).payload.bits());
+                Opening {
+                    value: bytes[..8].to_vec(),
+                    randomness: bytes[8..].to_vec(),
+                }
+            };
+            if !commit::verify(commitment, &opening) {
+                panic!("The opening of a commitment is not valid.");
+            }
+            let bytes: [u8; 8] = opening.value.as_slice().try_into().unwrap();
+            sum = sum.add(&T::new(u64::from_le_bytes(bytes)));
+        }
+    }
+    sum
+}
+
+/// Encodes bytes as bits to send them in a [`Payload::Bits`] message.
+pub(crate) fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect()
+}
+
+/// Decodes the bytes encoded by [`bytes_to_bits`].
+pub(crate) fn bits_to_bytes(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(0, |acc, bit| (acc << 1) | *bit as u8)
+        })
+        .collect()
+}
+
+/// Creates and distributes shares of multiplication triples among a set of
+/// parties.
+///
+/// This method simulates the generation of one multiplication (Beaver) triple
+/// among the set of parties. After the generation of the triple, the function
+/// computes additive shares of such triple. Those shares are stored in the
+/// share memory of each party with the provided ID tuple.
+pub fn generate_triple<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_triple: (&str, &str, &str),
+    prg: &mut Prg,
 ) where
     T: MersenneField,
-    'a: 'b,
 {
-    // Computing epsilon and delta
-    subtract_protocol(&mut *parties, id_x, triple_id.0, "epsilon");
-    subtract_protocol(&mut *parties, id_y, triple_id.1, "delta");
+    let a = T::random(&mut *prg);
+    let b = T::random(&mut *prg);
+    let c = a.multiply(&b);
 
-    let epsilon = reconstruct_share(&*parties, "epsilon");
-    let delta = reconstruct_share(&*parties, "delta");
+    simulate_random_dist(id_triple.0, &mut *parties, &a, &mut *prg);
+    simulate_random_dist(id_triple.1, &mut *parties, &b, &mut *prg);
+    simulate_random_dist(id_triple.2, &mut *parties, &c, &mut *prg);
+}
 
-    multiply_by_const_protocol(&mut *parties, &epsilon, triple_id.1, "t1");
-    multiply_by_const_protocol(&mut *parties, &delta, triple_id.0, "t2");
+/// Generates a multiplication triple between two parties using oblivious
+/// transfer.
+///
+/// Each party $j$ samples locally its shares $a_j$ and $b_j$, so the triple is
+/// $a = a_0 + a_1$, $b = b_0 + b_1$ and $c = a_0 b_0 + a_1 b_1 + a_0 b_1 +
+/// a_1 b_0$. The first two terms of $c$ are computed locally, and the cross
+/// terms are computed with the protocol of Gilboa: to obtain shares of $x y$,
+/// where the sender holds $x$ and the receiver holds $y$, the parties run one
+/// correlated OT for each bit $y_i$ of $y$ with correlation $2^i x$, in which
+/// the sender obtains a random $r_i$ and the receiver $r_i + y_i 2^i x$. The
+/// receiver adds up the values it learns, obtaining $\sum_i r_i + x y$, while
+/// the sender keeps $-\sum_i r_i$. The correlated OTs are generated with the
+/// IKNP OT extension (see
+/// [`correlated_ot_extension`](crate::utils::ot::correlated_ot_extension)), so
+/// no trusted party is involved apart from the simulated base OTs. Each party
+/// draws its randomness from its own PRG, and the messages of the extension
+/// are sent through the simulated network. The shares of the triple are
+/// stored in the share memory of each party with the provided ID tuple.
+pub fn ot_triple_protocol<T>(parties: &mut [&mut VirtualMachine<T>], id_triple: (&str, &str, &str))
+where
+    T: MersenneField,
+{
+    if parties.len() != 2 {
+        panic!("The OT-based triple generation needs exactly two parties.");
+    }
 
-    add_protocol(&mut *parties, "t1", "t2", "sum");
-    add_protocol(&mut *parties, "sum", triple_id.2, "sumc");
+    let a: Vec<T> = parties
+        .iter_mut()
+        .map(|party| T::random(&mut party.prg))
+        .collect();
+    let b: Vec<T> = parties
+        .iter_mut()
+        .map(|party| T::random(&mut party.prg))
+        .collect();
 
-    distribute_pub_value(&epsilon.multiply(&delta), "epsdelt", &mut *parties);
-    add_protocol(&mut *parties, "sumc", "epsdelt", id_result);
+    let (first, second) = parties.split_at_mut(1);
+    let (sender_0, receiver_1) = gilboa_product(first[0], second[0], &a[0], &b[1]);
+    let (sender_1, receiver_0) = gilboa_product(second[0], first[0], &a[1], &b[0]);
+    let c = [
+        a[0].multiply(&b[0]).add(&sender_0).add(&receiver_0),
+        a[1].multiply(&b[1]).add(&sender_1).add(&receiver_1),
+    ];
 
-    // Free memory of intermediate steps to make variables available.
-    for party in parties {
-        party.shares.remove("epsilon");
-        party.shares.remove("delta");
-        party.shares.remove("t1");
-        party.shares.remove("t2");
-        party.shares.remove("sum");
-        party.shares.remove("sumc");
-        party.shares.remove("epsdelt");
+    for (j, party) in parties.iter_mut().enumerate() {
+        party.insert_share(id_triple.0, Share::new(id_triple.0, a[j].clone()));
+        party.insert_share(id_triple.1, Share::new(id_triple.1, b[j].clone()));
+        party.insert_share(id_triple.2, Share::new(id_triple.2, c[j].clone()));
     }
 }
 
-/// Distributes shares of a publicly known value.
+/// Computes additive shares of the product of the value `x` of a sender with
+/// the value `y` of a receiver using one correlated OT for each bit of `y`.
+/// The share of the sender is returned first.
+fn gilboa_product<T: MersenneField>(
+    sender: &mut VirtualMachine<T>,
+    receiver: &mut VirtualMachine<T>,
+    x: &T,
+    y: &T,
+) -> (T, T) {
+    let deltas: Vec<T> = (0..T::POWER).map(|i| x.multiply(&T::new(1 << i))).collect();
+    let choices: Vec<bool> = (0..T::POWER).map(|i| (y.value() >> i) & 1 == 1).collect();
+    let (r, received) = ot::correlated_ot_extension(sender, receiver, &deltas, &choices);
+
+    let sender_share = r.iter().fold(T::new(0), |acc, r_i| acc.subtract(r_i));
+    let receiver_share = received.iter().fold(T::new(0), |acc, value| acc.add(value));
+    (sender_share, receiver_share)
+}
+
+/// Generates a multiplication triple between two parties using additively
+/// homomorphic encryption.
 ///
-/// This method distributes shares among a set of parties of a publicly known
-/// value. The shares are stored in the share memory of each party using the
-/// provided ID.
-pub fn distribute_pub_value<'a, 'b, T>(
-    value: &T,
-    id: &'a str,
-    parties: &mut [&'b mut VirtualMachine<'a, T>],
-) where
+/// Each party $j$ samples locally its shares $a_j$ and $b_j$, so the triple is
+/// $a = a_0 + a_1$, $b = b_0 + b_1$ and $c = a_0 b_0 + a_1 b_1 + a_0 b_1 +
+/// a_1 b_0$. The first party generates a toy Paillier key pair (see
+/// [`paillier`](crate::utils::paillier)) and sends the encryptions of $a_0$ and
+/// $b_0$ to the second party, which computes homomorphically an encryption of
+/// $a_0 b_1 + b_0 a_1 + s$ for a random integer $s$ that is
+/// [`HE_TRIPLE_STAT_SECURITY`] bits longer than the cross terms. The first
+/// party decrypts it and reduces it modulo $p$, while the second party keeps
+/// $-s$, so both hold shares of the cross terms. Since the plaintext modulus is
+/// larger than any value computed, the integer sum never wraps around. The
+/// shares of the triple are stored in the share memory of each party with the
+/// provided ID tuple.
+pub fn he_triple_protocol<T>(parties: &mut [&mut VirtualMachine<T>], id_triple: (&str, &str, &str))
+where
     T: MersenneField,
-    'a: 'b,
 {
-    parties[0].insert_share(id, Share::new(id, T::new(value.value())));
-    for party in parties.iter_mut().skip(1) {
-        party.insert_share(id, Share::new(id, T::new(0)));
+    if parties.len() != 2 {
+        panic!("The HE-based triple generation needs exactly two parties.");
+    }
+
+    let a: Vec<T> = parties
+        .iter_mut()
+        .map(|party| T::random(&mut party.prg))
+        .collect();
+    let b: Vec<T> = parties
+        .iter_mut()
+        .map(|party| T::random(&mut party.prg))
+        .collect();
+
+    // The first party generates the keys and sends its encrypted shares.
+    let prg = &mut parties[0].prg;
+    let mask_bits = 2 * T::POWER + 1 + HE_TRIPLE_STAT_SECURITY;
+    let (public_key, secret_key) = paillier::keygen(mask_bits + 64, prg);
+    let enc_a = public_key.encrypt(&BigUint::from(a[0].value()), prg);
+    let enc_b = public_key.encrypt(&BigUint::from(b[0].value()), prg);
+
+    // The second party computes the masked cross terms homomorphically.
+    let prg = &mut parties[1].prg;
+    let mask_bytes = prg.next(mask_bits.div_ceil(8) as usize);
+    let mask = BigUint::from_bytes_le(&mask_bytes) >> (mask_bytes.len() as u64 * 8 - mask_bits);
+    let cross = public_key.add(
+        &public_key.mul_const(&enc_a, &BigUint::from(b[1].value())),
+        &public_key.mul_const(&enc_b, &BigUint::from(a[1].value())),
+    );
+    let masked_cross = public_key.add(&cross, &public_key.encrypt(&mask, prg));
+
+    let reduce = |x: &BigUint| T::new((x % T::ORDER).to_u64_digits().first().copied().unwrap_or(0));
+    let c = [
+        a[0].multiply(&b[0])
+            .add(&reduce(&secret_key.decrypt(&masked_cross))),
+        a[1].multiply(&b[1]).subtract(&reduce(&mask)),
+    ];
+
+    for (j, party) in parties.iter_mut().enumerate() {
+        party.insert_share(id_triple.0, Share::new(id_triple.0, a[j].clone()));
+        party.insert_share(id_triple.1, Share::new(id_triple.1, b[j].clone()));
+        party.insert_share(id_triple.2, Share::new(id_triple.2, c[j].clone()));
     }
 }
 
-/// Computes the secure multiplication of a publicly known value with a
-/// previously secret-shared value.
+/// Creates and distributes shares of several multiplication triples among a
+/// set of parties.
 ///
-/// The function multiplies a public value with the shares with a private value
-/// that has been already secret-shared among the provided set of parties. The
-/// result of this computation will be shares of the result stored in the
-/// memory of each party under ID `id_result`.
-pub fn multiply_by_const_protocol<'a, 'b, T>(
-    parties: &mut Vec<&'b mut VirtualMachine<'a, T>>,
-    value: &T,
-    id: &'a str,
-    id_result: &'a str,
-) where
+/// This method simulates the generation of `count` multiplication triples in a
+/// single pass over the parties. Instead of being stored in the share memory,
+/// the shares are registered directly in the preprocessing store of each
+/// party, so no IDs are needed to refer to them. The handles of the new triples
+/// are returned in the order in which they will be consumed.
+pub fn generate_triples<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+    prg: &mut Prg,
+) -> Vec<TripleHandle>
+where
     T: MersenneField,
-    'a: 'b,
 {
-    for party in parties {
-        let share = party.get_share(id);
-        let value_mult = share.value.multiply(value);
+    let triples: Vec<Vec<(T, T, T)>> = (0..count)
+        .map(|_| {
+            let a = T::random(&mut *prg);
+            let b = T::random(&mut *prg);
+            let c = a.multiply(&b);
 
-        let share_mult = Share::new(id_result, value_mult);
-        party.insert_share(id_result, share_mult);
+            let shares_a = additive_shares(&a, parties.len(), prg);
+            let shares_b = additive_shares(&b, parties.len(), prg);
+            let shares_c = additive_shares(&c, parties.len(), prg);
+            shares_a
+                .into_iter()
+                .zip(shares_b)
+                .zip(shares_c)
+                .map(|((a, b), c)| (a, b, c))
+                .collect()
+        })
+        .collect();
+
+    let mut handles: Vec<Vec<TripleHandle>> = Vec::new();
+    for (j, party) in parties.iter_mut().enumerate() {
+        let party_handles = triples
+            .iter()
+            .map(|shares| party.preprocessing.add_triple(shares[j].clone()))
+            .collect();
+        handles.push(party_handles);
     }
+
+    transpose(&handles)
+        .iter()
+        .map(|triple_handles| common_handle(triple_handles))
+        .collect()
 }
 
-/// Computes the secure subtraction between two secret shared values.
+/// Generates multiplication triples with covert security using cut-and-choose.
 ///
-/// Computes the secure subraction between the shared value stored with ID
-/// `id_a` with the value store with ID `id_b`. The result of this function will
-/// be be the result of the operation stored as shares under the ID `id_result`.
-pub fn subtract_protocol<'a, T>(
-    parties: &mut Vec<&mut VirtualMachine<'a, T>>,
-    id_a: &'a str,
-    id_b: &'a str,
-    id_result: &'a str,
-) where
+/// For each of the `count` triples, the parties produce `deterrence` candidate
+/// triples. A random index is then chosen, which simulates a coin-tossing
+/// protocol, and all the candidates except the chosen one are opened and
+/// checked to satisfy $c = ab$. The chosen candidate is registered in the
+/// preprocessing store of each party. A cheating party (see
+/// [`adversary`](crate::adversary)) corrupts its share of $c$ in only one
+/// candidate, since corrupting more of them only makes it easier to catch. It
+/// is then caught with probability $1 - 1 / \textsf{deterrence}$, which is the
+/// deterrence factor of covert security; the function panics when this
+/// happens. The handles of the new triples are returned in the order in which
+/// they will be consumed.
+pub fn covert_triple_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+    deterrence: usize,
+    prg: &mut Prg,
+) -> Vec<TripleHandle>
+where
     T: MersenneField,
 {
-    multiply_by_const_protocol(&mut *parties, &T::new(1).negate(), id_b, "subtraction");
-    add_protocol(&mut *parties, id_a, "subtraction", id_result);
+    if deterrence < 2 {
+        panic!("The deterrence factor must be at least two.");
+    }
 
-    // Remove intermediate values
-    for party in parties {
-        party.shares.remove("subtraction");
+    let mut handles: Vec<Vec<TripleHandle>> = parties.iter().map(|_| Vec::new()).collect();
+    for _ in 0..count {
+        let corrupted = random_index(deterrence, prg);
+        let candidates: Vec<Vec<(T, T, T)>> = (0..deterrence)
+            .map(|k| {
+                let a = T::random(&mut *prg);
+                let b = T::random(&mut *prg);
+                let c = a.multiply(&b);
+
+                let shares_a = additive_shares(&a, parties.len(), prg);
+                let shares_b = additive_shares(&b, parties.len(), prg);
+                let shares_c = additive_shares(&c, parties.len(), prg);
+                parties
+                    .iter()
+                    .zip(shares_a.into_iter().zip(shares_b).zip(shares_c))
+                    .map(|(party, ((a, b), c))| {
+                        let c = if k == corrupted {
+                            party.behavior.stored_share(c)
+                        } else {
+                            c
+                        };
+                        (a, b, c)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let chosen = random_index(deterrence, prg);
+        for candidate in candidates
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| *k != chosen)
+            .map(|(_, c)| c)
+        {
+            let open = |values: Vec<T>| {
+                let shares = parties
+                    .iter()
+                    .zip(values)
+                    .map(|(party, value)| party.behavior.opened_share(&value))
+                    .collect();
+                open_single(parties, shares)
+            };
+            let a = open(candidate.iter().map(|triple| triple.0.clone()).collect());
+            let b = open(candidate.iter().map(|triple| triple.1.clone()).collect());
+            let c = open(candidate.iter().map(|triple| triple.2.clone()).collect());
+            if a.multiply(&b).value() != c.value() {
+                panic!("A party has been caught cheating in the triple generation.");
+            }
+        }
+
+        for ((party, party_handles), triple) in parties
+            .iter_mut()
+            .zip(handles.iter_mut())
+            .zip(&candidates[chosen])
+        {
+            party_handles.push(party.preprocessing.add_triple(triple.clone()));
+        }
     }
+
+    transpose(&handles)
+        .iter()
+        .map(|triple_handles| common_handle(triple_handles))
+        .collect()
 }
 
-/// Adds two secret-shared values distributed among a set of parties.
+/// Samples a uniformly random index in the range $[0, n)$, up to a negligible
+/// bias.
+fn random_index(n: usize, prg: &mut Prg) -> usize {
+    let bytes: [u8; 8] = prg.next(8).try_into().unwrap();
+    (u64::from_le_bytes(bytes) % n as u64) as usize
+}
+
+/// Creates and distributes shares of several square pairs among a set of
+/// parties.
 ///
-/// This protocol executes the addition between two secret-shared values
-/// whose shares has been distributed and stored in the memory of the parties
-/// involved in the protocol. The addition is executed locally by the parties.
-/// At the end of the execution of the protocol, the parties will end up with
-/// the shares of the addition under the ID `id_result` stored in the share
-/// memory.
-pub fn add_protocol<'a, T>(
-    parties: &mut Vec<&mut VirtualMachine<'a, T>>,
-    id_a: &'a str,
-    id_b: &'a str,
-    id_result: &'a str,
-) where
+/// This method simulates the generation of `count` pairs $(r, r^2)$ for a
+/// uniformly random $r$. The shares are registered directly in the
+/// preprocessing store of each party, and the handles of the new pairs are
+/// returned in the order in which they will be consumed by
+/// [`square_protocol`].
+pub fn generate_square_pairs<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+    prg: &mut Prg,
+) -> Vec<SquareHandle>
+where
     T: MersenneField,
 {
-    for party in parties {
-        let share_a = party.get_share(id_a);
-        let share_b = party.get_share(id_b);
+    let mut handles = Vec::new();
+    for _ in 0..count {
+        let r = T::random(&mut *prg);
+        let r_square = r.multiply(&r);
 
-        let value_sum = share_a.value.add(&share_b.value);
-        let share_sum = Share {
-            id: id_result,
-            value: value_sum,
-        };
-        party.insert_share(id_result, share_sum);
+        let shares_r = additive_shares(&r, parties.len(), prg);
+        let shares_square = additive_shares(&r_square, parties.len(), prg);
+        let pair_handles: Vec<SquareHandle> = parties
+            .iter_mut()
+            .zip(shares_r.into_iter().zip(shares_square))
+            .map(|(party, pair)| party.preprocessing.add_square(pair))
+            .collect();
+        if pair_handles.iter().any(|handle| *handle != pair_handles[0]) {
+            panic!("The parties do not agree on the next square pair.");
+        }
+        handles.push(pair_handles[0]);
     }
+    handles
 }
 
-/// Reconstructs a previously shared value among a set of parties.
+/// Creates and distributes shares of several daBits among a set of parties.
 ///
-/// The method reconstructs a shared value among the provided set of parties and
-/// identified with the provided ID.
-pub fn reconstruct_share<T>(parties: &Vec<&mut VirtualMachine<T>>, id: &str) -> T
+/// A daBit is a uniformly random bit shared both arithmetically, over the
+/// field, and in boolean form, over $\mathbb{F}_2$. This method simulates the
+/// generation of `count` daBits, which are registered directly in the
+/// preprocessing store of each party. The handles of the new daBits are
+/// returned in the order in which they will be consumed.
+pub fn generate_dabits<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+    prg: &mut Prg,
+) -> Vec<DaBitHandle>
 where
     T: MersenneField,
 {
-    let mut value = T::new(0);
-    for party in parties {
-        let share_value = &party.get_share(id).value;
-        value = value.add(share_value);
+    let mut handles = Vec::new();
+    for _ in 0..count {
+        let bit = prg.next(1)[0] & 1;
+        let shares = additive_shares(&T::new(bit as u64), parties.len(), prg);
+        let bool_shares = bool_shares(bit == 1, parties.len(), prg);
+
+        let dabit_handles: Vec<DaBitHandle> = parties
+            .iter_mut()
+            .zip(shares.into_iter().zip(bool_shares))
+            .map(|(party, dabit)| party.preprocessing.add_dabit(dabit))
+            .collect();
+        if dabit_handles
+            .iter()
+            .any(|handle| *handle != dabit_handles[0])
+        {
+            panic!("The parties do not agree on the next daBit.");
+        }
+        handles.push(dabit_handles[0]);
     }
+    handles
+}
 
-    value
+/// Creates and distributes shares of several edaBits among a set of parties.
+///
+/// This method simulates the generation of `count` random integers of
+/// `n_bits` bits, which must be lower than the number of bits of the field
+/// modulus, together with the shares of their bits (see
+/// [`EdaBit`](crate::preprocessing::EdaBit)). The shares are registered
+/// directly in the preprocessing store of each party, and the handles of the
+/// new edaBits are returned in the order in which they will be consumed.
+pub fn generate_edabits<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+    n_bits: usize,
+    prg: &mut Prg,
+) -> Vec<EdaBitHandle>
+where
+    T: MersenneField,
+{
+    if n_bits as u64 > T::POWER {
+        panic!("The edaBits cannot have more bits than the field modulus.");
+    }
+
+    let n_parties = parties.len();
+    let mut handles = Vec::new();
+    for _ in 0..count {
+        let bits: Vec<u8> = prg.next(n_bits).iter().map(|byte| byte & 1).collect();
+        let value = bits
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, bit)| acc | ((*bit as u64) << i));
+
+        let value_shares = additive_shares(&T::new(value), n_parties, prg);
+        let bit_shares: Vec<Vec<T>> = bits
+            .iter()
+            .map(|bit| additive_shares(&T::new(*bit as u64), n_parties, prg))
+            .collect();
+        let bool_bit_shares: Vec<Vec<bool>> = bits
+            .iter()
+            .map(|bit| bool_shares(*bit == 1, n_parties, prg))
+            .collect();
+
+        let edabit_handles: Vec<EdaBitHandle> = parties
+            .iter_mut()
+            .zip(value_shares)
+            .enumerate()
+            .map(|(j, (party, value))| {
+                party.preprocessing.add_edabit(EdaBit {
+                    value,
+                    bits: bit_shares.iter().map(|shares| shares[j].clone()).collect(),
+                    bool_bits: bool_bit_shares.iter().map(|shares| shares[j]).collect(),
+                })
+            })
+            .collect();
+        if edabit_handles
+            .iter()
+            .any(|handle| *handle != edabit_handles[0])
+        {
+            panic!("The parties do not agree on the next edaBit.");
+        }
+        handles.push(edabit_handles[0]);
+    }
+    handles
 }
 
-/// Creates and distributes shares of multiplication triples among a set of
-/// parties.
+/// Simulates the generation of several keys for the comparison based on
+/// function secret sharing (see [`less_than_fss_protocol`]).
 ///
-/// This method simulates the generation of one multiplication (Beaver) triple
-/// among the set of parties. After the generation of the triple, the function
-/// computes additive shares of such triple. Those shares are stored in the
-/// share memory of each party with the provided ID tuple.
-pub fn generate_triple<'a, 'b, T>(
-    parties: &mut Vec<&'b mut VirtualMachine<'a, T>>,
-    id_triple: (&'a str, &'a str, &'a str),
+/// For each key, a random mask $r$ is sampled, and the parties receive shares
+/// of $r$ and of its least significant bit $r_0$, and the keys of a
+/// distributed comparison function that is $1 - 2r_0$ at every input smaller
+/// than $r$, so that adding $r_0$ to its output gives $r_0 \oplus \[c < r\]$. The
+/// keys are registered in the preprocessing store of the parties, and their
+/// handles are returned in the order in which they will be consumed. The
+/// function panics if there are not exactly two parties.
+pub fn generate_comparison_keys<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+    prg: &mut Prg,
+) -> Vec<ComparisonKeyHandle>
+where
+    T: MersenneField,
+{
+    if parties.len() != 2 {
+        panic!("The comparison based on FSS needs exactly two parties.");
+    }
+
+    let mut handles = Vec::new();
+    for _ in 0..count {
+        let mask = T::random(prg);
+        let mask_lsb = T::new(mask.value() & 1);
+        let beta = T::new(1).subtract(&mask_lsb.add(&mask_lsb));
+        let (key_0, key_1) = fss::gen_dcf(mask.value(), &beta, T::POWER as usize, prg);
+
+        let mask_shares = additive_shares(&mask, 2, prg);
+        let lsb_shares = additive_shares(&mask_lsb, 2, prg);
+        let key_handles: Vec<ComparisonKeyHandle> = parties
+            .iter_mut()
+            .zip(mask_shares.into_iter().zip(lsb_shares).zip([key_0, key_1]))
+            .map(|(party, ((mask, mask_lsb), dcf))| {
+                party.preprocessing.add_comparison_key(ComparisonKey {
+                    mask,
+                    mask_lsb,
+                    dcf,
+                })
+            })
+            .collect();
+        if key_handles.iter().any(|handle| *handle != key_handles[0]) {
+            panic!("The parties do not agree on the next comparison key.");
+        }
+        handles.push(key_handles[0]);
+    }
+    handles
+}
+
+/// Simulates the distribution of boolean shares of a bit.
+///
+/// At the end, parties will have boolean shares of the provided bit stored in
+/// the boolean share memory under the provided ID.
+pub fn simulate_bool_dist<T>(
+    id: &str,
+    parties: &mut [&mut VirtualMachine<T>],
+    bit: bool,
+    prg: &mut Prg,
+) -> BoolHandle
+where
+    T: MersenneField,
+{
+    let shares = bool_shares(bit, parties.len(), prg);
+    for (party, share) in parties.iter_mut().zip(shares) {
+        party.insert_bool_share(id, share);
+    }
+
+    BoolHandle::new(id)
+}
+
+/// Reconstructs a boolean-shared bit among a set of parties.
+pub fn reconstruct_bool_share<T>(parties: &[&mut VirtualMachine<T>], id: impl BoolRef) -> bool
+where
+    T: MersenneField,
+{
+    let shares: Vec<Vec<bool>> = parties
+        .iter()
+        .map(|party| vec![party.get_bool_share(id.id())])
+        .collect();
+
+    Simulator::new(parties).with_tag("open").open_bits(&shares)[0]
+}
+
+/// Splits a bit into random boolean shares, one for each party.
+pub(crate) fn bool_shares(bit: bool, n_parties: usize, prg: &mut Prg) -> Vec<bool> {
+    let mut shares: Vec<bool> = prg
+        .next(n_parties - 1)
+        .iter()
+        .map(|byte| byte & 1 == 1)
+        .collect();
+    let xor = shares.iter().fold(bit, |acc, share| acc ^ share);
+    shares.push(xor);
+    shares
+}
+
+/// Splits a value into random additive shares, one for each party.
+pub(crate) fn additive_shares<T: MersenneField>(
+    value: &T,
+    n_parties: usize,
+    prg: &mut Prg,
+) -> Vec<T> {
+    let mut shares: Vec<T> = (0..n_parties - 1).map(|_| T::random(&mut *prg)).collect();
+    let sum = shares.iter().fold(T::new(0), |acc, share| acc.add(share));
+    shares.push(value.subtract(&sum));
+    shares
+}
+
+/// Creates and distributes shares of a matrix multiplication triple among a
+/// set of parties.
+///
+/// This method simulates the generation of random matrices $A$ and $B$, and
+/// computes $C = AB$. The dimensions of the matrices are taken from the
+/// matrices of IDs provided in `ids_triple`. The shares of each entry are
+/// stored in the share memory of each party with the corresponding ID.
+pub fn generate_matrix_triple<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    ids_triple: (&Matrix<&str>, &Matrix<&str>, &Matrix<&str>),
     prg: &mut Prg,
 ) where
     T: MersenneField,
-    'a: 'b,
 {
-    let a = T::random(&mut *prg);
-    let b = T::random(&mut *prg);
+    let (ids_a, ids_b, ids_c) = ids_triple;
+    if ids_c.rows() != ids_a.rows() || ids_c.cols() != ids_b.cols() {
+        panic!("The dimensions of the matrix triple are not consistent.");
+    }
+
+    let a: Matrix<T> = Matrix::random(ids_a.rows(), ids_a.cols(), &mut *prg);
+    let b: Matrix<T> = Matrix::random(ids_b.rows(), ids_b.cols(), &mut *prg);
     let c = a.multiply(&b);
 
-    simulate_random_dist(id_triple.0, &mut *parties, &a, &mut *prg);
-    simulate_random_dist(id_triple.1, &mut *parties, &b, &mut *prg);
-    simulate_random_dist(id_triple.2, &mut *parties, &c, &mut *prg);
+    for (ids, matrix) in [(ids_a, &a), (ids_b, &b), (ids_c, &c)] {
+        for (id, value) in ids.iter().zip(matrix.iter()) {
+            simulate_random_dist(id, &mut *parties, value, &mut *prg);
+        }
+    }
+}
+
+/// Creates and distributes shares of a random bit among a set of parties.
+///
+/// This method simulates the generation of a uniformly random bit, which is
+/// secret-shared among the parties and stored in the share memory of each
+/// party under the provided ID. See [`random_bit_protocol`] for a protocol that
+/// generates the bit without a trusted party.
+pub fn generate_random_bit<T>(parties: &mut [&mut VirtualMachine<T>], id: &str, prg: &mut Prg)
+where
+    T: MersenneField,
+{
+    let bit = T::new((prg.next(1)[0] & 1) as u64);
+    simulate_random_dist(id, parties, &bit, prg);
+}
+
+/// Generates shares of a uniformly random bit among a set of parties.
+///
+/// Each party samples locally a share of a random value $r$. Then, the parties
+/// compute and open $r^2$ using a square pair dealt with the provided PRG (see
+/// [`generate_square_pairs`] and [`square_protocol`]), and compute publicly its
+/// square root $s = \sqrt{r^2}$, which is either $r$ or $-r$ with the same
+/// probability. Therefore, $r / s$ is either $1$ or $-1$ with the same
+/// probability, and it can be mapped to a random bit computing
+/// $(r / s + 1) / 2$ locally. The square root is computed as
+/// $(r^2)^{(p + 1) / 4}$, which works because $p \equiv 3 \bmod 4$. If the
+/// opened square is zero, which happens with negligible probability, the
+/// parties discard $r$ and retry with a fresh random value. At the end of the
+/// protocol, the parties will end up with the shares of the bit under the
+/// provided ID.
+pub fn random_bit_protocol<T>(parties: &mut [&mut VirtualMachine<T>], id: &str, prg: &mut Prg)
+where
+    T: MersenneField,
+{
+    let (r, square) = loop {
+        generate_square_pairs(parties, 1, prg);
+        let r: Vec<T> = parties
+            .iter_mut()
+            .map(|party| T::random(&mut party.prg))
+            .collect();
+        let square_shares = square_local(parties, &r);
+        let square = open_single(parties, square_shares);
+        if square.value() != 0 {
+            break (r, square);
+        }
+    };
+
+    let root = square.pow((T::ORDER + 1) / 4);
+    let scale = root.inverse().multiply(&T::new(2).inverse());
+    let half = T::new(2).inverse();
+    for (j, (party, share_r)) in parties.iter_mut().zip(r).enumerate() {
+        let mut value = share_r.multiply(&scale);
+        if j == 0 {
+            value = value.add(&half);
+        }
+        party.insert_share(id, Share::new(id, value));
+    }
+}
+
+/// Creates and distributes shares of a random pair for the truncation protocol.
+///
+/// This method simulates the generation of a random value $r$ of
+/// [`TRUNC_INPUT_BITS`] + [`TRUNC_STAT_SECURITY`] bits, together with the value
+/// $r'$ formed by its `frac_bits` least significant bits. The shares of $r$ and
+/// $r'$ are stored in the share memory of each party with the IDs
+/// `ids_pair.0` and `ids_pair.1` respectively. The pair is consumed by
+/// [`trunc_protocol`].
+pub fn generate_trunc_pair<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    frac_bits: u32,
+    ids_pair: (&str, &str),
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let random_bits = u64::from_ne_bytes(prg.next(8).try_into().unwrap());
+    let r = random_bits % (1 << (TRUNC_INPUT_BITS + TRUNC_STAT_SECURITY));
+    let r_low = r % (1 << frac_bits);
+
+    simulate_random_dist(ids_pair.0, parties, &T::new(r), prg);
+    simulate_random_dist(ids_pair.1, parties, &T::new(r_low), prg);
 }
 
 /// Simulates the distribution of randomly generated shares of a value.
@@ -276,12 +3582,19 @@ pub fn generate_triple<'a, 'b, T>(
 /// secret-shared among the parties. This means that there is no protocol
 /// execution that perform this process. At the end, parties will have shares
 /// of the provided value stored in the share memory under the provided ID.
-pub fn simulate_random_dist<'a, T>(
-    id: &'a str,
-    parties: &mut Vec<&mut VirtualMachine<'a, T>>,
+///
+/// The value is known to whoever calls this function, so it is still needed
+/// to deal correlated randomness whose structure must be known when it is
+/// generated, such as multiplication triples. A random value that nobody
+/// knows can be shared without interaction with pseudo-random secret-sharing
+/// instead (see [`prss_setup`]).
+pub fn simulate_random_dist<T>(
+    id: &str,
+    parties: &mut [&mut VirtualMachine<T>],
     value: &T,
     prg: &mut Prg,
-) where
+) -> SecretHandle<T>
+where
     T: MersenneField,
 {
     let mut shares: Vec<Share<T>> = Vec::new();
@@ -300,4 +3613,6 @@ pub fn simulate_random_dist<'a, T>(
     for party in parties {
         party.insert_share(id, shares.pop().unwrap());
     }
+
+    SecretHandle::new(id)
 }
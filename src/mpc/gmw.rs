@@ -0,0 +1,318 @@
+//! Implements the GMW protocol over boolean circuits.
+//!
+//! In the GMW protocol, every bit is shared with XOR among the parties, that
+//! is, as boolean shares over $\mathbb{F}_2$ stored in the boolean share memory
+//! of the virtual machines. XOR gates are computed locally by adding the
+//! shares, and NOT gates by flipping the share of the first party. AND gates
+//! are the boolean analogue of multiplications: they consume a boolean Beaver
+//! triple $(a, b, c)$ with $c = a \land b$, taken from the same preprocessing
+//! store as the arithmetic triples. The parties open $d = x \oplus a$
+//! and $e = y \oplus b$, and compute the shares of
+//!    $$x \land y = c \oplus (d \land b) \oplus (e \land a) \oplus (d \land e),$$
+//! where the last term is added only by the first party. Together with the
+//! protocols in [`mpc`](crate::mpc), which work over the field, this gives a
+//! second protocol family for computations that are naturally expressed as
+//! boolean circuits, which can be described with the
+//! [`circuit`](crate::circuit) module and evaluated with [`evaluate_circuit`].
+
+use crate::circuit::{Circuit, Gate, Wire};
+use crate::handle::{BoolHandle, BoolRef};
+use crate::math::mersenne::MersenneField;
+use crate::mpc::bool_shares;
+use crate::network::{Payload, Simulator};
+use crate::preprocessing::AndTripleHandle;
+use crate::utils::ot;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+/// Creates and distributes boolean shares of several AND triples among a set of
+/// parties.
+///
+/// This method simulates the generation of `count` triples $(a, b, a \land
+/// b)$ of random bits. The shares are registered directly in the preprocessing
+/// store of each party, next to the arithmetic triples, and the handles of the
+/// new triples are returned in the order in which they will be consumed by
+/// [`and_protocol`].
+pub fn generate_and_triples<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+    prg: &mut Prg,
+) -> Vec<AndTripleHandle>
+where
+    T: MersenneField,
+{
+    (0..count)
+        .map(|_| {
+            let bits = prg.next(2);
+            let a = bits[0] & 1 == 1;
+            let b = bits[1] & 1 == 1;
+
+            let n_parties = parties.len();
+            let shares_a = bool_shares(a, n_parties, prg);
+            let shares_b = bool_shares(b, n_parties, prg);
+            let shares_c = bool_shares(a & b, n_parties, prg);
+            let triples = (0..n_parties)
+                .map(|j| (shares_a[j], shares_b[j], shares_c[j]))
+                .collect();
+            register_and_triple(parties, triples)
+        })
+        .collect()
+}
+
+/// Generates AND triples between two parties using oblivious transfer.
+///
+/// Each party $j$ samples its shares $a_j$ and $b_j$ with its own PRG, so $c =
+/// a_0 b_0 \oplus a_1 b_1 \oplus a_0 b_1 \oplus a_1 b_0$. Each cross term $a_i
+/// b_j$ is shared with one random OT (see
+/// [`random_ot_extension`](crate::utils::ot::random_ot_extension)), in which
+/// the party $i$ is the receiver with choice $a_i$ and the party $j$ obtains
+/// two random bits $x_0$ and $x_1$. The party $j$ sends the correction $d = x_0
+/// \oplus x_1 \oplus b_j$ through the simulated network, and the receiver
+/// computes $x_{a_i} \oplus a_i d = x_0 \oplus a_i b_j$, while the party $j$
+/// keeps $x_0$. The triples are registered in the preprocessing store of both
+/// parties, and their handles are returned in the order in which they will be
+/// consumed by [`and_protocol`].
+pub fn ot_and_triple_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    count: usize,
+) -> Vec<AndTripleHandle>
+where
+    T: MersenneField,
+{
+    if parties.len() != 2 {
+        panic!("The OT-based generation of AND triples needs exactly two parties.");
+    }
+
+    let random_bits =
+        |prg: &mut Prg| -> Vec<bool> { prg.next(count).iter().map(|byte| byte & 1 == 1).collect() };
+    let a: Vec<Vec<bool>> = parties
+        .iter_mut()
+        .map(|party| random_bits(&mut party.prg))
+        .collect();
+    let b: Vec<Vec<bool>> = parties
+        .iter_mut()
+        .map(|party| random_bits(&mut party.prg))
+        .collect();
+
+    // cross[i] holds the shares (of the receiver i, of the sender 1 - i) of
+    // the products a_i b_{1 - i}.
+    let mut cross: Vec<Vec<(bool, bool)>> = Vec::new();
+    for i in 0..2 {
+        let (first, second) = parties.split_at_mut(1);
+        let (receiver, sender) = if i == 0 {
+            (&mut *first[0], &mut *second[0])
+        } else {
+            (&mut *second[0], &mut *first[0])
+        };
+        let (pairs, received) = ot::random_ot_extension(sender, receiver, &a[i], 1);
+        let x0: Vec<bool> = pairs.iter().map(|(x0, _)| x0[0] & 1 == 1).collect();
+        let corrections: Vec<bool> = pairs
+            .iter()
+            .zip(&b[1 - i])
+            .map(|((x0, x1), b)| ((x0[0] ^ x1[0]) & 1 == 1) ^ b)
+            .collect();
+
+        let network = Simulator::new(parties).with_tag("and_triple");
+        network.send(1 - i, i, Payload::Bits(corrections));
+        network.deliver();
+        let corrections = network.recv_from(i, 1 - i).payload.bits();
+        cross.push(
+            received
+                .iter()
+                .zip(corrections)
+                .zip(x0)
+                .enumerate()
+                .map(|(k, ((x, d), x0))| ((x[0] & 1 == 1) ^ (a[i][k] & d), x0))
+                .collect(),
+        );
+    }
+
+    (0..count)
+        .map(|k| {
+            let triples = (0..2)
+                .map(|j| {
+                    let c = (a[j][k] & b[j][k]) ^ cross[j][k].0 ^ cross[1 - j][k].1;
+                    (a[j][k], b[j][k], c)
+                })
+                .collect();
+            register_and_triple(parties, triples)
+        })
+        .collect()
+}
+
+/// Registers the shares of an AND triple, one for each party, in the
+/// preprocessing store of the parties.
+fn register_and_triple<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+    triples: Vec<(bool, bool, bool)>,
+) -> AndTripleHandle {
+    let handles: Vec<AndTripleHandle> = parties
+        .iter_mut()
+        .zip(triples)
+        .map(|(party, triple)| party.preprocessing.add_and_triple(triple))
+        .collect();
+    if handles.iter().any(|handle| *handle != handles[0]) {
+        panic!("The parties do not agree on the next AND triple.");
+    }
+    handles[0]
+}
+
+/// Computes the XOR of two boolean-shared bits locally.
+pub fn xor_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: impl BoolRef,
+    id_b: impl BoolRef,
+    id_result: &str,
+) -> BoolHandle
+where
+    T: MersenneField,
+{
+    for party in parties.iter_mut() {
+        let share = party.get_bool_share(id_a.id()) ^ party.get_bool_share(id_b.id());
+        party.insert_bool_share(id_result, share);
+    }
+
+    BoolHandle::new(id_result)
+}
+
+/// Computes the negation of a boolean-shared bit locally.
+pub fn not_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id: impl BoolRef,
+    id_result: &str,
+) -> BoolHandle
+where
+    T: MersenneField,
+{
+    for (j, party) in parties.iter_mut().enumerate() {
+        let share = party.get_bool_share(id.id()) ^ (j == 0);
+        party.insert_bool_share(id_result, share);
+    }
+
+    BoolHandle::new(id_result)
+}
+
+/// Computes the AND of two boolean-shared bits.
+///
+/// The protocol consumes the next AND triple of the preprocessing store of the
+/// parties, and panics if there is none (see [`generate_and_triples`]). At the
+/// end, the parties hold boolean shares of the result under the ID
+/// `id_result`.
+pub fn and_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: impl BoolRef,
+    id_b: impl BoolRef,
+    id_result: &str,
+) -> BoolHandle
+where
+    T: MersenneField,
+{
+    let x: Vec<bool> = parties
+        .iter()
+        .map(|party| party.get_bool_share(id_a.id()))
+        .collect();
+    let y: Vec<bool> = parties
+        .iter()
+        .map(|party| party.get_bool_share(id_b.id()))
+        .collect();
+    let shares = and_local(parties, &x, &y);
+    for (party, share) in parties.iter_mut().zip(shares) {
+        party.insert_bool_share(id_result, share);
+    }
+
+    BoolHandle::new(id_result)
+}
+
+/// Evaluates a boolean circuit on boolean-shared inputs.
+///
+/// The $i$-th input of the circuit is the bit shared under the $i$-th ID in
+/// `ids_inputs`. The wires are evaluated gate by gate on the local shares of
+/// the parties, so no intermediate value is stored in their memory, and every
+/// AND gate consumes the next AND triple of the preprocessing store. At the
+/// end, the parties hold boolean shares of the $i$-th output under the $i$-th
+/// ID in `ids_outputs`. The function panics if the circuit contains arithmetic
+/// gates.
+pub fn evaluate_circuit<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    circuit: &Circuit<T>,
+    ids_inputs: &[&str],
+    ids_outputs: &[&str],
+) where
+    T: MersenneField,
+{
+    if ids_inputs.len() != circuit.inputs.len() || ids_outputs.len() != circuit.outputs.len() {
+        panic!("The number of IDs does not match the inputs and outputs of the circuit.");
+    }
+
+    // wires[w][j] holds the share of the wire w held by the party j.
+    let mut wires: Vec<Option<Vec<bool>>> = vec![None; circuit.n_wires];
+    for (wire, id) in circuit.inputs.iter().zip(ids_inputs) {
+        wires[*wire] = Some(
+            parties
+                .iter()
+                .map(|party| party.get_bool_share(id))
+                .collect(),
+        );
+    }
+
+    let get = |wires: &[Option<Vec<bool>>], wire: Wire| -> Vec<bool> {
+        wires[wire]
+            .clone()
+            .unwrap_or_else(|| panic!("The wire {} has not been evaluated.", wire))
+    };
+    for gate in &circuit.gates {
+        let shares = match gate {
+            Gate::Xor(a, b, _) => get(&wires, *a)
+                .iter()
+                .zip(get(&wires, *b))
+                .map(|(u, v)| u ^ v)
+                .collect(),
+            Gate::And(a, b, _) => and_local(parties, &get(&wires, *a), &get(&wires, *b)),
+            Gate::Inv(a, _) => get(&wires, *a)
+                .iter()
+                .enumerate()
+                .map(|(j, u)| u ^ (j == 0))
+                .collect(),
+            _ => panic!("The GMW protocol supports only boolean gates."),
+        };
+        wires[gate.output()] = Some(shares);
+    }
+
+    for (wire, id) in circuit.outputs.iter().zip(ids_outputs) {
+        for (party, share) in parties.iter_mut().zip(get(&wires, *wire)) {
+            party.insert_bool_share(id, share);
+        }
+    }
+}
+
+/// Computes the AND of two bits given by the local shares of each party,
+/// consuming the next AND triple of the preprocessing store.
+fn and_local<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+    x: &[bool],
+    y: &[bool],
+) -> Vec<bool> {
+    let stored: Vec<(AndTripleHandle, (bool, bool, bool))> = parties
+        .iter_mut()
+        .map(|party| party.preprocessing.next_and_triple())
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_else(|| {
+            panic!("Not enough AND triples in the preprocessing store.");
+        });
+    if stored.iter().any(|(handle, _)| *handle != stored[0].0) {
+        panic!("The parties do not agree on the next AND triple.");
+    }
+    let triples: Vec<(bool, bool, bool)> = stored.into_iter().map(|(_, triple)| triple).collect();
+
+    let masked: Vec<Vec<bool>> = (0..triples.len())
+        .map(|j| vec![x[j] ^ triples[j].0, y[j] ^ triples[j].1])
+        .collect();
+    let opened = Simulator::new(parties).with_tag("and").open_bits(&masked);
+    let (d, e) = (opened[0], opened[1]);
+
+    triples
+        .into_iter()
+        .enumerate()
+        .map(|(j, (a, b, c))| c ^ (d & b) ^ (e & a) ^ (j == 0 && d & e))
+        .collect()
+}
@@ -0,0 +1,1240 @@
+//! Implements a simulated network between virtual machines.
+//!
+//! In a real execution of an MPC protocol, each party only knows its own
+//! memory and learns the values of the other parties through the messages it
+//! receives. The [`Simulator`] enforces this in the protocols of this library:
+//! instead of reading the memory of other virtual machines, a party sends a
+//! message to another party, and the receiver later takes it from its inbox.
+//!
+//! Each virtual machine has a [`Mailbox`] with an outbox, where the messages
+//! that it sends wait until they are delivered, and an inbox, where the
+//! messages delivered to it wait until it receives them. The messages sent in
+//! a round are delivered together with [`Simulator::deliver`], so a party can
+//! only use a message in the rounds after it was sent.
+//!
+//! The mailboxes also count the messages and the bytes that each party sends
+//! and receives (see [`CommStats`]), so the communication cost of a protocol
+//! can be measured by resetting the counters with [`Simulator::reset_stats`]
+//! before running it and reading them with [`Simulator::stats`] afterwards.
+//! For example, a multiplication with a Beaver triple costs each party one
+//! message with two field elements for every other party. Each delivery that
+//! moves at least one message is a round, so the counters also give the round
+//! complexity of a protocol. The function [`measure`] wraps all of this and
+//! returns a [`Report`] of a protocol execution.
+//!
+//! Besides the counters, every message that a party sends is recorded in its
+//! mailbox together with the round in which it was sent and a tag that tells
+//! which step of a protocol sent it. The records of all the parties form the
+//! [`Transcript`] of the execution.
+//!
+//! The [`View`] of a party collects everything it saw during an execution: the
+//! shares in its memory, the messages it received and the values it opened.
+//! In the simulation paradigm, a protocol is secure against a corrupted party
+//! if its view can be generated by a simulator that only knows the inputs and
+//! the outputs of that party. The function [`simulate_add_view`] is such a
+//! simulator for the addition of two secret-shared values followed by the
+//! opening of the result.
+//!
+//! By default the simulated network is perfect. A [`NetworkConfig`] set with
+//! [`Simulator::configure`] adds latency to the links, reorders the messages
+//! in the inboxes and drops messages, which are resent according to a
+//! [`ResendPolicy`]. The simulated time of each round is the time of its
+//! slowest message, so the total time of a protocol grows with its number of
+//! rounds.
+//!
+//! The [`Simulator`] runs all the parties in the same thread. A party can also
+//! run on its own, exchanging [`Envelope`]s with the other parties through a
+//! [`Channel`]. The channels returned by [`mpsc_channels`] connect parties that
+//! run in different threads of the same process (see
+//! [`execute_threaded`](crate::protocol::execute_threaded)). With the `async`
+//! feature, the same layer is also available for parties that run as
+//! asynchronous tasks, with an [`AsyncChannel`] trait and channels over the
+//! [`tokio`] runtime created by [`tokio_channels`]. Finally, the `net` feature
+//! adds a [`Channel`] over TCP sockets in the `tcp` module, so the parties can
+//! run in different processes or machines.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::network::{Payload, Simulator};
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! let parties = vec![&mut alice, &mut bob];
+//!
+//! let network = Simulator::new(&parties);
+//! network.send(0, 1, Payload::Elements(vec![Fp::new(5)]));
+//! network.deliver();
+//! let message = network.recv(1);
+//! assert_eq!(message.from, "alice");
+//! assert_eq!(network.stats()[0].bytes_sent, 8);
+//! ```
+
+#[cfg(feature = "net")]
+pub mod tcp;
+
+use crate::counter;
+use crate::event::Event;
+use crate::math::mersenne::MersenneField;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Defines the content of a message.
+#[derive(Clone, Debug)]
+pub enum Payload<T> {
+    /// Field elements, such as the shares of an opened value.
+    Elements(Vec<T>),
+
+    /// Bits, such as the boolean shares of an opened bit.
+    Bits(Vec<bool>),
+}
+
+impl<T: Clone> Payload<T> {
+    /// Returns the field elements of the payload, and panics if the payload
+    /// contains bits.
+    pub fn elements(&self) -> Vec<T> {
+        match self {
+            Payload::Elements(elements) => elements.clone(),
+            Payload::Bits(_) => panic!("The message contains bits instead of field elements."),
+        }
+    }
+
+    /// Returns the bits of the payload, and panics if the payload contains
+    /// field elements.
+    pub fn bits(&self) -> Vec<bool> {
+        match self {
+            Payload::Bits(bits) => bits.clone(),
+            Payload::Elements(_) => panic!("The message contains field elements instead of bits."),
+        }
+    }
+}
+
+impl<T: MersenneField> Payload<T> {
+    /// Returns the size of the payload in bytes. Each field element takes the
+    /// bytes needed to represent a value of $\mathbb{F}_p$ with $p = 2^k - 1$,
+    /// and the bits are packed in bytes.
+    pub fn n_bytes(&self) -> usize {
+        match self {
+            Payload::Elements(elements) => elements.len() * T::POWER.div_ceil(8) as usize,
+            Payload::Bits(bits) => bits.len().div_ceil(8),
+        }
+    }
+}
+
+/// Defines a message sent between two virtual machines.
+#[derive(Clone, Debug)]
+pub struct Message<T> {
+    /// ID of the sender.
+    pub from: String,
+
+    /// ID of the receiver.
+    pub to: String,
+
+    /// Step of the protocol that sent the message, such as `"beaver"`.
+    pub tag: String,
+
+    /// Content of the message.
+    pub payload: Payload<T>,
+}
+
+/// Defines the record of a message in a transcript.
+#[derive(Clone, Debug)]
+pub struct Record<T> {
+    /// Round in which the message was sent, counted since the last reset of
+    /// the counters of the sender.
+    pub round: usize,
+
+    /// Size of the payload in bytes.
+    pub n_bytes: usize,
+
+    /// Message that was sent.
+    pub message: Message<T>,
+}
+
+/// Contains the records of the messages sent during a protocol execution,
+/// ordered by round.
+#[derive(Clone, Debug)]
+pub struct Transcript<T> {
+    records: Vec<Record<T>>,
+}
+
+impl<T: Clone> Transcript<T> {
+    /// Returns the records of the transcript.
+    pub fn records(&self) -> &[Record<T>] {
+        &self.records
+    }
+
+    /// Returns the transcript restricted to the messages sent or received by
+    /// the party with the provided ID.
+    pub fn for_party(&self, id: &str) -> Transcript<T> {
+        self.filter(|record| record.message.from == id || record.message.to == id)
+    }
+
+    /// Returns the transcript restricted to the messages with the provided
+    /// tag.
+    pub fn with_tag(&self, tag: &str) -> Transcript<T> {
+        self.filter(|record| record.message.tag == tag)
+    }
+
+    /// Returns the transcript as a Mermaid sequence diagram, with a lifeline
+    /// for each party and a note at the beginning of each round.
+    ///
+    /// ```text
+    /// sequenceDiagram
+    ///     participant alice
+    ///     participant bob
+    ///     Note over alice,bob: round 0
+    ///     alice->>bob: beaver (16 bytes)
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let parties = self.parties();
+        let mut diagram = String::from("sequenceDiagram\n");
+        for party in &parties {
+            diagram += &format!("    participant {}\n", party);
+        }
+
+        let span = match parties.len() {
+            0 => String::new(),
+            1 => parties[0].clone(),
+            n => format!("{},{}", parties[0], parties[n - 1]),
+        };
+        let mut round = None;
+        for record in &self.records {
+            if round != Some(record.round) {
+                round = Some(record.round);
+                diagram += &format!("    Note over {}: round {}\n", span, record.round);
+            }
+            diagram += &format!(
+                "    {}->>{}: {} ({} bytes)\n",
+                record.message.from, record.message.to, record.message.tag, record.n_bytes
+            );
+        }
+        diagram
+    }
+
+    /// Returns the transcript as a Graphviz DOT graph, with a node for each
+    /// party and an edge for each message labelled with its round, tag and
+    /// size.
+    ///
+    /// ```text
+    /// digraph transcript {
+    ///     "alice";
+    ///     "bob";
+    ///     "alice" -> "bob" [label="round 0: beaver (16 bytes)"];
+    /// }
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let quote = |text: &str| format!("\"{}\"", text.replace('"', "\\\""));
+        let mut graph = String::from("digraph transcript {\n");
+        for party in self.parties() {
+            graph += &format!("    {};\n", quote(&party));
+        }
+        for record in &self.records {
+            let label = format!(
+                "round {}: {} ({} bytes)",
+                record.round, record.message.tag, record.n_bytes
+            );
+            graph += &format!(
+                "    {} -> {} [label={}];\n",
+                quote(&record.message.from),
+                quote(&record.message.to),
+                quote(&label)
+            );
+        }
+        graph += "}\n";
+        graph
+    }
+
+    /// Returns the IDs of the parties in the transcript in order of appearance.
+    fn parties(&self) -> Vec<String> {
+        let mut parties: Vec<String> = Vec::new();
+        for record in &self.records {
+            for id in [&record.message.from, &record.message.to] {
+                if !parties.contains(id) {
+                    parties.push(id.clone());
+                }
+            }
+        }
+        parties
+    }
+
+    fn filter<F: Fn(&Record<T>) -> bool>(&self, predicate: F) -> Transcript<T> {
+        Transcript {
+            records: self
+                .records
+                .iter()
+                .filter(|record| predicate(record))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl<T> fmt::Display for Transcript<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(
+                f,
+                "round {}: {} -> {} [{}] {} bytes",
+                record.round,
+                record.message.from,
+                record.message.to,
+                record.message.tag,
+                record.n_bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the communication of a virtual machine.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommStats {
+    /// Number of messages sent by the party.
+    pub messages_sent: usize,
+
+    /// Number of bytes sent by the party.
+    pub bytes_sent: usize,
+
+    /// Number of messages delivered to the party.
+    pub messages_received: usize,
+
+    /// Number of bytes delivered to the party.
+    pub bytes_received: usize,
+
+    /// Number of rounds executed by the network while the party was connected.
+    pub rounds: usize,
+
+    /// Simulated time of those rounds, in milliseconds.
+    pub elapsed_ms: u64,
+
+    /// Number of messages of the party that were dropped and sent again.
+    pub resent: usize,
+
+    /// Number of messages of the party that were dropped and never arrived.
+    pub lost: usize,
+}
+
+/// Defines what a sender does when one of its messages is dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResendPolicy {
+    /// The message is lost.
+    Never,
+
+    /// The message is sent again after waiting `timeout_ms` milliseconds
+    /// without an acknowledgement, at most `max_retries` times.
+    Retry { max_retries: usize, timeout_ms: u64 },
+}
+
+/// Defines the conditions of the simulated network.
+#[derive(Clone, Debug)]
+pub struct NetworkConfig {
+    /// Latency of the links, in milliseconds.
+    pub latency_ms: u64,
+
+    /// Latency of specific links, given by the IDs of the sender and the
+    /// receiver, which replaces `latency_ms`.
+    pub link_latency_ms: HashMap<(String, String), u64>,
+
+    /// Whether the messages in each inbox are shuffled after each delivery.
+    pub reorder: bool,
+
+    /// Probability that a message is dropped in each attempt to send it.
+    pub drop_probability: f64,
+
+    /// What the senders do with the dropped messages.
+    pub resend: ResendPolicy,
+}
+
+impl NetworkConfig {
+    /// Creates the configuration of a perfect network, without latency,
+    /// reordering or drops.
+    pub fn new() -> Self {
+        Self {
+            latency_ms: 0,
+            link_latency_ms: HashMap::new(),
+            reorder: false,
+            drop_probability: 0.0,
+            resend: ResendPolicy::Never,
+        }
+    }
+
+    /// Sets the latency of all the links.
+    pub fn with_latency(mut self, latency_ms: u64) -> Self {
+        self.latency_ms = latency_ms;
+        self
+    }
+
+    /// Sets the latency of the link from the party `from` to the party `to`.
+    pub fn with_link_latency(mut self, from: &str, to: &str, latency_ms: u64) -> Self {
+        self.link_latency_ms
+            .insert((from.to_string(), to.to_string()), latency_ms);
+        self
+    }
+
+    /// Shuffles the inboxes after each delivery.
+    pub fn with_reordering(mut self) -> Self {
+        self.reorder = true;
+        self
+    }
+
+    /// Drops each message with the given probability, and resends the dropped
+    /// messages according to `resend`.
+    pub fn with_drops(mut self, drop_probability: f64, resend: ResendPolicy) -> Self {
+        self.drop_probability = drop_probability;
+        self.resend = resend;
+        self
+    }
+
+    /// Returns the latency of the link from the party `from` to the party `to`.
+    pub fn latency(&self, from: &str, to: &str) -> u64 {
+        *self
+            .link_latency_ms
+            .get(&(from.to_string(), to.to_string()))
+            .unwrap_or(&self.latency_ms)
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Contains everything that a party saw during a protocol execution.
+#[derive(Clone, Debug)]
+pub struct View<T> {
+    /// ID of the party.
+    pub id: String,
+
+    /// Shares in the memory of the party, ordered by ID. Since the randomness
+    /// of the protocols is given to the parties as shares, this includes the
+    /// random tape of the party.
+    pub shares: Vec<(String, T)>,
+
+    /// Messages received by the party, in the order in which they were
+    /// delivered.
+    pub received: Vec<Record<T>>,
+
+    /// Values opened by the party.
+    pub opened: Vec<T>,
+}
+
+/// Generates the view of a party in the addition of two secret-shared values
+/// followed by the opening of the result, that is, in an execution of
+/// [`add_protocol`](crate::mpc::add_protocol) and
+/// [`reconstruct_share`](crate::mpc::reconstruct_share).
+///
+/// The simulator only knows the IDs of the parties, the position `party` of
+/// the corrupted party, its shares `inputs` of the summands with their IDs, the
+/// ID `id_result` of the sum and the opened sum `output`. The share of the sum
+/// is computed locally as in the protocol, and the shares received from the
+/// other parties are random elements conditioned on adding up to `output`,
+/// which have the same distribution as in a real execution. Hence, the view
+/// reveals nothing about the inputs of the other parties beyond the output.
+pub fn simulate_add_view<T: MersenneField>(
+    ids: &[&str],
+    party: usize,
+    inputs: &[(&str, T)],
+    id_result: &str,
+    output: &T,
+    prg: &mut Prg,
+) -> View<T> {
+    let own_share = inputs
+        .iter()
+        .fold(T::new(0), |acc, (_, share)| acc.add(share));
+    let mut shares: Vec<(String, T)> = inputs
+        .iter()
+        .map(|(id, share)| (id.to_string(), share.clone()))
+        .chain([(id_result.to_string(), own_share.clone())])
+        .collect();
+    shares.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let others: Vec<usize> = (0..ids.len()).filter(|j| *j != party).collect();
+    let mut remaining = output.subtract(&own_share);
+    let received = others
+        .iter()
+        .enumerate()
+        .map(|(k, from)| {
+            let share = if k == others.len() - 1 {
+                remaining.clone()
+            } else {
+                T::random(&mut *prg)
+            };
+            remaining = remaining.subtract(&share);
+
+            let payload = Payload::Elements(vec![share]);
+            Record {
+                round: 0,
+                n_bytes: payload.n_bytes(),
+                message: Message {
+                    from: ids[*from].to_string(),
+                    to: ids[party].to_string(),
+                    tag: "open".to_string(),
+                    payload,
+                },
+            }
+        })
+        .collect();
+
+    View {
+        id: ids[party].to_string(),
+        shares,
+        received,
+        opened: vec![output.clone()],
+    }
+}
+
+/// Summarizes the communication of a protocol execution.
+#[derive(Clone, Debug)]
+pub struct Report<T> {
+    /// Number of communication rounds of the execution.
+    pub rounds: usize,
+
+    /// Simulated time of the execution, in milliseconds.
+    pub elapsed_ms: u64,
+
+    /// Communication of each party, in the order of the parties.
+    pub stats: Vec<CommStats>,
+
+    /// Messages sent during the execution.
+    pub transcript: Transcript<T>,
+}
+
+/// Executes a protocol over a set of parties and reports its communication.
+///
+/// The counters and the records of the parties are reset before calling
+/// `protocol`, so the report only contains the messages and the rounds of this
+/// execution. The
+/// function returns the output of the protocol together with the report.
+pub fn measure<T, R, F>(parties: &mut [&mut VirtualMachine<T>], protocol: F) -> (R, Report<T>)
+where
+    T: MersenneField,
+    F: FnOnce(&mut [&mut VirtualMachine<T>]) -> R,
+{
+    Simulator::new(parties).reset_stats();
+    let output = protocol(parties);
+
+    let network = Simulator::new(parties);
+    let report = Report {
+        rounds: network.rounds(),
+        elapsed_ms: network.elapsed_ms(),
+        stats: network.stats(),
+        transcript: network.transcript(),
+    };
+    (output, report)
+}
+
+/// Defines the inbox and the outbox of a virtual machine.
+///
+/// The boxes use interior mutability, so a protocol can send and receive
+/// messages while it reads the memory of the parties.
+pub struct Mailbox<T> {
+    inbox: RefCell<VecDeque<Message<T>>>,
+    outbox: RefCell<VecDeque<Message<T>>>,
+    stats: Cell<CommStats>,
+    sent: RefCell<Vec<Record<T>>>,
+    opened: RefCell<Vec<T>>,
+    conditions: RefCell<Option<(NetworkConfig, Prg)>>,
+    session: RefCell<Option<String>>,
+    offline: Cell<bool>,
+}
+
+impl<T> Mailbox<T> {
+    /// Creates an empty mailbox.
+    pub fn new() -> Self {
+        Self {
+            inbox: RefCell::new(VecDeque::new()),
+            outbox: RefCell::new(VecDeque::new()),
+            stats: Cell::new(CommStats::default()),
+            sent: RefCell::new(Vec::new()),
+            opened: RefCell::new(Vec::new()),
+            conditions: RefCell::new(None),
+            session: RefCell::new(None),
+            offline: Cell::new(false),
+        }
+    }
+
+    /// Sets the session of the protocol instance being executed, whose ID
+    /// prefixes the tags of the messages sent as `session:tag`, or clears it.
+    pub fn set_session(&self, session: Option<&str>) {
+        *self.session.borrow_mut() = session.map(|session| session.to_string());
+    }
+
+    /// Returns the session of the protocol instance being executed, if any.
+    pub fn session(&self) -> Option<String> {
+        self.session.borrow().clone()
+    }
+
+    /// Takes the party offline or brings it back online. An offline party
+    /// sends nothing and the messages delivered to it are lost, so it can drop
+    /// out in the middle of a protocol.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.set(offline);
+    }
+
+    /// Returns `true` if the party is offline.
+    pub fn is_offline(&self) -> bool {
+        self.offline.get()
+    }
+
+    /// Returns the number of messages waiting in the inbox.
+    pub fn pending(&self) -> usize {
+        self.inbox.borrow().len()
+    }
+
+    /// Returns the communication of the party since the counters were reset.
+    pub fn stats(&self) -> CommStats {
+        self.stats.get()
+    }
+}
+
+impl<T: MersenneField> Mailbox<T> {
+    /// Counts a message sent by the party and records it in the transcript.
+    pub(crate) fn record_sent(&self, message: &Message<T>) {
+        let n_bytes = message.payload.n_bytes();
+        let mut stats = self.stats.get();
+        stats.messages_sent += 1;
+        stats.bytes_sent += n_bytes;
+        self.stats.set(stats);
+        self.sent.borrow_mut().push(Record {
+            round: stats.rounds,
+            n_bytes,
+            message: message.clone(),
+        });
+    }
+
+    /// Counts a message delivered to the party.
+    pub(crate) fn record_received(&self, message: &Message<T>) {
+        let mut stats = self.stats.get();
+        stats.messages_received += 1;
+        stats.bytes_received += message.payload.n_bytes();
+        self.stats.set(stats);
+    }
+
+    /// Counts the end of a round that took `elapsed_ms` milliseconds.
+    pub(crate) fn record_round(&self, elapsed_ms: u64) {
+        let mut stats = self.stats.get();
+        stats.rounds += 1;
+        stats.elapsed_ms += elapsed_ms;
+        self.stats.set(stats);
+    }
+
+    /// Sends a message of the party through the configured network, and
+    /// returns the time until it arrives, or `None` if it is lost.
+    fn transmit(&self, message: &Message<T>) -> Option<u64> {
+        let mut conditions = self.conditions.borrow_mut();
+        let Some((config, prg)) = conditions.as_mut() else {
+            return Some(0);
+        };
+
+        let (max_retries, timeout_ms) = match config.resend {
+            ResendPolicy::Never => (0, 0),
+            ResendPolicy::Retry {
+                max_retries,
+                timeout_ms,
+            } => (max_retries, timeout_ms),
+        };
+        let mut stats = self.stats.get();
+        let mut arrival = None;
+        for attempt in 0..=max_retries {
+            let sample =
+                u64::from_le_bytes(prg.next(8).try_into().unwrap()) as f64 / u64::MAX as f64;
+            if sample >= config.drop_probability {
+                arrival =
+                    Some(attempt as u64 * timeout_ms + config.latency(&message.from, &message.to));
+                break;
+            }
+            if attempt < max_retries {
+                stats.resent += 1;
+            }
+        }
+        if arrival.is_none() {
+            stats.lost += 1;
+        }
+        self.stats.set(stats);
+        arrival
+    }
+
+    /// Shuffles the inbox if the network reorders the messages.
+    fn reorder(&self) {
+        let mut conditions = self.conditions.borrow_mut();
+        if let Some((config, prg)) = conditions.as_mut() {
+            if config.reorder {
+                let mut inbox = self.inbox.borrow_mut();
+                for i in (1..inbox.len()).rev() {
+                    let j = (u64::from_le_bytes(prg.next(8).try_into().unwrap()) % (i as u64 + 1))
+                        as usize;
+                    inbox.swap(i, j);
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for Mailbox<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Moves messages between the mailboxes of a set of virtual machines.
+///
+/// The parties are referred to by their position in the vector used to create
+/// the simulator. All the messages sent through a simulator carry its tag,
+/// prefixed by the session of the sender if there is one (see
+/// [`Mailbox::set_session`]).
+pub struct Simulator<'s, 'v, T: MersenneField> {
+    parties: &'s [&'v mut VirtualMachine<T>],
+    tag: String,
+}
+
+impl<'s, 'v, T: MersenneField> Simulator<'s, 'v, T> {
+    /// Creates a simulator over the provided parties, with an empty tag.
+    pub fn new(parties: &'s [&'v mut VirtualMachine<T>]) -> Self {
+        Self {
+            parties,
+            tag: String::new(),
+        }
+    }
+
+    /// Sets the tag of the messages sent through the simulator.
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = tag.to_string();
+        self
+    }
+
+    /// Puts a message from the party `from` to the party `to` in the outbox of
+    /// the sender. Nothing is sent if the sender is offline.
+    pub fn send(&self, from: usize, to: usize, payload: Payload<T>) {
+        let mailbox = &self.parties[from].mailbox;
+        if mailbox.is_offline() {
+            return;
+        }
+        let tag = match mailbox.session() {
+            Some(session) => format!("{}:{}", session, self.tag),
+            None => self.tag.clone(),
+        };
+        let message = Message {
+            from: self.parties[from].id.clone(),
+            to: self.parties[to].id.clone(),
+            tag,
+            payload,
+        };
+        self.parties[from].emit(|| Event::MessageSent(message.clone()));
+        mailbox.record_sent(&message);
+        mailbox.outbox.borrow_mut().push_back(message);
+    }
+
+    /// Sends the same message from the party `from` to all the other parties.
+    pub fn send_to_all(&self, from: usize, payload: Payload<T>) {
+        for to in (0..self.parties.len()).filter(|to| *to != from) {
+            self.send(from, to, payload.clone());
+        }
+    }
+
+    /// Delivers all the messages in the outboxes to the inboxes of their
+    /// receivers, which marks the end of a round. A delivery without messages
+    /// does not count as a round.
+    pub fn deliver(&self) {
+        let mut delivered = false;
+        let mut elapsed_ms = 0;
+        for party in self.parties.iter() {
+            let messages: Vec<Message<T>> = party.mailbox.outbox.borrow_mut().drain(..).collect();
+            for message in messages {
+                if let Some(arrival) = party.mailbox.transmit(&message) {
+                    elapsed_ms = elapsed_ms.max(arrival);
+                    self.put_in_inbox(message);
+                }
+                delivered = true;
+            }
+        }
+
+        if delivered {
+            for party in self.parties.iter() {
+                party.mailbox.reorder();
+                party.mailbox.record_round(elapsed_ms);
+            }
+        }
+    }
+
+    /// Sets the conditions of the network for all the parties. Each party
+    /// takes a seed from `prg` for the random drops and reorderings of its
+    /// messages.
+    pub fn configure(&self, config: &NetworkConfig, prg: &mut Prg) {
+        for party in self.parties.iter() {
+            let party_prg = Prg::new(Some(prg.next(32)));
+            *party.mailbox.conditions.borrow_mut() = Some((config.clone(), party_prg));
+        }
+    }
+
+    /// Returns the communication of each party since the counters were reset,
+    /// in the order of the parties in the simulator.
+    pub fn stats(&self) -> Vec<CommStats> {
+        self.parties
+            .iter()
+            .map(|party| party.mailbox.stats())
+            .collect()
+    }
+
+    /// Returns the number of rounds since the counters were reset, which is the
+    /// largest round count among the parties.
+    pub fn rounds(&self) -> usize {
+        self.stats()
+            .iter()
+            .map(|stats| stats.rounds)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the view of the party with the provided ID since the counters
+    /// were reset, and panics if the party is not in the simulator.
+    pub fn view(&self, id: &str) -> View<T> {
+        let party = self
+            .parties
+            .iter()
+            .find(|party| party.id == id)
+            .unwrap_or_else(|| panic!("The party {} is not connected to the network.", id));
+
+        let shares: Vec<(String, T)> = party
+            .shares()
+            .into_iter()
+            .map(|(id, share)| (id.to_string(), share.value.clone()))
+            .collect();
+
+        View {
+            id: id.to_string(),
+            shares,
+            received: self
+                .transcript()
+                .records
+                .into_iter()
+                .filter(|record| record.message.to == id)
+                .collect(),
+            opened: party.mailbox.opened.borrow().clone(),
+        }
+    }
+
+    /// Returns the transcript of the messages sent by the parties since the
+    /// counters were reset.
+    pub fn transcript(&self) -> Transcript<T> {
+        let mut records: Vec<Record<T>> = self
+            .parties
+            .iter()
+            .flat_map(|party| party.mailbox.sent.borrow().clone())
+            .collect();
+        records.sort_by_key(|record| record.round);
+        Transcript { records }
+    }
+
+    /// Returns the simulated time since the counters were reset, which is the
+    /// largest time among the parties.
+    pub fn elapsed_ms(&self) -> u64 {
+        self.stats()
+            .iter()
+            .map(|stats| stats.elapsed_ms)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Resets the communication counters, the records of the sent messages and
+    /// the opened values of all the parties.
+    pub fn reset_stats(&self) {
+        for party in self.parties.iter() {
+            party.mailbox.stats.set(CommStats::default());
+            party.mailbox.sent.borrow_mut().clear();
+            party.mailbox.opened.borrow_mut().clear();
+        }
+    }
+
+    /// Delivers only the oldest message in the outboxes, taking the outboxes in
+    /// the order of the parties, and returns it, even if the network drops it.
+    /// A single delivery does not count as a round.
+    pub fn deliver_next(&self) -> Option<Message<T>> {
+        let message = self
+            .parties
+            .iter()
+            .find_map(|party| party.mailbox.outbox.borrow_mut().pop_front())?;
+        let sender = self
+            .parties
+            .iter()
+            .find(|party| party.id == message.from)
+            .unwrap();
+        if sender.mailbox.transmit(&message).is_some() {
+            self.put_in_inbox(message.clone());
+        }
+        Some(message)
+    }
+
+    /// Puts a message in the inbox of its receiver and counts it, unless the
+    /// receiver is offline.
+    fn put_in_inbox(&self, message: Message<T>) {
+        let receiver = self
+            .parties
+            .iter()
+            .find(|receiver| receiver.id == message.to)
+            .unwrap_or_else(|| panic!("The party {} is not connected to the network.", message.to));
+        if receiver.mailbox.is_offline() {
+            return;
+        }
+        receiver.mailbox.record_received(&message);
+        receiver.mailbox.inbox.borrow_mut().push_back(message);
+    }
+
+    /// Returns the messages waiting in the outboxes of the parties.
+    pub fn undelivered(&self) -> Vec<Message<T>> {
+        self.parties
+            .iter()
+            .flat_map(|party| party.mailbox.outbox.borrow().clone())
+            .collect()
+    }
+
+    /// Returns the messages waiting in the inbox of the party `to`, without
+    /// taking them.
+    pub fn inbox(&self, to: usize) -> Vec<Message<T>> {
+        self.parties[to]
+            .mailbox
+            .inbox
+            .borrow()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Takes the oldest message from the inbox of the party `to`, and panics if
+    /// the inbox is empty.
+    pub fn recv(&self, to: usize) -> Message<T> {
+        self.parties[to]
+            .mailbox
+            .inbox
+            .borrow_mut()
+            .pop_front()
+            .unwrap_or_else(|| {
+                panic!(
+                    "The party {} has no messages to receive.",
+                    self.parties[to].id
+                )
+            })
+    }
+
+    /// Takes all the messages from the inbox of the party `to`, from the oldest
+    /// to the newest.
+    pub fn recv_all(&self, to: usize) -> Vec<Message<T>> {
+        self.parties[to]
+            .mailbox
+            .inbox
+            .borrow_mut()
+            .drain(..)
+            .collect()
+    }
+
+    /// Takes the oldest message sent by the party `from` from the inbox of the
+    /// party `to`, and panics if there is none, in particular if the sender
+    /// is offline.
+    pub fn recv_from(&self, to: usize, from: usize) -> Message<T> {
+        let mut inbox = self.parties[to].mailbox.inbox.borrow_mut();
+        let position = inbox
+            .iter()
+            .position(|message| message.from == self.parties[from].id)
+            .unwrap_or_else(|| {
+                if self.parties[from].mailbox.is_offline() {
+                    panic!("{}", MissingParty::new(&self.parties[from].id));
+                }
+                panic!(
+                    "The party {} has no messages from {}.",
+                    self.parties[to].id, self.parties[from].id
+                )
+            });
+        inbox.remove(position).unwrap()
+    }
+
+    /// Opens secret-shared field elements in one round.
+    ///
+    /// Each party $j$ sends its shares `shares[j]` to all the other parties,
+    /// and each party adds the shares it receives to its own. The function
+    /// returns the opened values, which are the same for all the parties.
+    pub fn open(&self, shares: &[Vec<T>]) -> Vec<T> {
+        for (from, party_shares) in shares.iter().enumerate() {
+            self.send_to_all(from, Payload::Elements(party_shares.clone()));
+        }
+        self.deliver();
+
+        let mut opened = Vec::new();
+        for (to, own_shares) in shares
+            .iter()
+            .enumerate()
+            .filter(|(to, _)| !self.is_offline(*to))
+        {
+            let mut values = own_shares.clone();
+            for from in (0..self.parties.len()).filter(|from| *from != to) {
+                let received = self.recv_from(to, from).payload.elements();
+                for (value, share) in values.iter_mut().zip(received) {
+                    *value = value.add(&share);
+                }
+            }
+            self.record_opened(to, &values);
+            opened = values;
+        }
+        opened
+    }
+
+    /// Opens secret-shared field elements through the party in position
+    /// `king`, in two rounds.
+    ///
+    /// Each party $j$ sends its shares `shares[j]` only to the king, which adds
+    /// them up and sends the opened values back to all the other parties. This
+    /// takes $2(n - 1)$ messages instead of the $n(n - 1)$ of
+    /// [`Simulator::open`], at the cost of an extra round, and the king can
+    /// send a different result to each party.
+    pub fn open_via_king(&self, shares: &[Vec<T>], king: usize) -> Vec<T> {
+        if self.is_offline(king) {
+            panic!("{}", MissingParty::new(&self.parties[king].id));
+        }
+        for (from, party_shares) in shares.iter().enumerate().filter(|(from, _)| *from != king) {
+            self.send(from, king, Payload::Elements(party_shares.clone()));
+        }
+        self.deliver();
+
+        let mut values = shares[king].clone();
+        for from in (0..self.parties.len()).filter(|from| *from != king) {
+            let received = self.recv_from(king, from).payload.elements();
+            for (value, share) in values.iter_mut().zip(received) {
+                *value = value.add(&share);
+            }
+        }
+        self.record_opened(king, &values);
+
+        self.send_to_all(king, Payload::Elements(values.clone()));
+        self.deliver();
+        for to in (0..self.parties.len()).filter(|to| *to != king && !self.is_offline(*to)) {
+            let received = self.recv_from(to, king).payload.elements();
+            self.record_opened(to, &received);
+        }
+        values
+    }
+
+    /// Returns `true` if the party in position `party` is offline.
+    fn is_offline(&self, party: usize) -> bool {
+        self.parties[party].mailbox.is_offline()
+    }
+
+    /// Stores the values opened by the party in position `to`.
+    fn record_opened(&self, to: usize, values: &[T]) {
+        self.parties[to]
+            .mailbox
+            .opened
+            .borrow_mut()
+            .extend(values.iter().cloned());
+        counter::record(|counts| counts.openings += values.len() as u64);
+        self.parties[to].emit(|| Event::Opened {
+            tag: self.tag.clone(),
+            payload: Payload::Elements(values.to_vec()),
+        });
+    }
+
+    /// Opens boolean-shared bits in one round, in the same way as
+    /// [`Simulator::open`]. The opened bits are stored in the opened values of
+    /// the parties as the field elements 0 and 1.
+    pub fn open_bits(&self, shares: &[Vec<bool>]) -> Vec<bool> {
+        for (from, party_shares) in shares.iter().enumerate() {
+            self.send_to_all(from, Payload::Bits(party_shares.clone()));
+        }
+        self.deliver();
+
+        let mut opened = Vec::new();
+        for (to, own_shares) in shares
+            .iter()
+            .enumerate()
+            .filter(|(to, _)| !self.is_offline(*to))
+        {
+            let mut values = own_shares.clone();
+            for from in (0..self.parties.len()).filter(|from| *from != to) {
+                let received = self.recv_from(to, from).payload.bits();
+                for (value, share) in values.iter_mut().zip(received) {
+                    *value ^= share;
+                }
+            }
+            self.parties[to]
+                .mailbox
+                .opened
+                .borrow_mut()
+                .extend(values.iter().map(|bit| T::new(*bit as u64)));
+            counter::record(|counts| counts.openings += values.len() as u64);
+            self.parties[to].emit(|| Event::Opened {
+                tag: self.tag.clone(),
+                payload: Payload::Bits(values.clone()),
+            });
+            opened = values;
+        }
+        opened
+    }
+}
+
+/// Reports that a protocol could not finish because a party it needs is
+/// offline (see [`Mailbox::set_offline`]).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingParty {
+    /// ID of the offline party.
+    pub id: String,
+}
+
+impl MissingParty {
+    /// Creates the error for the party with the provided ID.
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
+}
+
+impl fmt::Display for MissingParty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The party {} is offline.", self.id)
+    }
+}
+
+impl std::error::Error for MissingParty {}
+
+/// Contains the messages sent by a party to another party in a round.
+#[derive(Clone, Debug)]
+pub struct Envelope<T> {
+    /// Round in which the messages were sent.
+    pub round: usize,
+
+    /// Position of the sender among the parties.
+    pub from: usize,
+
+    /// Whether the sender sent messages to any party in this round, so the
+    /// receiver knows if the protocol continues.
+    pub active: bool,
+
+    /// Messages for the receiver, which may be empty.
+    pub messages: Vec<Message<T>>,
+}
+
+/// Defines the endpoint of a party in a network where the parties run
+/// independently.
+pub trait Channel<T> {
+    /// Sends an envelope to the party in position `to`.
+    fn send(&mut self, to: usize, envelope: Envelope<T>);
+
+    /// Receives the next envelope sent to this party, blocking until it
+    /// arrives.
+    fn recv(&mut self) -> Envelope<T>;
+}
+
+/// Connects a party with the other parties of the same process through
+/// [`std::sync::mpsc`] channels.
+pub struct MpscChannel<T> {
+    senders: Vec<Option<Sender<Envelope<T>>>>,
+    receiver: Receiver<Envelope<T>>,
+}
+
+impl<T> Channel<T> for MpscChannel<T> {
+    fn send(&mut self, to: usize, envelope: Envelope<T>) {
+        let sender = self.senders[to].as_ref().unwrap_or_else(|| {
+            panic!("A party cannot send envelopes to itself.");
+        });
+        sender.send(envelope).unwrap_or_else(|_| {
+            panic!("The party {} has left the execution.", to);
+        });
+    }
+
+    fn recv(&mut self) -> Envelope<T> {
+        self.receiver.recv().unwrap_or_else(|_| {
+            panic!("All the other parties have left the execution.");
+        })
+    }
+}
+
+/// Creates the endpoints of `n_parties` parties connected with each other,
+/// where the $j$-th endpoint belongs to the $j$-th party.
+pub fn mpsc_channels<T>(n_parties: usize) -> Vec<MpscChannel<T>> {
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..n_parties).map(|_| mpsc::channel()).unzip();
+    receivers
+        .into_iter()
+        .enumerate()
+        .map(|(j, receiver)| MpscChannel {
+            senders: senders
+                .iter()
+                .enumerate()
+                .map(|(to, sender)| (to != j).then(|| sender.clone()))
+                .collect(),
+            receiver,
+        })
+        .collect()
+}
+
+/// Defines the endpoint of a party that runs as an asynchronous task, in the
+/// same way as [`Channel`].
+#[cfg(feature = "async")]
+pub trait AsyncChannel<T> {
+    /// Sends an envelope to the party in position `to`.
+    fn send(
+        &mut self,
+        to: usize,
+        envelope: Envelope<T>,
+    ) -> impl std::future::Future<Output = ()> + Send;
+
+    /// Receives the next envelope sent to this party, waiting until it
+    /// arrives.
+    fn recv(&mut self) -> impl std::future::Future<Output = Envelope<T>> + Send;
+}
+
+/// Connects a party with the other tasks of the same runtime through
+/// [`tokio::sync::mpsc`] channels.
+#[cfg(feature = "async")]
+pub struct TokioChannel<T> {
+    senders: Vec<Option<tokio::sync::mpsc::UnboundedSender<Envelope<T>>>>,
+    receiver: tokio::sync::mpsc::UnboundedReceiver<Envelope<T>>,
+}
+
+#[cfg(feature = "async")]
+impl<T: Send> AsyncChannel<T> for TokioChannel<T> {
+    fn send(
+        &mut self,
+        to: usize,
+        envelope: Envelope<T>,
+    ) -> impl std::future::Future<Output = ()> + Send {
+        let sender = self.senders[to].as_ref().unwrap_or_else(|| {
+            panic!("A party cannot send envelopes to itself.");
+        });
+        sender.send(envelope).unwrap_or_else(|_| {
+            panic!("The party {} has left the execution.", to);
+        });
+        std::future::ready(())
+    }
+
+    async fn recv(&mut self) -> Envelope<T> {
+        self.receiver.recv().await.unwrap_or_else(|| {
+            panic!("All the other parties have left the execution.");
+        })
+    }
+}
+
+/// Creates the endpoints of `n_parties` tasks connected with each other, where
+/// the $j$-th endpoint belongs to the $j$-th party.
+#[cfg(feature = "async")]
+pub fn tokio_channels<T>(n_parties: usize) -> Vec<TokioChannel<T>> {
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..n_parties)
+        .map(|_| tokio::sync::mpsc::unbounded_channel())
+        .unzip();
+    receivers
+        .into_iter()
+        .enumerate()
+        .map(|(j, receiver)| TokioChannel {
+            senders: senders
+                .iter()
+                .enumerate()
+                .map(|(to, sender)| (to != j).then(|| sender.clone()))
+                .collect(),
+            receiver,
+        })
+        .collect()
+}
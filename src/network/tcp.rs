@@ -0,0 +1,446 @@
+//! Implements a channel over TCP sockets.
+//!
+//! With this backend, each party can run in its own process, possibly on a
+//! different machine, and execute a [`Protocol`](crate::protocol::Protocol)
+//! with [`run_party`](crate::protocol::run_party). Every pair of parties is
+//! connected by a TCP stream: party $j$ listens on its own address, connects
+//! to the parties before it, and accepts the connections of the parties after
+//! it.
+//!
+//! The envelopes travel in a simple length-prefixed wire format. A frame is
+//! the length of the encoded envelope as a 4-byte little-endian integer,
+//! followed by the envelope (see [`encode`]). Integers are little-endian,
+//! strings are prefixed by their length as a 4-byte integer, and each field
+//! element is sent as its 8-byte canonical value. Frames longer than
+//! [`MAX_FRAME_LEN`] are rejected, so a peer cannot make a party allocate an
+//! arbitrary amount of memory.
+//!
+//! A malformed frame, an envelope on behalf of another party, or a connection
+//! that breaks in the middle of a frame makes the next call to
+//! [`Channel::recv`] panic with the corresponding [`TcpError`], instead of
+//! leaving the party waiting forever. A party that closes its connection
+//! between two frames has finished the execution, and the receiving party
+//! only panics once all the other parties have left.
+//!
+//! # Example
+//!
+//! Each party runs the same program with its own position, for example with
+//! `0` on one machine and `1` on the other.
+//!
+//! ```rust,no_run
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::network::tcp::TcpChannel;
+//! use smol_mpc::protocol::{self, DistributeShares, Reconstruct};
+//! use smol_mpc::vm::VirtualMachine;
+//! use std::net::{SocketAddr, TcpListener};
+//!
+//! type Fp = Mersenne61;
+//!
+//! let own: usize = std::env::args().nth(1).unwrap().parse().unwrap();
+//! let ids = vec!["alice".to_string(), "bob".to_string()];
+//! let addresses: Vec<SocketAddr> = vec![
+//!     "10.0.0.1:7000".parse().unwrap(),
+//!     "10.0.0.2:7000".parse().unwrap(),
+//! ];
+//!
+//! let mut vm: VirtualMachine<Fp> = VirtualMachine::new(&ids[own]);
+//! vm.insert_priv_value(&format!("x{}", own), Fp::new(20 + own as u64));
+//! let listener = TcpListener::bind(addresses[own]).unwrap();
+//! let mut channel = TcpChannel::new(own, listener, &addresses).unwrap();
+//!
+//! for (j, owner) in ids.iter().enumerate() {
+//!     let mut machine = DistributeShares::new(&format!("x{}", j), owner);
+//!     protocol::run_party(&mut vm, &mut machine, &ids, &mut channel);
+//! }
+//! let mut machine = protocol::Add::new("x0", "x1", "sum");
+//! protocol::run_party(&mut vm, &mut machine, &ids, &mut channel);
+//! let mut machine = Reconstruct::new("sum");
+//! let sum = protocol::run_party(&mut vm, &mut machine, &ids, &mut channel);
+//! assert_eq!(sum.value(), 41);
+//! ```
+
+use super::{Channel, Envelope, Message, Payload};
+use crate::math::mersenne::MersenneField;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// Maximum length of a frame, in bytes.
+pub const MAX_FRAME_LEN: usize = 1 << 26;
+
+/// Reports that an envelope could not be decoded from the wire format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Description of the malformed field.
+    pub reason: String,
+}
+
+impl DecodeError {
+    /// Creates the error with the provided description.
+    pub fn new(reason: &str) -> Self {
+        Self {
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The envelope cannot be decoded: {}.", self.reason)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Reports a failure of the connections between the parties.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TcpError {
+    /// A connection could not be established.
+    Connection(io::ErrorKind),
+
+    /// A party announced a position that is out of range, that is not after
+    /// the own position, or that is already connected.
+    InvalidPosition(usize),
+
+    /// The party in the provided position sent a frame of the provided length,
+    /// which is longer than [`MAX_FRAME_LEN`].
+    FrameTooLong(usize, usize),
+
+    /// The party in the provided position sent a malformed envelope.
+    Decode(usize, DecodeError),
+
+    /// The party in position `stream` sent an envelope on behalf of the party
+    /// in position `from`.
+    WrongSender {
+        /// Position of the party connected to the stream.
+        stream: usize,
+
+        /// Position claimed by the envelope.
+        from: usize,
+    },
+
+    /// The connection with the party in the provided position broke in the
+    /// middle of a frame.
+    Disconnected(usize),
+}
+
+impl fmt::Display for TcpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TcpError::Connection(kind) => {
+                write!(f, "The connection with a party has failed: {}.", kind)
+            }
+            TcpError::InvalidPosition(position) => {
+                write!(
+                    f,
+                    "A party connected with the invalid position {}.",
+                    position
+                )
+            }
+            TcpError::FrameTooLong(position, length) => write!(
+                f,
+                "The party {} sent a frame of {} bytes, longer than {}.",
+                position, length, MAX_FRAME_LEN
+            ),
+            TcpError::Decode(position, error) => write!(
+                f,
+                "The party {} sent an envelope that cannot be decoded: {}.",
+                position, error.reason
+            ),
+            TcpError::WrongSender { stream, from } => write!(
+                f,
+                "The party {} sent an envelope on behalf of the party {}.",
+                stream, from
+            ),
+            TcpError::Disconnected(position) => {
+                write!(f, "The party {} has left the execution.", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TcpError {}
+
+/// Defines what the thread reading a stream forwards to the channel: an
+/// envelope, the clean end of the stream, or an error.
+enum Event<T> {
+    Envelope(Envelope<T>),
+    Closed,
+    Error(TcpError),
+}
+
+/// Connects a party with the other parties through TCP streams.
+pub struct TcpChannel<T> {
+    streams: Vec<Option<TcpStream>>,
+    receiver: Receiver<Event<T>>,
+    open: usize,
+}
+
+impl<T: MersenneField + Send + 'static> TcpChannel<T> {
+    /// Connects the party in position `own` with the parties listening on
+    /// `addresses`, where `listener` is bound to the address of this party.
+    ///
+    /// The connections to the parties before this one are retried until they
+    /// start listening. The function returns once all the parties are
+    /// connected, or an error if a connection fails or a party announces a
+    /// position that is not after `own`, is out of range, or is already
+    /// connected.
+    pub fn new(
+        own: usize,
+        listener: TcpListener,
+        addresses: &[SocketAddr],
+    ) -> Result<Self, TcpError> {
+        let mut streams: Vec<Option<TcpStream>> = addresses.iter().map(|_| None).collect();
+        for (j, address) in addresses.iter().enumerate().take(own) {
+            let mut stream = loop {
+                match TcpStream::connect(address) {
+                    Ok(stream) => break stream,
+                    Err(_) => thread::sleep(Duration::from_millis(50)),
+                }
+            };
+            stream
+                .write_all(&(own as u32).to_le_bytes())
+                .map_err(|_| TcpError::Disconnected(j))?;
+            streams[j] = Some(stream);
+        }
+        for _ in own + 1..addresses.len() {
+            let (mut stream, _) = listener
+                .accept()
+                .map_err(|error| TcpError::Connection(error.kind()))?;
+            let mut position = [0u8; 4];
+            stream
+                .read_exact(&mut position)
+                .map_err(|error| TcpError::Connection(error.kind()))?;
+            let position = u32::from_le_bytes(position) as usize;
+            if position <= own || position >= addresses.len() || streams[position].is_some() {
+                return Err(TcpError::InvalidPosition(position));
+            }
+            streams[position] = Some(stream);
+        }
+
+        // Each stream is read by its own thread, so the envelopes can arrive in
+        // any order.
+        let (sender, receiver) = mpsc::channel();
+        for (j, stream) in streams.iter().enumerate() {
+            if let Some(stream) = stream {
+                let stream = stream
+                    .try_clone()
+                    .map_err(|error| TcpError::Connection(error.kind()))?;
+                let sender = sender.clone();
+                thread::spawn(move || read_stream(j, stream, sender));
+            }
+        }
+
+        let open = streams.iter().flatten().count();
+        Ok(Self {
+            streams,
+            receiver,
+            open,
+        })
+    }
+}
+
+impl<T: MersenneField> Channel<T> for TcpChannel<T> {
+    fn send(&mut self, to: usize, envelope: Envelope<T>) {
+        let stream = self.streams[to].as_mut().unwrap_or_else(|| {
+            panic!("A party cannot send envelopes to itself.");
+        });
+        let bytes = encode(&envelope);
+        if bytes.len() > MAX_FRAME_LEN {
+            panic!(
+                "The envelope takes {} bytes, more than the maximum of {}.",
+                bytes.len(),
+                MAX_FRAME_LEN
+            );
+        }
+        stream
+            .write_all(&(bytes.len() as u32).to_le_bytes())
+            .and_then(|_| stream.write_all(&bytes))
+            .unwrap_or_else(|_| panic!("The party {} has left the execution.", to));
+    }
+
+    fn recv(&mut self) -> Envelope<T> {
+        loop {
+            if self.open == 0 {
+                panic!("All the other parties have left the execution.");
+            }
+            match self.receiver.recv() {
+                Ok(Event::Envelope(envelope)) => return envelope,
+                Ok(Event::Closed) => self.open -= 1,
+                Ok(Event::Error(error)) => panic!("{}", error),
+                Err(_) => panic!("All the other parties have left the execution."),
+            }
+        }
+    }
+}
+
+/// Reads the frames of the party in position `position` and forwards them to
+/// the channel until the stream is closed or fails.
+fn read_stream<T: MersenneField>(position: usize, mut stream: TcpStream, sender: Sender<Event<T>>) {
+    loop {
+        let event = match read_frame(position, &mut stream) {
+            Ok(Some(frame)) => match decode::<T>(&frame) {
+                Ok(envelope) if envelope.from == position => Event::Envelope(envelope),
+                Ok(envelope) => Event::Error(TcpError::WrongSender {
+                    stream: position,
+                    from: envelope.from,
+                }),
+                Err(error) => Event::Error(TcpError::Decode(position, error)),
+            },
+            Ok(None) => Event::Closed,
+            Err(error) => Event::Error(error),
+        };
+        let last = !matches!(event, Event::Envelope(_));
+        if sender.send(event).is_err() || last {
+            break;
+        }
+    }
+}
+
+/// Reads a length-prefixed frame, and returns `None` if the stream is closed
+/// before the frame starts.
+fn read_frame(position: usize, stream: &mut TcpStream) -> Result<Option<Vec<u8>>, TcpError> {
+    let mut length = [0u8; 4];
+    match stream.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(_) => return Err(TcpError::Disconnected(position)),
+    }
+    let length = u32::from_le_bytes(length) as usize;
+    if length > MAX_FRAME_LEN {
+        return Err(TcpError::FrameTooLong(position, length));
+    }
+    let mut frame = vec![0u8; length];
+    stream
+        .read_exact(&mut frame)
+        .map_err(|_| TcpError::Disconnected(position))?;
+    Ok(Some(frame))
+}
+
+/// Encodes an envelope in the wire format.
+///
+/// The envelope is encoded as the round (8 bytes), the position of the sender
+/// (4 bytes), the `active` flag (1 byte) and the number of messages (4 bytes),
+/// followed by the messages. Each message is encoded as its sender, its
+/// receiver and its tag, as strings, and its payload. A payload is a kind byte,
+/// `0` for field elements and `1` for bits, the number of entries (4 bytes),
+/// and the entries, 8 bytes per field element or 1 byte per bit.
+pub fn encode<T: MersenneField>(envelope: &Envelope<T>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend((envelope.round as u64).to_le_bytes());
+    bytes.extend((envelope.from as u32).to_le_bytes());
+    bytes.push(envelope.active as u8);
+    bytes.extend((envelope.messages.len() as u32).to_le_bytes());
+
+    for message in &envelope.messages {
+        for text in [&message.from, &message.to, &message.tag] {
+            bytes.extend((text.len() as u32).to_le_bytes());
+            bytes.extend(text.as_bytes());
+        }
+        match &message.payload {
+            Payload::Elements(elements) => {
+                bytes.push(0);
+                bytes.extend((elements.len() as u32).to_le_bytes());
+                for element in elements {
+                    bytes.extend(element.value().to_le_bytes());
+                }
+            }
+            Payload::Bits(bits) => {
+                bytes.push(1);
+                bytes.extend((bits.len() as u32).to_le_bytes());
+                bytes.extend(bits.iter().map(|bit| *bit as u8));
+            }
+        }
+    }
+    bytes
+}
+
+/// Decodes an envelope from the wire format (see [`encode`]), and returns an
+/// error if the bytes are malformed.
+pub fn decode<T: MersenneField>(bytes: &[u8]) -> Result<Envelope<T>, DecodeError> {
+    let mut reader = Reader { bytes, position: 0 };
+    let round = reader.u64()? as usize;
+    let from = reader.u32()? as usize;
+    let active = reader.take(1)?[0] == 1;
+    let n_messages = reader.u32()?;
+
+    let messages = (0..n_messages)
+        .map(|_| {
+            let from = reader.string()?;
+            let to = reader.string()?;
+            let tag = reader.string()?;
+            let kind = reader.take(1)?[0];
+            let n_entries = reader.u32()? as usize;
+            let payload = match kind {
+                0 => Payload::Elements(
+                    (0..n_entries)
+                        .map(|_| reader.u64().map(T::new))
+                        .collect::<Result<_, _>>()?,
+                ),
+                1 => Payload::Bits(
+                    reader
+                        .take(n_entries)?
+                        .iter()
+                        .map(|byte| *byte == 1)
+                        .collect(),
+                ),
+                _ => {
+                    return Err(DecodeError::new(&format!(
+                        "the payload kind {} is not valid",
+                        kind
+                    )))
+                }
+            };
+            Ok(Message {
+                from,
+                to,
+                tag,
+                payload,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    if reader.position != bytes.len() {
+        return Err(DecodeError::new("there are bytes after the envelope"));
+    }
+
+    Ok(Envelope {
+        round,
+        from,
+        active,
+        messages,
+    })
+}
+
+/// Reads the fields of an encoded envelope.
+struct Reader<'b> {
+    bytes: &'b [u8],
+    position: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, n_bytes: usize) -> Result<&'b [u8], DecodeError> {
+        if n_bytes > self.bytes.len() - self.position {
+            return Err(DecodeError::new("the envelope is too short"));
+        }
+        let bytes = &self.bytes[self.position..self.position + n_bytes];
+        self.position += n_bytes;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let length = self.u32()? as usize;
+        String::from_utf8(self.take(length)?.to_vec())
+            .map_err(|_| DecodeError::new("a string is not valid UTF-8"))
+    }
+}
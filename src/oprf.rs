@@ -0,0 +1,142 @@
+//! Implements an oblivious pseudo-random function (OPRF) between two parties.
+//!
+//! In an OPRF, a server holds the key $k$ of a pseudo-random function $F_k$,
+//! and a client obtains $F_k(x)$ on its private input $x$, without learning
+//! anything else about the key and without the server learning anything about
+//! the input. The function here is the hashed Diffie-Hellman PRF
+//!    $$F_k(x) = H'(x, H(x)^k),$$
+//! where $H$ hashes the input to an element of a [`Group`] and $H'$ hashes the
+//! result to 32 bytes, which is pseudo-random under the Diffie-Hellman
+//! assumption in the group. The key is a field element, since the exponents of
+//! the group are in the field.
+//!
+//! The client blinds the hash of its input with a random exponent $r$ and
+//! sends $a = H(x)^r$, which is uniformly distributed in the group. The server
+//! answers with $b = a^k$, and the client removes the blinding as
+//! $b^{1/r} = H(x)^k$. The server can also evaluate the function on its own
+//! inputs with [`evaluate`], which makes the OPRF a building block of private
+//! set intersection.
+//!
+//! The group elements cannot be sent through the simulated network, whose
+//! messages carry field elements, so the messages are passed directly between
+//! the two parties. The hashes are computed with the random oracle of
+//! [`ro`](crate::utils::ro), so this module is only available with the `sha2`
+//! feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::math::group::Group;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::oprf;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let group: Group<Fp> = Group::generate(128, &mut prg);
+//!
+//! let mut client: VirtualMachine<Fp> = VirtualMachine::new("client");
+//! let mut server: VirtualMachine<Fp> = VirtualMachine::new("server");
+//! client.insert_priv_value("x", Fp::new(42));
+//! server.insert_priv_value("k", Fp::random(&mut prg));
+//!
+//! let output = oprf::oprf_protocol(&mut client, &mut server, &group, "x", "k");
+//! assert_eq!(output, oprf::evaluate(&group, server.get_priv_value("k"), &Fp::new(42)));
+//! ```
+
+use crate::math::group::{Group, GroupElement};
+use crate::math::mersenne::MersenneField;
+use crate::utils::ro::RandomOracle;
+use crate::vm::VirtualMachine;
+
+/// Length in bytes of the outputs of the function.
+pub const OUTPUT_LEN: usize = 32;
+
+/// Hashes a field element to an element of the group, whose discrete logarithm
+/// is unknown.
+pub fn hash_to_group<T: MersenneField>(group: &Group<T>, input: &T) -> GroupElement {
+    let mut oracle = RandomOracle::new("smol-mpc oprf hash to group");
+    oracle.absorb_field_element(input);
+    group.random_element(&mut oracle.squeeze_prg())
+}
+
+/// Computes the output of the function from the input and $H(x)^k$.
+fn finalize<T: MersenneField>(input: &T, element: &GroupElement) -> Vec<u8> {
+    let mut oracle = RandomOracle::new("smol-mpc oprf output");
+    oracle.absorb_field_element(input);
+    oracle.absorb(&element.0.to_bytes_le());
+    oracle.squeeze(OUTPUT_LEN)
+}
+
+/// Evaluates the function with the provided key on an input, as done by the
+/// server on its own inputs.
+pub fn evaluate<T: MersenneField>(group: &Group<T>, key: &T, input: &T) -> Vec<u8> {
+    finalize(input, &group.exp(&hash_to_group(group, input), key))
+}
+
+/// Evaluates the function obliviously on a private input of the client.
+///
+/// The client holds the input with ID `id_input` and the server holds the key
+/// with ID `id_key`, both in their private memories. The client receives the
+/// output, and the server learns nothing.
+pub fn oprf_protocol<T>(
+    client: &mut VirtualMachine<T>,
+    server: &mut VirtualMachine<T>,
+    group: &Group<T>,
+    id_input: &str,
+    id_key: &str,
+) -> Vec<u8>
+where
+    T: MersenneField,
+{
+    oprf_batch_protocol(client, server, group, &[id_input], id_key).remove(0)
+}
+
+/// Evaluates the function obliviously on several private inputs of the client
+/// in a single round trip, blinding each of them with a different exponent.
+pub fn oprf_batch_protocol<T>(
+    client: &mut VirtualMachine<T>,
+    server: &mut VirtualMachine<T>,
+    group: &Group<T>,
+    ids_inputs: &[&str],
+    id_key: &str,
+) -> Vec<Vec<u8>>
+where
+    T: MersenneField,
+{
+    // The client blinds the hashes of its inputs.
+    let inputs: Vec<T> = ids_inputs
+        .iter()
+        .map(|id| client.get_priv_value(id).clone())
+        .collect();
+    let blinds: Vec<T> = inputs
+        .iter()
+        .map(|_| loop {
+            let r = T::random(&mut client.prg);
+            if r.value() != 0 {
+                break r;
+            }
+        })
+        .collect();
+    let blinded: Vec<GroupElement> = inputs
+        .iter()
+        .zip(&blinds)
+        .map(|(input, r)| group.exp(&hash_to_group(group, input), r))
+        .collect();
+
+    // The server raises them to its key.
+    let key = server.get_priv_value(id_key);
+    let evaluated: Vec<GroupElement> = blinded
+        .iter()
+        .map(|element| group.exp(element, key))
+        .collect();
+
+    // The client removes the blinding.
+    inputs
+        .iter()
+        .zip(blinds.iter().zip(&evaluated))
+        .map(|(input, (r, element))| finalize(input, &group.exp(element, &r.inverse())))
+        .collect()
+}
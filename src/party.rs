@@ -0,0 +1,163 @@
+//! Implements a validated set of parties.
+//!
+//! The protocols receive the parties as a vector of virtual machines. A
+//! [`PartySet`] wraps such vector, checking that the IDs of the parties are
+//! unique, and it carries the threshold $t$ of the access structure, that is,
+//! the maximum number of parties that may be corrupted. A party set
+//! dereferences to the vector of parties, so it can be passed directly to the
+//! protocols that only read the parties. The protocols that modify them
+//! receive the slice returned by [`PartySet::parties_mut`], which lets them
+//! change the virtual machines but not add or remove parties, so the IDs stay
+//! unique.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::mpc;
+//! use smol_mpc::party::PartySet;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+//! let mut parties = PartySet::new(vec![&mut alice, &mut bob, &mut charlie]).with_threshold(1);
+//!
+//! mpc::simulate_random_dist("x", parties.parties_mut(), &Fp::new(5), &mut Prg::new(None));
+//! mpc::add_protocol(parties.parties_mut(), "x", "x", "y");
+//! assert_eq!(mpc::reconstruct_share(&parties, "y").value(), 10);
+//! assert!(parties.is_qualified(&["alice", "bob"]));
+//! ```
+
+use crate::math::mersenne::MersenneField;
+use crate::mpc;
+use crate::vm::VirtualMachine;
+use std::ops::Deref;
+
+/// Defines an ordered set of parties with unique IDs and an optional
+/// threshold.
+pub struct PartySet<'v, T: MersenneField> {
+    parties: Vec<&'v mut VirtualMachine<T>>,
+    threshold: Option<usize>,
+}
+
+impl<'v, T: MersenneField> PartySet<'v, T> {
+    /// Creates a set with the provided parties, keeping their order. The
+    /// function panics if two parties have the same ID.
+    pub fn new(parties: Vec<&'v mut VirtualMachine<T>>) -> Self {
+        for (i, party) in parties.iter().enumerate() {
+            if parties[..i].iter().any(|other| other.id == party.id) {
+                panic!("The party {} appears more than once.", party.id);
+            }
+        }
+        Self {
+            parties,
+            threshold: None,
+        }
+    }
+
+    /// Sets the threshold $t$ of the set, that is, the maximum number of
+    /// corrupted parties tolerated. The function panics if $t \geq n$.
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.set_threshold(threshold);
+        self
+    }
+
+    /// Changes the threshold $t$ of the set, and panics if $t \geq n$.
+    pub fn set_threshold(&mut self, threshold: usize) {
+        if threshold >= self.parties.len() {
+            panic!("The threshold must be smaller than the number of parties.");
+        }
+        self.threshold = Some(threshold);
+    }
+
+    /// Returns the threshold of the set, if any.
+    pub fn threshold(&self) -> Option<usize> {
+        self.threshold
+    }
+
+    /// Returns the IDs of the parties in order.
+    pub fn ids(&self) -> Vec<String> {
+        self.parties.iter().map(|party| party.id.clone()).collect()
+    }
+
+    /// Returns `true` if there is a party with the provided ID.
+    pub fn contains(&self, id: &str) -> bool {
+        self.parties.iter().any(|party| party.id == id)
+    }
+
+    /// Returns the position of the party with the provided ID, and panics if
+    /// the party is not in the set.
+    pub fn position(&self, id: &str) -> usize {
+        self.parties
+            .iter()
+            .position(|party| party.id == id)
+            .unwrap_or_else(|| panic!("Party with that id does not exist."))
+    }
+
+    /// Returns the party with the provided ID, and panics if the party is not
+    /// in the set.
+    pub fn party(&mut self, id: &str) -> &mut VirtualMachine<T> {
+        let position = self.position(id);
+        self.parties[position]
+    }
+
+    /// Returns the parties as a mutable slice, which can be passed to the
+    /// protocols. The slice does not allow to add or remove parties.
+    pub fn parties_mut(&mut self) -> &mut [&'v mut VirtualMachine<T>] {
+        &mut self.parties
+    }
+
+    /// Removes the party with the provided ID from the set and returns it,
+    /// keeping the order of the rest. The function panics if the party is not
+    /// in the set. The threshold is not changed.
+    pub fn remove(&mut self, id: &str) -> &'v mut VirtualMachine<T> {
+        let position = self.position(id);
+        self.parties.remove(position)
+    }
+
+    /// Returns `true` if the parties with the provided IDs can reconstruct a
+    /// value shared with the threshold of the set, that is, if they are more
+    /// than $t$ distinct parties of the set. Without a threshold, all the
+    /// parties are needed.
+    pub fn is_qualified(&self, ids: &[&str]) -> bool {
+        let mut members: Vec<&str> = ids.iter().copied().filter(|id| self.contains(id)).collect();
+        members.sort();
+        members.dedup();
+        match self.threshold {
+            Some(threshold) => members.len() > threshold,
+            None => members.len() == self.parties.len(),
+        }
+    }
+
+    /// Returns the subsets of $n - t$ parties in lexicographic order, which are
+    /// the complements of the maximal sets of corrupted parties. In replicated
+    /// secret-sharing and PRSS, a value is associated to each of them. The
+    /// function panics if the set has no threshold.
+    pub fn replicated_subsets(&self) -> Vec<Vec<String>> {
+        let threshold = self.threshold.unwrap_or_else(|| {
+            panic!("The party set has no threshold.");
+        });
+        let ids = self.ids();
+        mpc::subsets(ids.len(), ids.len() - threshold)
+            .into_iter()
+            .map(|subset| subset.into_iter().map(|i| ids[i].clone()).collect())
+            .collect()
+    }
+
+    /// Returns the underlying vector of parties.
+    pub fn into_inner(self) -> Vec<&'v mut VirtualMachine<T>> {
+        self.parties
+    }
+}
+
+impl<'v, T: MersenneField> Deref for PartySet<'v, T> {
+    type Target = Vec<&'v mut VirtualMachine<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.parties
+    }
+}
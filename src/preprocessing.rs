@@ -0,0 +1,242 @@
+//! Implements the storage of the preprocessed material of a virtual machine.
+//!
+//! The correlated randomness consumed during the online phase, such as Beaver
+//! triples, must be used only once, so the parties need to agree on which
+//! pieces have already been consumed. The store defined here keeps the shares
+//! of the unused material in the order in which it was registered, so the
+//! protocols can pull the next one automatically without naming it. Each piece
+//! of material is identified by a handle, which is the same for all the parties
+//! as long as they register the material in the same order.
+
+use crate::counter;
+use crate::fss::DcfKey;
+use crate::math::mersenne::MersenneField;
+use std::collections::VecDeque;
+
+/// Identifies a multiplication triple registered in a preprocessing store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TripleHandle(pub usize);
+
+/// Identifies a square pair registered in a preprocessing store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SquareHandle(pub usize);
+
+/// Identifies a daBit registered in a preprocessing store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DaBitHandle(pub usize);
+
+/// Identifies an edaBit registered in a preprocessing store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EdaBitHandle(pub usize);
+
+/// Identifies a boolean AND triple registered in a preprocessing store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AndTripleHandle(pub usize);
+
+/// Identifies a comparison key registered in a preprocessing store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComparisonKeyHandle(pub usize);
+
+/// Defines the shares held by a party of an extended daBit (edaBit).
+///
+/// An edaBit is a random integer $r$ of a fixed number of bits shared over the
+/// field, together with the shares of each of its bits $r_i$, starting from the
+/// least significant one. Since the bitwise protocols of this library work on
+/// arithmetic shares of bits, the bits are shared both over the field and over
+/// $\mathbb{F}_2$.
+#[derive(Clone)]
+pub struct EdaBit<T: MersenneField> {
+    /// Share of the integer $r$.
+    pub value: T,
+
+    /// Shares of the bits of $r$ over the field.
+    pub bits: Vec<T>,
+
+    /// Boolean shares of the bits of $r$.
+    pub bool_bits: Vec<bool>,
+}
+
+/// Defines the material held by a party for a comparison based on function
+/// secret sharing.
+///
+/// The material is made of a random mask $r$, its least significant bit $r_0$,
+/// and a [distributed comparison function](crate::fss) for $f^<_{r, 1 - 2r_0}$
+/// over the inputs with as many bits as the field modulus. For a public value
+/// $c$, the sum of $r_0$ and the evaluation of the DCF at $c$ is
+/// $r_0 \oplus \[c < r\]$. It is only defined for two parties.
+#[derive(Clone)]
+pub struct ComparisonKey<T: MersenneField> {
+    /// Share of the mask $r$.
+    pub mask: T,
+
+    /// Share of the least significant bit of the mask.
+    pub mask_lsb: T,
+
+    /// Key of the party in the DCF.
+    pub dcf: DcfKey<T>,
+}
+
+/// Defines a pool of preprocessed material held by a virtual machine.
+#[derive(Clone)]
+pub struct Preprocessing<T: MersenneField> {
+    triples: VecDeque<(TripleHandle, (T, T, T))>,
+    next_handle: usize,
+    squares: VecDeque<(SquareHandle, (T, T))>,
+    next_square_handle: usize,
+    dabits: VecDeque<(DaBitHandle, (T, bool))>,
+    next_dabit_handle: usize,
+    edabits: VecDeque<(EdaBitHandle, EdaBit<T>)>,
+    next_edabit_handle: usize,
+    and_triples: VecDeque<(AndTripleHandle, (bool, bool, bool))>,
+    next_and_triple_handle: usize,
+    comparison_keys: VecDeque<(ComparisonKeyHandle, ComparisonKey<T>)>,
+    next_comparison_key_handle: usize,
+}
+
+impl<T: MersenneField> Preprocessing<T> {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self {
+            triples: VecDeque::new(),
+            next_handle: 0,
+            squares: VecDeque::new(),
+            next_square_handle: 0,
+            dabits: VecDeque::new(),
+            next_dabit_handle: 0,
+            edabits: VecDeque::new(),
+            next_edabit_handle: 0,
+            and_triples: VecDeque::new(),
+            next_and_triple_handle: 0,
+            comparison_keys: VecDeque::new(),
+            next_comparison_key_handle: 0,
+        }
+    }
+
+    /// Registers the shares of a multiplication triple and returns its handle.
+    pub fn add_triple(&mut self, triple: (T, T, T)) -> TripleHandle {
+        let handle = TripleHandle(self.next_handle);
+        self.next_handle += 1;
+        self.triples.push_back((handle, triple));
+        handle
+    }
+
+    /// Removes from the store and returns the oldest unused multiplication
+    /// triple together with its handle, if any.
+    pub fn next_triple(&mut self) -> Option<(TripleHandle, (T, T, T))> {
+        let triple = self.triples.pop_front();
+        if triple.is_some() {
+            counter::record(|counts| counts.triples += 1);
+        }
+        triple
+    }
+
+    /// Returns the number of unused multiplication triples in the store.
+    pub fn remaining_triples(&self) -> usize {
+        self.triples.len()
+    }
+
+    /// Registers the shares of a square pair $(r, r^2)$ and returns its
+    /// handle.
+    pub fn add_square(&mut self, pair: (T, T)) -> SquareHandle {
+        let handle = SquareHandle(self.next_square_handle);
+        self.next_square_handle += 1;
+        self.squares.push_back((handle, pair));
+        handle
+    }
+
+    /// Removes from the store and returns the oldest unused square pair
+    /// together with its handle, if any.
+    pub fn next_square(&mut self) -> Option<(SquareHandle, (T, T))> {
+        self.squares.pop_front()
+    }
+
+    /// Returns the number of unused square pairs in the store.
+    pub fn remaining_squares(&self) -> usize {
+        self.squares.len()
+    }
+
+    /// Registers the shares of a daBit, that is, the arithmetic and the boolean
+    /// shares of the same random bit, and returns its handle.
+    pub fn add_dabit(&mut self, dabit: (T, bool)) -> DaBitHandle {
+        let handle = DaBitHandle(self.next_dabit_handle);
+        self.next_dabit_handle += 1;
+        self.dabits.push_back((handle, dabit));
+        handle
+    }
+
+    /// Removes from the store and returns the oldest unused daBit together
+    /// with its handle, if any.
+    pub fn next_dabit(&mut self) -> Option<(DaBitHandle, (T, bool))> {
+        self.dabits.pop_front()
+    }
+
+    /// Returns the number of unused daBits in the store.
+    pub fn remaining_dabits(&self) -> usize {
+        self.dabits.len()
+    }
+
+    /// Registers the shares of an edaBit and returns its handle.
+    pub fn add_edabit(&mut self, edabit: EdaBit<T>) -> EdaBitHandle {
+        let handle = EdaBitHandle(self.next_edabit_handle);
+        self.next_edabit_handle += 1;
+        self.edabits.push_back((handle, edabit));
+        handle
+    }
+
+    /// Removes from the store and returns the oldest unused edaBit together
+    /// with its handle, if any.
+    pub fn next_edabit(&mut self) -> Option<(EdaBitHandle, EdaBit<T>)> {
+        self.edabits.pop_front()
+    }
+
+    /// Returns the number of unused edaBits in the store.
+    pub fn remaining_edabits(&self) -> usize {
+        self.edabits.len()
+    }
+
+    /// Registers the boolean shares of an AND triple $(a, b, a \land b)$ and
+    /// returns its handle.
+    pub fn add_and_triple(&mut self, triple: (bool, bool, bool)) -> AndTripleHandle {
+        let handle = AndTripleHandle(self.next_and_triple_handle);
+        self.next_and_triple_handle += 1;
+        self.and_triples.push_back((handle, triple));
+        handle
+    }
+
+    /// Removes from the store and returns the oldest unused AND triple
+    /// together with its handle, if any.
+    pub fn next_and_triple(&mut self) -> Option<(AndTripleHandle, (bool, bool, bool))> {
+        self.and_triples.pop_front()
+    }
+
+    /// Returns the number of unused AND triples in the store.
+    pub fn remaining_and_triples(&self) -> usize {
+        self.and_triples.len()
+    }
+
+    /// Registers the material of a comparison based on function secret
+    /// sharing and returns its handle.
+    pub fn add_comparison_key(&mut self, key: ComparisonKey<T>) -> ComparisonKeyHandle {
+        let handle = ComparisonKeyHandle(self.next_comparison_key_handle);
+        self.next_comparison_key_handle += 1;
+        self.comparison_keys.push_back((handle, key));
+        handle
+    }
+
+    /// Removes from the store and returns the oldest unused comparison key
+    /// together with its handle, if any.
+    pub fn next_comparison_key(&mut self) -> Option<(ComparisonKeyHandle, ComparisonKey<T>)> {
+        self.comparison_keys.pop_front()
+    }
+
+    /// Returns the number of unused comparison keys in the store.
+    pub fn remaining_comparison_keys(&self) -> usize {
+        self.comparison_keys.len()
+    }
+}
+
+impl<T: MersenneField> Default for Preprocessing<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,790 @@
+//! Implements protocols as round-based state machines.
+//!
+//! The functions in [`mpc`](crate::mpc) receive all the parties at once and
+//! run the whole protocol in a single call, which hides its round structure.
+//! Here, each party runs its own instance of a [`Protocol`], which only has
+//! access to the memory of that party. In every round, a party receives the
+//! messages sent to it in the previous round and returns the messages it sends
+//! in the current one. The driver [`execute`] interleaves the parties: it calls
+//! the state machine of every party once per round and delivers the messages
+//! through the simulated [`network`](crate::network) between rounds, until a
+//! round ends without messages. The same execution can be followed step by
+//! step with a [`Debugger`], which stops after each round or each message so
+//! the memories of the parties and the pending messages can be inspected.
+//!
+//! The driver [`execute`] is single-threaded. With [`execute_threaded`], each
+//! party runs on its own thread instead and the messages travel through
+//! [`std::sync::mpsc`] channels, so the parties really wait for each other.
+//! Both drivers return the same outputs and count the same communication. The
+//! code run by each party, [`run_party`], only needs a
+//! [`Channel`](crate::network::Channel) to the other parties.
+//!
+//! With the `async` feature, [`run_party_async`] is the asynchronous version
+//! of [`run_party`], and [`execute_async`] runs each party as a [`tokio`]
+//! task.
+//!
+//! The basic protocols are ported to this model: [`DistributeShares`], [`Add`],
+//! [`Mult`] and [`Reconstruct`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::protocol::{self, Add, Reconstruct};
+//! use smol_mpc::mpc;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+//! let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+//! let mut parties = vec![&mut alice, &mut bob];
+//!
+//! mpc::simulate_random_dist("a", &mut parties, &Fp::new(3), &mut prg);
+//! mpc::simulate_random_dist("b", &mut parties, &Fp::new(4), &mut prg);
+//! protocol::execute(&mut parties, vec![Add::new("a", "b", "sum"); 2]);
+//! let outputs = protocol::execute(&mut parties, vec![Reconstruct::new("sum"); 2]);
+//! assert!(outputs.iter().all(|value| value.value() == 7));
+//! ```
+
+use crate::counter;
+use crate::event::Event;
+use crate::handle::SecretHandle;
+use crate::math::mersenne::MersenneField;
+use crate::mpc::{additive_shares, Share};
+use crate::network::{self, Channel, Envelope, Message, Payload, Simulator};
+use crate::vm::VirtualMachine;
+use std::thread;
+
+/// Defines the state machine of a party in a round-based protocol.
+pub trait Protocol<T: MersenneField> {
+    /// Value returned by each party at the end of the protocol.
+    type Output;
+
+    /// Tag of the messages sent by the protocol.
+    const TAG: &'static str;
+
+    /// Prepares the execution, given the memory of the party and the IDs of
+    /// all the parties in the execution, in the order of the driver.
+    fn init(&mut self, vm: &mut VirtualMachine<T>, parties: &[String]);
+
+    /// Executes a round: takes the messages received from the previous round
+    /// and returns the messages sent in this one, each with the ID of its
+    /// receiver.
+    fn round(
+        &mut self,
+        vm: &mut VirtualMachine<T>,
+        incoming: Vec<Message<T>>,
+    ) -> Vec<(String, Payload<T>)>;
+
+    /// Returns the output of the party once the protocol has finished.
+    fn output(&self) -> Self::Output;
+}
+
+/// Executes a protocol over a set of parties, where the $j$-th state machine
+/// in `machines` is run by the $j$-th party, and returns the output of each
+/// party.
+///
+/// The rounds are executed until no party sends a message, so every protocol
+/// ends with a round that only processes the messages received.
+pub fn execute<T, P>(parties: &mut [&mut VirtualMachine<T>], machines: Vec<P>) -> Vec<P::Output>
+where
+    T: MersenneField,
+    P: Protocol<T>,
+{
+    Debugger::new(parties, machines).run()
+}
+
+/// Executes a protocol over a set of parties with one thread per party.
+///
+/// This driver is equivalent to [`execute`], but each party runs
+/// [`run_party`] on its own thread, connected to the others with
+/// [`mpsc_channels`](crate::network::mpsc_channels).
+pub fn execute_threaded<T, P>(
+    parties: &mut [&mut VirtualMachine<T>],
+    machines: Vec<P>,
+) -> Vec<P::Output>
+where
+    T: MersenneField + Send,
+    P: Protocol<T> + Send,
+    P::Output: Send,
+{
+    if machines.len() != parties.len() {
+        panic!("Each party needs its own state machine.");
+    }
+
+    let ids: Vec<String> = parties.iter().map(|party| party.id.clone()).collect();
+    let channels = network::mpsc_channels(parties.len());
+    thread::scope(|scope| {
+        let handles: Vec<_> = parties
+            .iter_mut()
+            .zip(machines)
+            .zip(channels)
+            .map(|((party, mut machine), mut channel)| {
+                let ids = &ids;
+                scope.spawn(move || run_party(party, &mut machine, ids, &mut channel))
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle.join().unwrap_or_else(|_| {
+                    panic!("A party has failed during the execution.");
+                })
+            })
+            .collect()
+    })
+}
+
+/// Runs the state machine of a single party, which exchanges its messages with
+/// the other parties through `channel`, and returns its output.
+///
+/// The IDs of all the parties are given in `ids`, which must be the same for
+/// all of them. In every round, the party sends one [`Envelope`] to each of the
+/// other parties, even if it has no messages for it, and waits for the
+/// envelopes of the same round from all of them. The envelopes of later rounds
+/// that arrive early are kept until their round. The execution ends after a
+/// round in which no party sends a message.
+pub fn run_party<T, P, C>(
+    vm: &mut VirtualMachine<T>,
+    machine: &mut P,
+    ids: &[String],
+    channel: &mut C,
+) -> P::Output
+where
+    T: MersenneField,
+    P: Protocol<T>,
+    C: Channel<T>,
+{
+    let mut run = PartyRun::new(vm, machine, ids);
+    loop {
+        for (to, envelope) in run.start_round() {
+            channel.send(to, envelope);
+        }
+        while !run.is_round_complete() {
+            run.accept(channel.recv());
+        }
+        if !run.end_round() {
+            break;
+        }
+    }
+    run.output()
+}
+
+/// Runs the state machine of a single party as an asynchronous task, in the
+/// same way as [`run_party`].
+#[cfg(feature = "async")]
+pub async fn run_party_async<T, P, C>(
+    vm: &mut VirtualMachine<T>,
+    machine: &mut P,
+    ids: &[String],
+    channel: &mut C,
+) -> P::Output
+where
+    T: MersenneField,
+    P: Protocol<T>,
+    C: network::AsyncChannel<T>,
+{
+    let mut run = PartyRun::new(vm, machine, ids);
+    loop {
+        for (to, envelope) in run.start_round() {
+            channel.send(to, envelope).await;
+        }
+        while !run.is_round_complete() {
+            run.accept(channel.recv().await);
+        }
+        if !run.end_round() {
+            break;
+        }
+    }
+    run.output()
+}
+
+/// Executes a protocol with one [`tokio`] task per party, which must be called
+/// from a tokio runtime.
+///
+/// A spawned task cannot borrow the parties, so each task takes ownership of
+/// its virtual machine and the function returns it together with the output
+/// of the party, in the order of `parties`.
+#[cfg(feature = "async")]
+pub async fn execute_async<T, P>(
+    parties: Vec<VirtualMachine<T>>,
+    machines: Vec<P>,
+) -> Vec<(VirtualMachine<T>, P::Output)>
+where
+    T: MersenneField + Send + 'static,
+    P: Protocol<T> + Send + 'static,
+    P::Output: Send + 'static,
+{
+    if machines.len() != parties.len() {
+        panic!("Each party needs its own state machine.");
+    }
+
+    let ids: Vec<String> = parties.iter().map(|party| party.id.clone()).collect();
+    let channels = network::tokio_channels(parties.len());
+    let handles: Vec<_> = parties
+        .into_iter()
+        .zip(machines)
+        .zip(channels)
+        .map(|((mut party, mut machine), mut channel)| {
+            let ids = ids.clone();
+            tokio::spawn(async move {
+                let output = run_party_async(&mut party, &mut machine, &ids, &mut channel).await;
+                (party, output)
+            })
+        })
+        .collect();
+
+    let mut outputs = Vec::new();
+    for handle in handles {
+        outputs.push(handle.await.unwrap_or_else(|_| {
+            panic!("A party has failed during the execution.");
+        }));
+    }
+    outputs
+}
+
+/// Keeps the state of a party that runs on its own, independently of how the
+/// envelopes are sent and received.
+struct PartyRun<'r, T: MersenneField, P: Protocol<T>> {
+    vm: &'r mut VirtualMachine<T>,
+    machine: &'r mut P,
+    ids: &'r [String],
+    own: usize,
+    round: usize,
+    active: bool,
+    incoming: Vec<Message<T>>,
+    envelopes: Vec<Envelope<T>>,
+    early: Vec<Envelope<T>>,
+}
+
+impl<'r, T: MersenneField, P: Protocol<T>> PartyRun<'r, T, P> {
+    fn new(vm: &'r mut VirtualMachine<T>, machine: &'r mut P, ids: &'r [String]) -> Self {
+        let own = Self::position(ids, &vm.id);
+        vm.emit(|| Event::ProtocolStarted {
+            name: P::TAG.to_string(),
+        });
+        machine.init(vm, ids);
+        Self {
+            vm,
+            machine,
+            ids,
+            own,
+            round: 0,
+            active: false,
+            incoming: Vec::new(),
+            envelopes: Vec::new(),
+            early: Vec::new(),
+        }
+    }
+
+    fn position(ids: &[String], id: &str) -> usize {
+        ids.iter()
+            .position(|party| party == id)
+            .unwrap_or_else(|| panic!("The party {} is not connected to the network.", id))
+    }
+
+    /// Runs the state machine for the current round and returns the envelopes
+    /// for the other parties, with their positions.
+    fn start_round(&mut self) -> Vec<(usize, Envelope<T>)> {
+        let mut outgoing: Vec<Vec<Message<T>>> = self.ids.iter().map(|_| Vec::new()).collect();
+        let incoming = std::mem::take(&mut self.incoming);
+        for (to, payload) in self.machine.round(self.vm, incoming) {
+            let message = Message {
+                from: self.vm.id.clone(),
+                to: to.clone(),
+                tag: P::TAG.to_string(),
+                payload,
+            };
+            self.vm.emit(|| Event::MessageSent(message.clone()));
+            self.vm.mailbox.record_sent(&message);
+            outgoing[Self::position(self.ids, &to)].push(message);
+        }
+
+        self.active = outgoing.iter().any(|messages| !messages.is_empty());
+        let round = self.round;
+        self.envelopes = Vec::new();
+        let early = std::mem::take(&mut self.early);
+        for envelope in early {
+            self.accept(envelope);
+        }
+
+        outgoing
+            .into_iter()
+            .enumerate()
+            .filter(|(to, _)| *to != self.own)
+            .map(|(to, messages)| {
+                let envelope = Envelope {
+                    round,
+                    from: self.own,
+                    active: self.active,
+                    messages,
+                };
+                (to, envelope)
+            })
+            .collect()
+    }
+
+    /// Keeps an envelope received from another party.
+    fn accept(&mut self, envelope: Envelope<T>) {
+        if envelope.round == self.round {
+            self.envelopes.push(envelope);
+        } else {
+            self.early.push(envelope);
+        }
+    }
+
+    fn is_round_complete(&self) -> bool {
+        self.envelopes.len() == self.ids.len() - 1
+    }
+
+    /// Finishes the current round, and returns `false` if the protocol has
+    /// finished.
+    fn end_round(&mut self) -> bool {
+        let mut envelopes = std::mem::take(&mut self.envelopes);
+        envelopes.sort_by_key(|envelope| envelope.from);
+        if !self.active && envelopes.iter().all(|envelope| !envelope.active) {
+            return false;
+        }
+        self.vm.mailbox.record_round(0);
+
+        self.incoming = envelopes
+            .into_iter()
+            .flat_map(|envelope| envelope.messages)
+            .collect();
+        for message in &self.incoming {
+            self.vm.mailbox.record_received(message);
+        }
+        self.round += 1;
+        true
+    }
+
+    fn output(&self) -> P::Output {
+        self.vm.emit(|| Event::ProtocolFinished {
+            name: P::TAG.to_string(),
+        });
+        self.machine.output()
+    }
+}
+
+/// Executes a protocol one round or one message at a time.
+///
+/// The debugger runs the same execution as [`execute`], but the caller decides
+/// when to advance it. [`Debugger::step_round`] delivers the messages sent in
+/// the previous round and runs the state machines of all the parties once,
+/// leaving the messages they send in their outboxes. [`Debugger::step_message`]
+/// delivers only the oldest undelivered message instead. Between steps, the
+/// memories of the parties and the pending messages can be inspected.
+pub struct Debugger<'p, 'v, T: MersenneField, P: Protocol<T>> {
+    parties: &'p mut [&'v mut VirtualMachine<T>],
+    machines: Vec<P>,
+    ids: Vec<String>,
+    round: usize,
+    finished: bool,
+}
+
+impl<'p, 'v, T: MersenneField, P: Protocol<T>> Debugger<'p, 'v, T, P> {
+    /// Creates a debugger over a set of parties, where the $j$-th state machine
+    /// in `machines` is run by the $j$-th party, and initializes the machines.
+    pub fn new(parties: &'p mut [&'v mut VirtualMachine<T>], mut machines: Vec<P>) -> Self {
+        if machines.len() != parties.len() {
+            panic!("Each party needs its own state machine.");
+        }
+
+        let ids: Vec<String> = parties.iter().map(|party| party.id.clone()).collect();
+        for (party, machine) in parties.iter_mut().zip(machines.iter_mut()) {
+            party.emit(|| Event::ProtocolStarted {
+                name: P::TAG.to_string(),
+            });
+            machine.init(party, &ids);
+        }
+
+        Self {
+            parties,
+            machines,
+            ids,
+            round: 0,
+            finished: false,
+        }
+    }
+
+    /// Delivers all the pending messages and runs the next round of the state
+    /// machines. Returns `false` if the protocol had already finished.
+    pub fn step_round(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        let network = Simulator::new(self.parties);
+        network.deliver();
+        let incoming: Vec<Vec<Message<T>>> =
+            (0..self.ids.len()).map(|to| network.recv_all(to)).collect();
+
+        let outgoing: Vec<Vec<(String, Payload<T>)>> = self
+            .parties
+            .iter_mut()
+            .zip(self.machines.iter_mut())
+            .zip(incoming)
+            .map(|((party, machine), messages)| machine.round(party, messages))
+            .collect();
+        self.round += 1;
+        if outgoing.iter().all(|messages| messages.is_empty()) {
+            self.finished = true;
+            for party in self.parties.iter() {
+                party.emit(|| Event::ProtocolFinished {
+                    name: P::TAG.to_string(),
+                });
+            }
+            return true;
+        }
+
+        let network = Simulator::new(self.parties).with_tag(P::TAG);
+        for (from, messages) in outgoing.into_iter().enumerate() {
+            for (to, payload) in messages {
+                let to =
+                    self.ids.iter().position(|id| *id == to).unwrap_or_else(|| {
+                        panic!("The party {} is not connected to the network.", to)
+                    });
+                network.send(from, to, payload);
+            }
+        }
+        true
+    }
+
+    /// Delivers the oldest pending message and returns it. If there are no
+    /// pending messages, runs the next round instead and returns `None`.
+    pub fn step_message(&mut self) -> Option<Message<T>> {
+        let delivered = Simulator::new(self.parties).deliver_next();
+        if delivered.is_none() {
+            self.step_round();
+        }
+        delivered
+    }
+
+    /// Runs the remaining rounds and returns the output of each party.
+    pub fn run(mut self) -> Vec<P::Output> {
+        while self.step_round() {}
+        self.outputs()
+    }
+
+    /// Returns the output of each party, which is only meaningful once the
+    /// protocol has finished.
+    pub fn outputs(&self) -> Vec<P::Output> {
+        self.machines
+            .iter()
+            .map(|machine| machine.output())
+            .collect()
+    }
+
+    /// Returns the number of rounds executed so far.
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Returns `true` if the protocol has finished.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns the virtual machine of the party with the provided ID, to
+    /// inspect its memory.
+    pub fn party(&self, id: &str) -> &VirtualMachine<T> {
+        self.parties
+            .iter()
+            .find(|party| party.id == id)
+            .unwrap_or_else(|| panic!("Party with that id does not exist."))
+    }
+
+    /// Returns the messages sent that have not been delivered yet.
+    pub fn pending(&self) -> Vec<Message<T>> {
+        Simulator::new(self.parties).undelivered()
+    }
+
+    /// Returns the messages delivered to the party with the provided ID that
+    /// its state machine has not processed yet.
+    pub fn inbox(&self, id: &str) -> Vec<Message<T>> {
+        let to = self
+            .ids
+            .iter()
+            .position(|party| party == id)
+            .unwrap_or_else(|| panic!("Party with that id does not exist."));
+        Simulator::new(self.parties).inbox(to)
+    }
+}
+
+/// Distributes shares of a private value of one party among all the parties,
+/// as in [`distribute_shares`](crate::mpc::distribute_shares).
+///
+/// In the first round the owner splits the value with ID `id_var` into
+/// additive shares using its PRG and sends one to each party. In the second
+/// round, every party stores the share it received under the same ID.
+pub struct DistributeShares {
+    id_var: String,
+    id_owner: String,
+    parties: Vec<String>,
+    sent: bool,
+}
+
+impl DistributeShares {
+    /// Creates the state machine of a party. The owner draws the randomness of
+    /// the shares from its own PRG.
+    pub fn new(id_var: &str, id_owner: &str) -> Self {
+        Self {
+            id_var: id_var.to_string(),
+            id_owner: id_owner.to_string(),
+            parties: Vec::new(),
+            sent: false,
+        }
+    }
+}
+
+impl<T: MersenneField> Protocol<T> for DistributeShares {
+    type Output = SecretHandle<T>;
+    const TAG: &'static str = "input";
+
+    fn init(&mut self, _vm: &mut VirtualMachine<T>, parties: &[String]) {
+        if !parties.contains(&self.id_owner) {
+            panic!("Party with that id does not exist.");
+        }
+        self.parties = parties.to_vec();
+    }
+
+    fn round(
+        &mut self,
+        vm: &mut VirtualMachine<T>,
+        incoming: Vec<Message<T>>,
+    ) -> Vec<(String, Payload<T>)> {
+        if let Some(message) = incoming
+            .into_iter()
+            .find(|message| message.from == self.id_owner)
+        {
+            let value = message.payload.elements().remove(0);
+            vm.insert_share(&self.id_var, Share::new(&self.id_var, value));
+            return Vec::new();
+        }
+        if vm.id != self.id_owner || self.sent {
+            return Vec::new();
+        }
+        self.sent = true;
+
+        let value = vm.get_priv_value(&self.id_var).clone();
+        let mut outgoing = Vec::new();
+        for (id, share) in
+            self.parties
+                .iter()
+                .zip(additive_shares(&value, self.parties.len(), &mut vm.prg))
+        {
+            if *id == vm.id {
+                vm.insert_share(&self.id_var, Share::new(&self.id_var, share));
+            } else {
+                outgoing.push((id.clone(), Payload::Elements(vec![share])));
+            }
+        }
+        outgoing
+    }
+
+    fn output(&self) -> SecretHandle<T> {
+        SecretHandle::new(&self.id_var)
+    }
+}
+
+/// Adds two secret-shared values, as in
+/// [`add_protocol`](crate::mpc::add_protocol).
+///
+/// The addition is local, so the protocol has a single round without messages.
+#[derive(Clone)]
+pub struct Add {
+    id_x: String,
+    id_y: String,
+    id_result: String,
+}
+
+impl Add {
+    /// Creates the state machine of a party.
+    pub fn new(id_x: &str, id_y: &str, id_result: &str) -> Self {
+        Self {
+            id_x: id_x.to_string(),
+            id_y: id_y.to_string(),
+            id_result: id_result.to_string(),
+        }
+    }
+}
+
+impl<T: MersenneField> Protocol<T> for Add {
+    type Output = SecretHandle<T>;
+    const TAG: &'static str = "add";
+
+    fn init(&mut self, _vm: &mut VirtualMachine<T>, _parties: &[String]) {}
+
+    fn round(
+        &mut self,
+        vm: &mut VirtualMachine<T>,
+        _incoming: Vec<Message<T>>,
+    ) -> Vec<(String, Payload<T>)> {
+        let value = vm
+            .get_share(&self.id_x)
+            .value
+            .add(&vm.get_share(&self.id_y).value);
+        vm.insert_share(&self.id_result, Share::new(&self.id_result, value));
+        Vec::new()
+    }
+
+    fn output(&self) -> SecretHandle<T> {
+        SecretHandle::new(&self.id_result)
+    }
+}
+
+/// Multiplicates two secret-shared values with a Beaver triple, as in
+/// [`mult_protocol`](crate::mpc::mult_protocol).
+///
+/// In the first round, each party takes the next triple $(a, b, c)$ of its
+/// preprocessing store and sends its shares of $\epsilon = x - a$ and
+/// $\delta = y - b$ to the other parties. In the second round, it opens both
+/// values and computes its share of $c + \epsilon b + \delta a + \epsilon
+/// \delta$, where the last term is added only by the first party.
+#[derive(Clone)]
+pub struct Mult<T> {
+    id_x: String,
+    id_y: String,
+    id_result: String,
+    parties: Vec<String>,
+    state: Option<(T, T, T, T, T)>,
+}
+
+impl<T> Mult<T> {
+    /// Creates the state machine of a party.
+    pub fn new(id_x: &str, id_y: &str, id_result: &str) -> Self {
+        Self {
+            id_x: id_x.to_string(),
+            id_y: id_y.to_string(),
+            id_result: id_result.to_string(),
+            parties: Vec::new(),
+            state: None,
+        }
+    }
+}
+
+impl<T: MersenneField> Protocol<T> for Mult<T> {
+    type Output = SecretHandle<T>;
+    const TAG: &'static str = "beaver";
+
+    fn init(&mut self, _vm: &mut VirtualMachine<T>, parties: &[String]) {
+        self.parties = parties.to_vec();
+    }
+
+    fn round(
+        &mut self,
+        vm: &mut VirtualMachine<T>,
+        incoming: Vec<Message<T>>,
+    ) -> Vec<(String, Payload<T>)> {
+        match self.state.take() {
+            None => {
+                let (_, (a, b, c)) = vm.preprocessing.next_triple().unwrap_or_else(|| {
+                    panic!("Not enough triples to compute the multiplication.");
+                });
+                let epsilon = vm.get_share(&self.id_x).value.subtract(&a);
+                let delta = vm.get_share(&self.id_y).value.subtract(&b);
+                self.state = Some((a, b, c, epsilon.clone(), delta.clone()));
+
+                self.parties
+                    .iter()
+                    .filter(|id| **id != vm.id)
+                    .map(|id| {
+                        (
+                            id.clone(),
+                            Payload::Elements(vec![epsilon.clone(), delta.clone()]),
+                        )
+                    })
+                    .collect()
+            }
+            Some((a, b, c, mut epsilon, mut delta)) => {
+                for message in incoming {
+                    let values = message.payload.elements();
+                    epsilon = epsilon.add(&values[0]);
+                    delta = delta.add(&values[1]);
+                }
+
+                let mut value = c.add(&epsilon.multiply(&b)).add(&delta.multiply(&a));
+                if vm.id == self.parties[0] {
+                    value = value.add(&epsilon.multiply(&delta));
+                }
+                vm.insert_share(&self.id_result, Share::new(&self.id_result, value));
+                Vec::new()
+            }
+        }
+    }
+
+    fn output(&self) -> SecretHandle<T> {
+        SecretHandle::new(&self.id_result)
+    }
+}
+
+/// Reconstructs a secret-shared value towards all the parties, as in
+/// [`reconstruct_share`](crate::mpc::reconstruct_share).
+///
+/// In the first round, each party sends its share to the other parties, and
+/// in the second round it adds up the shares it received to its own.
+#[derive(Clone)]
+pub struct Reconstruct<T> {
+    id: String,
+    parties: Vec<String>,
+    share: Option<T>,
+    value: Option<T>,
+}
+
+impl<T> Reconstruct<T> {
+    /// Creates the state machine of a party.
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            parties: Vec::new(),
+            share: None,
+            value: None,
+        }
+    }
+}
+
+impl<T: MersenneField> Protocol<T> for Reconstruct<T> {
+    type Output = T;
+    const TAG: &'static str = "open";
+
+    fn init(&mut self, _vm: &mut VirtualMachine<T>, parties: &[String]) {
+        self.parties = parties.to_vec();
+    }
+
+    fn round(
+        &mut self,
+        vm: &mut VirtualMachine<T>,
+        incoming: Vec<Message<T>>,
+    ) -> Vec<(String, Payload<T>)> {
+        match self.share.take() {
+            None => {
+                let share = vm.behavior.opened_share(&vm.get_share(&self.id).value);
+                self.share = Some(share.clone());
+
+                self.parties
+                    .iter()
+                    .filter(|id| **id != vm.id)
+                    .map(|id| (id.clone(), Payload::Elements(vec![share.clone()])))
+                    .collect()
+            }
+            Some(share) => {
+                let value = incoming.iter().fold(share, |acc, message| {
+                    acc.add(&message.payload.elements()[0])
+                });
+                counter::record(|counts| counts.openings += 1);
+                self.value = Some(value);
+                Vec::new()
+            }
+        }
+    }
+
+    fn output(&self) -> T {
+        self.value.clone().unwrap_or_else(|| {
+            panic!("The value has not been reconstructed yet.");
+        })
+    }
+}
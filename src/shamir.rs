@@ -0,0 +1,239 @@
+//! Implements protocols based on Shamir secret-sharing.
+//!
+//! In Shamir secret-sharing with threshold $t$, a value $s$ is shared by
+//! sampling a random polynomial $f$ of degree at most $t$ with $f(0) = s$. The
+//! $j$-th party of the vector of parties receives the share $f(j + 1)$. Any set
+//! of $t + 1$ parties can reconstruct the value using Lagrange interpolation,
+//! while any set of at most $t$ parties learns nothing about it. In contrast
+//! with additive sharing, the parties are identified by their position in the
+//! vector of parties, so the same order must be used in every call.
+//!
+//! Shamir shares are linear, so the additions and the multiplications by a
+//! public constant are done locally with the functions in [`mpc`](crate::mpc).
+//! However, the product of two sharings of degree $t$ is a sharing of degree
+//! $2t$, which must be reduced back to degree $t$. Here, the degree reduction
+//! is done with random double sharings, as in the protocol of Damgård and
+//! Nielsen (DN07), which requires an honest majority, that is, $n \geq 2t + 1$.
+//!
+//! Since any $t + 1$ shares determine the value, a Shamir-shared value can
+//! still be reconstructed when some parties drop out, as long as $t + 1$ of them
+//! remain online (see [`try_reconstruct_share`]). An additive sharing, instead,
+//! needs all the parties.
+
+use crate::math::mersenne::MersenneField;
+use crate::mpc::Share;
+use crate::network::MissingParty;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+/// Computes the Shamir shares of a value for `n_parties` parties using a random
+/// polynomial of degree `degree`.
+pub fn share<T: MersenneField>(
+    value: &T,
+    degree: usize,
+    n_parties: usize,
+    prg: &mut Prg,
+) -> Vec<T> {
+    let coeffs: Vec<T> = (0..degree).map(|_| T::random(&mut *prg)).collect();
+
+    (1..=n_parties)
+        .map(|point| {
+            // Horner's rule for f(point), whose constant term is the value.
+            let point = T::new(point as u64);
+            coeffs
+                .iter()
+                .rev()
+                .fold(T::new(0), |acc, coeff| acc.add(coeff).multiply(&point))
+                .add(value)
+        })
+        .collect()
+}
+
+/// Reconstructs a value from the Shamir shares of the parties at the points
+/// $1, \dots, n$ using Lagrange interpolation at zero.
+///
+/// All the given shares are used, so the degree of the sharing must be lower
+/// than the number of shares.
+pub fn reconstruct<T: MersenneField>(shares: &[T]) -> T {
+    let points: Vec<u64> = (1..=shares.len() as u64).collect();
+    interpolate_at_zero(&points, shares)
+}
+
+/// Reconstructs a value from the Shamir shares at the provided points using
+/// Lagrange interpolation at zero.
+///
+/// The points must be distinct and non-zero, and the degree of the sharing must
+/// be lower than the number of shares.
+pub fn interpolate_at_zero<T: MersenneField>(points: &[u64], shares: &[T]) -> T {
+    shares
+        .iter()
+        .zip(lagrange_at_zero::<T>(points))
+        .fold(T::new(0), |acc, (share, coeff)| {
+            acc.add(&share.multiply(&coeff))
+        })
+}
+
+/// Returns the Lagrange coefficients to interpolate at zero a polynomial given
+/// by its evaluations at the provided points.
+pub(crate) fn lagrange_at_zero<T: MersenneField>(points: &[u64]) -> Vec<T> {
+    points
+        .iter()
+        .map(|&j| {
+            let mut numerator = T::new(1);
+            let mut denominator = T::new(1);
+            for &m in points.iter().filter(|&&m| m != j) {
+                numerator = numerator.multiply(&T::new(m));
+                denominator = denominator.multiply(&T::new(m).subtract(&T::new(j)));
+            }
+            numerator.multiply(&denominator.inverse())
+        })
+        .collect()
+}
+
+/// Simulates the distribution of Shamir shares of a value.
+///
+/// This function acts as a helper to simulate that a value has been shared with
+/// a polynomial of degree `degree`. At the end, parties will have shares of the
+/// provided value stored in the share memory under the provided ID.
+pub fn simulate_shamir_dist<T>(
+    id: &str,
+    parties: &mut [&mut VirtualMachine<T>],
+    value: &T,
+    degree: usize,
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let shares = share(value, degree, parties.len(), prg);
+    for (party, value) in parties.iter_mut().zip(shares) {
+        party.insert_share(id, Share::new(id, value));
+    }
+}
+
+/// Reconstructs a Shamir-shared value among a set of parties.
+///
+/// Only the parties that are online take part, so the value is correct as long
+/// as their number is greater than the degree of the sharing.
+pub fn reconstruct_share<T>(parties: &[&mut VirtualMachine<T>], id: &str) -> T
+where
+    T: MersenneField,
+{
+    let (points, shares): (Vec<u64>, Vec<T>) = parties
+        .iter()
+        .enumerate()
+        .filter(|(_, party)| !party.mailbox.is_offline())
+        .map(|(j, party)| {
+            (
+                j as u64 + 1,
+                party.behavior.opened_share(&party.get_share(id).value),
+            )
+        })
+        .unzip();
+    interpolate_at_zero(&points, &shares)
+}
+
+/// Reconstructs a Shamir-shared value of degree `degree` among a set of
+/// parties, or returns an error with the first offline party if fewer than
+/// `degree + 1` parties are online.
+pub fn try_reconstruct_share<T>(
+    parties: &[&mut VirtualMachine<T>],
+    id: &str,
+    degree: usize,
+) -> Result<T, MissingParty>
+where
+    T: MersenneField,
+{
+    let online = parties
+        .iter()
+        .filter(|party| !party.mailbox.is_offline())
+        .count();
+    if online <= degree {
+        let missing = parties
+            .iter()
+            .find(|party| party.mailbox.is_offline())
+            .unwrap_or_else(|| panic!("The degree must be smaller than the number of parties."));
+        return Err(MissingParty::new(&missing.id));
+    }
+    Ok(reconstruct_share(parties, id))
+}
+
+/// Generates a random double sharing among a set of parties.
+///
+/// A double sharing is a pair of Shamir sharings of the same random value $r$,
+/// one with degree $t$ and the other with degree $2t$. Each party samples a
+/// random value and shares it with both degrees, and every party adds up the
+/// shares received from all the parties. The resulting value is unknown to any
+/// set of at most $t$ parties because it contains the contribution of at least
+/// one honest party. At the end of the protocol, the parties will end up with
+/// the shares of degree $t$ and $2t$ under the IDs `ids_double.0` and
+/// `ids_double.1` respectively.
+pub fn double_sharing_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    threshold: usize,
+    ids_double: (&str, &str),
+) where
+    T: MersenneField,
+{
+    let n_parties = parties.len();
+    if n_parties < 2 * threshold + 1 {
+        panic!("Double sharings need at least 2t + 1 parties.");
+    }
+
+    let mut sum_t: Vec<T> = (0..n_parties).map(|_| T::new(0)).collect();
+    let mut sum_2t: Vec<T> = (0..n_parties).map(|_| T::new(0)).collect();
+    for party in parties.iter_mut() {
+        let prg = &mut party.prg;
+        let contribution = T::random(&mut *prg);
+        let shares_t = share(&contribution, threshold, n_parties, prg);
+        let shares_2t = share(&contribution, 2 * threshold, n_parties, prg);
+        for j in 0..n_parties {
+            sum_t[j] = sum_t[j].add(&shares_t[j]);
+            sum_2t[j] = sum_2t[j].add(&shares_2t[j]);
+        }
+    }
+
+    for (party, (value_t, value_2t)) in parties.iter_mut().zip(sum_t.into_iter().zip(sum_2t)) {
+        party.insert_share(ids_double.0, Share::new(ids_double.0, value_t));
+        party.insert_share(ids_double.1, Share::new(ids_double.1, value_2t));
+    }
+}
+
+/// Multiplicates two Shamir-shared values with threshold $t$.
+///
+/// The parties multiply locally their shares, obtaining a sharing of the
+/// product of degree $2t$, and subtract the sharing of degree $2t$ of the
+/// double sharing with IDs `ids_double`. The first party, acting as king,
+/// reconstructs the masked product $e = xy - r$ and sends it to all the
+/// parties, which compute locally the shares of $e + r$ using the sharing of
+/// degree $t$ of the double sharing. The double sharing is removed from the
+/// memory of the parties. At the end of the execution of the protocol, the
+/// parties will end up with the shares of the product of degree $t$ under the
+/// ID `id_result`.
+pub fn mult_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_x: &str,
+    id_y: &str,
+    id_result: &str,
+    ids_double: (&str, &str),
+) where
+    T: MersenneField,
+{
+    let masked_shares: Vec<T> = parties
+        .iter()
+        .map(|party| {
+            let product = party
+                .get_share(id_x)
+                .value
+                .multiply(&party.get_share(id_y).value);
+            product.subtract(&party.get_share(ids_double.1).value)
+        })
+        .collect();
+    let masked_product = reconstruct(&masked_shares);
+
+    for party in parties.iter_mut() {
+        let value = party.get_share(ids_double.0).value.add(&masked_product);
+        party.insert_share(id_result, Share::new(id_result, value));
+        party.remove_share(ids_double.0);
+        party.remove_share(ids_double.1);
+    }
+}
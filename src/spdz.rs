@@ -0,0 +1,370 @@
+//! Implements authenticated secret-sharing in the style of SPDZ.
+//!
+//! The protocols in [`mpc`](crate::mpc) are secure only against passive
+//! adversaries: a corrupted party that changes its share changes the result
+//! without being noticed. SPDZ protects against such behavior using
+//! information-theoretic MACs. The parties hold additive shares of a global
+//! MAC key $\alpha$, and every shared value $x$ comes with additive shares of
+//! its MAC $\gamma(x) = \alpha x$. Both the shares and the MACs are linear, so
+//! additions are still local. When a value is opened, the parties check that
+//! $\gamma(x) - \alpha x = 0$ without revealing $\alpha$. An adversary that
+//! changes a share must also guess how to change the MAC, which happens with
+//! probability $1 / p$.
+//!
+//! Values opened in the middle of a protocol, such as $\epsilon$ and $\delta$
+//! in a multiplication, are only partially opened: their MACs are recorded and
+//! checked in a batch by [`mac_check`], which must be called before the outputs
+//! are revealed. In this library, the preprocessing material is generated by a
+//! trusted party and the MAC check opens the values directly instead of using
+//! commitments.
+
+use crate::math::mersenne::MersenneField;
+use crate::mpc::additive_shares;
+use crate::network::Simulator;
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+use std::collections::HashMap;
+
+/// Defines an authenticated share, that is, a share of a value together with a
+/// share of its MAC.
+#[derive(Clone)]
+pub struct AuthShare<T: MersenneField> {
+    /// Share of the value.
+    pub value: T,
+
+    /// Share of the MAC of the value.
+    pub mac: T,
+}
+
+/// Defines the SPDZ state of a virtual machine.
+pub struct SpdzState<T: MersenneField> {
+    /// Share of the global MAC key, available after [`setup`].
+    pub key_share: Option<T>,
+
+    /// Memory for authenticated shares.
+    pub shares: HashMap<String, AuthShare<T>>,
+
+    /// Values partially opened whose MACs have not been checked yet, together
+    /// with the share of their MAC held by the virtual machine.
+    pub opened: Vec<(T, T)>,
+}
+
+impl<T: MersenneField> SpdzState<T> {
+    /// Creates an empty state without a MAC key.
+    pub fn new() -> Self {
+        Self {
+            key_share: None,
+            shares: HashMap::new(),
+            opened: Vec::new(),
+        }
+    }
+
+    /// Returns the authenticated share with the provided ID.
+    pub fn get_share(&self, id: &str) -> &AuthShare<T> {
+        self.shares.get(id).unwrap_or_else(|| {
+            panic!("The id `{}` is not registered in the virtual machine.", id);
+        })
+    }
+
+    fn key_share(&self) -> &T {
+        self.key_share.as_ref().unwrap_or_else(|| {
+            panic!("The SPDZ setup has not been executed.");
+        })
+    }
+}
+
+impl<T: MersenneField> Default for SpdzState<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Distributes shares of a random global MAC key among a set of parties.
+pub fn setup<T>(parties: &mut [&mut VirtualMachine<T>], prg: &mut Prg)
+where
+    T: MersenneField,
+{
+    for party in parties.iter_mut() {
+        party.spdz.key_share = Some(T::random(&mut *prg));
+    }
+}
+
+/// Simulates the distribution of authenticated shares of a value.
+///
+/// A trusted party, which knows the global MAC key, computes additive shares
+/// of the value and of its MAC, and stores them in the SPDZ memory of each
+/// party under the provided ID.
+pub fn simulate_auth_dist<T>(
+    id: &str,
+    parties: &mut [&mut VirtualMachine<T>],
+    value: &T,
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let mac = global_key(parties).multiply(value);
+    let value_shares = additive_shares(value, parties.len(), prg);
+    let mac_shares = additive_shares(&mac, parties.len(), prg);
+
+    for (party, (value, mac)) in parties
+        .iter_mut()
+        .zip(value_shares.into_iter().zip(mac_shares))
+    {
+        insert_auth_share(party, id, AuthShare { value, mac });
+    }
+}
+
+/// Distributes authenticated shares of a value stored in the private memory of
+/// the party with ID `id_owner`.
+///
+/// The authentication of the input is simulated by a trusted party. The
+/// shares are stored in the SPDZ memory of each party with the same ID that
+/// the owner has in its private memory.
+pub fn distribute_shares<T>(
+    id_var: &str,
+    id_owner: &str,
+    parties: &mut [&mut VirtualMachine<T>],
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let value = parties
+        .iter()
+        .find(|party| party.id == id_owner)
+        .map(|party| party.get_priv_value(id_var).clone())
+        .unwrap_or_else(|| {
+            panic!("Party with that id does not exist.");
+        });
+
+    simulate_auth_dist(id_var, parties, &value, prg);
+}
+
+/// Creates and distributes authenticated shares of a multiplication triple
+/// among a set of parties.
+pub fn generate_triple<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_triple: (&str, &str, &str),
+    prg: &mut Prg,
+) where
+    T: MersenneField,
+{
+    let a = T::random(&mut *prg);
+    let b = T::random(&mut *prg);
+    let c = a.multiply(&b);
+
+    simulate_auth_dist(id_triple.0, parties, &a, prg);
+    simulate_auth_dist(id_triple.1, parties, &b, prg);
+    simulate_auth_dist(id_triple.2, parties, &c, prg);
+}
+
+/// Adds two authenticated values locally.
+pub fn add_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_a: &str,
+    id_b: &str,
+    id_result: &str,
+) where
+    T: MersenneField,
+{
+    for party in parties.iter_mut() {
+        let a = party.spdz.get_share(id_a);
+        let b = party.spdz.get_share(id_b);
+        let sum = AuthShare {
+            value: a.value.add(&b.value),
+            mac: a.mac.add(&b.mac),
+        };
+        insert_auth_share(party, id_result, sum);
+    }
+}
+
+/// Multiplicates two authenticated values using an authenticated triple.
+///
+/// The values $\epsilon = x - a$ and $\delta = y - b$ are partially opened, so
+/// their MACs are recorded to be checked later by [`mac_check`]. The parties
+/// compute locally the authenticated shares of $c + \epsilon b + \delta a +
+/// \epsilon \delta$; the public term is added to the value share of the first
+/// party, while every party adds its share of $\alpha \epsilon \delta$ to its
+/// MAC share. The triple is removed from the memory once consumed.
+pub fn mult_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_x: &str,
+    id_y: &str,
+    id_result: &str,
+    id_triple: (&str, &str, &str),
+) where
+    T: MersenneField,
+{
+    let epsilon_shares: Vec<AuthShare<T>> = parties
+        .iter()
+        .map(|party| {
+            sub_share(
+                party.spdz.get_share(id_x),
+                party.spdz.get_share(id_triple.0),
+            )
+        })
+        .collect();
+    let delta_shares: Vec<AuthShare<T>> = parties
+        .iter()
+        .map(|party| {
+            sub_share(
+                party.spdz.get_share(id_y),
+                party.spdz.get_share(id_triple.1),
+            )
+        })
+        .collect();
+    let epsilon = partial_open(parties, &epsilon_shares);
+    let delta = partial_open(parties, &delta_shares);
+
+    let epsilon_delta = epsilon.multiply(&delta);
+    for (j, party) in parties.iter_mut().enumerate() {
+        let a = party.spdz.get_share(id_triple.0);
+        let b = party.spdz.get_share(id_triple.1);
+        let c = party.spdz.get_share(id_triple.2);
+
+        let mut value = c
+            .value
+            .add(&epsilon.multiply(&b.value))
+            .add(&delta.multiply(&a.value));
+        if j == 0 {
+            value = value.add(&epsilon_delta);
+        }
+        let mac = c
+            .mac
+            .add(&epsilon.multiply(&b.mac))
+            .add(&delta.multiply(&a.mac))
+            .add(&party.spdz.key_share().multiply(&epsilon_delta));
+
+        for id in [id_triple.0, id_triple.1, id_triple.2] {
+            party.spdz.shares.remove(id);
+        }
+        insert_auth_share(party, id_result, AuthShare { value, mac });
+    }
+}
+
+/// Opens an authenticated value and checks its MAC together with the MACs of
+/// all the values partially opened before.
+///
+/// The function panics if the MAC check fails, which means that some party
+/// has tampered with its shares.
+pub fn reconstruct_share<T>(parties: &mut [&mut VirtualMachine<T>], id: &str, prg: &mut Prg) -> T
+where
+    T: MersenneField,
+{
+    let shares: Vec<AuthShare<T>> = parties
+        .iter()
+        .map(|party| party.spdz.get_share(id).clone())
+        .collect();
+    let value = partial_open(parties, &shares);
+    mac_check(parties, prg);
+    value
+}
+
+/// Checks the MACs of all the values partially opened since the last check.
+///
+/// For public random coefficients $r_k$, the parties compute the combination
+/// $a = \sum_k r_k x_k$ of the opened values, and each party $j$ computes
+/// $\sigma_j = \sum_k r_k \gamma(x_k)_j - \alpha_j a$. If every opened value is
+/// correct, the values $\sigma_j$ add up to zero. The coefficients are sampled
+/// with the provided pseudo-random generator, which simulates a coin-tossing
+/// protocol. With the `sha2` feature, the values $\sigma_j$ are opened with a
+/// commit-then-open protocol (see [`coin_toss`](crate::mpc::coin_toss)), so a
+/// corrupted party can not choose its $\sigma_j$ after seeing the others to
+/// cancel out an error; otherwise, they are opened directly through the
+/// network. The function panics if the check fails.
+pub fn mac_check<T>(parties: &mut [&mut VirtualMachine<T>], prg: &mut Prg)
+where
+    T: MersenneField,
+{
+    let n_opened = parties[0].spdz.opened.len();
+    if parties
+        .iter()
+        .any(|party| party.spdz.opened.len() != n_opened)
+    {
+        panic!("The parties have not opened the same values.");
+    }
+    let coeffs: Vec<T> = (0..n_opened).map(|_| T::random(&mut *prg)).collect();
+
+    let sigmas: Vec<T> = parties
+        .iter_mut()
+        .map(|party| {
+            let opened = std::mem::take(&mut party.spdz.opened);
+            let (combination, mac_combination) = opened.iter().zip(&coeffs).fold(
+                (T::new(0), T::new(0)),
+                |(acc_value, acc_mac), ((value, mac), coeff)| {
+                    (
+                        acc_value.add(&coeff.multiply(value)),
+                        acc_mac.add(&coeff.multiply(mac)),
+                    )
+                },
+            );
+            mac_combination.subtract(&party.spdz.key_share().multiply(&combination))
+        })
+        .collect();
+    #[cfg(feature = "sha2")]
+    let sigma = crate::mpc::open_committed(parties, &sigmas, "mac_check");
+    #[cfg(not(feature = "sha2"))]
+    let sigma = {
+        let sigmas: Vec<Vec<T>> = sigmas.into_iter().map(|sigma| vec![sigma]).collect();
+        Simulator::new(parties)
+            .with_tag("mac_check")
+            .open(&sigmas)
+            .remove(0)
+    };
+
+    if sigma.value() != 0 {
+        panic!("The MAC check failed, some party has cheated.");
+    }
+}
+
+/// Opens a value through the network without checking its MAC, which is
+/// recorded by every party to be checked later.
+fn partial_open<T: MersenneField>(
+    parties: &mut [&mut VirtualMachine<T>],
+    shares: &[AuthShare<T>],
+) -> T {
+    let sent: Vec<Vec<T>> = parties
+        .iter()
+        .zip(shares)
+        .map(|(party, share)| vec![party.behavior.opened_share(&share.value)])
+        .collect();
+    let value = Simulator::new(parties)
+        .with_tag("spdz_open")
+        .open(&sent)
+        .remove(0);
+    for (party, share) in parties.iter_mut().zip(shares) {
+        party.spdz.opened.push((value.clone(), share.mac.clone()));
+    }
+    value
+}
+
+/// Subtracts two authenticated shares locally.
+fn sub_share<T: MersenneField>(a: &AuthShare<T>, b: &AuthShare<T>) -> AuthShare<T> {
+    AuthShare {
+        value: a.value.subtract(&b.value),
+        mac: a.mac.subtract(&b.mac),
+    }
+}
+
+/// Inserts an authenticated share in the SPDZ memory of a party.
+fn insert_auth_share<T: MersenneField>(
+    party: &mut VirtualMachine<T>,
+    id: &str,
+    share: AuthShare<T>,
+) {
+    if party.spdz.shares.contains_key(id) {
+        panic!("There exists an authenticated share with this id.");
+    }
+    let share = AuthShare {
+        value: party.behavior.stored_share(share.value),
+        mac: share.mac,
+    };
+    party.spdz.shares.insert(id.to_string(), share);
+}
+
+/// Reconstructs the global MAC key, which is only possible for the trusted
+/// party that simulates the preprocessing.
+fn global_key<T: MersenneField>(parties: &[&mut VirtualMachine<T>]) -> T {
+    parties
+        .iter()
+        .fold(T::new(0), |acc, party| acc.add(party.spdz.key_share()))
+}
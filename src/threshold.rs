@@ -0,0 +1,616 @@
+//! Implements threshold cryptography on top of Shamir secret-sharing.
+//!
+//! In a threshold scheme, the secret key is never held by a single party.
+//! Instead, it is Shamir-shared among the parties with threshold $t$ (see
+//! [`shamir`](crate::shamir)), and any $t + 1$ of them can use it together,
+//! while $t$ of them learn nothing about it. The operations with the key in
+//! these schemes are linear in the exponent: given the shares $x_j$ of the key
+//! $x$, the parties compute $a^{x_j}$ locally, and the value $a^x$ is recovered
+//! with Lagrange interpolation in the exponent,
+//!    $$a^x = \prod_j \left(a^{x_j}\right)^{\lambda_j},$$
+//! where $\lambda_j$ are the Lagrange coefficients of the parties taking part.
+//! The key itself is never reconstructed.
+//!
+//! The shared key can be produced without any trusted dealer with the
+//! distributed key generation in [`dkg_protocol`], in which every party deals
+//! a random sharing with Feldman verifiable secret-sharing and the key is the
+//! sum of the valid contributions.
+//!
+//! With the output of the key generation, the parties can also sign together
+//! with [`threshold_sign_protocol`], a simplified version of the FROST
+//! threshold Schnorr signatures, which needs the `sha2` feature.
+//!
+//! The commitments and the partial results are sent through the simulated
+//! network as bits, with the encoding of [`Group::encode`].
+//!
+//! # Example
+//!
+//! Here, the secret key of an ElGamal key pair is shared among five parties
+//! with threshold two, and a ciphertext is decrypted even though two of the
+//! parties are offline.
+//!
+//! ```rust
+//! use smol_mpc::math::group::Group;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::shamir;
+//! use smol_mpc::threshold;
+//! use smol_mpc::utils::elgamal;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let group: Group<Fp> = Group::generate(128, &mut prg);
+//! let (public_key, secret_key) = elgamal::keygen(&group, &mut prg);
+//!
+//! let mut machines: Vec<VirtualMachine<Fp>> = ["a", "b", "c", "d", "e"]
+//!     .iter()
+//!     .map(|id| VirtualMachine::new(id))
+//!     .collect();
+//! let mut parties: Vec<&mut VirtualMachine<Fp>> = machines.iter_mut().collect();
+//! shamir::simulate_shamir_dist("key", &mut parties, secret_key.exponent(), 2, &mut prg);
+//!
+//! let message = group.random_element(&mut prg);
+//! let ciphertext = public_key.encrypt(&message, &mut prg);
+//! parties[1].mailbox.set_offline(true);
+//! parties[3].mailbox.set_offline(true);
+//!
+//! let decrypted =
+//!     threshold::threshold_decrypt_protocol(&parties, "key", &public_key, &ciphertext);
+//! assert_eq!(decrypted, message);
+//! ```
+//!
+//! The same works with a key generated by the parties themselves, which nobody
+//! ever learns.
+//!
+//! ```rust
+//! use smol_mpc::math::group::Group;
+//! use smol_mpc::math::mersenne::Mersenne61;
+//! use smol_mpc::threshold;
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let group: Group<Fp> = Group::generate(128, &mut prg);
+//!
+//! let mut machines: Vec<VirtualMachine<Fp>> =
+//!     ["a", "b", "c"].iter().map(|id| VirtualMachine::new(id)).collect();
+//! let mut parties: Vec<&mut VirtualMachine<Fp>> = machines.iter_mut().collect();
+//! let dkg = threshold::dkg_protocol(&mut parties, &group, 1, "key");
+//!
+//! let message = group.random_element(&mut prg);
+//! let ciphertext = dkg.public_key.encrypt(&message, &mut prg);
+//! let decrypted =
+//!     threshold::threshold_decrypt_protocol(&parties, "key", &dkg.public_key, &ciphertext);
+//! assert_eq!(decrypted, message);
+//! ```
+
+use crate::math::group::{Group, GroupElement};
+use crate::math::mersenne::MersenneField;
+use crate::mpc::{bits_to_bytes, bytes_to_bits, Share};
+use crate::network::{Payload, Simulator};
+use crate::shamir;
+use crate::utils::elgamal::{Ciphertext, PublicKey};
+use crate::utils::prg::Prg;
+#[cfg(feature = "sha2")]
+use crate::utils::ro::RandomOracle;
+use crate::vm::VirtualMachine;
+
+/// Defines the commitment of a dealer in Feldman verifiable secret-sharing.
+///
+/// To share a value $s$ with a polynomial $f(X) = \sum_k a_k X^k$ with
+/// $a_0 = s$, the dealer publishes $C_k = g^{a_k}$ for each coefficient. Then,
+/// the share $f(j)$ of a party is checked against the commitment as
+///    $$g^{f(j)} = \prod_k C_k^{j^k}$$
+/// without learning anything more than $g^s = C_0$.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeldmanCommitment(pub Vec<GroupElement>);
+
+impl FeldmanCommitment {
+    /// Returns the commitment $C_0 = g^s$ to the shared value.
+    pub fn constant(&self) -> &GroupElement {
+        &self.0[0]
+    }
+
+    /// Computes $g^{f(j)}$ for the party at the point $j$ from the commitment.
+    pub fn eval<T: MersenneField>(&self, group: &Group<T>, point: u64) -> GroupElement {
+        // Horner's rule in the exponent.
+        self.0.iter().rev().fold(group.identity(), |acc, coeff| {
+            group.mul(&group.exp(&acc, &T::new(point)), coeff)
+        })
+    }
+
+    /// Checks that the provided value is the share of the party at the point
+    /// $j$.
+    pub fn verify<T: MersenneField>(&self, group: &Group<T>, point: u64, share: &T) -> bool {
+        group.exp(group.generator(), share) == self.eval(group, point)
+    }
+}
+
+/// Computes the Shamir shares of a value for `n_parties` parties using a random
+/// polynomial of degree `degree`, together with the Feldman commitment to the
+/// polynomial.
+pub fn feldman_share<T: MersenneField>(
+    group: &Group<T>,
+    value: &T,
+    degree: usize,
+    n_parties: usize,
+    prg: &mut Prg,
+) -> (Vec<T>, FeldmanCommitment) {
+    let coeffs: Vec<T> = std::iter::once(value.clone())
+        .chain((0..degree).map(|_| T::random(&mut *prg)))
+        .collect();
+    let shares = (1..=n_parties as u64)
+        .map(|point| {
+            let point = T::new(point);
+            coeffs
+                .iter()
+                .rev()
+                .fold(T::new(0), |acc, coeff| acc.multiply(&point).add(coeff))
+        })
+        .collect();
+    let commitment = coeffs
+        .iter()
+        .map(|coeff| group.exp(group.generator(), coeff))
+        .collect();
+    (shares, FeldmanCommitment(commitment))
+}
+
+/// Defines the public output of the distributed key generation.
+#[derive(Clone)]
+pub struct DkgOutput<T: MersenneField> {
+    /// Joint public key $y = g^x$.
+    pub public_key: PublicKey<T>,
+
+    /// Degree of the sharing of the secret key.
+    pub threshold: usize,
+
+    /// Indices of the parties whose contributions were accepted.
+    pub qualified: Vec<usize>,
+
+    /// Verification keys $g^{x_j}$ of the shares of the key of every party,
+    /// which allow checking the partial results computed with them.
+    pub verification_keys: Vec<GroupElement>,
+}
+
+/// Generates a secret key Shamir-shared with threshold `threshold` among a set
+/// of parties, without any trusted dealer, in the style of the protocol of
+/// Pedersen (Ped91).
+///
+/// Each online party samples a random contribution $s_i$ and deals it with
+/// [`feldman_share`], broadcasting the commitment and sending the $j$-th share
+/// to the $j$-th party, which checks it against the commitment. Then, every
+/// online party broadcasts a complaint bit for each dealer, and a dealer with a
+/// share that fails the check of some party is disqualified. In the original
+/// protocol, the dealer can answer a complaint by revealing the share; here,
+/// the complaint is enough. The secret key is $x = \sum_{i \in Q} s_i$ over the
+/// set $Q$ of the qualified dealers, which is random as long as one of them is
+/// honest. Every online party adds up the shares received from them and stores
+/// the sum under the ID `id_key`, and the public key is
+/// $y = \prod_{i \in Q} C_{i,0}$. The offline parties receive nothing, so they
+/// do not store a share of the key.
+///
+/// The messages go through the simulated network with the tag `"dkg"`, in two
+/// rounds.
+///
+/// The function panics if the threshold is not smaller than the number of
+/// parties, or if no dealer is qualified.
+pub fn dkg_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    group: &Group<T>,
+    threshold: usize,
+    id_key: &str,
+) -> DkgOutput<T>
+where
+    T: MersenneField,
+{
+    let n_parties = parties.len();
+    if threshold >= n_parties {
+        panic!("The threshold must be smaller than the number of parties.");
+    }
+
+    // Dealing: the j-th party receives the j-th share of each online dealer.
+    let online: Vec<usize> = (0..n_parties)
+        .filter(|&i| !parties[i].mailbox.is_offline())
+        .collect();
+    let dealings: Vec<(usize, Vec<T>, FeldmanCommitment)> = online
+        .iter()
+        .map(|&i| {
+            let party = &mut parties[i];
+            let contribution = T::random(&mut party.prg);
+            let (shares, commitment) =
+                feldman_share(group, &contribution, threshold, n_parties, &mut party.prg);
+            let sent = shares
+                .iter()
+                .enumerate()
+                .map(|(j, share)| party.behavior.sent_value(share, j))
+                .collect();
+            (i, sent, commitment)
+        })
+        .collect();
+
+    let network = Simulator::new(parties).with_tag("dkg");
+    for (i, sent, commitment) in dealings.iter() {
+        network.send_to_all(*i, elements_payload(group, &commitment.0));
+        for (j, share) in sent.iter().enumerate().filter(|(j, _)| j != i) {
+            network.send(*i, j, Payload::Elements(vec![share.clone()]));
+        }
+    }
+    network.deliver();
+
+    // Verification: each party complains about the dealers whose share fails
+    // the check, and a single complaint disqualifies the dealer.
+    let received: Vec<(Vec<T>, Vec<bool>)> = online
+        .iter()
+        .map(|&j| {
+            let (shares, complaints): (Vec<T>, Vec<bool>) = dealings
+                .iter()
+                .map(|(i, sent, commitment)| {
+                    if *i == j {
+                        let share = sent[j].clone();
+                        let complaint = !commitment.verify(group, j as u64 + 1, &share);
+                        return (share, complaint);
+                    }
+                    let payload = network.recv_from(j, *i).payload;
+                    let commitment = FeldmanCommitment(payload_elements(group, &payload));
+                    let share = network.recv_from(j, *i).payload.elements().remove(0);
+                    let complaint = !commitment.verify(group, j as u64 + 1, &share);
+                    (share, complaint)
+                })
+                .unzip();
+            network.send_to_all(j, Payload::Bits(complaints.clone()));
+            (shares, complaints)
+        })
+        .collect();
+    network.deliver();
+
+    let mut qualified: Vec<usize> = Vec::new();
+    let mut keys = Vec::new();
+    for (&j, (shares, complaints)) in online.iter().zip(&received) {
+        let mut complaints = complaints.clone();
+        for &from in online.iter().filter(|&&from| from != j) {
+            let bits = network.recv_from(j, from).payload.bits();
+            for (complaint, bit) in complaints.iter_mut().zip(bits) {
+                *complaint |= bit;
+            }
+        }
+        qualified = (0..dealings.len()).filter(|&d| !complaints[d]).collect();
+        let key = qualified
+            .iter()
+            .fold(T::new(0), |acc, &d| acc.add(&shares[d]));
+        keys.push((j, key));
+    }
+    if qualified.is_empty() {
+        panic!("No dealer is qualified in the distributed key generation.");
+    }
+
+    for (j, key) in keys {
+        parties[j].insert_share(id_key, Share::new(id_key, key));
+    }
+
+    let qualified: Vec<&(usize, Vec<T>, FeldmanCommitment)> =
+        qualified.iter().map(|&d| &dealings[d]).collect();
+    let combine = |eval: &dyn Fn(&FeldmanCommitment) -> GroupElement| {
+        qualified
+            .iter()
+            .fold(group.identity(), |acc, (_, _, commitment)| {
+                group.mul(&acc, &eval(commitment))
+            })
+    };
+    DkgOutput {
+        public_key: PublicKey::new(
+            group.clone(),
+            combine(&|commitment| commitment.constant().clone()),
+        ),
+        threshold,
+        qualified: qualified.iter().map(|(i, _, _)| *i).collect(),
+        verification_keys: (1..=n_parties as u64)
+            .map(|point| combine(&|commitment| commitment.eval(group, point)))
+            .collect(),
+    }
+}
+
+/// Computes $a^x$ for a Shamir-shared exponent $x$ with ID `id`, combining the
+/// partial results of the online parties with Lagrange interpolation in the
+/// exponent. Each online party sends its partial result $a^{x_j}$ to all the
+/// other parties through the simulated network, with the tag `"shared_exp"`.
+/// The result is correct as long as the number of online parties is greater
+/// than the degree of the sharing.
+pub fn shared_exp<T>(
+    parties: &[&mut VirtualMachine<T>],
+    group: &Group<T>,
+    base: &GroupElement,
+    id: &str,
+) -> GroupElement
+where
+    T: MersenneField,
+{
+    let online: Vec<usize> = (0..parties.len())
+        .filter(|&j| !parties[j].mailbox.is_offline())
+        .collect();
+    let network = Simulator::new(parties).with_tag("shared_exp");
+    let partials: Vec<GroupElement> = online
+        .iter()
+        .map(|&j| {
+            let share = parties[j]
+                .behavior
+                .opened_share(&parties[j].get_share(id).value);
+            let partial = group.exp(base, &share);
+            network.send_to_all(j, elements_payload(group, std::slice::from_ref(&partial)));
+            partial
+        })
+        .collect();
+    network.deliver();
+
+    let points: Vec<u64> = online.iter().map(|&j| j as u64 + 1).collect();
+    let lagrange = shamir::lagrange_at_zero::<T>(&points);
+    let mut result = group.identity();
+    for (k, &to) in online.iter().enumerate() {
+        result = online
+            .iter()
+            .zip(&lagrange)
+            .fold(group.identity(), |acc, (&from, coeff)| {
+                let partial = if from == to {
+                    partials[k].clone()
+                } else {
+                    payload_elements(group, &network.recv_from(to, from).payload).remove(0)
+                };
+                group.mul(&acc, &group.exp(&partial, coeff))
+            });
+    }
+    result
+}
+
+/// Encodes group elements as the bits of a message (see [`Group::encode`]).
+fn elements_payload<T: MersenneField>(group: &Group<T>, elements: &[GroupElement]) -> Payload<T> {
+    Payload::Bits(bytes_to_bits(&group.encode(elements)))
+}
+
+/// Decodes the group elements of a message encoded with [`elements_payload`].
+fn payload_elements<T: MersenneField>(group: &Group<T>, payload: &Payload<T>) -> Vec<GroupElement> {
+    group.decode(&bits_to_bytes(&payload.bits()))
+}
+
+/// Decrypts an ElGamal ciphertext with a Shamir-shared secret key.
+///
+/// The parties hold Shamir shares of the secret key $x$ under the ID `id_key`.
+/// Each online party computes the partial decryption $c_1^{x_j}$ with its
+/// share, and the partial decryptions are combined into the mask $c_1^x$,
+/// which is removed from $c_2$ to obtain the message. The message is correct
+/// as long as the number of online parties is greater than the degree of the
+/// sharing of the key.
+pub fn threshold_decrypt_protocol<T>(
+    parties: &[&mut VirtualMachine<T>],
+    id_key: &str,
+    public_key: &PublicKey<T>,
+    ciphertext: &Ciphertext,
+) -> GroupElement
+where
+    T: MersenneField,
+{
+    let mask = shared_exp(parties, public_key.group(), &ciphertext.c1, id_key);
+    public_key.unmask(ciphertext, &mask)
+}
+
+/// Defines a Schnorr signature $(R, z)$, which is valid for the message $m$
+/// under the public key $y$ if
+///    $$g^z = R \cdot y^c,$$
+/// where the challenge $c = H(y, R, m)$ is computed with a random oracle.
+#[cfg(feature = "sha2")]
+#[derive(Clone)]
+pub struct SchnorrSignature<T: MersenneField> {
+    /// Commitment $R$ to the nonce.
+    pub r: GroupElement,
+
+    /// Response $z$.
+    pub z: T,
+}
+
+#[cfg(feature = "sha2")]
+impl<T: MersenneField> SchnorrSignature<T> {
+    /// Checks the signature of a message under a public key.
+    pub fn verify(&self, public_key: &PublicKey<T>, message: &[u8]) -> bool {
+        let group = public_key.group();
+        let c: T = schnorr_challenge(public_key, &self.r, message);
+        group.exp(group.generator(), &self.z)
+            == group.mul(&self.r, &group.exp(public_key.element(), &c))
+    }
+}
+
+/// Computes the challenge $c = H(y, R, m)$ of a Schnorr signature.
+#[cfg(feature = "sha2")]
+fn schnorr_challenge<T: MersenneField>(
+    public_key: &PublicKey<T>,
+    r: &GroupElement,
+    message: &[u8],
+) -> T {
+    let mut oracle = RandomOracle::new("smol-mpc schnorr challenge");
+    oracle.absorb(&public_key.element().0.to_bytes_le());
+    oracle.absorb(&r.0.to_bytes_le());
+    oracle.absorb(message);
+    oracle.squeeze_field_element()
+}
+
+/// Computes the binding factors $\rho_i$, the commitments $D_i E_i^{\rho_i}$,
+/// the group commitment $R$ and the challenge $c$ of a FROST signature from the
+/// commitments of the signers.
+#[cfg(feature = "sha2")]
+#[allow(clippy::type_complexity)]
+fn group_commitment<T: MersenneField>(
+    dkg: &DkgOutput<T>,
+    signers: &[usize],
+    commitments: &[(GroupElement, GroupElement)],
+    message: &[u8],
+) -> (Vec<T>, Vec<GroupElement>, GroupElement, T) {
+    let group = dkg.public_key.group();
+    let mut oracle = RandomOracle::new("smol-mpc frost binding");
+    oracle.absorb(message);
+    for (i, (d, e)) in signers.iter().zip(commitments) {
+        oracle.absorb(&(*i as u64).to_le_bytes());
+        oracle.absorb(&d.0.to_bytes_le());
+        oracle.absorb(&e.0.to_bytes_le());
+    }
+    let binding: Vec<T> = signers
+        .iter()
+        .map(|&i| {
+            let mut oracle = oracle.clone();
+            oracle.absorb(&(i as u64).to_le_bytes());
+            oracle.squeeze_field_element()
+        })
+        .collect();
+    let nonce_commitments: Vec<GroupElement> = commitments
+        .iter()
+        .zip(&binding)
+        .map(|((d, e), rho)| group.mul(d, &group.exp(e, rho)))
+        .collect();
+    let r = nonce_commitments
+        .iter()
+        .fold(group.identity(), |acc, commitment| {
+            group.mul(&acc, commitment)
+        });
+    let c: T = schnorr_challenge(&dkg.public_key, &r, message);
+    (binding, nonce_commitments, r, c)
+}
+
+/// Signs a message with a secret key Shamir-shared by [`dkg_protocol`], in the
+/// style of the FROST protocol of Komlo and Goldberg (KG20), simplified.
+///
+/// The signers are the online parties, which must be more than the threshold
+/// of the sharing of the key. The protocol has two rounds:
+/// 1. Each signer $i$ samples two nonces $d_i$ and $e_i$ and publishes the
+///    commitments $D_i = g^{d_i}$ and $E_i = g^{e_i}$.
+/// 2. Each signer computes the binding factors $\rho_i = H(i, m, B)$, where
+///    $B$ is the list of all the commitments, the group commitment
+///    $R = \prod_i D_i E_i^{\rho_i}$ and the challenge $c = H(y, R, m)$, and
+///    sends the signature share $z_i = d_i + e_i \rho_i + \lambda_i x_i c$,
+///    where $\lambda_i$ is its Lagrange coefficient in the set of signers.
+///
+/// The messages go through the simulated network with the tag `"frost"`: the
+/// commitments are sent to all the signers, and the signature shares to the
+/// first signer, which combines them. Each signature share is checked against
+/// the verification key $Y_i$ of the signer as
+/// $g^{z_i} = D_i E_i^{\rho_i} Y_i^{\lambda_i c}$, and the signature is
+/// $(R, \sum_i z_i)$. The binding factors tie every nonce to the message and to
+/// the set of commitments, so that the commitments of a signing session cannot
+/// be combined with those of another one. In the original protocol, the first
+/// round is done ahead of time for many signatures, and the nonces are never
+/// reused.
+///
+/// The function panics if there are not enough online parties, or if a
+/// signature share is invalid, which identifies the cheating signer.
+#[cfg(feature = "sha2")]
+pub fn threshold_sign_protocol<T>(
+    parties: &mut [&mut VirtualMachine<T>],
+    id_key: &str,
+    dkg: &DkgOutput<T>,
+    message: &[u8],
+) -> SchnorrSignature<T>
+where
+    T: MersenneField,
+{
+    let group = dkg.public_key.group();
+    let signers: Vec<usize> = (0..parties.len())
+        .filter(|&i| !parties[i].mailbox.is_offline())
+        .collect();
+    if signers.len() <= dkg.threshold {
+        panic!(
+            "Signing needs at least {} online parties.",
+            dkg.threshold + 1
+        );
+    }
+
+    // Round 1: the signers commit to their nonces and send the commitments to
+    // all the other signers.
+    let nonces: Vec<(T, T)> = signers
+        .iter()
+        .map(|&i| {
+            let prg = &mut parties[i].prg;
+            (T::random(&mut *prg), T::random(&mut *prg))
+        })
+        .collect();
+    let network = Simulator::new(parties).with_tag("frost");
+    for (&i, (d, e)) in signers.iter().zip(&nonces) {
+        let commitments = [
+            group.exp(group.generator(), d),
+            group.exp(group.generator(), e),
+        ];
+        for &to in signers.iter().filter(|&&to| to != i) {
+            network.send(i, to, elements_payload(group, &commitments));
+        }
+    }
+    network.deliver();
+    let received: Vec<Vec<(GroupElement, GroupElement)>> = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(&to, (d, e))| {
+            signers
+                .iter()
+                .map(|&from| {
+                    if from == to {
+                        (
+                            group.exp(group.generator(), d),
+                            group.exp(group.generator(), e),
+                        )
+                    } else {
+                        let mut elements =
+                            payload_elements(group, &network.recv_from(to, from).payload);
+                        let e = elements.pop().unwrap();
+                        (elements.pop().unwrap(), e)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    // Round 2: each signer computes the group commitment from the commitments
+    // it received, and sends its share to the first signer, which combines
+    // them.
+    let points: Vec<u64> = signers.iter().map(|&i| i as u64 + 1).collect();
+    let lagrange = shamir::lagrange_at_zero::<T>(&points);
+    let coordinator = signers[0];
+    let mut own_share = None;
+    for (k, &i) in signers.iter().enumerate() {
+        let (binding, _, _, c) = group_commitment(dkg, &signers, &received[k], message);
+        let (d, e) = &nonces[k];
+        let share = d.add(&e.multiply(&binding[k])).add(
+            &lagrange[k]
+                .multiply(&c)
+                .multiply(&parties[i].get_share(id_key).value),
+        );
+        let share = parties[i].behavior.opened_share(&share);
+        if i == coordinator {
+            own_share = Some(share);
+        } else {
+            network.send(i, coordinator, Payload::Elements(vec![share]));
+        }
+    }
+    network.deliver();
+
+    let (_, nonce_commitments, r, c) = group_commitment(dkg, &signers, &received[0], message);
+    let mut z = T::new(0);
+    for (k, &i) in signers.iter().enumerate() {
+        let share = if i == coordinator {
+            own_share.clone().unwrap()
+        } else {
+            network
+                .recv_from(coordinator, i)
+                .payload
+                .elements()
+                .remove(0)
+        };
+        let weight = lagrange[k].multiply(&c);
+        let expected = group.mul(
+            &nonce_commitments[k],
+            &group.exp(&dkg.verification_keys[i], &weight),
+        );
+        if group.exp(group.generator(), &share) != expected {
+            panic!("The signature share of party {} is invalid.", parties[i].id);
+        }
+        z = z.add(&share);
+    }
+
+    SchnorrSignature { r, z }
+}
@@ -0,0 +1,56 @@
+//! Implements a hash-based commitment scheme.
+//!
+//! A commitment scheme lets a party fix a value without revealing it, and
+//! reveal it later in a way that the other parties can verify. Here, the
+//! commitment to a value $v$ with randomness $r$ is
+//!    $$c = H(|v| \parallel v \parallel r),$$
+//! where $H$ is SHA-256 and $|v|$ is the length of the value encoded in eight
+//! bytes, so that the value and the randomness cannot be split differently.
+//! The scheme is hiding as long as the randomness is secret and long enough,
+//! and binding because finding collisions in $H$ is hard. This module is only
+//! available with the `sha2` feature.
+
+use sha2::{Digest, Sha256};
+
+/// Defines a commitment to a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commitment(pub [u8; 32]);
+
+/// Defines the opening of a commitment, that is, the committed value together
+/// with the randomness used to commit to it.
+#[derive(Clone, Debug)]
+pub struct Opening {
+    /// Committed value.
+    pub value: Vec<u8>,
+
+    /// Randomness of the commitment.
+    pub randomness: Vec<u8>,
+}
+
+/// Commits to a value using the provided randomness, which should be at least
+/// 16 bytes long and sampled uniformly to hide the value.
+///
+/// The commitment is sent to the other parties, while the opening is kept
+/// until the value is revealed.
+pub fn commit(value: &[u8], randomness: &[u8]) -> (Commitment, Opening) {
+    let commitment = Commitment(hash(value, randomness));
+    let opening = Opening {
+        value: value.to_vec(),
+        randomness: randomness.to_vec(),
+    };
+    (commitment, opening)
+}
+
+/// Checks that an opening corresponds to a commitment.
+pub fn verify(commitment: &Commitment, opening: &Opening) -> bool {
+    hash(&opening.value, &opening.randomness) == commitment.0
+}
+
+/// Computes the hash of a value and its randomness.
+fn hash(value: &[u8], randomness: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update((value.len() as u64).to_le_bytes());
+    hasher.update(value);
+    hasher.update(randomness);
+    hasher.finalize().into()
+}
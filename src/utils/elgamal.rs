@@ -0,0 +1,112 @@
+//! Implements the ElGamal encryption scheme.
+//!
+//! Given a [`Group`] with generator $g$, the secret key is a random exponent
+//! $x \in \mathbb{F}_q$ and the public key is $h = g^x$. A message $m$, which
+//! is an element of the group, is encrypted with a random $r$ as
+//!    $$(c_1, c_2) = (g^r, m h^r),$$
+//! and decrypted as $m = c_2 / c_1^x$. The product of two ciphertexts,
+//! component by component, is an encryption of the product of the messages, so
+//! the scheme is multiplicatively homomorphic.
+//!
+//! Decryption only needs $c_1^x$, which is linear in the secret key in the
+//! exponent, so the key can be secret-shared among several parties that
+//! decrypt together without reconstructing it (see
+//! [`threshold_decrypt_protocol`](crate::threshold::threshold_decrypt_protocol)).
+
+use crate::math::group::{Group, GroupElement};
+use crate::math::mersenne::MersenneField;
+use crate::utils::prg::Prg;
+
+/// Defines an ElGamal public key.
+#[derive(Clone)]
+pub struct PublicKey<T: MersenneField> {
+    group: Group<T>,
+    h: GroupElement,
+}
+
+/// Defines an ElGamal secret key.
+#[derive(Clone)]
+pub struct SecretKey<T: MersenneField> {
+    x: T,
+    public: PublicKey<T>,
+}
+
+/// Defines an ElGamal ciphertext.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext {
+    /// Randomness component $c_1 = g^r$.
+    pub c1: GroupElement,
+
+    /// Masked message $c_2 = m h^r$.
+    pub c2: GroupElement,
+}
+
+/// Generates a key pair in the provided group.
+pub fn keygen<T: MersenneField>(group: &Group<T>, prg: &mut Prg) -> (PublicKey<T>, SecretKey<T>) {
+    let x = T::random(prg);
+    let public = PublicKey::new(group.clone(), group.exp(group.generator(), &x));
+    let secret = SecretKey {
+        x,
+        public: public.clone(),
+    };
+    (public, secret)
+}
+
+impl<T: MersenneField> PublicKey<T> {
+    /// Creates a public key from the element $h = g^x$, for example computed by
+    /// the parties that hold shares of $x$.
+    pub fn new(group: Group<T>, h: GroupElement) -> Self {
+        Self { group, h }
+    }
+
+    /// Returns the group of the key.
+    pub fn group(&self) -> &Group<T> {
+        &self.group
+    }
+
+    /// Returns the element $h = g^x$ of the key.
+    pub fn element(&self) -> &GroupElement {
+        &self.h
+    }
+
+    /// Encrypts a message with fresh randomness.
+    pub fn encrypt(&self, message: &GroupElement, prg: &mut Prg) -> Ciphertext {
+        let r = T::random(prg);
+        Ciphertext {
+            c1: self.group.exp(self.group.generator(), &r),
+            c2: self.group.mul(message, &self.group.exp(&self.h, &r)),
+        }
+    }
+
+    /// Computes an encryption of the product of the messages of two
+    /// ciphertexts.
+    pub fn mul(&self, a: &Ciphertext, b: &Ciphertext) -> Ciphertext {
+        Ciphertext {
+            c1: self.group.mul(&a.c1, &b.c1),
+            c2: self.group.mul(&a.c2, &b.c2),
+        }
+    }
+
+    /// Removes the mask $c_1^x$ from a ciphertext, which gives the message.
+    pub fn unmask(&self, ciphertext: &Ciphertext, mask: &GroupElement) -> GroupElement {
+        self.group.mul(&ciphertext.c2, &self.group.inverse(mask))
+    }
+}
+
+impl<T: MersenneField> SecretKey<T> {
+    /// Returns the public key associated to this secret key.
+    pub fn public_key(&self) -> &PublicKey<T> {
+        &self.public
+    }
+
+    /// Returns the exponent $x$ of the key, for example to secret-share it.
+    pub fn exponent(&self) -> &T {
+        &self.x
+    }
+
+    /// Decrypts a ciphertext.
+    pub fn decrypt(&self, ciphertext: &Ciphertext) -> GroupElement {
+        let mask = self.public.group.exp(&ciphertext.c1, &self.x);
+        self.public.unmask(ciphertext, &mask)
+    }
+}
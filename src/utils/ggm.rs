@@ -0,0 +1,207 @@
+//! Implements the tree of Goldreich, Goldwasser and Micali (GGM), which turns a
+//! pseudo-random generator into a puncturable pseudo-random function.
+//!
+//! A length-doubling PRG $G(s) = (G_0(s), G_1(s))$ expands a seed into two
+//! seeds. Starting with a root seed $k$, the tree of depth $d$ is obtained by
+//! expanding every node into its two children, and the PRF is evaluated at an
+//! index $x = x_1 \dots x_d$, read from the most significant bit, by following
+//! the path of $x$ down to a leaf:
+//!    $$F_k(x) = G_{x_d}(\dots G_{x_2}(G_{x_1}(k)) \dots).$$
+//!
+//! The key can be punctured at an index $\alpha$: the punctured key is made of
+//! the $d$ siblings of the nodes on the path of $\alpha$, the co-path. It allows
+//! to evaluate the PRF at every index but $\alpha$, while $F_k(\alpha)$ stays
+//! pseudo-random for whoever holds it. This is the building block of
+//! distributed point functions and of the OT extensions that expand a few base
+//! OTs into many correlated ones.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::utils::ggm::GgmTree;
+//! use smol_mpc::utils::prg::Prg;
+//!
+//! let mut prg = Prg::new(None);
+//! let tree = GgmTree::random(4, &mut prg);
+//! let key = tree.puncture(5);
+//!
+//! assert_eq!(key.eval(3), Some(tree.eval(3)));
+//! assert_eq!(key.eval(5), None);
+//! ```
+
+use crate::utils::prg::Prg;
+
+/// Length in bytes of the seeds of the nodes of the tree.
+pub const SEED_LEN: usize = 16;
+
+/// Maximum depth of a tree, so that its indices fit in a `u64`.
+pub const MAX_DEPTH: usize = 63;
+
+/// Defines the seed of a node of the tree.
+pub type Seed = [u8; SEED_LEN];
+
+/// Expands a seed into the seeds of its left and right children.
+pub fn expand(seed: &Seed) -> [Seed; 2] {
+    let bytes = Prg::new(Some(seed.to_vec())).next(2 * SEED_LEN);
+    let mut children = [[0; SEED_LEN]; 2];
+    children[0].copy_from_slice(&bytes[..SEED_LEN]);
+    children[1].copy_from_slice(&bytes[SEED_LEN..]);
+    children
+}
+
+/// Returns the bit of the index that selects the child at the provided level,
+/// where the level 0 is the root.
+fn bit(index: u64, depth: usize, level: usize) -> usize {
+    ((index >> (depth - level - 1)) & 1) as usize
+}
+
+/// Walks down the tree from the node `seed`, at the provided level, to the
+/// leaf with the provided index.
+fn walk(mut seed: Seed, index: u64, depth: usize, level: usize) -> Seed {
+    for level in level..depth {
+        seed = expand(&seed)[bit(index, depth, level)];
+    }
+    seed
+}
+
+/// Checks that an index belongs to the domain of a tree of the provided depth.
+fn check_index(index: u64, depth: usize) {
+    if index >> depth != 0 {
+        panic!("The index {} is outside the domain of the tree.", index);
+    }
+}
+
+/// Expands the nodes of a level of the tree into the nodes of the next level.
+fn expand_level(nodes: Vec<Seed>) -> Vec<Seed> {
+    nodes.iter().flat_map(expand).collect()
+}
+
+/// Defines a GGM tree, that is, the key of the PRF.
+#[derive(Clone)]
+pub struct GgmTree {
+    root: Seed,
+    depth: usize,
+}
+
+impl GgmTree {
+    /// Creates a tree of the provided depth with the provided root seed. The
+    /// function panics if the depth is larger than [`MAX_DEPTH`].
+    pub fn new(root: Seed, depth: usize) -> Self {
+        if depth > MAX_DEPTH {
+            panic!("The depth of a GGM tree can be at most {}.", MAX_DEPTH);
+        }
+        Self { root, depth }
+    }
+
+    /// Creates a tree of the provided depth with a random root seed.
+    pub fn random(depth: usize, prg: &mut Prg) -> Self {
+        let mut root = [0; SEED_LEN];
+        prg.fill_bytes(&mut root);
+        Self::new(root, depth)
+    }
+
+    /// Returns the root seed of the tree.
+    pub fn root(&self) -> &Seed {
+        &self.root
+    }
+
+    /// Returns the depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of leaves of the tree, which is $2^d$.
+    pub fn domain_size(&self) -> u64 {
+        1 << self.depth
+    }
+
+    /// Evaluates the PRF at the provided index, that is, returns the seed of
+    /// the leaf with that index.
+    pub fn eval(&self, index: u64) -> Seed {
+        check_index(index, self.depth);
+        walk(self.root, index, self.depth, 0)
+    }
+
+    /// Evaluates the PRF at every index, expanding the tree level by level,
+    /// which takes $2^d - 1$ expansions instead of $d$ for each leaf.
+    pub fn leaves(&self) -> Vec<Seed> {
+        (0..self.depth).fold(vec![self.root], |nodes, _| expand_level(nodes))
+    }
+
+    /// Punctures the key at the provided index.
+    pub fn puncture(&self, index: u64) -> PuncturedKey {
+        check_index(index, self.depth);
+
+        let mut co_path = Vec::with_capacity(self.depth);
+        let mut seed = self.root;
+        for level in 0..self.depth {
+            let children = expand(&seed);
+            let bit = bit(index, self.depth, level);
+            co_path.push(children[1 - bit]);
+            seed = children[bit];
+        }
+
+        PuncturedKey {
+            index,
+            depth: self.depth,
+            co_path,
+        }
+    }
+}
+
+/// Defines a key of the PRF punctured at an index, which allows to evaluate
+/// the PRF at every other index.
+#[derive(Clone)]
+pub struct PuncturedKey {
+    index: u64,
+    depth: usize,
+    co_path: Vec<Seed>,
+}
+
+impl PuncturedKey {
+    /// Returns the index at which the key is punctured.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Returns the depth of the tree of the key.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the seeds of the siblings of the nodes on the path of the
+    /// punctured index, from the top of the tree.
+    pub fn co_path(&self) -> &[Seed] {
+        &self.co_path
+    }
+
+    /// Evaluates the PRF at the provided index, which returns [`None`] for the
+    /// punctured index.
+    pub fn eval(&self, index: u64) -> Option<Seed> {
+        check_index(index, self.depth);
+
+        // The path of the index leaves the path of the punctured index at the
+        // first bit in which they differ, which is a node of the co-path.
+        let level = (0..self.depth)
+            .find(|level| bit(index, self.depth, *level) != bit(self.index, self.depth, *level))?;
+        Some(walk(self.co_path[level], index, self.depth, level + 1))
+    }
+
+    /// Evaluates the PRF at every index, where the punctured index is
+    /// [`None`]. Each node of the co-path is expanded into the leaves of its
+    /// subtree.
+    pub fn leaves(&self) -> Vec<Option<Seed>> {
+        let mut leaves = vec![None; 1 << self.depth];
+        for (level, seed) in self.co_path.iter().enumerate() {
+            let height = self.depth - level - 1;
+            // The subtree of the sibling starts at the prefix of the punctured
+            // index with the bit of this level flipped.
+            let first = ((self.index >> height) ^ 1) << height;
+            let subtree = (0..height).fold(vec![*seed], |nodes, _| expand_level(nodes));
+            for (offset, leaf) in subtree.into_iter().enumerate() {
+                leaves[first as usize + offset] = Some(leaf);
+            }
+        }
+        leaves
+    }
+}
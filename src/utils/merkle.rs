@@ -0,0 +1,189 @@
+//! Implements Merkle trees, which commit to a vector of values with a single
+//! hash.
+//!
+//! The leaves of the tree are the hashes of the values, and each inner node is
+//! the hash of its two children, up to the root, which is the commitment to the
+//! whole vector. A value can then be opened alone, together with a proof made
+//! of the siblings of the nodes on its path, which has a size logarithmic in
+//! the length of the vector. The leaves and the inner nodes are hashed with
+//! different prefixes, so that a leaf cannot be passed as an inner node. When
+//! a level has an odd number of nodes, the last one is moved up unchanged. The
+//! hash is SHA-256, so this module is only available with the `sha2` feature.
+//!
+//! # Example
+//!
+//! A party commits to its shares of a batch of candidate triples before the
+//! cut-and-choose check chooses which of them are opened, so that it cannot
+//! change them after seeing the choice. Then, it opens the chosen ones with a
+//! proof for each of them.
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::utils::merkle::{self, MerkleTree};
+//! use smol_mpc::utils::prg::Prg;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let triples: Vec<Vec<u8>> = (0..8)
+//!     .map(|_| {
+//!         let shares = [Fp::random(&mut prg), Fp::random(&mut prg), Fp::random(&mut prg)];
+//!         merkle::encode_field_elements(&shares)
+//!     })
+//!     .collect();
+//!
+//! // The party publishes the root before the check.
+//! let tree = MerkleTree::new(&triples);
+//! let root = tree.root();
+//!
+//! // The other parties check the opened triples against the root.
+//! for index in [1, 4, 6] {
+//!     let proof = tree.prove(index);
+//!     assert!(proof.verify(&root, &triples[index]));
+//!     assert!(!proof.verify(&root, &triples[0]));
+//! }
+//! ```
+
+use crate::math::mersenne::MersenneField;
+use sha2::{Digest, Sha256};
+
+/// Defines the hash of a node of the tree.
+pub type Hash = [u8; 32];
+
+/// Prefix of the hashes of the leaves.
+const LEAF: u8 = 0;
+
+/// Prefix of the hashes of the inner nodes.
+const NODE: u8 = 1;
+
+/// Computes the hash of a leaf.
+fn hash_leaf(value: &[u8]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF]);
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+/// Computes the hash of an inner node.
+fn hash_node(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Encodes a list of field elements as a value of a leaf, concatenating their
+/// values in eight bytes each.
+pub fn encode_field_elements<T: MersenneField>(elements: &[T]) -> Vec<u8> {
+    elements
+        .iter()
+        .flat_map(|element| element.value().to_le_bytes())
+        .collect()
+}
+
+/// Defines a Merkle tree.
+#[derive(Clone)]
+pub struct MerkleTree {
+    // The levels of the tree, from the hashes of the leaves to the root.
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree over the provided values. The function panics if there
+    /// are no values.
+    pub fn new<V: AsRef<[u8]>>(values: &[V]) -> Self {
+        if values.is_empty() {
+            panic!("A Merkle tree needs at least one leaf.");
+        }
+
+        let mut levels = vec![values
+            .iter()
+            .map(|value| hash_leaf(value.as_ref()))
+            .collect::<Vec<Hash>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    /// Builds the tree over a list of field elements, with one element in each
+    /// leaf.
+    pub fn from_field_elements<T: MersenneField>(elements: &[T]) -> Self {
+        let values: Vec<Vec<u8>> = elements
+            .iter()
+            .map(|element| encode_field_elements(std::slice::from_ref(element)))
+            .collect();
+        Self::new(&values)
+    }
+
+    /// Returns the root of the tree, which is the commitment to the values.
+    pub fn root(&self) -> Hash {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// Returns the number of leaves of the tree.
+    pub fn n_leaves(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Generates the proof that the value with the provided index is a leaf of
+    /// the tree. The function panics if there is no leaf with that index.
+    pub fn prove(&self, index: usize) -> MerkleProof {
+        if index >= self.n_leaves() {
+            panic!("The tree does not have a leaf with index {}.", index);
+        }
+
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| level.get((index >> height) ^ 1).copied())
+            .collect();
+        MerkleProof { index, siblings }
+    }
+}
+
+/// Defines the proof that a value is a leaf of a Merkle tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// Index of the leaf.
+    pub index: usize,
+
+    /// Siblings of the nodes on the path from the leaf to the root, where a
+    /// node without sibling is moved up unchanged.
+    pub siblings: Vec<Option<Hash>>,
+}
+
+impl MerkleProof {
+    /// Checks that the provided value is the leaf with the index of the proof
+    /// in the tree with the provided root.
+    pub fn verify(&self, root: &Hash, value: &[u8]) -> bool {
+        let mut node = hash_leaf(value);
+        for (height, sibling) in self.siblings.iter().enumerate() {
+            if let Some(sibling) = sibling {
+                node = if (self.index >> height) & 1 == 0 {
+                    hash_node(&node, sibling)
+                } else {
+                    hash_node(sibling, &node)
+                };
+            }
+        }
+        node == *root
+    }
+
+    /// Checks that the provided field element is the leaf with the index of
+    /// the proof in the tree with the provided root.
+    pub fn verify_field_element<T: MersenneField>(&self, root: &Hash, element: &T) -> bool {
+        self.verify(root, &encode_field_elements(std::slice::from_ref(element)))
+    }
+}
@@ -2,4 +2,15 @@
 //! 
 //! In this module you can find utilities like pseudo-random generator which is
 //! needed to perform some MPC protocols.
+#[cfg(feature = "sha2")]
+pub mod commit;
+pub mod elgamal;
+pub mod ggm;
+#[cfg(feature = "sha2")]
+pub mod merkle;
+pub mod ot;
+pub mod paillier;
+pub mod pedersen;
 pub mod prg;
+#[cfg(feature = "sha2")]
+pub mod ro;
@@ -0,0 +1,538 @@
+//! Implements oblivious transfer (OT).
+//!
+//! In a 1-out-of-2 oblivious transfer, a sender holds two messages $m_0$ and
+//! $m_1$, and a receiver holds a choice bit $c$. At the end of the protocol,
+//! the receiver learns $m_c$ and nothing about $m_{1 - c}$, while the sender
+//! learns nothing about $c$.
+//!
+//! The module offers two flavors of OT. The simulated OT is an ideal
+//! functionality: a trusted party, represented by an [`OtChannel`], receives
+//! the inputs of both parties and hands the chosen message to the receiver.
+//! It only provides the base OTs of the OT extension, so the OTs between
+//! virtual machines (see [`ot_protocol`]) send their messages through the
+//! simulated network.
+//! With the `sha2` feature, the module also implements the OT of Chou and
+//! Orlandi based on the Diffie-Hellman key exchange over a
+//! [`Group`](crate::math::group::Group). The sender publishes $A = g^a$, and
+//! the receiver answers with $B = g^b$ if $c = 0$ or $B = A g^b$ if $c = 1$.
+//! The sender derives the keys $k_0 = H(B^a)$ and $k_1 = H((B / A)^a)$ and
+//! encrypts each message with its key, while the receiver can only compute
+//! $k_c = H(A^b)$.
+//!
+//! Base OTs are expensive, so large batches of OTs are obtained with OT
+//! extension (see [`iknp_extension`]), which only needs a fixed number of base
+//! OTs and cheap symmetric operations. Many protocols do not need to choose
+//! the messages, so the extension also provides random OTs (see
+//! [`random_ot_extension`]) and correlated OTs (see
+//! [`correlated_ot_extension`]), which are cheaper.
+
+use crate::math::matrix::Matrix;
+use crate::math::mersenne::MersenneField;
+use crate::mpc::{bits_to_bytes, bytes_to_bits};
+use crate::network::{Payload, Simulator};
+use crate::utils::prg::Prg;
+use crate::vm::VirtualMachine;
+
+#[cfg(feature = "sha2")]
+use crate::math::group::{Group, GroupElement};
+
+/// Number of base OTs of the IKNP OT extension, which is also its
+/// computational security parameter $\kappa$.
+pub const IKNP_BASE_OTS: usize = 128;
+
+/// Defines a pair of messages of the sender of an OT.
+pub type MessagePair = (Vec<u8>, Vec<u8>);
+
+/// Simulates an ideal 1-out-of-2 oblivious transfer and returns the message
+/// learned by the receiver.
+pub fn ideal_ot<M: Clone>(m0: &M, m1: &M, choice: bool) -> M {
+    if choice {
+        m1.clone()
+    } else {
+        m0.clone()
+    }
+}
+
+/// Defines a simulated channel that implements the ideal OT functionality.
+///
+/// The sender calls [`OtChannel::send`] with its two messages, and then the
+/// receiver calls [`OtChannel::receive`] with its choice bit.
+pub struct OtChannel<M: Clone> {
+    messages: Option<(M, M)>,
+}
+
+impl<M: Clone> OtChannel<M> {
+    /// Creates a channel without messages.
+    pub fn new() -> Self {
+        Self { messages: None }
+    }
+
+    /// Inputs the messages of the sender.
+    pub fn send(&mut self, m0: M, m1: M) {
+        if self.messages.is_some() {
+            panic!("The sender has already sent the messages of this OT.");
+        }
+        self.messages = Some((m0, m1));
+    }
+
+    /// Returns the message chosen by the receiver, which consumes the messages
+    /// of the channel.
+    pub fn receive(&mut self, choice: bool) -> M {
+        let (m0, m1) = self.messages.take().unwrap_or_else(|| {
+            panic!("The sender has not sent the messages of this OT.");
+        });
+        ideal_ot(&m0, &m1, choice)
+    }
+}
+
+impl<M: Clone> Default for OtChannel<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Executes an OT between two virtual machines through the simulated network.
+///
+/// The sender holds the messages in its private memory under the IDs
+/// `ids_messages`, and the receiver holds its choice, which must be zero or
+/// one, in its private memory under the ID `id_choice`. The OT is run with the
+/// IKNP OT extension (see [`iknp_extension`]), so its messages appear in the
+/// transcript and the communication statistics of the network. At the end, the
+/// receiver stores the chosen message in its private memory under the ID
+/// `id_result`.
+pub fn ot_protocol<T>(
+    sender: &mut VirtualMachine<T>,
+    receiver: &mut VirtualMachine<T>,
+    ids_messages: (&str, &str),
+    id_choice: &str,
+    id_result: &str,
+) where
+    T: MersenneField,
+{
+    let messages = vec![(
+        sender
+            .get_priv_value(ids_messages.0)
+            .value()
+            .to_le_bytes()
+            .to_vec(),
+        sender
+            .get_priv_value(ids_messages.1)
+            .value()
+            .to_le_bytes()
+            .to_vec(),
+    )];
+
+    let choice = match receiver.get_priv_value(id_choice).value() {
+        0 => false,
+        1 => true,
+        _ => panic!("The choice of an OT must be a bit."),
+    };
+    let message = iknp_extension(sender, receiver, &messages, &[choice]).remove(0);
+    let value = u64::from_le_bytes(message.try_into().unwrap());
+    receiver.insert_priv_value(id_result, T::new(value));
+}
+
+/// Executes the IKNP OT extension for a batch of chosen-message OTs between
+/// two virtual machines.
+///
+/// The pair `messages[j]` belongs to the sender and the bit `choices[j]` to the
+/// receiver. Instead of running one base OT for each pair, the parties run only
+/// [`IKNP_BASE_OTS`] base OTs with reversed roles, using the simulated
+/// [`OtChannel`]: the sender chooses a random string $s$ of $\kappa$ bits, and
+/// obtains one of the seeds $(k_i^0, k_i^1)$ of the receiver for each bit
+/// $s_i$. The receiver expands the seeds with the PRG into the columns
+/// $t_i = G(k_i^0)$ of a matrix $T$, and sends $u_i = t_i \oplus G(k_i^1)
+/// \oplus r$, where $r$ is the vector of choices. The sender computes the
+/// columns $q_i = G(k_i^{s_i}) \oplus s_i u_i = t_i \oplus s_i r$ of a matrix
+/// $Q$. After transposing both matrices, the rows satisfy $q_j = t_j \oplus r_j
+/// s$, so the sender can mask the messages with the hashes of $q_j$ and $q_j
+/// \oplus s$, while the receiver only knows the hash of $t_j$, which matches
+/// the mask of its chosen message.
+///
+/// Each party draws its randomness from its own PRG, and the columns $u_i$ and
+/// the masked messages are sent through the simulated network with the tag
+/// `"iknp"`. The two messages of each pair must have the same length. The
+/// function returns the messages learned by the receiver.
+pub fn iknp_extension<T: MersenneField>(
+    sender: &mut VirtualMachine<T>,
+    receiver: &mut VirtualMachine<T>,
+    messages: &[MessagePair],
+    choices: &[bool],
+) -> Vec<Vec<u8>> {
+    if messages.len() != choices.len() {
+        panic!("The number of messages and choices must be the same.");
+    }
+    if messages.iter().any(|(m0, m1)| m0.len() != m1.len()) {
+        panic!("The messages of an OT must have the same length.");
+    }
+
+    let IknpMatrices { q, s, t } = iknp_matrices(sender, receiver, choices);
+    let parties = [sender, receiver];
+    let network = Simulator::new(&parties).with_tag("iknp");
+    for (j, (m0, m1)) in messages.iter().enumerate() {
+        let y0 = xor_with_row(q.row(j), j, m0);
+        let y1 = xor_with_row(&xor_bits(q.row(j), &s), j, m1);
+        network.send(0, 1, Payload::Bits(bytes_to_bits(&[y0, y1].concat())));
+    }
+    network.deliver();
+
+    choices
+        .iter()
+        .enumerate()
+        .map(|(j, choice)| {
+            let masked = bits_to_bytes(&network.recv_from(1, 0).payload.bits());
+            let (y0, y1) = masked.split_at(masked.len() / 2);
+            let y = if *choice { y1 } else { y0 };
+            xor_with_row(t.row(j), j, y)
+        })
+        .collect()
+}
+
+/// Executes a batch of random OTs between two virtual machines using the IKNP
+/// OT extension.
+///
+/// In a random OT, the messages are not chosen by the sender but are random
+/// strings of `len` bytes output by the protocol. The sender obtains the pair
+/// $(H(q_j), H(q_j \oplus s))$ and the receiver obtains $H(t_j)$, which is the
+/// message of the pair selected by its choice, so no message needs to be sent
+/// after the columns $u_i$ (see [`iknp_extension`]). The function returns the
+/// pairs of the sender and the messages of the receiver.
+pub fn random_ot_extension<T: MersenneField>(
+    sender: &mut VirtualMachine<T>,
+    receiver: &mut VirtualMachine<T>,
+    choices: &[bool],
+    len: usize,
+) -> (Vec<MessagePair>, Vec<Vec<u8>>) {
+    let IknpMatrices { q, s, t } = iknp_matrices(sender, receiver, choices);
+    let zeros = vec![0; len];
+
+    let pairs = (0..choices.len())
+        .map(|j| {
+            let x0 = xor_with_row(q.row(j), j, &zeros);
+            let x1 = xor_with_row(&xor_bits(q.row(j), &s), j, &zeros);
+            (x0, x1)
+        })
+        .collect();
+    let chosen = (0..choices.len())
+        .map(|j| xor_with_row(t.row(j), j, &zeros))
+        .collect();
+    (pairs, chosen)
+}
+
+/// Executes a batch of correlated OTs over a field between two virtual machines
+/// using the IKNP OT extension.
+///
+/// In a correlated OT, the sender provides a correlation $\Delta_j$ instead of
+/// two messages. The sender obtains a random field element $x_j$ and the
+/// receiver obtains $x_j + c_j \Delta_j$, where $c_j$ is its choice. The sender
+/// sets $x_j = H(q_j)$ and sends the single correction $d_j = x_j + \Delta_j -
+/// H(q_j \oplus s)$ through the simulated network, and the receiver adds it to
+/// $H(t_j)$ when its choice is one (see [`iknp_extension`]). The function
+/// returns the elements of the sender and those of the receiver.
+pub fn correlated_ot_extension<T: MersenneField>(
+    sender: &mut VirtualMachine<T>,
+    receiver: &mut VirtualMachine<T>,
+    deltas: &[T],
+    choices: &[bool],
+) -> (Vec<T>, Vec<T>) {
+    if deltas.len() != choices.len() {
+        panic!("The number of correlations and choices must be the same.");
+    }
+
+    let IknpMatrices { q, s, t } = iknp_matrices(sender, receiver, choices);
+    let sender_values: Vec<T> = (0..choices.len()).map(|j| hash_row(q.row(j), j)).collect();
+    let corrections: Vec<T> = sender_values
+        .iter()
+        .zip(deltas)
+        .enumerate()
+        .map(|(j, (x, delta))| x.add(delta).subtract(&hash_row(&xor_bits(q.row(j), &s), j)))
+        .collect();
+
+    let parties = [sender, receiver];
+    let network = Simulator::new(&parties).with_tag("iknp");
+    network.send(0, 1, Payload::Elements(corrections));
+    network.deliver();
+    let corrections = network.recv_from(1, 0).payload.elements();
+
+    let receiver_values = corrections
+        .iter()
+        .zip(choices)
+        .enumerate()
+        .map(|(j, (correction, choice))| {
+            let h = hash_row::<T>(t.row(j), j);
+            if *choice {
+                h.add(correction)
+            } else {
+                h
+            }
+        })
+        .collect();
+    (sender_values, receiver_values)
+}
+
+/// Matrices obtained by the parties at the end of the IKNP OT extension.
+struct IknpMatrices {
+    /// Matrix of the sender, whose rows are $q_j = t_j \oplus r_j s$.
+    q: Matrix<bool>,
+
+    /// Secret choices of the sender in the base OTs.
+    s: Vec<bool>,
+
+    /// Matrix of the receiver.
+    t: Matrix<bool>,
+}
+
+/// State of the sender of the IKNP OT extension after the base OTs.
+struct IknpSender {
+    /// Choices of the sender in the base OTs.
+    s: Vec<bool>,
+
+    /// Seeds $k_i^{s_i}$ obtained in the base OTs.
+    seeds: Vec<Vec<u8>>,
+}
+
+impl IknpSender {
+    /// Computes the matrix $Q$ from the columns $u_i$ sent by the receiver,
+    /// concatenated in a single vector of bits.
+    fn extend(&self, u: &[bool], m: usize) -> Matrix<bool> {
+        let q_columns: Vec<Vec<bool>> = self
+            .seeds
+            .iter()
+            .zip(u.chunks(m))
+            .zip(&self.s)
+            .map(|((seed, u_i), s_i)| {
+                let q_i = expand_seed(seed, m);
+                if *s_i {
+                    xor_bits(&q_i, u_i)
+                } else {
+                    q_i
+                }
+            })
+            .collect();
+        Matrix::from_rows(q_columns).transpose()
+    }
+}
+
+/// State of the receiver of the IKNP OT extension after the base OTs.
+struct IknpReceiver {
+    /// Pairs of seeds $(k_i^0, k_i^1)$ sent in the base OTs.
+    seeds: Vec<MessagePair>,
+}
+
+impl IknpReceiver {
+    /// Computes the matrix $T$ and the columns $u_i$ for the provided choices.
+    /// The columns are concatenated in a single vector of bits, which is the
+    /// message sent to the sender.
+    fn extend(&self, choices: &[bool]) -> (Matrix<bool>, Vec<bool>) {
+        let m = choices.len();
+        let t_columns: Vec<Vec<bool>> = self
+            .seeds
+            .iter()
+            .map(|(k0, _)| expand_seed(k0, m))
+            .collect();
+        let u: Vec<bool> = self
+            .seeds
+            .iter()
+            .zip(&t_columns)
+            .flat_map(|((_, k1), t_i)| xor_bits(&xor_bits(t_i, &expand_seed(k1, m)), choices))
+            .collect();
+        (Matrix::from_rows(t_columns).transpose(), u)
+    }
+}
+
+/// Runs the base OTs of IKNP, in which the receiver of the extension acts as
+/// the sender. Each party samples its values with the provided PRG.
+fn iknp_base_ots(sender_prg: &mut Prg, receiver_prg: &mut Prg) -> (IknpSender, IknpReceiver) {
+    let s: Vec<bool> = random_bits(IKNP_BASE_OTS, sender_prg);
+    let seeds: Vec<MessagePair> = (0..IKNP_BASE_OTS)
+        .map(|_| {
+            (
+                receiver_prg.next(IKNP_BASE_OTS / 8),
+                receiver_prg.next(IKNP_BASE_OTS / 8),
+            )
+        })
+        .collect();
+    let sender_seeds: Vec<Vec<u8>> = seeds
+        .iter()
+        .zip(&s)
+        .map(|((k0, k1), s_i)| {
+            let mut channel = OtChannel::new();
+            channel.send(k0.clone(), k1.clone());
+            channel.receive(*s_i)
+        })
+        .collect();
+
+    (
+        IknpSender {
+            s,
+            seeds: sender_seeds,
+        },
+        IknpReceiver { seeds },
+    )
+}
+
+/// Runs the base OTs and the extension phase of IKNP for the provided choices
+/// of the receiver, which sends the columns $u_i$ to the sender through the
+/// simulated network (see [`iknp_extension`]).
+fn iknp_matrices<T: MersenneField>(
+    sender: &mut VirtualMachine<T>,
+    receiver: &mut VirtualMachine<T>,
+    choices: &[bool],
+) -> IknpMatrices {
+    let (sender_state, receiver_state) = iknp_base_ots(&mut sender.prg, &mut receiver.prg);
+    let (t, u) = receiver_state.extend(choices);
+
+    let parties = [sender, receiver];
+    let network = Simulator::new(&parties).with_tag("iknp");
+    network.send(1, 0, Payload::Bits(u));
+    network.deliver();
+    let q = sender_state.extend(&network.recv_from(0, 1).payload.bits(), choices.len());
+
+    IknpMatrices {
+        q,
+        s: sender_state.s,
+        t,
+    }
+}
+
+/// Samples a vector of random bits.
+fn random_bits(n: usize, prg: &mut Prg) -> Vec<bool> {
+    prg.next(n).iter().map(|byte| byte & 1 == 1).collect()
+}
+
+/// Expands a seed into a vector of `n` pseudo-random bits.
+fn expand_seed(seed: &[u8], n: usize) -> Vec<bool> {
+    let bytes = Prg::new(Some(seed.to_vec())).next(n.div_ceil(8));
+    (0..n).map(|i| (bytes[i / 8] >> (i % 8)) & 1 == 1).collect()
+}
+
+/// Computes the XOR of two vectors of bits.
+fn xor_bits(a: &[bool], b: &[bool]) -> Vec<bool> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+/// Returns a PRG seeded with a row of bits and the index of the OT, which
+/// plays the role of a random oracle.
+fn row_oracle(row: &[bool], index: usize) -> Prg {
+    let mut seed: Vec<u8> = row
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(0, |acc, bit| (acc << 1) | *bit as u8)
+        })
+        .collect();
+    seed.extend((index as u64).to_le_bytes());
+    Prg::new(Some(seed))
+}
+
+/// Masks a message with the output of the random oracle on a row.
+fn xor_with_row(row: &[bool], index: usize, message: &[u8]) -> Vec<u8> {
+    let pad = row_oracle(row, index).next(message.len());
+    message.iter().zip(pad).map(|(m, p)| m ^ p).collect()
+}
+
+/// Hashes a row into a field element with the random oracle.
+fn hash_row<T: MersenneField>(row: &[bool], index: usize) -> T {
+    T::random(&mut row_oracle(row, index))
+}
+
+/// Defines the sender of the Diffie-Hellman based OT.
+#[cfg(feature = "sha2")]
+pub struct DhOtSender<'g, T: MersenneField> {
+    group: &'g Group<T>,
+    secret: T,
+    public: GroupElement,
+}
+
+#[cfg(feature = "sha2")]
+impl<'g, T: MersenneField> DhOtSender<'g, T> {
+    /// Samples the secret $a$ of the sender and computes its first message
+    /// $A = g^a$.
+    pub fn new(group: &'g Group<T>, prg: &mut Prg) -> Self {
+        let secret = T::random(prg);
+        let public = group.exp(group.generator(), &secret);
+        Self {
+            group,
+            secret,
+            public,
+        }
+    }
+
+    /// Returns the first message $A$ of the sender.
+    pub fn first_message(&self) -> &GroupElement {
+        &self.public
+    }
+
+    /// Encrypts the two messages, which must have the same length, with the
+    /// keys derived from the message $B$ of the receiver.
+    pub fn send(&self, receiver_message: &GroupElement, m0: &[u8], m1: &[u8]) -> MessagePair {
+        if m0.len() != m1.len() {
+            panic!("The messages of an OT must have the same length.");
+        }
+
+        let b_over_a = self
+            .group
+            .mul(receiver_message, &self.group.inverse(&self.public));
+        let k0 = self.group.exp(receiver_message, &self.secret);
+        let k1 = self.group.exp(&b_over_a, &self.secret);
+        (xor_with_key(&k0, m0), xor_with_key(&k1, m1))
+    }
+}
+
+/// Defines the receiver of the Diffie-Hellman based OT.
+#[cfg(feature = "sha2")]
+pub struct DhOtReceiver<T: MersenneField> {
+    choice: bool,
+    key: GroupElement,
+    _field: std::marker::PhantomData<T>,
+}
+
+#[cfg(feature = "sha2")]
+impl<T: MersenneField> DhOtReceiver<T> {
+    /// Samples the secret $b$ of the receiver given the first message $A$ of
+    /// the sender, and returns the receiver together with its message $B$.
+    pub fn new(
+        group: &Group<T>,
+        sender_message: &GroupElement,
+        choice: bool,
+        prg: &mut Prg,
+    ) -> (Self, GroupElement) {
+        let secret = T::random(prg);
+        let g_b = group.exp(group.generator(), &secret);
+        let message = if choice {
+            group.mul(sender_message, &g_b)
+        } else {
+            g_b
+        };
+
+        let receiver = Self {
+            choice,
+            key: group.exp(sender_message, &secret),
+            _field: std::marker::PhantomData,
+        };
+        (receiver, message)
+    }
+
+    /// Decrypts the chosen message from the ciphertexts of the sender.
+    pub fn receive(&self, ciphertexts: &MessagePair) -> Vec<u8> {
+        let ciphertext = if self.choice {
+            &ciphertexts.1
+        } else {
+            &ciphertexts.0
+        };
+        xor_with_key(&self.key, ciphertext)
+    }
+}
+
+/// Encrypts or decrypts a message by XORing it with a pad derived from the
+/// hash of a group element.
+#[cfg(feature = "sha2")]
+fn xor_with_key(key: &GroupElement, message: &[u8]) -> Vec<u8> {
+    use sha2::{Digest, Sha256};
+
+    let seed = Sha256::digest(key.0.to_bytes_le()).to_vec();
+    let pad = Prg::new(Some(seed)).next(message.len());
+    message.iter().zip(pad).map(|(m, p)| m ^ p).collect()
+}
@@ -0,0 +1,241 @@
+//! Implements a toy version of the Paillier cryptosystem.
+//!
+//! Paillier is an additively homomorphic public-key encryption scheme. The
+//! public key is a modulus $n = pq$ for two primes $p$ and $q$, and a message
+//! $m \in \mathbb{Z}_n$ is encrypted as
+//!    $$c = (1 + n)^m r^n \bmod n^2$$
+//! for a random $r \in \mathbb{Z}_n^*$. The product of two ciphertexts is an
+//! encryption of the sum of the messages, and raising a ciphertext to a public
+//! constant $k$ gives an encryption of $k m$. To decrypt, the owner of the
+//! secret key $\lambda = \textsf{lcm}(p - 1, q - 1)$ computes
+//!    $$m = L(c^\lambda \bmod n^2) \cdot \lambda^{-1} \bmod n,$$
+//! where $L(x) = (x - 1) / n$.
+//!
+//! The primes are generated with the Miller-Rabin test using the library PRG,
+//! and the key sizes used in this library are far too small to be secure.
+//!
+//! # Example
+//!
+//! In an outsourced addition, several clients want an analyst to learn the sum
+//! of their inputs, and nothing else, with the help of untrusted servers. With
+//! Paillier, a single server is enough: the clients encrypt their inputs under
+//! the key of the analyst, the server multiplies the ciphertexts without
+//! learning anything, and the analyst decrypts the sum. With secret sharing,
+//! the clients share their inputs among several servers, which add their
+//! shares locally and send them to the analyst. No server needs to do any
+//! public-key operation, but the inputs stay private only as long as the
+//! servers do not collude.
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::mpc;
+//! use smol_mpc::utils::paillier::{self, BigUint};
+//! use smol_mpc::utils::prg::Prg;
+//! use smol_mpc::vm::VirtualMachine;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut prg = Prg::new(None);
+//! let inputs = [12u32, 30, 7];
+//!
+//! // Homomorphic encryption with a single server.
+//! let (public_key, secret_key) = paillier::keygen(128, &mut prg);
+//! let ciphertexts: Vec<_> = inputs
+//!     .iter()
+//!     .map(|input| public_key.encrypt(&BigUint::from(*input), &mut prg))
+//!     .collect();
+//! let encrypted_sum = public_key.sum(&ciphertexts);
+//! assert_eq!(secret_key.decrypt(&encrypted_sum), BigUint::from(49u32));
+//!
+//! // Secret sharing with two servers.
+//! let mut server_1: VirtualMachine<Fp> = VirtualMachine::new("server_1");
+//! let mut server_2: VirtualMachine<Fp> = VirtualMachine::new("server_2");
+//! let mut servers = vec![&mut server_1, &mut server_2];
+//! for (id, input) in ["x_0", "x_1", "x_2"].iter().zip(inputs) {
+//!     mpc::simulate_random_dist(id, &mut servers, &Fp::new(input as u64), &mut prg);
+//! }
+//! mpc::add_protocol(&mut servers, "x_0", "x_1", "partial_sum");
+//! mpc::add_protocol(&mut servers, "partial_sum", "x_2", "sum");
+//! assert_eq!(mpc::reconstruct_share(&servers, "sum").value(), 49);
+//! ```
+
+pub use num_bigint::BigUint;
+
+use crate::utils::prg::Prg;
+
+/// Number of rounds of the Miller-Rabin primality test.
+const MILLER_RABIN_ROUNDS: usize = 20;
+
+/// Defines a Paillier public key.
+#[derive(Clone)]
+pub struct PublicKey {
+    n: BigUint,
+    n_square: BigUint,
+}
+
+/// Defines a Paillier secret key.
+pub struct SecretKey {
+    lambda: BigUint,
+    mu: BigUint,
+    public: PublicKey,
+}
+
+/// Defines a Paillier ciphertext.
+#[derive(Clone)]
+pub struct Ciphertext(BigUint);
+
+/// Generates a key pair whose modulus has `bits` bits.
+pub fn keygen(bits: u64, prg: &mut Prg) -> (PublicKey, SecretKey) {
+    loop {
+        let p = random_prime(bits / 2, prg);
+        let q = random_prime(bits - bits / 2, prg);
+        if p == q {
+            continue;
+        }
+
+        let one = BigUint::from(1u32);
+        let p_minus = &p - &one;
+        let q_minus = &q - &one;
+        let lambda = &p_minus * &q_minus / gcd(&p_minus, &q_minus);
+
+        let n = p * q;
+        let Some(mu) = lambda.modinv(&n) else {
+            continue;
+        };
+
+        let public = PublicKey {
+            n_square: &n * &n,
+            n,
+        };
+        let secret = SecretKey {
+            lambda,
+            mu,
+            public: public.clone(),
+        };
+        return (public, secret);
+    }
+}
+
+impl PublicKey {
+    /// Returns the modulus $n$ of the key, which bounds the messages.
+    pub fn modulus(&self) -> &BigUint {
+        &self.n
+    }
+
+    /// Encrypts a message, which is reduced modulo $n$.
+    pub fn encrypt(&self, message: &BigUint, prg: &mut Prg) -> Ciphertext {
+        let r = loop {
+            let r = random_below(&self.n, prg);
+            if r != BigUint::from(0u32) && gcd(&r, &self.n) == BigUint::from(1u32) {
+                break r;
+            }
+        };
+
+        // (1 + n)^m = 1 + m n modulo n^2.
+        let g_m = (BigUint::from(1u32) + (message % &self.n) * &self.n) % &self.n_square;
+        Ciphertext(g_m * r.modpow(&self.n, &self.n_square) % &self.n_square)
+    }
+
+    /// Computes an encryption of the sum of the messages of two ciphertexts.
+    pub fn add(&self, a: &Ciphertext, b: &Ciphertext) -> Ciphertext {
+        Ciphertext(&a.0 * &b.0 % &self.n_square)
+    }
+
+    /// Computes an encryption of the sum of the messages of several
+    /// ciphertexts. The sum of no ciphertexts is the encryption of zero with
+    /// $r = 1$, which is not randomized.
+    pub fn sum(&self, ciphertexts: &[Ciphertext]) -> Ciphertext {
+        ciphertexts
+            .iter()
+            .fold(Ciphertext(BigUint::from(1u32)), |sum, c| self.add(&sum, c))
+    }
+
+    /// Computes an encryption of the product of the message of a ciphertext
+    /// with a public constant.
+    pub fn mul_const(&self, a: &Ciphertext, k: &BigUint) -> Ciphertext {
+        Ciphertext(a.0.modpow(k, &self.n_square))
+    }
+}
+
+impl SecretKey {
+    /// Returns the public key associated to this secret key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public
+    }
+
+    /// Decrypts a ciphertext.
+    pub fn decrypt(&self, c: &Ciphertext) -> BigUint {
+        let n = &self.public.n;
+        let x = c.0.modpow(&self.lambda, &self.public.n_square);
+        let l = (x - BigUint::from(1u32)) / n;
+        l * &self.mu % n
+    }
+}
+
+/// Computes the greatest common divisor of two integers.
+fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
+    let mut a = a.clone();
+    let mut b = b.clone();
+    while b != BigUint::from(0u32) {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Samples an integer with exactly `bits` bits.
+pub(crate) fn random_bits(bits: u64, prg: &mut Prg) -> BigUint {
+    let bytes = bits.div_ceil(8) as usize;
+    let value = BigUint::from_bytes_le(&prg.next(bytes)) >> (bytes as u64 * 8 - bits);
+    value | (BigUint::from(1u32) << (bits - 1))
+}
+
+/// Samples an integer in the range $[0, \textsf{bound})$.
+pub(crate) fn random_below(bound: &BigUint, prg: &mut Prg) -> BigUint {
+    let bytes = bound.bits().div_ceil(8) as usize + 8;
+    BigUint::from_bytes_le(&prg.next(bytes)) % bound
+}
+
+/// Samples a random odd prime with exactly `bits` bits.
+fn random_prime(bits: u64, prg: &mut Prg) -> BigUint {
+    loop {
+        let candidate = random_bits(bits, prg) | BigUint::from(1u32);
+        if is_probable_prime(&candidate, prg) {
+            return candidate;
+        }
+    }
+}
+
+/// Tests if an odd integer is prime with the Miller-Rabin test.
+pub(crate) fn is_probable_prime(n: &BigUint, prg: &mut Prg) -> bool {
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    if *n < BigUint::from(4u32) {
+        return *n >= two;
+    }
+    for small in [3u32, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        if n % small == BigUint::from(0u32) {
+            return *n == BigUint::from(small);
+        }
+    }
+
+    let n_minus = n - &one;
+    let s = n_minus.trailing_zeros().unwrap_or(0);
+    let d = &n_minus >> s;
+    'witness: for _ in 0..MILLER_RABIN_ROUNDS {
+        let a = random_below(&(n - &two - &one), prg) + &two;
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
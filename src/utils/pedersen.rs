@@ -0,0 +1,64 @@
+//! Implements Pedersen commitments to field elements.
+//!
+//! Given two generators $g$ and $h$ of a [`Group`] whose discrete logarithm
+//! $\log_g h$ is unknown, the commitment to a value $v$ with randomness $r$ is
+//!    $$c = g^v h^r.$$
+//! In contrast with the [`commit`](crate::utils::commit) module, Pedersen
+//! commitments are perfectly hiding, since $c$ is uniformly distributed for a
+//! random $r$, but only computationally binding: whoever knows $\log_g h$ can
+//! open a commitment to any value. They are also additively homomorphic, as the
+//! product of the commitments to $v_1$ and $v_2$ is a commitment to $v_1 + v_2$
+//! with randomness $r_1 + r_2$.
+
+use crate::math::group::{Group, GroupElement};
+use crate::math::mersenne::MersenneField;
+use crate::utils::prg::Prg;
+
+/// Defines the public parameters of the Pedersen commitment scheme.
+#[derive(Clone)]
+pub struct PedersenParams<T: MersenneField> {
+    /// Group in which the commitments are computed.
+    pub group: Group<T>,
+
+    /// Second generator $h$, independent from the generator of the group.
+    pub h: GroupElement,
+}
+
+/// Defines a Pedersen commitment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenCommitment(pub GroupElement);
+
+impl<T: MersenneField> PedersenParams<T> {
+    /// Samples the second generator for the provided group.
+    ///
+    /// In this library, the generator is sampled by the party running the
+    /// setup, which is trusted not to learn its discrete logarithm.
+    pub fn new(group: Group<T>, prg: &mut Prg) -> Self {
+        let h = group.random_element(prg);
+        Self { group, h }
+    }
+
+    /// Commits to a value with the provided randomness.
+    pub fn commit(&self, value: &T, randomness: &T) -> PedersenCommitment {
+        let g_v = self.group.exp(self.group.generator(), value);
+        let h_r = self.group.exp(&self.h, randomness);
+        PedersenCommitment(self.group.mul(&g_v, &h_r))
+    }
+
+    /// Commits to a value with fresh randomness, which is returned together
+    /// with the commitment.
+    pub fn commit_random(&self, value: &T, prg: &mut Prg) -> (PedersenCommitment, T) {
+        let randomness = T::random(prg);
+        (self.commit(value, &randomness), randomness)
+    }
+
+    /// Checks that a commitment opens to the provided value and randomness.
+    pub fn verify(&self, commitment: &PedersenCommitment, value: &T, randomness: &T) -> bool {
+        self.commit(value, randomness) == *commitment
+    }
+
+    /// Computes a commitment to the sum of the values of two commitments.
+    pub fn add(&self, a: &PedersenCommitment, b: &PedersenCommitment) -> PedersenCommitment {
+        PedersenCommitment(self.group.mul(&a.0, &b.0))
+    }
+}
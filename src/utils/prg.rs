@@ -1,26 +1,201 @@
-//! Implementation of a PRG based on AES-CTR.
+//! Implementation of PRGs based on stream ciphers.
 //!
 //! The idea is that a pseudo-random block of bits is generated by computing
-//!    $$\textsf{Block} = \textsf{AES}(\textsf{Seed}, \textsf{CurrentCounter})$$
-//! where $\textsf{CurrentCounter} = \textsf{Nonce} \Vert \textsf{Counter}$, and the counter
-//! is increased when a new block is generated.
+//!    $$\textsf{Block} = \textsf{Cipher}(\textsf{Seed}, \textsf{CurrentCounter})$$
+//! where the counter is increased when a new block is generated. The
+//! generators implement the [`PseudoRandomGenerator`] trait, so the protocols
+//! can be run with either of them:
+//! - [`AesPrg`] uses AES-CTR, with $\textsf{CurrentCounter} = \textsf{Nonce}
+//!   \Vert \textsf{Counter}$, and it is enabled by the `aes` feature.
+//! - [`ChaChaPrg`] uses the ChaCha20 stream cipher, and it is enabled by the
+//!   `chacha20` feature.
+//!
+//! Each generator has a nonce besides its seed, and generators with the same
+//! seed and different nonces produce independent streams. A generator can be
+//! forked with a label into a child generator that is independent of the
+//! parent and of the children with other labels, which gives domain-separated
+//! randomness, for example one generator per session, out of a single seed:
+//!
+//! ```rust
+//! use smol_mpc::utils::prg::Prg;
+//!
+//! let prg = Prg::new(Some(vec![1; 32]));
+//! let mut session_prg = prg.fork("session 0");
+//! assert_eq!(session_prg.next(8), prg.fork("session 0").next(8));
+//! assert_ne!(session_prg.next(8), prg.fork("session 1").next(8));
+//! ```
+//!
+//! Parties that share a seed can synchronize the positions of their streams
+//! with `set_counter` and `skip_blocks`, and a generator can be seeded from the
+//! randomness of the operating system with `from_entropy` when the `getrandom`
+//! feature is enabled.
+//!
+//! The library uses [`Prg`], which is [`AesPrg`] if the `aes` feature is enabled,
+//! which it is by default, and [`ChaChaPrg`] otherwise.
+//!
+//! Both generators implement the [`RngCore`], [`CryptoRng`] and [`SeedableRng`]
+//! traits, so they can be used with the distributions and the other tools of
+//! the `rand` crate. In the other direction, any generator of `rand` can be
+//! used where the library expects a [`PseudoRandomGenerator`] by wrapping it in
+//! a [`RngAdapter`].
+//!
+//! ```rust
+//! use rand::seq::SliceRandom;
+//! use rand::SeedableRng;
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::utils::prg::{Prg, RngAdapter};
+//!
+//! let mut prg = Prg::from_seed([7; 32]);
+//! let mut parties = vec!["alice", "bob", "charlie"];
+//! parties.shuffle(&mut prg);
+//!
+//! let mut rng = RngAdapter::new(rand::rngs::StdRng::seed_from_u64(7));
+//! let value = Mersenne61::random(&mut rng);
+//! assert!(value.value() < Mersenne61::ORDER);
+//! ```
 //!
 //! This implementation is based on the one used in [Secure Computation Library].
 //!
 //! [Secure Computation Library]: https://github.com/anderspkd/secure-computation-library/blob/master/include/scl/util/prg.h
 
-use aes::cipher::{KeyIvInit, StreamCipher};
-use std::vec;
+#[cfg(not(any(feature = "aes", feature = "chacha20")))]
+compile_error!("At least one of the features `aes` and `chacha20` must be enabled to have a PRG.");
 
+use crate::counter;
+#[cfg(feature = "aes")]
+use aes::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use rand::RngCore;
+#[cfg(any(feature = "aes", feature = "chacha20"))]
+use rand::{CryptoRng, SeedableRng};
+
+#[cfg(feature = "aes")]
 type Aes128Ctr64LE = ctr::Ctr64LE<aes::Aes128>;
 
-/// Defines a pseudo-random number generator.
-pub struct Prg {
+/// Defines the PRG used by the library.
+#[cfg(feature = "aes")]
+pub type Prg = AesPrg;
+
+/// Defines the PRG used by the library.
+#[cfg(all(feature = "chacha20", not(feature = "aes")))]
+pub type Prg = ChaChaPrg;
+
+/// Defines the operations of a pseudo-random number generator that produces
+/// its output in blocks.
+pub trait PseudoRandomGenerator {
+    /// Returns the length in bytes of the blocks produced by the generator.
+    fn block_len(&self) -> usize;
+
+    /// Fills the provided buffer with the next random bytes of the stream.
+    fn fill_bytes(&mut self, dest: &mut [u8]);
+
+    /// Generates a stream of random bytes.
+    fn next(&mut self, n_bytes: usize) -> Vec<u8> {
+        let mut out = vec![0; n_bytes];
+        self.fill_bytes(&mut out);
+        out
+    }
+
+    /// Returns the current state of the counter, that is, the number of blocks
+    /// generated so far.
+    fn counter(&self) -> u64;
+
+    /// Resets the generator to its initial state.
+    fn reset(&mut self);
+}
+
+/// Crops or pads with zeros the provided seed to the given length. A missing
+/// seed is filled with zeros.
+fn fit_seed(seed: Option<Vec<u8>>, len: usize) -> Vec<u8> {
+    let mut seed = seed.unwrap_or_default();
+    seed.resize(len, 0);
+    seed
+}
+
+/// Nonce of the generators used to fork a generator, which is never the nonce
+/// of a generator that produces output.
+const FORK_NONCE: u64 = u64::MAX;
+
+/// Checks that a nonce chosen by the caller is not reserved.
+fn check_nonce(nonce: u64) {
+    if nonce == FORK_NONCE {
+        panic!("The nonce {} is reserved to fork generators.", nonce);
+    }
+}
+
+/// Generates a seed of the provided length from the randomness of the
+/// operating system.
+#[cfg(feature = "getrandom")]
+fn entropy_seed(len: usize) -> Vec<u8> {
+    let mut seed = vec![0; len];
+    getrandom::getrandom(&mut seed).expect("The operating system could not provide randomness.");
+    seed
+}
+
+/// Derives the seed of a forked generator from the seed of its parent and a
+/// label, walking down a tree of generators as in the construction of
+/// Goldreich, Goldwasser and Micali: `step(seed, byte)` expands the seed of a
+/// node into the seed of its child with index `byte`. The walk starts with the
+/// length of the label, so that no label leads to a node on the path of a
+/// longer one.
+fn derive_seed<F: FnMut(Vec<u8>, u8) -> Vec<u8>>(seed: &[u8], label: &str, mut step: F) -> Vec<u8> {
+    let length = (label.len() as u64).to_le_bytes();
+    length
+        .iter()
+        .chain(label.as_bytes())
+        .fold(seed.to_vec(), |seed, byte| step(seed, *byte))
+}
+
+/// Keeps the bytes of the last block generated by a PRG that were not used
+/// yet, so that a request shorter than a block does not waste the rest of it.
+#[derive(Clone)]
+struct Leftover<const LEN: usize> {
+    block: [u8; LEN],
+    start: usize,
+}
+
+impl<const LEN: usize> Leftover<LEN> {
+    fn empty() -> Self {
+        Self {
+            block: [0; LEN],
+            start: LEN,
+        }
+    }
+
+    /// Fills `dest` with the unused bytes first and then with new blocks. The
+    /// function `generate` fills a buffer whose length is a multiple of `LEN`
+    /// with new blocks.
+    fn fill<F: FnMut(&mut [u8])>(&mut self, dest: &mut [u8], mut generate: F) {
+        let n_unused = dest.len().min(LEN - self.start);
+        dest[..n_unused].copy_from_slice(&self.block[self.start..self.start + n_unused]);
+        self.start += n_unused;
+
+        let dest = &mut dest[n_unused..];
+        let n_full = dest.len() - dest.len() % LEN;
+        if n_full > 0 {
+            generate(&mut dest[..n_full]);
+        }
+
+        let rest = &mut dest[n_full..];
+        if !rest.is_empty() {
+            generate(&mut self.block);
+            rest.copy_from_slice(&self.block[..rest.len()]);
+            self.start = rest.len();
+        }
+    }
+}
+
+/// Defines a pseudo-random number generator based on AES-CTR.
+#[cfg(feature = "aes")]
+#[derive(Clone)]
+pub struct AesPrg {
     seed: Vec<u8>,
+    nonce: u64,
     counter: u64,
+    leftover: Leftover<16>,
 }
 
-impl Prg {
+#[cfg(feature = "aes")]
+impl AesPrg {
     const PRG_NONCE: u64 = 0x0123456789ABCDEF;
     const PRG_INITIAL_COUNTER: u64 = 0;
 
@@ -38,37 +213,58 @@ impl Prg {
     /// expected key, the key is padded with zeros. The key will be divided in
     /// two halves to generate the encryption key and the initialization vector
     /// for the CTR mode.
-    pub fn new(seed: Option<Vec<u8>>) -> Prg {
-        let cropped_seed = if let Some(mut value_seed) = seed {
-            if value_seed.len() > Self::KEY_LEN + Self::IV_LEN {
-                value_seed[0..Self::KEY_LEN + Self::IV_LEN].to_vec()
-            } else {
-                let original_seed_length = value_seed.len();
-                let mut appended_seed = Vec::new();
-                appended_seed.append(&mut value_seed);
-                appended_seed.append(&mut vec![
-                    0;
-                    Self::KEY_LEN + Self::IV_LEN - original_seed_length
-                ]);
-                appended_seed
-            }
-        } else {
-            vec![0; Self::KEY_LEN + Self::IV_LEN]
-        };
-
-        let counter = Self::PRG_INITIAL_COUNTER;
-
-        let mut prg = Prg {
-            seed: cropped_seed,
-            counter,
+    pub fn new(seed: Option<Vec<u8>>) -> AesPrg {
+        let mut prg = AesPrg {
+            seed: fit_seed(seed, Self::KEY_LEN + Self::IV_LEN),
+            nonce: 0,
+            counter: Self::PRG_INITIAL_COUNTER,
+            leftover: Leftover::empty(),
         };
         prg.init();
         prg
     }
 
+    /// Creates a new PRG with the provided nonce, so that generators with the
+    /// same seed and different nonces produce independent streams. The seed is
+    /// handled as in [`AesPrg::new`], and the function panics if the nonce is
+    /// `u64::MAX`, which is reserved to fork generators.
+    pub fn with_nonce(seed: Option<Vec<u8>>, nonce: u64) -> AesPrg {
+        check_nonce(nonce);
+        let mut prg = AesPrg::new(seed);
+        prg.nonce = nonce;
+        prg
+    }
+
+    /// Creates a new PRG whose seed is taken from the randomness of the
+    /// operating system.
+    #[cfg(feature = "getrandom")]
+    pub fn from_entropy() -> AesPrg {
+        AesPrg::new(Some(entropy_seed(Self::KEY_LEN + Self::IV_LEN)))
+    }
+
+    /// Returns the nonce of the PRG.
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Sets the counter of the PRG, so that the next bytes start at the
+    /// beginning of the block with that index. The unused bytes of the last
+    /// block generated are discarded.
+    pub fn set_counter(&mut self, counter: u64) {
+        self.counter = counter;
+        self.leftover = Leftover::empty();
+    }
+
+    /// Skips the provided number of blocks of the stream, discarding the unused
+    /// bytes of the last block generated.
+    pub fn skip_blocks(&mut self, n_blocks: u64) {
+        self.set_counter(self.counter + n_blocks);
+    }
+
     /// Initializes the PRG.
     pub fn init(&mut self) {
         self.counter = Self::PRG_INITIAL_COUNTER;
+        self.leftover = Leftover::empty();
     }
 
     /// Resets the PRG.
@@ -81,36 +277,351 @@ impl Prg {
         self.counter
     }
 
-    /// Generates a stream of random bytes.
+    /// Fills the provided buffer with the next random bytes of the stream.
     ///
     /// The method divides the seed into two halves: the first part will be used
     /// as the key for the AES encryption and the second part will be used as
-    /// the initialization vector for the encryption.
+    /// the initialization vector for the encryption, whose upper half is
+    /// combined with the nonce. The bytes of the last block that are not used
+    /// are kept for the next call.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        counter::record(|counts| counts.prg_bytes += dest.len() as u64);
+
+        let (seed, nonce, counter) = (&self.seed, self.nonce, &mut self.counter);
+        self.leftover.fill(dest, |blocks| {
+            let key = &seed[0..Self::KEY_LEN];
+            let mut iv = [0; Self::IV_LEN];
+            iv.copy_from_slice(&seed[Self::KEY_LEN..]);
+            // The counter of the CTR mode is added to the lower half of the IV.
+            for (iv_byte, nonce_byte) in iv[8..].iter_mut().zip(nonce.to_le_bytes()) {
+                *iv_byte ^= nonce_byte;
+            }
+
+            let mut cipher = Aes128Ctr64LE::new(key.into(), (&iv).into());
+            cipher.seek(*counter * Self::BLOCK_LEN as u64);
+
+            for block in blocks.chunks_exact_mut(Self::BLOCK_LEN) {
+                block[..8].copy_from_slice(&Self::PRG_NONCE.to_ne_bytes());
+                block[8..].copy_from_slice(&counter.to_ne_bytes());
+                *counter += 1;
+            }
+            cipher.apply_keystream(blocks);
+        });
+    }
+
+    /// Generates a stream of random bytes.
     pub fn next(&mut self, n_bytes: usize) -> Vec<u8> {
-        if n_bytes == 0 {
-            return Vec::new();
+        let mut out = vec![0; n_bytes];
+        self.fill_bytes(&mut out);
+        out
+    }
+
+    /// Derives a child generator from the seed of this generator and the
+    /// provided label. The children with different labels, and the generator
+    /// itself, produce independent streams, and the child does not depend on
+    /// the state of the counter.
+    pub fn fork(&self, label: &str) -> AesPrg {
+        let seed = derive_seed(&self.seed, label, |seed, byte| {
+            let mut node = AesPrg::new(Some(seed));
+            node.nonce = FORK_NONCE;
+            node.counter = byte as u64 * 2;
+            node.next(Self::KEY_LEN + Self::IV_LEN)
+        });
+        AesPrg::new(Some(seed))
+    }
+
+    /// Generates the next 16 random bytes.
+    pub fn next_block(&mut self) -> [u8; 16] {
+        let mut block = [0; 16];
+        self.fill_bytes(&mut block);
+        block
+    }
+
+    /// Generates a random `u64` from the next 8 bytes, in little-endian order.
+    pub fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Generates a random `u128` from the next 16 bytes, in little-endian
+    /// order.
+    pub fn next_u128(&mut self) -> u128 {
+        u128::from_le_bytes(self.next_block())
+    }
+}
+
+#[cfg(feature = "aes")]
+impl PseudoRandomGenerator for AesPrg {
+    fn block_len(&self) -> usize {
+        Self::BLOCK_LEN
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        AesPrg::fill_bytes(self, dest)
+    }
+
+    fn counter(&self) -> u64 {
+        AesPrg::counter(self)
+    }
+
+    fn reset(&mut self) {
+        AesPrg::reset(self)
+    }
+}
+
+/// Defines a pseudo-random number generator based on the ChaCha20 stream
+/// cipher.
+///
+/// The seed is the 256-bit key of the cipher, and each block is 64 bytes of
+/// the keystream under a fixed nonce.
+#[cfg(feature = "chacha20")]
+#[derive(Clone)]
+pub struct ChaChaPrg {
+    seed: Vec<u8>,
+    nonce: u64,
+    counter: u64,
+    leftover: Leftover<64>,
+}
+
+#[cfg(feature = "chacha20")]
+impl ChaChaPrg {
+    const PRG_NONCE: [u8; 12] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0, 0, 0, 0];
+    const KEY_LEN: usize = 32;
+    const BLOCK_LEN: usize = 64;
+
+    /// Creates a new PRG. The seed is cropped or padded with zeros to the
+    /// length of the key, and a missing seed is filled with zeros.
+    pub fn new(seed: Option<Vec<u8>>) -> ChaChaPrg {
+        ChaChaPrg {
+            seed: fit_seed(seed, Self::KEY_LEN),
+            nonce: 0,
+            counter: 0,
+            leftover: Leftover::empty(),
         }
+    }
+
+    /// Creates a new PRG with the provided nonce, so that generators with the
+    /// same seed and different nonces produce independent streams. The seed is
+    /// handled as in [`ChaChaPrg::new`], and the function panics if the nonce is
+    /// `u64::MAX`, which is reserved to fork generators.
+    pub fn with_nonce(seed: Option<Vec<u8>>, nonce: u64) -> ChaChaPrg {
+        check_nonce(nonce);
+        let mut prg = ChaChaPrg::new(seed);
+        prg.nonce = nonce;
+        prg
+    }
+
+    /// Creates a new PRG whose seed is taken from the randomness of the
+    /// operating system.
+    #[cfg(feature = "getrandom")]
+    pub fn from_entropy() -> ChaChaPrg {
+        ChaChaPrg::new(Some(entropy_seed(Self::KEY_LEN)))
+    }
+
+    /// Returns the nonce of the PRG.
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Sets the counter of the PRG, so that the next bytes start at the
+    /// beginning of the block with that index. The unused bytes of the last
+    /// block generated are discarded.
+    pub fn set_counter(&mut self, counter: u64) {
+        self.counter = counter;
+        self.leftover = Leftover::empty();
+    }
+
+    /// Skips the provided number of blocks of the stream, discarding the unused
+    /// bytes of the last block generated.
+    pub fn skip_blocks(&mut self, n_blocks: u64) {
+        self.set_counter(self.counter + n_blocks);
+    }
+
+    /// Resets the PRG.
+    pub fn reset(&mut self) {
+        self.counter = 0;
+        self.leftover = Leftover::empty();
+    }
 
-        // Compute the number of blocks needed
-        let mut n_blocks = n_bytes / Self::BLOCK_LEN;
-        if n_bytes % Self::BLOCK_LEN != 0 {
-            n_blocks += 1;
+    /// Returns the current state of the counter in the PRG.
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// Fills the provided buffer with the next bytes of the keystream of
+    /// ChaCha20, whose nonce is combined with the nonce of the generator. The
+    /// bytes of the last block that are not used are kept for the next call.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        use chacha20::cipher::{KeyIvInit as _, StreamCipher as _, StreamCipherSeek as _};
+
+        counter::record(|counts| counts.prg_bytes += dest.len() as u64);
+
+        let mut cipher_nonce = Self::PRG_NONCE;
+        for (cipher_byte, nonce_byte) in cipher_nonce.iter_mut().zip(self.nonce.to_le_bytes()) {
+            *cipher_byte ^= nonce_byte;
         }
 
-        let key = &self.seed[0..Self::KEY_LEN];
-        let iv = &self.seed[Self::KEY_LEN..];
+        let (seed, counter) = (&self.seed, &mut self.counter);
+        self.leftover.fill(dest, |blocks| {
+            let mut cipher = chacha20::ChaCha20::new(seed[..].into(), (&cipher_nonce).into());
+            cipher.seek(*counter * Self::BLOCK_LEN as u64);
 
-        let mut cipher = Aes128Ctr64LE::new(key.into(), iv.into());
+            blocks.fill(0);
+            cipher.apply_keystream(blocks);
+            *counter += (blocks.len() / Self::BLOCK_LEN) as u64;
+        });
+    }
 
-        let mut out = Vec::new();
-        for _ in 0..n_blocks {
-            let mut buffer = [Self::PRG_NONCE.to_ne_bytes(), self.counter.to_ne_bytes()].concat();
-            cipher.apply_keystream(&mut buffer);
-            out.append(&mut buffer);
+    /// Generates a stream of random bytes from the keystream of ChaCha20.
+    pub fn next(&mut self, n_bytes: usize) -> Vec<u8> {
+        let mut out = vec![0; n_bytes];
+        self.fill_bytes(&mut out);
+        out
+    }
+
+    /// Derives a child generator from the seed of this generator and the
+    /// provided label. The children with different labels, and the generator
+    /// itself, produce independent streams, and the child does not depend on
+    /// the state of the counter.
+    pub fn fork(&self, label: &str) -> ChaChaPrg {
+        let seed = derive_seed(&self.seed, label, |seed, byte| {
+            let mut node = ChaChaPrg::new(Some(seed));
+            node.nonce = FORK_NONCE;
+            node.counter = byte as u64;
+            node.next(Self::KEY_LEN)
+        });
+        ChaChaPrg::new(Some(seed))
+    }
+
+    /// Generates the next 64 random bytes.
+    pub fn next_block(&mut self) -> [u8; 64] {
+        let mut block = [0; 64];
+        self.fill_bytes(&mut block);
+        block
+    }
+
+    /// Generates a random `u64` from the next 8 bytes, in little-endian order.
+    pub fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
 
-            self.counter += 1;
+    /// Generates a random `u128` from the next 16 bytes, in little-endian
+    /// order.
+    pub fn next_u128(&mut self) -> u128 {
+        let mut bytes = [0; 16];
+        self.fill_bytes(&mut bytes);
+        u128::from_le_bytes(bytes)
+    }
+}
+
+#[cfg(feature = "chacha20")]
+impl PseudoRandomGenerator for ChaChaPrg {
+    fn block_len(&self) -> usize {
+        Self::BLOCK_LEN
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        ChaChaPrg::fill_bytes(self, dest)
+    }
+
+    fn counter(&self) -> u64 {
+        ChaChaPrg::counter(self)
+    }
+
+    fn reset(&mut self) {
+        ChaChaPrg::reset(self)
+    }
+}
+
+/// Implements the traits of the `rand` crate for a generator that implements
+/// [`PseudoRandomGenerator`] and has a constructor `new(Option<Vec<u8>>)`.
+#[cfg(any(feature = "aes", feature = "chacha20"))]
+macro_rules! impl_rand_traits {
+    ($prg:ty) => {
+        impl RngCore for $prg {
+            fn next_u32(&mut self) -> u32 {
+                let mut bytes = [0; 4];
+                <$prg>::fill_bytes(self, &mut bytes);
+                u32::from_le_bytes(bytes)
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                <$prg>::next_u64(self)
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                <$prg>::fill_bytes(self, dest)
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+                <$prg>::fill_bytes(self, dest);
+                Ok(())
+            }
         }
 
-        out[..n_bytes].to_vec()
+        impl CryptoRng for $prg {}
+
+        impl SeedableRng for $prg {
+            type Seed = [u8; 32];
+
+            fn from_seed(seed: Self::Seed) -> Self {
+                Self::new(Some(seed.to_vec()))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "aes")]
+impl_rand_traits!(AesPrg);
+
+#[cfg(feature = "chacha20")]
+impl_rand_traits!(ChaChaPrg);
+
+/// Wraps a generator of the `rand` crate so it can be used as a
+/// [`PseudoRandomGenerator`], for example in
+/// [`MersenneField::random`](crate::math::mersenne::MersenneField::random).
+///
+/// The counter of the adapter counts the bytes drawn in steps of 8.
+#[derive(Clone)]
+pub struct RngAdapter<R> {
+    rng: R,
+    counter: u64,
+}
+
+impl<R: RngCore> RngAdapter<R> {
+    const BLOCK_LEN: usize = 8;
+
+    /// Wraps the provided generator.
+    pub fn new(rng: R) -> Self {
+        Self { rng, counter: 0 }
+    }
+
+    /// Returns the wrapped generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: RngCore> PseudoRandomGenerator for RngAdapter<R> {
+    fn block_len(&self) -> usize {
+        Self::BLOCK_LEN
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        self.counter += dest.len().div_ceil(Self::BLOCK_LEN) as u64;
+        counter::record(|counts| counts.prg_bytes += dest.len() as u64);
+    }
+
+    fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// Only resets the counter, since a generic generator cannot be rewound.
+    fn reset(&mut self) {
+        self.counter = 0;
     }
 }
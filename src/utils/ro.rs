@@ -0,0 +1,95 @@
+//! Implements a random oracle with domain separation.
+//!
+//! A random oracle is an idealized hash function that answers every new query
+//! with a uniformly random value. It is instantiated here with SHA-256 in the
+//! style of a sponge: the data is absorbed into a running hash, and the outputs
+//! are squeezed out of it. Every call is encoded with a tag and the length of
+//! its data, so that different sequences of calls never produce the same
+//! input to the hash, and the oracle is created with a domain label, so that
+//! oracles used for different purposes are independent.
+//!
+//! The typical use is the Fiat-Shamir transform, in which the challenge of an
+//! interactive proof is replaced by the output of the oracle on the messages
+//! sent so far, so that the proof can be produced without the verifier. This
+//! module is only available with the `sha2` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+//! use smol_mpc::utils::ro::RandomOracle;
+//!
+//! type Fp = Mersenne61;
+//!
+//! let mut oracle = RandomOracle::new("schnorr proof");
+//! oracle.absorb(b"statement");
+//! oracle.absorb_field_element(&Fp::new(42));
+//! let challenge: Fp = oracle.clone().squeeze_field_element();
+//!
+//! assert_eq!(challenge.value(), oracle.squeeze_field_element::<Fp>().value());
+//! ```
+
+use crate::math::mersenne::MersenneField;
+use crate::utils::prg::Prg;
+use sha2::{Digest, Sha256};
+
+/// Tag of the absorbed data.
+const ABSORB: u8 = 0;
+
+/// Tag of the squeezed outputs.
+const SQUEEZE: u8 = 1;
+
+/// Defines a random oracle instantiated with SHA-256.
+#[derive(Clone)]
+pub struct RandomOracle {
+    hasher: Sha256,
+}
+
+impl RandomOracle {
+    /// Creates an oracle for the provided domain.
+    pub fn new(domain: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update((domain.len() as u64).to_le_bytes());
+        hasher.update(domain);
+        Self { hasher }
+    }
+
+    /// Absorbs a string of bytes.
+    pub fn absorb(&mut self, data: &[u8]) {
+        self.hasher.update([ABSORB]);
+        self.hasher.update((data.len() as u64).to_le_bytes());
+        self.hasher.update(data);
+    }
+
+    /// Absorbs a field element, encoded as its value in eight bytes.
+    pub fn absorb_field_element<T: MersenneField>(&mut self, element: &T) {
+        self.absorb(&element.value().to_le_bytes());
+    }
+
+    /// Squeezes a pseudo-random generator seeded with the output of the oracle
+    /// on everything absorbed so far. The following outputs are independent
+    /// from it.
+    pub fn squeeze_prg(&mut self) -> Prg {
+        self.hasher.update([SQUEEZE]);
+        Prg::new(Some(self.hasher.clone().finalize().to_vec()))
+    }
+
+    /// Squeezes the provided number of bytes.
+    pub fn squeeze(&mut self, n_bytes: usize) -> Vec<u8> {
+        self.squeeze_prg().next(n_bytes)
+    }
+
+    /// Squeezes a uniformly random field element. The element is sampled by
+    /// rejection, so that it is not biased by the reduction modulo the order
+    /// of the field.
+    pub fn squeeze_field_element<T: MersenneField>(&mut self) -> T {
+        let mut prg = self.squeeze_prg();
+        let mask = u64::MAX >> (u64::BITS as u64 - T::POWER);
+        loop {
+            let value = prg.next_u64() & mask;
+            if value < T::ORDER {
+                return T::new(value);
+            }
+        }
+    }
+}
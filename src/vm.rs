@@ -5,8 +5,17 @@
 //! a node in a network that receives, processes, and send information according
 //! to a protocol specification.
 
+use crate::adversary::Behavior;
+use crate::bdoz::BdozState;
+use crate::event::{Event, Hook};
 use crate::math::mersenne::MersenneField;
-use crate::mpc::Share;
+use crate::mpc::{PrssKey, Share};
+use crate::network::Mailbox;
+use crate::preprocessing::Preprocessing;
+use crate::spdz::SpdzState;
+use crate::utils::prg::Prg;
+use rand::rngs::OsRng;
+use rand::RngCore;
 use std::collections::HashMap;
 
 /// Defines a virtual machine.
@@ -29,52 +38,437 @@ use std::collections::HashMap;
 /// particular, if a value is secret-shared among a certain set of parties, it
 /// will have the same ID in memory for all the virtual machines involved in the
 /// protocol.
-pub struct VirtualMachine<'a, T: MersenneField> {
+///
+/// The memory can be divided in nested scopes (see
+/// [`VirtualMachine::enter_scope`]), so a sub-protocol can store its
+/// intermediate values without colliding with the IDs of the caller.
+pub struct VirtualMachine<T: MersenneField> {
     /// ID of the virtual machine.
-    pub id: &'a str,
+    pub id: String,
 
     /// Memory for private values.
-    pub private_values: HashMap<&'a str, T>,
+    private_values: HashMap<String, T>,
 
     /// Memory for shared values.
-    pub shares: HashMap<&'a str, Share<'a, T>>,
+    shares: HashMap<String, Share<T>>,
+
+    /// Memory for boolean shares, that is, shares of bits over
+    /// $\mathbb{F}_2$ whose XOR is the shared bit.
+    bool_shares: HashMap<String, bool>,
+
+    /// Keys received during the PRSS setup, one for each subset of parties
+    /// that this virtual machine belongs to.
+    pub prss_keys: Vec<PrssKey>,
+
+    /// Pseudo-random generator of the virtual machine, from which the
+    /// protocols draw the randomness sampled by this party.
+    pub prg: Prg,
+
+    /// Pseudo-random generators shared with each of the other parties, indexed
+    /// by their ID (see [`mpc::pairwise_setup`](crate::mpc::pairwise_setup)).
+    pairwise_prgs: HashMap<String, Prg>,
+
+    /// Store of the preprocessed material that has not been used yet.
+    pub preprocessing: Preprocessing<T>,
+
+    /// State of the authenticated secret-sharing of SPDZ.
+    pub spdz: SpdzState<T>,
+
+    /// State of the authenticated secret-sharing with pairwise MACs of BDOZ.
+    pub bdoz: BdozState<T>,
+
+    /// Behavior of the virtual machine during the protocols, which is honest
+    /// unless the machine is created with [`VirtualMachine::new_cheating`].
+    pub behavior: Behavior<T>,
+
+    /// Messages sent and received through the simulated network (see
+    /// [`network`](crate::network)).
+    pub mailbox: Mailbox<T>,
+
+    /// Policy applied by [`VirtualMachine::insert_priv_value`],
+    /// [`VirtualMachine::insert_share`] and
+    /// [`VirtualMachine::insert_bool_share`] when the ID is already used.
+    pub insert_policy: InsertPolicy,
+
+    /// Scopes opened in the memory, from the outermost to the innermost.
+    scopes: Vec<Scope<T>>,
+
+    /// Counter used to generate fresh IDs for temporary values.
+    next_temp: usize,
+
+    /// Functions that observe the events of the virtual machine.
+    hooks: Vec<Hook<T>>,
+}
+
+/// Defines what happens when a value is inserted in the private memory or the
+/// share memories with an ID that is already used in the same scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum InsertPolicy {
+    /// The insertion panics, which is the default.
+    #[default]
+    Error,
+
+    /// The previous value is replaced and returned.
+    Overwrite,
+
+    /// The previous value is kept under the ID `id@n`, where $n$ is the
+    /// number of previous versions of the value, and the new value is stored
+    /// under `id`.
+    Versioned,
+}
+
+/// Reports the number of values stored in the memories of a virtual machine,
+/// counting the values of all the open scopes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// Number of values in the private memory.
+    pub private_values: usize,
+
+    /// Number of shares in the share memory.
+    pub shares: usize,
+
+    /// Number of boolean shares in the boolean share memory.
+    pub bool_shares: usize,
+
+    /// Number of open scopes.
+    pub scopes: usize,
+}
+
+/// Captures the state of a virtual machine at some point of a protocol, so the
+/// machine can be rewound to that point with [`VirtualMachine::restore`].
+///
+/// The snapshot holds the private memory, the share memories, the open scopes,
+/// the unused preprocessed material and the state of the PRG, of the PRGs
+/// shared with the other parties and of the PRSS keys, so the correlated
+/// randomness is rewound together with the own randomness of the machine.
+#[derive(Clone)]
+pub struct Snapshot<T: MersenneField> {
+    id: String,
+    private_values: HashMap<String, T>,
+    shares: HashMap<String, Share<T>>,
+    bool_shares: HashMap<String, bool>,
+    scopes: Vec<Scope<T>>,
+    next_temp: usize,
+    preprocessing: Preprocessing<T>,
+    prg: Prg,
+    pairwise_prgs: HashMap<String, Prg>,
+    prss_keys: Vec<PrssKey>,
+}
+
+/// Defines the memory of a scope of a virtual machine.
+#[derive(Clone)]
+struct Scope<T: MersenneField> {
+    name: String,
+    private_values: HashMap<String, T>,
+    shares: HashMap<String, Share<T>>,
+    bool_shares: HashMap<String, bool>,
 }
 
-impl<'a, 'b, T: MersenneField> VirtualMachine<'a, T>
-where
-    'a: 'b,
-{
+impl<T: MersenneField> VirtualMachine<T> {
     /// Creates a new virtual machine using a provided ID.
-    pub fn new(id_machine: &'a str) -> Self {
+    ///
+    /// The PRG of the machine is seeded with fresh randomness from the
+    /// operating system, since the ID is public and the PRG samples the masks
+    /// and the keys of the party. Reproducible executions can be obtained with
+    /// [`VirtualMachine::with_seed`].
+    pub fn new(id_machine: &str) -> Self {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        Self::with_seed(id_machine, &seed)
+    }
+
+    /// Creates a new virtual machine whose PRG is derived from the provided
+    /// seed, so the executions are reproducible.
+    ///
+    /// The ID is only used as a label to fork the PRG of the seed, so that
+    /// machines created with the same seed and different IDs draw independent
+    /// randomness. The seed must be kept secret, since it determines all the
+    /// randomness of the machine.
+    pub fn with_seed(id_machine: &str, seed: &[u8]) -> Self {
         Self {
-            id: id_machine,
+            id: id_machine.to_string(),
             private_values: HashMap::new(),
             shares: HashMap::new(),
+            bool_shares: HashMap::new(),
+            prss_keys: Vec::new(),
+            prg: Prg::new(Some(seed.to_vec())).fork(id_machine),
+            pairwise_prgs: HashMap::new(),
+            preprocessing: Preprocessing::new(),
+            spdz: SpdzState::new(),
+            bdoz: BdozState::new(),
+            behavior: Behavior::Honest,
+            mailbox: Mailbox::new(),
+            insert_policy: InsertPolicy::Error,
+            scopes: Vec::new(),
+            next_temp: 0,
+            hooks: Vec::new(),
         }
     }
 
-    /// Inserts a value in the private memory using a provided ID.
-    pub fn insert_priv_value(&mut self, id: &'a str, value: T) {
-        if self.shares.contains_key(id) {
-            panic!("There exists a share with this id");
+    /// Creates a new virtual machine that deviates from the protocols as
+    /// specified by the provided behavior (see [`adversary`](crate::adversary)).
+    pub fn new_cheating(id_machine: &str, behavior: Behavior<T>) -> Self {
+        Self {
+            behavior,
+            ..Self::new(id_machine)
         }
+    }
+
+    /// Registers a function that receives the ID of the virtual machine and
+    /// each event it emits (see [`event`](crate::event)).
+    pub fn add_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&str, &Event<T>) + Send + 'static,
+    {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Removes all the hooks of the virtual machine.
+    pub fn clear_hooks(&mut self) {
+        self.hooks.clear();
+    }
+
+    /// Emits an event to the hooks of the virtual machine. The event is only
+    /// built if someone observes it.
+    pub(crate) fn emit<F>(&self, event: F)
+    where
+        F: FnOnce() -> Event<T>,
+    {
+        if self.hooks.is_empty() && !cfg!(feature = "tracing") {
+            return;
+        }
+        let event = event();
+        for hook in &self.hooks {
+            hook(&self.id, &event);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(party = %self.id, "{}", event);
+    }
+
+    /// Opens a new scope in the memory with the provided name.
+    ///
+    /// Until the scope is closed with [`VirtualMachine::exit_scope`], the
+    /// values are inserted in the memory of the scope, where they may have
+    /// the same IDs as values of the outer scopes without colliding with them.
+    /// The lookups search the innermost scope first and then the outer ones,
+    /// so the values of the caller remain accessible by their IDs.
+    pub fn enter_scope(&mut self, name: &str) {
+        self.scopes.push(Scope {
+            name: name.to_string(),
+            private_values: HashMap::new(),
+            shares: HashMap::new(),
+            bool_shares: HashMap::new(),
+        });
+    }
 
-        self.private_values.insert(id, value);
+    /// Closes the innermost scope, erasing all the values stored in it.
+    pub fn exit_scope(&mut self) {
+        if self.scopes.pop().is_none() {
+            panic!("There is no scope to exit.");
+        }
     }
 
-    /// Insert a share in the share memory using a provided ID.
-    pub fn insert_share(&mut self, id: &'a str, share: Share<'a, T>) {
-        if self.shares.contains_key(id) {
-            panic!("There exists a share with this id.");
+    /// Returns the names of the open scopes joined with `/`, or `None` if no
+    /// scope is open.
+    pub fn current_scope(&self) -> Option<String> {
+        if self.scopes.is_empty() {
+            return None;
+        }
+        let names: Vec<&str> = self
+            .scopes
+            .iter()
+            .map(|scope| scope.name.as_str())
+            .collect();
+        Some(names.join("/"))
+    }
+
+    /// Captures the current state of the virtual machine.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            id: self.id.clone(),
+            private_values: self.private_values.clone(),
+            shares: self.shares.clone(),
+            bool_shares: self.bool_shares.clone(),
+            scopes: self.scopes.clone(),
+            next_temp: self.next_temp,
+            preprocessing: self.preprocessing.clone(),
+            prg: self.prg.clone(),
+            pairwise_prgs: self.pairwise_prgs.clone(),
+            prss_keys: self.prss_keys.clone(),
+        }
+    }
+
+    /// Rewinds the virtual machine to the state captured in the snapshot.
+    ///
+    /// The snapshot is not consumed, so it can be restored several times to
+    /// run alternative continuations from the same point. The function panics
+    /// if the snapshot was taken from another virtual machine.
+    pub fn restore(&mut self, snapshot: &Snapshot<T>) {
+        if snapshot.id != self.id {
+            panic!(
+                "The snapshot was taken from the virtual machine {}.",
+                snapshot.id
+            );
+        }
+        self.private_values = snapshot.private_values.clone();
+        self.shares = snapshot.shares.clone();
+        self.bool_shares = snapshot.bool_shares.clone();
+        self.scopes = snapshot.scopes.clone();
+        self.next_temp = snapshot.next_temp;
+        self.preprocessing = snapshot.preprocessing.clone();
+        self.prg = snapshot.prg.clone();
+        self.pairwise_prgs = snapshot.pairwise_prgs.clone();
+        self.prss_keys = snapshot.prss_keys.clone();
+    }
+
+    /// Returns `true` if the ID is used in any memory of any open scope.
+    pub fn contains_id(&self, id: &str) -> bool {
+        let in_scopes = self.scopes.iter().any(|scope| {
+            scope.private_values.contains_key(id)
+                || scope.shares.contains_key(id)
+                || scope.bool_shares.contains_key(id)
+        });
+        in_scopes
+            || self.private_values.contains_key(id)
+            || self.shares.contains_key(id)
+            || self.bool_shares.contains_key(id)
+    }
+
+    /// Returns a fresh ID for a temporary value with the form `prefix#n`,
+    /// which is not used in the memory of this virtual machine.
+    ///
+    /// The IDs are generated with a counter, so the virtual machines that call
+    /// this method in the same order obtain the same IDs unless some of them
+    /// already use one. Protocols should use
+    /// [`mpc::temp_id`](crate::mpc::temp_id) instead, which agrees on an ID
+    /// for all the parties.
+    pub fn fresh_id(&mut self, prefix: &str) -> String {
+        loop {
+            let id = format!("{}#{}", prefix, self.next_temp);
+            self.next_temp += 1;
+            if !self.contains_id(&id) {
+                return id;
+            }
+        }
+    }
+
+    /// Inserts a value in the private memory using a provided ID, following
+    /// the insertion policy of the virtual machine (see [`InsertPolicy`]).
+    ///
+    /// The value previously stored with the same ID is returned if it is
+    /// overwritten.
+    pub fn insert_priv_value(&mut self, id: &str, value: T) -> Option<T> {
+        self.insert_priv_value_with(id, value, self.insert_policy)
+    }
+
+    /// Inserts a value in the private memory using a provided ID, following
+    /// the provided insertion policy instead of the one of the virtual
+    /// machine.
+    pub fn insert_priv_value_with(
+        &mut self,
+        id: &str,
+        value: T,
+        policy: InsertPolicy,
+    ) -> Option<T> {
+        let (private_values, shares, _) = self.innermost_memory();
+        if shares.contains_key(id) {
+            panic!("There exists a share with this id");
         }
 
-        self.shares.insert(id, share);
+        insert_with_policy(
+            private_values,
+            id,
+            value,
+            policy,
+            "There exists a private value with this id.",
+            |_, _| {},
+        )
+    }
+
+    /// Insert a share in the share memory using a provided ID, following the
+    /// insertion policy of the virtual machine (see [`InsertPolicy`]).
+    ///
+    /// The share previously stored with the same ID is returned if it is
+    /// overwritten.
+    pub fn insert_share(&mut self, id: &str, share: Share<T>) -> Option<Share<T>> {
+        self.insert_share_with(id, share, self.insert_policy)
+    }
+
+    /// Insert a share in the share memory using a provided ID, following the
+    /// provided insertion policy instead of the one of the virtual machine.
+    pub fn insert_share_with(
+        &mut self,
+        id: &str,
+        mut share: Share<T>,
+        policy: InsertPolicy,
+    ) -> Option<Share<T>> {
+        share.value = self.behavior.stored_share(share.value);
+        let (_, shares, _) = self.innermost_memory();
+        let previous = insert_with_policy(
+            shares,
+            id,
+            share,
+            policy,
+            "There exists a share with this id.",
+            |share, version| share.id = version.to_string(),
+        );
+        self.emit(|| Event::ShareInserted { id: id.to_string() });
+        previous
+    }
+
+    /// Inserts a boolean share in the boolean share memory using a provided
+    /// ID, following the insertion policy of the virtual machine (see
+    /// [`InsertPolicy`]).
+    pub fn insert_bool_share(&mut self, id: &str, share: bool) -> Option<bool> {
+        self.insert_bool_share_with(id, share, self.insert_policy)
+    }
+
+    /// Inserts a boolean share in the boolean share memory using a provided
+    /// ID, following the provided insertion policy instead of the one of the
+    /// virtual machine.
+    pub fn insert_bool_share_with(
+        &mut self,
+        id: &str,
+        share: bool,
+        policy: InsertPolicy,
+    ) -> Option<bool> {
+        let (_, _, bool_shares) = self.innermost_memory();
+        insert_with_policy(
+            bool_shares,
+            id,
+            share,
+            policy,
+            "There exists a boolean share with this id.",
+            |_, _| {},
+        )
+    }
+
+    /// Returns the boolean share with the provided ID previously stored in the
+    /// boolean share memory.
+    pub fn get_bool_share(&self, id: &str) -> bool {
+        let share = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.bool_shares.get(id))
+            .or_else(|| self.bool_shares.get(id));
+        if let Some(share) = share {
+            *share
+        } else {
+            panic!("The id `{}` is not registered in the virtual machine.", id);
+        }
     }
 
     /// Returns a private value with the provided id stored in the private
     /// memory.
-    pub fn get_priv_value(&'a self, id: &'a str) -> &'b T {
-        if let Some(share) = self.private_values.get(id) {
+    pub fn get_priv_value(&self, id: &str) -> &T {
+        let value = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.private_values.get(id))
+            .or_else(|| self.private_values.get(id));
+        if let Some(share) = value {
             share
         } else {
             panic!("The id is not registered in the virtual machine.")
@@ -83,11 +477,251 @@ where
 
     /// Returns the share with the provided ID previously stored in the share
     /// memory.
-    pub fn get_share(&'a self, id: &'a str) -> &'b Share<'a, T> {
-        if let Some(share) = self.shares.get(id) {
+    pub fn get_share(&self, id: &str) -> &Share<T> {
+        let share = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.shares.get(id))
+            .or_else(|| self.shares.get(id));
+        if let Some(share) = share {
             share
         } else {
             panic!("The id `{}` is not registered in the virtual machine.", id);
         }
     }
+
+    /// Returns `true` if there is a share with the provided ID in the share
+    /// memory.
+    pub fn contains_share(&self, id: &str) -> bool {
+        self.scopes
+            .iter()
+            .any(|scope| scope.shares.contains_key(id))
+            || self.shares.contains_key(id)
+    }
+
+    /// Returns `true` if there is a value with the provided ID in the private
+    /// memory.
+    pub fn contains_priv_value(&self, id: &str) -> bool {
+        self.scopes
+            .iter()
+            .any(|scope| scope.private_values.contains_key(id))
+            || self.private_values.contains_key(id)
+    }
+
+    /// Returns the shares accessible by their ID sorted by ID, that is, the
+    /// shares that are not shadowed by a share of an inner scope.
+    pub fn shares(&self) -> Vec<(&str, &Share<T>)> {
+        let mut visible: HashMap<&str, &Share<T>> = HashMap::new();
+        for memory in
+            std::iter::once(&self.shares).chain(self.scopes.iter().map(|scope| &scope.shares))
+        {
+            visible.extend(memory.iter().map(|(id, share)| (id.as_str(), share)));
+        }
+        let mut shares: Vec<(&str, &Share<T>)> = visible.into_iter().collect();
+        shares.sort_by(|a, b| a.0.cmp(b.0));
+        shares
+    }
+
+    /// Returns the private values accessible by their ID sorted by ID, that is,
+    /// the values that are not shadowed by a value of an inner scope.
+    pub fn priv_values(&self) -> Vec<(&str, &T)> {
+        let mut visible: HashMap<&str, &T> = HashMap::new();
+        for memory in std::iter::once(&self.private_values)
+            .chain(self.scopes.iter().map(|scope| &scope.private_values))
+        {
+            visible.extend(memory.iter().map(|(id, value)| (id.as_str(), value)));
+        }
+        let mut values: Vec<(&str, &T)> = visible.into_iter().collect();
+        values.sort_by(|a, b| a.0.cmp(b.0));
+        values
+    }
+
+    /// Removes the share with the provided ID from the innermost scope that
+    /// contains it, and returns it.
+    pub fn remove_share(&mut self, id: &str) -> Option<Share<T>> {
+        match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find(|scope| scope.shares.contains_key(id))
+        {
+            Some(scope) => scope.shares.remove(id),
+            None => self.shares.remove(id),
+        }
+    }
+
+    /// Removes the private value with the provided ID from the innermost scope
+    /// that contains it, and returns it.
+    pub fn remove_priv_value(&mut self, id: &str) -> Option<T> {
+        match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find(|scope| scope.private_values.contains_key(id))
+        {
+            Some(scope) => scope.private_values.remove(id),
+            None => self.private_values.remove(id),
+        }
+    }
+
+    /// Removes the boolean share with the provided ID from the innermost scope
+    /// that contains it, and returns it.
+    pub fn remove_bool_share(&mut self, id: &str) -> Option<bool> {
+        match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find(|scope| scope.bool_shares.contains_key(id))
+        {
+            Some(scope) => scope.bool_shares.remove(id),
+            None => self.bool_shares.remove(id),
+        }
+    }
+
+    /// Erases all the shares and boolean shares of every scope, keeping the
+    /// private values.
+    pub fn clear_shares(&mut self) {
+        self.shares.clear();
+        self.bool_shares.clear();
+        for scope in self.scopes.iter_mut() {
+            scope.shares.clear();
+            scope.bool_shares.clear();
+        }
+    }
+
+    /// Keeps only the shares of every scope for which the predicate returns
+    /// `true`, given their ID and the share.
+    pub fn retain_shares<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&str, &Share<T>) -> bool,
+    {
+        self.shares.retain(|id, share| predicate(id, share));
+        for scope in self.scopes.iter_mut() {
+            scope.shares.retain(|id, share| predicate(id, share));
+        }
+    }
+
+    /// Keeps only the private values of every scope for which the predicate
+    /// returns `true`, given their ID and the value.
+    pub fn retain_priv_values<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&str, &T) -> bool,
+    {
+        self.private_values.retain(|id, value| predicate(id, value));
+        for scope in self.scopes.iter_mut() {
+            scope
+                .private_values
+                .retain(|id, value| predicate(id, value));
+        }
+    }
+
+    /// Returns the number of values stored in each memory.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage {
+            private_values: self.private_values.len(),
+            shares: self.shares.len(),
+            bool_shares: self.bool_shares.len(),
+            scopes: self.scopes.len(),
+        };
+        for scope in &self.scopes {
+            usage.private_values += scope.private_values.len();
+            usage.shares += scope.shares.len();
+            usage.bool_shares += scope.bool_shares.len();
+        }
+        usage
+    }
+
+    /// Returns the memories of the innermost scope, or the main memories if no
+    /// scope is open.
+    #[allow(clippy::type_complexity)]
+    fn innermost_memory(
+        &mut self,
+    ) -> (
+        &mut HashMap<String, T>,
+        &mut HashMap<String, Share<T>>,
+        &mut HashMap<String, bool>,
+    ) {
+        match self.scopes.last_mut() {
+            Some(scope) => (
+                &mut scope.private_values,
+                &mut scope.shares,
+                &mut scope.bool_shares,
+            ),
+            None => (
+                &mut self.private_values,
+                &mut self.shares,
+                &mut self.bool_shares,
+            ),
+        }
+    }
+
+    /// Stores a PRG shared with the party with the provided ID.
+    pub(crate) fn insert_pairwise_prg(&mut self, id: &str, prg: Prg) {
+        self.pairwise_prgs.insert(id.to_string(), prg);
+    }
+
+    /// Returns the PRG shared with the party with the provided ID, which
+    /// produces the same values for both parties as long as they use it in
+    /// the same order.
+    pub fn pairwise_prg(&mut self, id: &str) -> &mut Prg {
+        self.pairwise_prgs.get_mut(id).unwrap_or_else(|| {
+            panic!("There is no PRG shared with the party {}.", id);
+        })
+    }
+
+    /// Computes locally a share of a common random value using the keys
+    /// obtained in the PRSS setup.
+    ///
+    /// Each key is used to derive a pseudo-random element known by all the
+    /// parties in the subset associated to the key. The first party of the
+    /// subset adds such element to its share, so the sum of the shares of all
+    /// the parties is the sum of the elements derived from every key. No
+    /// communication is needed, but all the parties of the setup must call this
+    /// method with the same ID to keep their keys synchronized. The resulting
+    /// share is stored in the share memory under the provided ID.
+    pub fn prss_random_share(&mut self, id: &str) {
+        let mut value = T::new(0);
+        for key in self.prss_keys.iter_mut() {
+            let random_elem = T::random(&mut key.prg);
+            if key.subset[0] == self.id {
+                value = value.add(&random_elem);
+            }
+        }
+
+        self.insert_share(id, Share::new(id, value));
+    }
+}
+
+/// Inserts a value in a memory following the provided policy, and returns the
+/// overwritten value, if any. The message is used to panic with the
+/// [`InsertPolicy::Error`] policy, and `rename` updates a previous value moved
+/// to a versioned ID with the [`InsertPolicy::Versioned`] policy.
+fn insert_with_policy<V>(
+    memory: &mut HashMap<String, V>,
+    id: &str,
+    value: V,
+    policy: InsertPolicy,
+    message: &str,
+    rename: impl Fn(&mut V, &str),
+) -> Option<V> {
+    if !memory.contains_key(id) {
+        memory.insert(id.to_string(), value);
+        return None;
+    }
+
+    match policy {
+        InsertPolicy::Error => panic!("{}", message),
+        InsertPolicy::Overwrite => memory.insert(id.to_string(), value),
+        InsertPolicy::Versioned => {
+            let version = (0..)
+                .map(|n| format!("{}@{}", id, n))
+                .find(|version| !memory.contains_key(version))
+                .unwrap();
+            let mut previous = memory.insert(id.to_string(), value).unwrap();
+            rename(&mut previous, &version);
+            memory.insert(version, previous);
+            None
+        }
+    }
 }
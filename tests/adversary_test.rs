@@ -0,0 +1,49 @@
+use smol_mpc::adversary::Behavior;
+use smol_mpc::broadcast;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::spdz;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn wrong_opening_goes_unnoticed_in_passive_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob = VirtualMachine::new_cheating("bob", Behavior::WrongOpening(Fp::new(1)));
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(10), &mut prg);
+    assert_eq!(mpc::reconstruct_share(&parties, "x").value(), 11);
+}
+
+#[test]
+#[should_panic(expected = "MAC check failed")]
+fn share_error_detected_by_mac_check() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob = VirtualMachine::new_cheating("bob", Behavior::ShareError(Fp::new(3)));
+    let mut parties = vec![&mut alice, &mut bob];
+
+    spdz::setup(&mut parties, &mut prg);
+    spdz::simulate_auth_dist("x", &mut parties, &Fp::new(10), &mut prg);
+    spdz::simulate_auth_dist("y", &mut parties, &Fp::new(20), &mut prg);
+    spdz::add_protocol(&mut parties, "x", "y", "z");
+    spdz::reconstruct_share(&mut parties, "z", &mut prg);
+}
+
+#[test]
+#[should_panic(expected = "inconsistent values")]
+fn inconsistent_broadcast_detected() {
+    let mut alice = VirtualMachine::new_cheating("alice", Behavior::Inconsistent(Fp::new(1)));
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+
+    alice.insert_priv_value("x", Fp::new(42));
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+    broadcast::broadcast_protocol(&mut parties, "alice", "x");
+}
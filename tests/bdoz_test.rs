@@ -0,0 +1,53 @@
+use smol_mpc::bdoz;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn bdoz_add_and_mult() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+
+    alice.insert_priv_value("a", Fp::new(6));
+    bob.insert_priv_value("b", Fp::new(7));
+
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+    bdoz::setup(&mut parties, &mut prg);
+    bdoz::distribute_shares("a", "alice", &mut parties, &mut prg);
+    bdoz::distribute_shares("b", "bob", &mut parties, &mut prg);
+    bdoz::generate_triple(&mut parties, ("a1", "b1", "c1"), &mut prg);
+    bdoz::generate_triple(&mut parties, ("a2", "b2", "c2"), &mut prg);
+
+    bdoz::add_protocol(&mut parties, "a", "b", "sum");
+    bdoz::mult_protocol(&mut parties, "a", "b", "prod", ("a1", "b1", "c1"));
+    bdoz::mult_protocol(&mut parties, "prod", "sum", "result", ("a2", "b2", "c2"));
+
+    assert_eq!(bdoz::reconstruct_share(&parties, "sum").value(), 13);
+    assert_eq!(bdoz::reconstruct_share(&parties, "prod").value(), 42);
+    assert_eq!(bdoz::reconstruct_share(&parties, "result").value(), 546);
+    assert!(!parties[0].bdoz.shares.contains_key("a1"));
+}
+
+#[test]
+#[should_panic(expected = "MAC check failed")]
+fn bdoz_detects_tampering() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    bdoz::setup(&mut parties, &mut prg);
+    bdoz::simulate_auth_dist("x", &mut parties, &Fp::new(10), &mut prg);
+
+    // Alice adds one to its share without updating the MACs.
+    let share = parties[0].bdoz.shares.get_mut("x").unwrap();
+    share.value = share.value.add(&Fp::new(1));
+
+    bdoz::reconstruct_share(&parties, "x");
+}
@@ -0,0 +1,33 @@
+use smol_mpc::broadcast;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn broadcast_protocol() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+
+    alice.insert_priv_value("x", Fp::new(42));
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+    broadcast::broadcast_protocol(&mut parties, "alice", "x");
+
+    for party in &parties {
+        assert_eq!(party.get_priv_value("x").value(), 42);
+    }
+}
+
+#[test]
+fn echo_broadcast_consistent() {
+    let received = vec![Fp::new(5), Fp::new(5), Fp::new(5)];
+    assert_eq!(broadcast::echo_broadcast(&received).value(), 5);
+}
+
+#[test]
+#[should_panic(expected = "inconsistent values")]
+fn echo_broadcast_detects_equivocation() {
+    let received = vec![Fp::new(5), Fp::new(6), Fp::new(5)];
+    broadcast::echo_broadcast(&received);
+}
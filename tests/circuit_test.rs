@@ -0,0 +1,198 @@
+use smol_mpc::circuit::{Backend, CircuitBuilder, CostEstimate, Gate, TypedCircuitBuilder};
+use smol_mpc::gc::GarblingScheme;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn builder() {
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let z = builder.xor(x, y);
+    let w = builder.inv(z);
+    builder.output(w);
+    let circuit = builder.build();
+
+    assert_eq!(circuit.n_wires, 4);
+    assert_eq!(circuit.inputs, vec![0, 1]);
+    assert_eq!(circuit.outputs, vec![3]);
+    assert!(matches!(circuit.gates[0], Gate::Xor(0, 1, 2)));
+    assert!(matches!(circuit.gates[1], Gate::Inv(2, 3)));
+    assert!(circuit.is_boolean());
+    assert!(!circuit.is_arithmetic());
+}
+
+#[test]
+fn evaluate_arithmetic() {
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let sum = builder.add(x, y);
+    let two = builder.constant(Fp::new(2));
+    let result = builder.mul(sum, two);
+    builder.output(result);
+    builder.output(sum);
+    let circuit = builder.build();
+
+    let output = circuit.evaluate(&[Fp::new(3), Fp::new(4)]);
+    assert_eq!(output[0].value(), 14);
+    assert_eq!(output[1].value(), 7);
+    assert!(circuit.is_arithmetic());
+}
+
+#[test]
+fn evaluate_boolean() {
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let and = builder.and(x, y);
+    let xor = builder.xor(x, y);
+    let not = builder.inv(x);
+    builder.output(and);
+    builder.output(xor);
+    builder.output(not);
+    let circuit = builder.build();
+
+    for (u, v) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let output = circuit.evaluate(&[Fp::new(u), Fp::new(v)]);
+        let values: Vec<u64> = output.iter().map(|value| value.value()).collect();
+        assert_eq!(values, vec![u & v, u ^ v, 1 - u]);
+    }
+}
+
+#[test]
+#[should_panic]
+fn output_of_missing_wire() {
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    builder.input();
+    builder.output(1);
+}
+
+#[test]
+fn gate_counts() {
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let sum = builder.add(x, y);
+    let product = builder.mul(sum, y);
+    let one = builder.constant(Fp::new(1));
+    let result = builder.add(product, one);
+    builder.output(result);
+    let circuit = builder.build();
+
+    let counts = circuit.gate_counts();
+    assert_eq!((counts.add, counts.mul, counts.constant), (2, 1, 1));
+    assert_eq!((counts.xor, counts.and, counts.inv), (0, 0, 0));
+}
+
+#[test]
+fn multiplicative_depth_and_width() {
+    // Computes x0 x1 x2 x3 as (x0 x1) (x2 x3).
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let inputs: Vec<usize> = (0..4).map(|_| builder.input()).collect();
+    let left = builder.mul(inputs[0], inputs[1]);
+    let right = builder.mul(inputs[2], inputs[3]);
+    let sum = builder.add(left, inputs[0]);
+    let result = builder.mul(sum, right);
+    builder.output(result);
+    let circuit = builder.build();
+
+    assert_eq!(circuit.multiplicative_depth(), 2);
+    assert_eq!(circuit.multiplicative_width(), 2);
+}
+
+#[test]
+fn estimate_cost() {
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let z = builder.input();
+    let and = builder.and(x, y);
+    let xor = builder.xor(and, z);
+    let not = builder.inv(xor);
+    builder.output(not);
+    let circuit = builder.build();
+
+    let cost = circuit.estimate_cost(Backend::SecretSharing, 3);
+    assert_eq!(
+        cost,
+        CostEstimate {
+            rounds: 2,
+            bits: 2 * 2 * 6 * 61
+        }
+    );
+
+    let cost = circuit.estimate_cost(Backend::Gmw, 3);
+    assert_eq!(
+        cost,
+        CostEstimate {
+            rounds: 1,
+            bits: 2 * 6
+        }
+    );
+
+    let textbook = circuit.estimate_cost(
+        Backend::Yao {
+            scheme: GarblingScheme::Textbook,
+            n_garbler_inputs: 1,
+        },
+        2,
+    );
+    let half_gates = circuit.estimate_cost(
+        Backend::Yao {
+            scheme: GarblingScheme::HalfGates,
+            n_garbler_inputs: 1,
+        },
+        2,
+    );
+    assert_eq!(textbook.rounds, 2);
+    assert_eq!(textbook.bits - half_gates.bits, (16 - 2) * 128);
+}
+
+#[test]
+fn typed_builder() {
+    let mut builder: TypedCircuitBuilder<Fp> = TypedCircuitBuilder::new();
+    let x = builder.input();
+    let b = builder.bool_input();
+    let c = builder.bool_input();
+    let k = builder.constant(Fp::new(5));
+    let product = builder.mul(x, k);
+    let sum = builder.add(product, x);
+    let and = builder.and(b, c);
+    let not = builder.inv(and);
+    builder.output(sum);
+    builder.bool_output(not);
+    let circuit = builder.build();
+
+    assert_eq!(x.wire(), 0);
+    assert_eq!(not.wire(), 7);
+    assert_eq!(circuit.inputs, vec![0, 1, 2]);
+    assert_eq!(circuit.outputs, vec![sum.wire(), not.wire()]);
+    let output = circuit.evaluate(&[Fp::new(7), Fp::new(1), Fp::new(1)]);
+    assert_eq!(output[0].value(), 42);
+    assert_eq!(output[1].value(), 0);
+}
+
+#[test]
+fn typed_circuit_with_backend() {
+    let mut builder: TypedCircuitBuilder<Fp> = TypedCircuitBuilder::new();
+    let a = builder.input();
+    let b = builder.input();
+    let c = builder.add(a, b);
+    let d = builder.mul(c, b);
+    builder.output(d);
+    let circuit = builder.build();
+
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(2), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(3), &mut prg);
+    mpc::evaluate_circuit(&mut parties, &circuit, &["a", "b"], &["d"], Some(&mut prg));
+    assert_eq!(mpc::reconstruct_share(&parties, "d").value(), 15);
+}
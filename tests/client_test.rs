@@ -0,0 +1,49 @@
+use smol_mpc::client::{InputClient, OutputClient};
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn input_client_share_input() {
+    let mut prg = Prg::new(None);
+
+    let mut client: InputClient<Fp> = InputClient::new("client");
+    client.insert_input("x", Fp::new(55));
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let mask = Fp::random(&mut prg);
+    mpc::simulate_random_dist("mask", &mut parties, &mask, &mut prg);
+    client.share_input(&mut parties, "x", "mask");
+
+    let rec_value = mpc::reconstruct_share(&parties, "x");
+    assert_eq!(rec_value.value(), 55);
+    for party in &parties {
+        assert!(!party.contains_share("mask"));
+    }
+}
+
+#[test]
+fn output_client_receive_output() {
+    let mut prg = Prg::new(None);
+
+    let mut client: OutputClient<Fp> = OutputClient::new("client");
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("y", &mut parties, &Fp::new(19), &mut prg);
+    client.receive_output(&parties, "y");
+
+    assert_eq!(client.get_output("y").value(), 19);
+    for party in &parties {
+        assert!(party.memory_usage().private_values == 0);
+    }
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "sha2")]
+
+use smol_mpc::utils::commit;
+use smol_mpc::utils::prg::Prg;
+
+#[test]
+fn commit_and_verify() {
+    let mut prg = Prg::new(None);
+
+    let randomness = prg.next(32);
+    let (commitment, opening) = commit::commit(&42u64.to_le_bytes(), &randomness);
+    assert!(commit::verify(&commitment, &opening));
+    assert_eq!(opening.value, 42u64.to_le_bytes());
+}
+
+#[test]
+fn verify_rejects_wrong_opening() {
+    let mut prg = Prg::new(None);
+
+    let randomness = prg.next(32);
+    let (commitment, mut opening) = commit::commit(b"heads", &randomness);
+
+    opening.value = b"tails".to_vec();
+    assert!(!commit::verify(&commitment, &opening));
+
+    opening.value = b"heads".to_vec();
+    opening.randomness = prg.next(32);
+    assert!(!commit::verify(&commitment, &opening));
+}
@@ -0,0 +1,227 @@
+use smol_mpc::adversary::Behavior;
+use smol_mpc::context::MpcContext;
+use smol_mpc::dealer::Dealer;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::network::Simulator;
+use smol_mpc::utils::prg::{Prg, PseudoRandomGenerator};
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn arithmetic() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    alice.insert_priv_value("a", Fp::new(7));
+    bob.insert_priv_value("b", Fp::new(3));
+
+    let dealer = Dealer::new(Prg::new(Some(vec![1])));
+    let mut ctx = MpcContext::new(vec![&mut alice, &mut bob, &mut charlie], dealer);
+    let a = ctx.input("a", "alice");
+    let b = ctx.input("b", "bob");
+    ctx.sub(&a, &b, "diff");
+    ctx.mult_const(&Fp::new(2), "diff", "double");
+    ctx.add_const(&Fp::new(1), "double", "shifted");
+    ctx.mult("shifted", &a, "product");
+    assert_eq!(ctx.open("product").value(), 63);
+
+    ctx.open_to("product", "charlie");
+    assert_eq!(charlie.get_priv_value("product").value(), 63);
+}
+
+#[test]
+fn preprocess() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let dealer = Dealer::new(Prg::new(Some(vec![2])));
+    let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], dealer);
+
+    assert_eq!(ctx.preprocess(2).len(), 2);
+    ctx.parties()[0].insert_priv_value("x", Fp::new(5));
+    ctx.input("x", "alice");
+    ctx.mult("x", "x", "y");
+    ctx.add("x", "y", "z");
+    assert_eq!(ctx.open("z").value(), 30);
+    assert_eq!(ctx.parties()[1].preprocessing.remaining_triples(), 1);
+}
+
+#[test]
+fn sessions() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    alice.insert_priv_value("x", Fp::new(2));
+    bob.insert_priv_value("x", Fp::new(9));
+
+    let dealer = Dealer::new(Prg::new(Some(vec![3])));
+    let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], dealer).with_session("sid");
+    let first = ctx.sub_session("1");
+    let second = ctx.sub_session("2");
+    assert_eq!(first, "sid/1");
+
+    // Both instances use the same IDs without colliding.
+    ctx.in_session(&first, |ctx| ctx.input("x", "alice"));
+    ctx.in_session(&second, |ctx| ctx.input("x", "bob"));
+    ctx.in_session(&first, |ctx| ctx.mult("x", "x", "y"));
+    ctx.in_session(&second, |ctx| ctx.mult("x", "x", "y"));
+    assert_eq!(ctx.in_session(&first, |ctx| ctx.open("y")).value(), 4);
+    assert_eq!(ctx.in_session(&second, |ctx| ctx.open("y")).value(), 81);
+    assert_eq!(ctx.session(), Some("sid"));
+    assert_eq!(ctx.qualify("y"), "sid:y");
+
+    let tags: Vec<String> = Simulator::new(ctx.parties())
+        .transcript()
+        .records()
+        .iter()
+        .map(|record| record.message.tag.clone())
+        .collect();
+    assert!(tags.contains(&"sid/1:beaver".to_string()));
+    assert!(tags.contains(&"sid/2:input".to_string()));
+
+    assert!(alice.contains_share("sid/1:y"));
+    assert!(!alice.contains_priv_value("sid/1:x"));
+    assert!(alice.mailbox.session().is_none());
+}
+
+#[test]
+fn counts() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    alice.insert_priv_value("a", Fp::new(4));
+
+    let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], Dealer::new(Prg::new(None)));
+    ctx.input("a", "alice");
+    ctx.preprocess(3);
+    ctx.reset_counts();
+    ctx.mult("a", "a", "b");
+    ctx.mult("b", "a", "c");
+    assert_eq!(ctx.counts().triples, 4);
+    assert_eq!(ctx.counts().prg_bytes, 0);
+    assert_eq!(ctx.counts().openings, 8);
+
+    assert_eq!(ctx.open("c").value(), 64);
+    assert_eq!(ctx.counts().openings, 10);
+    ctx.reset_counts();
+    assert_eq!(ctx.counts().triples, 0);
+}
+
+#[test]
+fn corruption() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    alice.insert_priv_value("a", Fp::new(5));
+    bob.insert_priv_value("b", Fp::new(6));
+
+    let mut ctx = MpcContext::new(
+        vec![&mut alice, &mut bob, &mut charlie],
+        Dealer::new(Prg::new(None)),
+    );
+    ctx.corrupt("bob");
+    ctx.corrupt("bob");
+    ctx.input("a", "alice");
+    ctx.input("b", "bob");
+    ctx.mult("a", "b", "c");
+    assert_eq!(ctx.open("c").value(), 30);
+    assert_eq!(ctx.corrupted(), vec!["bob"]);
+    assert!(matches!(ctx.parties()[1].behavior, Behavior::Passive));
+
+    let view = ctx.adversary_view();
+    assert_eq!(view.ids(), vec!["bob"]);
+    let bob_view = view.party("bob");
+    assert_eq!(bob_view.inputs.len(), 1);
+    assert_eq!(bob_view.inputs[0].0, "b");
+    assert_eq!(bob_view.randomness.len(), Prg::new(None).block_len());
+    assert!(bob_view
+        .received
+        .iter()
+        .all(|record| record.message.to == "bob"));
+    assert_eq!(
+        bob_view
+            .received
+            .iter()
+            .filter(|record| record.message.tag == "beaver")
+            .count(),
+        2
+    );
+    assert_eq!(bob_view.opened.last().unwrap().value(), 30);
+    assert!(bob_view.shares.iter().any(|(id, _)| id == "c"));
+}
+
+#[test]
+#[should_panic(expected = "Party with that id does not exist.")]
+fn corrupt_unknown_party() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut ctx = MpcContext::new(vec![&mut alice], Dealer::new(Prg::new(None)));
+    ctx.corrupt("bob");
+}
+
+#[test]
+fn straight_line_program() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    alice.insert_priv_value("x", Fp::new(5));
+    bob.insert_priv_value("y", Fp::new(3));
+    charlie.insert_priv_value("z", Fp::new(2));
+
+    let dealer = Dealer::new(Prg::new(None));
+    let mut ctx =
+        MpcContext::new(vec![&mut alice, &mut bob, &mut charlie], dealer).with_session("dsl");
+    let revealed = mpc! { ctx:
+        input x from alice;
+        input y from bob;
+        input z from charlie;
+        sum = x + y;
+        diff = x - y;
+        scaled = 3 * (x + 10);
+        poly = ((x * x) - (y * z)) * (z - 1);
+        reveal sum;
+        reveal diff;
+        reveal scaled;
+        reveal poly;
+    };
+    let values: Vec<u64> = revealed.iter().map(|value| value.value()).collect();
+    assert_eq!(values, vec![8, 2, 45, 19]);
+    assert!(ctx.parties()[0].contains_share("dsl:poly"));
+    assert!(ctx.parties()[0]
+        .shares()
+        .iter()
+        .any(|(id, _)| id.starts_with("dsl:mpc#")));
+
+    let temp = ctx.temp_id("mpc");
+    let temp = ctx.qualify(&temp);
+    assert!(!ctx.parties()[0].contains_share(&temp));
+}
+
+#[test]
+fn straight_line_program_constants() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    alice.insert_priv_value("x", Fp::new(5));
+
+    let dealer = Dealer::new(Prg::new(None));
+    let mut ctx = MpcContext::new(vec![&mut alice, &mut bob], dealer);
+    let revealed = mpc! { ctx:
+        input x from alice;
+        a = x + 10;
+        b = 10 + x;
+        c = x - 10;
+        d = 10 - x;
+        e = x * 3;
+        f = 3 * x;
+        g = 10 - (x * x);
+        reveal a;
+        reveal b;
+        reveal c;
+        reveal d;
+        reveal e;
+        reveal f;
+        reveal g;
+    };
+    let values: Vec<u64> = revealed.iter().map(|value| value.value()).collect();
+    let minus_five = Fp::new(5).negate().value();
+    let minus_fifteen = Fp::new(15).negate().value();
+    assert_eq!(values, vec![15, 15, minus_five, 5, 15, 15, minus_fifteen]);
+}
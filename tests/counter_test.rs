@@ -0,0 +1,62 @@
+use smol_mpc::counter::{self, OpCounts};
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn field_operations() {
+    let (_, counts) = counter::measure(|| {
+        let x = Fp::new(5).multiply(&Fp::new(3));
+        let y = x.subtract(&Fp::new(1)).add(&Fp::new(2));
+        y.inverse()
+    });
+    assert_eq!(
+        counts,
+        OpCounts {
+            mults: 1,
+            adds: 2,
+            inversions: 1,
+            ..OpCounts::default()
+        }
+    );
+}
+
+#[test]
+fn prg_bytes() {
+    let mut prg = Prg::new(None);
+    let (_, counts) = counter::measure(|| {
+        prg.next(20);
+        Fp::random(&mut prg)
+    });
+    assert_eq!(counts.prg_bytes, 28);
+}
+
+#[test]
+fn protocol_run() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::generate_triples(&mut parties, 2, &mut prg);
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(3), &mut prg);
+    let (_, counts) = counter::measure(|| {
+        mpc::mult_protocol(&mut parties, "x", "x", "y");
+        mpc::reconstruct_share(&parties, "y")
+    });
+    assert_eq!(counts.triples, 3);
+    assert_eq!(counts.openings, 9);
+    assert_eq!(counts.inversions, 0);
+    assert_eq!(counts.prg_bytes, 0);
+}
+
+#[test]
+fn reset() {
+    Fp::new(1).add(&Fp::new(1));
+    counter::reset();
+    assert_eq!(counter::current(), OpCounts::default());
+}
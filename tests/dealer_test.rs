@@ -0,0 +1,46 @@
+use smol_mpc::dealer::Dealer;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn dealer_value_and_triple() {
+    let mut dealer = Dealer::new(Prg::new(None));
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    dealer.deal_value(&mut parties, "x", &Fp::new(6));
+    dealer.deal_value(&mut parties, "y", &Fp::new(7));
+    dealer.deal_triple(&mut parties, ("a", "b", "c"));
+
+    let a = mpc::reconstruct_share(&parties, "a");
+    let b = mpc::reconstruct_share(&parties, "b");
+    let c = mpc::reconstruct_share(&parties, "c");
+    assert_eq!(a.multiply(&b).value(), c.value());
+
+    mpc::register_triple(&mut parties, ("a", "b", "c"));
+    mpc::mult_protocol(&mut parties, "x", "y", "z");
+    assert_eq!(mpc::reconstruct_share(&parties, "z").value(), 42);
+}
+
+#[test]
+fn dealer_random_bit() {
+    let mut dealer = Dealer::new(Prg::new(None));
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let ids = ["b0", "b1", "b2", "b3", "b4", "b5", "b6", "b7"];
+    for id in ids {
+        dealer.deal_random_bit(&mut parties, id);
+        let bit = mpc::reconstruct_share(&parties, id);
+        assert!(bit.value() <= 1);
+    }
+}
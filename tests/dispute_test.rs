@@ -0,0 +1,113 @@
+use smol_mpc::adversary::Behavior;
+use smol_mpc::broadcast;
+use smol_mpc::dispute::{self, Dispute, TooFewParties};
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::party::PartySet;
+use smol_mpc::vm::{InsertPolicy, VirtualMachine};
+
+type Fp = Mersenne61;
+
+#[test]
+fn eliminate_pair() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = PartySet::new(vec![&mut alice, &mut bob, &mut charlie]).with_threshold(1);
+
+    let dispute = Dispute::new("charlie", "alice", "wrong share");
+    assert_eq!(dispute.to_string(), "charlie accuses alice: wrong share");
+    dispute::eliminate(&mut parties, &dispute).unwrap();
+    assert_eq!(parties.ids(), vec!["bob"]);
+    assert_eq!(parties.threshold(), Some(0));
+}
+
+#[test]
+#[should_panic(expected = "with no corrupted parties")]
+fn dispute_without_corruptions() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = PartySet::new(vec![&mut alice, &mut bob]).with_threshold(0);
+    let _ = dispute::eliminate(&mut parties, &Dispute::new("alice", "bob", "wrong share"));
+}
+
+#[test]
+fn eliminate_too_few_parties() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = PartySet::new(vec![&mut alice, &mut bob]).with_threshold(1);
+
+    let dispute = Dispute::new("alice", "bob", "wrong share");
+    let error = dispute::eliminate(&mut parties, &dispute).unwrap_err();
+    assert_eq!(
+        error,
+        TooFewParties {
+            dispute,
+            remaining: 0
+        }
+    );
+    assert_eq!(parties.ids(), vec!["alice", "bob"]);
+    assert_eq!(parties.threshold(), Some(1));
+}
+
+#[test]
+fn restart_after_elimination() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut dave = VirtualMachine::new_cheating("dave", Behavior::Inconsistent(Fp::new(3)));
+    let mut eve: VirtualMachine<Fp> = VirtualMachine::new("eve");
+    for (value, party) in [&mut alice, &mut bob, &mut charlie, &mut dave, &mut eve]
+        .into_iter()
+        .enumerate()
+    {
+        party.insert_priv_value(&party.id.clone(), Fp::new(value as u64 + 1));
+    }
+
+    let parties = vec![&mut alice, &mut bob, &mut charlie, &mut dave, &mut eve];
+    let mut parties = PartySet::new(parties).with_threshold(2);
+    let mut executions = 0;
+    let (sum, disputes) = dispute::run_with_elimination(&mut parties, |parties| {
+        executions += 1;
+        let ids = parties.ids();
+        for id in ids.iter() {
+            broadcast::try_broadcast_protocol(parties.parties_mut(), id, id)?;
+        }
+        Ok(ids
+            .iter()
+            .fold(Fp::new(0), |acc, id| acc.add(parties[0].get_priv_value(id))))
+    })
+    .unwrap();
+
+    assert_eq!(executions, 2);
+    assert_eq!(
+        disputes,
+        vec![Dispute::new(
+            "bob",
+            "dave",
+            "received a different value in the broadcast"
+        )]
+    );
+    assert_eq!(parties.ids(), vec!["alice", "charlie", "eve"]);
+    assert_eq!(parties.threshold(), Some(1));
+    assert_eq!(sum.value(), 1 + 3 + 5);
+    assert_eq!(parties[0].insert_policy, InsertPolicy::Error);
+}
+
+#[test]
+fn restart_with_too_few_parties() {
+    let mut alice = VirtualMachine::new_cheating("alice", Behavior::Inconsistent(Fp::new(1)));
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    alice.insert_priv_value("alice", Fp::new(1));
+
+    let mut parties = PartySet::new(vec![&mut alice, &mut bob]).with_threshold(1);
+    let error = dispute::run_with_elimination(&mut parties, |parties| {
+        broadcast::try_broadcast_protocol(parties.parties_mut(), "alice", "alice")
+    })
+    .unwrap_err();
+    assert_eq!(error.dispute.accused, "alice");
+    assert_eq!(
+        error.to_string(),
+        "Eliminating bob and alice leaves only 0 parties."
+    );
+    assert_eq!(parties.ids(), vec!["alice", "bob"]);
+}
@@ -0,0 +1,35 @@
+use smol_mpc::math::group::Group;
+use smol_mpc::math::mersenne::Mersenne61;
+use smol_mpc::utils::elgamal;
+use smol_mpc::utils::prg::Prg;
+
+type Fp = Mersenne61;
+
+#[test]
+fn elgamal_encrypt_and_decrypt() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+    let (public_key, secret_key) = elgamal::keygen(&group, &mut prg);
+
+    let message = group.random_element(&mut prg);
+    let c1 = public_key.encrypt(&message, &mut prg);
+    let c2 = public_key.encrypt(&message, &mut prg);
+    assert_ne!(c1, c2);
+    assert_eq!(secret_key.decrypt(&c1), message);
+    assert_eq!(secret_key.decrypt(&c2), message);
+}
+
+#[test]
+fn elgamal_mul() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+    let (public_key, secret_key) = elgamal::keygen(&group, &mut prg);
+
+    let a = group.random_element(&mut prg);
+    let b = group.random_element(&mut prg);
+    let product = public_key.mul(
+        &public_key.encrypt(&a, &mut prg),
+        &public_key.encrypt(&b, &mut prg),
+    );
+    assert_eq!(secret_key.decrypt(&product), group.mul(&a, &b));
+}
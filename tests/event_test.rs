@@ -0,0 +1,72 @@
+use smol_mpc::event::Event;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::protocol::{self, Add};
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+use std::sync::{Arc, Mutex};
+
+type Fp = Mersenne61;
+
+fn logged(id: &str, log: &Arc<Mutex<Vec<String>>>) -> VirtualMachine<Fp> {
+    let mut vm = VirtualMachine::new(id);
+    let log = log.clone();
+    vm.add_hook(move |party, event: &Event<Fp>| {
+        log.lock().unwrap().push(format!("{}: {}", party, event))
+    });
+    vm
+}
+
+#[test]
+fn multiplication_events() {
+    let mut prg = Prg::new(None);
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut alice = logged("alice", &log);
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(2), &mut prg);
+    mpc::simulate_random_dist("y", &mut parties, &Fp::new(3), &mut prg);
+    log.lock().unwrap().clear();
+    mpc::mult_protocol(&mut parties, "x", "y", "z");
+
+    let log = log.lock().unwrap();
+    assert_eq!(log[0], "alice: sent 16 bytes to bob [beaver]");
+    assert!(log[1].starts_with("alice: opened ["));
+    assert!(log[1].ends_with("[beaver]"));
+    assert_eq!(log[2], "alice: share z inserted");
+    assert_eq!(log.len(), 3);
+}
+
+#[test]
+fn protocol_events() {
+    let mut prg = Prg::new(None);
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut alice = logged("alice", &log);
+    let mut bob = logged("bob", &log);
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(1), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(2), &mut prg);
+    log.lock().unwrap().clear();
+    protocol::execute(&mut parties, vec![Add::new("a", "b", "c"); 2]);
+    mpc::subtract_protocol(&mut parties, "c", "a", "d");
+
+    let log = log.lock().unwrap();
+    assert_eq!(log[0], "alice: started add");
+    assert_eq!(log[1], "bob: started add");
+    assert!(log.contains(&"alice: finished add".to_string()));
+    assert!(log.contains(&"bob: started subtract".to_string()));
+    assert!(log.contains(&"bob: finished subtract".to_string()));
+    assert_eq!(log.last().unwrap(), "bob: share d inserted");
+}
+
+#[test]
+fn clear_hooks() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut alice = logged("alice", &log);
+    alice.clear_hooks();
+    mpc::simulate_random_dist("x", &mut [&mut alice], &Fp::new(1), &mut Prg::new(None));
+    assert!(log.lock().unwrap().is_empty());
+}
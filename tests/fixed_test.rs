@@ -0,0 +1,25 @@
+use smol_mpc::math::fixed;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+
+type Fp = Mersenne61;
+
+#[test]
+fn fixed_encode_decode() {
+    let encoded: Fp = fixed::encode(3.25, 16);
+    assert_eq!(encoded.value(), 3 * (1 << 16) + (1 << 14));
+    assert_eq!(fixed::decode(&encoded, 16), 3.25);
+}
+
+#[test]
+fn fixed_encode_decode_negative() {
+    let encoded: Fp = fixed::encode(-1.5, 16);
+    assert_eq!(encoded.value(), Fp::ORDER - 3 * (1 << 15));
+    assert_eq!(fixed::decode(&encoded, 16), -1.5);
+}
+
+#[test]
+fn fixed_add() {
+    let a: Fp = fixed::encode(1.75, 16);
+    let b: Fp = fixed::encode(-4.5, 16);
+    assert_eq!(fixed::decode(&a.add(&b), 16), -2.75);
+}
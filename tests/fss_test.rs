@@ -0,0 +1,81 @@
+use smol_mpc::fss;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn dpf() {
+    let mut prg = Prg::new(None);
+    let (key_0, key_1) = fss::gen_dpf(11, &Fp::new(7), 4, &mut prg);
+    assert_eq!((key_0.party(), key_1.party()), (0, 1));
+    assert_eq!(key_0.depth(), 4);
+
+    let all_0 = key_0.eval_all();
+    let all_1 = key_1.eval_all();
+    for x in 0..16 {
+        let expected = if x == 11 { 7 } else { 0 };
+        assert_eq!(key_0.eval(x).add(&key_1.eval(x)).value(), expected);
+        assert_eq!(all_0[x as usize].value(), key_0.eval(x).value());
+        assert_eq!(all_1[x as usize].value(), key_1.eval(x).value());
+    }
+    assert_ne!(key_0.eval(11).value(), 7);
+}
+
+#[test]
+fn dpf_single_point_domain() {
+    let mut prg = Prg::new(None);
+    let (key_0, key_1) = fss::gen_dpf(0, &Fp::new(3), 0, &mut prg);
+    assert_eq!(key_0.eval(0).add(&key_1.eval(0)).value(), 3);
+}
+
+#[test]
+#[should_panic(expected = "The input 16 is outside the domain of the function.")]
+fn dpf_input_outside_domain() {
+    let mut prg = Prg::new(None);
+    fss::gen_dpf(16, &Fp::new(1), 4, &mut prg);
+}
+
+#[test]
+fn pir() {
+    let mut prg = Prg::new(None);
+    let mut server_1: VirtualMachine<Fp> = VirtualMachine::new("server_1");
+    let mut server_2: VirtualMachine<Fp> = VirtualMachine::new("server_2");
+    let ids = ["r0", "r1", "r2", "r3", "r4"];
+    for (i, id) in ids.iter().enumerate() {
+        server_1.insert_priv_value(id, Fp::new(100 + i as u64));
+        server_2.insert_priv_value(id, Fp::new(100 + i as u64));
+    }
+
+    let servers = vec![&mut server_1, &mut server_2];
+    for index in 0..ids.len() as u64 {
+        assert_eq!(
+            fss::pir_protocol(&servers, &ids, index, &mut prg).value(),
+            100 + index
+        );
+    }
+}
+
+#[test]
+fn dcf() {
+    let mut prg = Prg::new(None);
+    for alpha in [0, 6, 15] {
+        let (key_0, key_1) = fss::gen_dcf(alpha, &Fp::new(9), 4, &mut prg);
+        assert_eq!((key_0.party(), key_1.party()), (0, 1));
+        for x in 0..16 {
+            let expected = if x < alpha { 9 } else { 0 };
+            assert_eq!(key_0.eval(x).add(&key_1.eval(x)).value(), expected);
+        }
+    }
+}
+
+#[test]
+fn dcf_large_domain() {
+    let mut prg = Prg::new(None);
+    let alpha = Fp::ORDER - 5;
+    let (key_0, key_1) = fss::gen_dcf(alpha, &Fp::new(1), Fp::POWER as usize, &mut prg);
+    for (x, expected) in [(0, 1), (alpha - 1, 1), (alpha, 0), (Fp::ORDER, 0)] {
+        assert_eq!(key_0.eval(x).add(&key_1.eval(x)).value(), expected);
+    }
+}
@@ -0,0 +1,183 @@
+use smol_mpc::circuit::CircuitBuilder;
+use smol_mpc::gc::{self, Circuit, GarblingScheme, Gate};
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::network::Simulator;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+const SCHEMES: [GarblingScheme; 4] = [
+    GarblingScheme::Textbook,
+    GarblingScheme::PointAndPermute,
+    GarblingScheme::FreeXor,
+    GarblingScheme::HalfGates,
+];
+
+/// Circuit that computes (a AND b) XOR c, where the garbler holds a and the
+/// evaluator holds b and c.
+fn and_xor_circuit() -> Circuit {
+    Circuit {
+        n_garbler_inputs: 1,
+        n_evaluator_inputs: 2,
+        n_wires: 5,
+        gates: vec![Gate::And(0, 1, 3), Gate::Xor(3, 2, 4)],
+        outputs: vec![4],
+    }
+}
+
+/// Circuit that adds two 2-bit numbers, where the garbler holds the first one
+/// and the evaluator the second one, with the bits in little-endian order.
+fn adder_circuit() -> Circuit {
+    Circuit {
+        n_garbler_inputs: 2,
+        n_evaluator_inputs: 2,
+        n_wires: 11,
+        gates: vec![
+            Gate::Xor(0, 2, 4),
+            Gate::And(0, 2, 5),
+            Gate::Xor(1, 3, 6),
+            Gate::Xor(6, 5, 7),
+            Gate::And(1, 3, 8),
+            Gate::And(6, 5, 9),
+            Gate::Xor(8, 9, 10),
+        ],
+        outputs: vec![4, 7, 10],
+    }
+}
+
+#[test]
+fn garble_and_evaluate() {
+    let mut prg = Prg::new(None);
+    let circuit = and_xor_circuit();
+
+    for scheme in SCHEMES {
+        for input in 0..8u8 {
+            let bits = [input & 1 == 1, input & 2 == 2, input & 4 == 4];
+            let (garbler, garbled) = gc::garble(&circuit, scheme, &mut prg);
+            let labels: Vec<gc::Label> = bits
+                .iter()
+                .enumerate()
+                .map(|(wire, bit)| garbler.encode(wire, *bit))
+                .collect();
+
+            let output = gc::evaluate(&circuit, &garbled, &labels);
+            assert_eq!(output, vec![(bits[0] & bits[1]) ^ bits[2]]);
+        }
+    }
+}
+
+#[test]
+fn n_ciphertexts() {
+    let mut prg = Prg::new(None);
+    let circuit = and_xor_circuit();
+
+    let counts: Vec<usize> = SCHEMES
+        .iter()
+        .map(|scheme| gc::garble(&circuit, *scheme, &mut prg).1.n_ciphertexts())
+        .collect();
+    assert_eq!(counts, vec![8, 8, 4, 2]);
+}
+
+#[test]
+fn half_gates_matches_textbook() {
+    let mut prg = Prg::new(None);
+    let circuit = adder_circuit();
+
+    for input in 0..16u8 {
+        let bits: Vec<bool> = (0..4).map(|i| input >> i & 1 == 1).collect();
+        let outputs: Vec<Vec<bool>> = [GarblingScheme::Textbook, GarblingScheme::HalfGates]
+            .iter()
+            .map(|scheme| {
+                let (garbler, garbled) = gc::garble(&circuit, *scheme, &mut prg);
+                let labels: Vec<gc::Label> = bits
+                    .iter()
+                    .enumerate()
+                    .map(|(wire, bit)| garbler.encode(wire, *bit))
+                    .collect();
+                gc::evaluate(&circuit, &garbled, &labels)
+            })
+            .collect();
+
+        let sum = (input & 3) + (input >> 2);
+        assert_eq!(
+            outputs[0],
+            (0..3).map(|i| sum >> i & 1 == 1).collect::<Vec<bool>>()
+        );
+        assert_eq!(outputs[0], outputs[1]);
+    }
+}
+
+#[test]
+fn from_ir() {
+    let mut prg = Prg::new(None);
+
+    // Computes NOT (x AND z) XOR y, with the wires of the inputs interleaved
+    // with the gates.
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let z = builder.input();
+    let and = builder.and(x, z);
+    let y = builder.input();
+    let not = builder.inv(and);
+    let result = builder.xor(not, y);
+    builder.output(result);
+    let ir = builder.build();
+
+    let circuit = Circuit::from_ir(&ir, 1);
+    assert_eq!(circuit.n_garbler_inputs, 1);
+    assert_eq!(circuit.n_evaluator_inputs, 2);
+    assert_eq!(circuit.n_wires, ir.n_wires);
+
+    for scheme in SCHEMES {
+        for input in 0..8u64 {
+            let bits: Vec<u64> = (0..3).map(|i| input >> i & 1).collect();
+            let (garbler, garbled) = gc::garble(&circuit, scheme, &mut prg);
+            let labels: Vec<gc::Label> = bits
+                .iter()
+                .enumerate()
+                .map(|(wire, bit)| garbler.encode(wire, *bit == 1))
+                .collect();
+
+            let output = gc::evaluate(&circuit, &garbled, &labels);
+            let expected = ir.evaluate(&bits.iter().map(|bit| Fp::new(*bit)).collect::<Vec<Fp>>());
+            assert_eq!(output, vec![expected[0].value() == 1]);
+        }
+    }
+}
+
+#[test]
+fn yao_protocol() {
+    let mut prg = Prg::new(None);
+    let circuit = and_xor_circuit();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    alice.insert_priv_value("a", Fp::new(1));
+    bob.insert_priv_value("b", Fp::new(1));
+    bob.insert_priv_value("c", Fp::new(1));
+
+    gc::yao_protocol(
+        &mut alice,
+        &mut bob,
+        &circuit,
+        &["a"],
+        &["b", "c"],
+        &["out"],
+        GarblingScheme::FreeXor,
+        &mut prg,
+    );
+    assert_eq!(alice.get_priv_value("out").value(), 0);
+    assert_eq!(bob.get_priv_value("out").value(), 0);
+
+    // The labels of the evaluator inputs are obtained with networked OTs.
+    let parties = vec![&mut alice, &mut bob];
+    let network = Simulator::new(&parties);
+    let stats = network.stats();
+    assert_eq!(stats[0].bytes_sent, 2 * 2 * gc::LABEL_LEN);
+    assert!(network
+        .transcript()
+        .records()
+        .iter()
+        .all(|record| record.message.tag == "iknp"));
+}
@@ -0,0 +1,47 @@
+use smol_mpc::utils::ggm::{self, GgmTree, SEED_LEN};
+use smol_mpc::utils::prg::Prg;
+
+#[test]
+fn eval_follows_the_path() {
+    let tree = GgmTree::new([3; SEED_LEN], 2);
+    let [left, right] = ggm::expand(&[3; SEED_LEN]);
+
+    assert_eq!(tree.eval(0), ggm::expand(&left)[0]);
+    assert_eq!(tree.eval(3), ggm::expand(&right)[1]);
+    assert_eq!(GgmTree::new([3; SEED_LEN], 0).eval(0), [3; SEED_LEN]);
+}
+
+#[test]
+fn leaves_match_eval() {
+    let mut prg = Prg::new(None);
+    let tree = GgmTree::random(5, &mut prg);
+    let leaves = tree.leaves();
+
+    assert_eq!(leaves.len() as u64, tree.domain_size());
+    for (index, leaf) in leaves.iter().enumerate() {
+        assert_eq!(*leaf, tree.eval(index as u64));
+    }
+}
+
+#[test]
+fn puncture() {
+    let mut prg = Prg::new(None);
+    let tree = GgmTree::random(5, &mut prg);
+    let key = tree.puncture(13);
+    let leaves = key.leaves();
+
+    assert_eq!(key.index(), 13);
+    assert_eq!(key.co_path().len(), 5);
+    assert!(!key.co_path().contains(&tree.eval(13)));
+    for index in 0..tree.domain_size() {
+        let expected = (index != 13).then(|| tree.eval(index));
+        assert_eq!(key.eval(index), expected);
+        assert_eq!(leaves[index as usize], expected);
+    }
+}
+
+#[test]
+#[should_panic(expected = "The index 8 is outside the domain of the tree.")]
+fn index_outside_domain() {
+    GgmTree::new([0; SEED_LEN], 3).eval(8);
+}
@@ -0,0 +1,126 @@
+use smol_mpc::circuit::CircuitBuilder;
+use smol_mpc::math::mersenne::Mersenne61;
+use smol_mpc::mpc::{self, gmw};
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn xor_and_not() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::simulate_bool_dist("x", &mut parties, true, &mut prg);
+    mpc::simulate_bool_dist("y", &mut parties, false, &mut prg);
+    gmw::xor_protocol(&mut parties, "x", "y", "x_xor_y");
+    gmw::not_protocol(&mut parties, "x", "not_x");
+
+    assert!(mpc::reconstruct_bool_share(&parties, "x_xor_y"));
+    assert!(!mpc::reconstruct_bool_share(&parties, "not_x"));
+}
+
+#[test]
+fn and_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    gmw::generate_and_triples(&mut parties, 4, &mut prg);
+    let inputs = [(false, false), (false, true), (true, false), (true, true)];
+    let ids = [
+        ("x0", "y0", "r0"),
+        ("x1", "y1", "r1"),
+        ("x2", "y2", "r2"),
+        ("x3", "y3", "r3"),
+    ];
+    for ((x, y), (id_x, id_y, id_result)) in inputs.into_iter().zip(ids) {
+        mpc::simulate_bool_dist(id_x, &mut parties, x, &mut prg);
+        mpc::simulate_bool_dist(id_y, &mut parties, y, &mut prg);
+        gmw::and_protocol(&mut parties, id_x, id_y, id_result);
+
+        assert_eq!(mpc::reconstruct_bool_share(&parties, id_result), x & y);
+    }
+}
+
+#[test]
+fn ot_and_triple_protocol() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    let handles = gmw::ot_and_triple_protocol(&mut parties, 20);
+    assert_eq!(handles.len(), 20);
+    for _ in 0..20 {
+        let (_, (a0, b0, c0)) = parties[0].preprocessing.next_and_triple().unwrap();
+        let (_, (a1, b1, c1)) = parties[1].preprocessing.next_and_triple().unwrap();
+        assert_eq!(c0 ^ c1, (a0 ^ a1) & (b0 ^ b1));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Not enough AND triples")]
+fn and_protocol_without_triples() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_bool_dist("x", &mut parties, true, &mut prg);
+    mpc::simulate_bool_dist("y", &mut parties, true, &mut prg);
+    gmw::and_protocol(&mut parties, "x", "y", "z");
+}
+
+#[test]
+fn evaluate_circuit() {
+    let mut prg = Prg::new(None);
+
+    // Computes (x AND y) XOR (NOT z).
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let z = builder.input();
+    let and = builder.and(x, y);
+    let not = builder.inv(z);
+    let result = builder.xor(and, not);
+    builder.output(result);
+    let circuit = builder.build();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    gmw::generate_and_triples(&mut parties, 4, &mut prg);
+    let inputs = [
+        [false, true, true],
+        [true, true, false],
+        [true, false, true],
+        [true, true, true],
+    ];
+    let ids = [
+        (["x0", "y0", "z0"], "r0"),
+        (["x1", "y1", "z1"], "r1"),
+        (["x2", "y2", "z2"], "r2"),
+        (["x3", "y3", "z3"], "r3"),
+    ];
+    for (bits, (ids_inputs, id_result)) in inputs.into_iter().zip(ids) {
+        for (id, bit) in ids_inputs.into_iter().zip(bits) {
+            mpc::simulate_bool_dist(id, &mut parties, bit, &mut prg);
+        }
+        gmw::evaluate_circuit(&mut parties, &circuit, &ids_inputs, &[id_result]);
+
+        assert_eq!(
+            mpc::reconstruct_bool_share(&parties, id_result),
+            (bits[0] & bits[1]) ^ !bits[2]
+        );
+    }
+    assert_eq!(parties[0].preprocessing.remaining_and_triples(), 0);
+}
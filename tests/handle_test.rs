@@ -0,0 +1,54 @@
+use smol_mpc::dealer::Dealer;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc::{self, gmw};
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn arithmetic_handles() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    alice.insert_priv_value("a", Fp::new(6));
+    let a = mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
+    assert_eq!(a.id(), "a");
+
+    let mut parties = vec![&mut alice, &mut bob];
+    let b = mpc::simulate_random_dist("b", &mut parties, &Fp::new(4), &mut prg);
+    let mut dealer = Dealer::new(Prg::new(Some(vec![1])));
+    dealer.deal_triple(&mut parties, ("t1", "t2", "t3"));
+    mpc::register_triple(&mut parties, ("t1", "t2", "t3"));
+
+    let difference = mpc::subtract_protocol(&mut parties, &a, &b, "difference");
+    let product = mpc::mult_protocol(&mut parties, &difference, "b", "product");
+    let scaled = mpc::multiply_by_const_protocol(&mut parties, &Fp::new(3), &product, "scaled");
+    let result = mpc::add_const_protocol(&mut parties, &Fp::new(1), &scaled, "result");
+
+    assert_eq!(mpc::reconstruct_share(&parties, &result).value(), 25);
+    assert_eq!(
+        mpc::reconstruct_share(&parties, result.clone().id()).value(),
+        25
+    );
+}
+
+#[test]
+fn boolean_handles() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    gmw::generate_and_triples(&mut parties, 1, &mut prg);
+    let x = mpc::simulate_bool_dist("x", &mut parties, true, &mut prg);
+    let y = mpc::simulate_bool_dist("y", &mut parties, true, &mut prg);
+    let and = gmw::and_protocol(&mut parties, &x, &y, "and");
+    let not = gmw::not_protocol(&mut parties, &and, "not");
+    let xor = gmw::xor_protocol(&mut parties, &not, &x, "xor");
+
+    assert!(mpc::reconstruct_bool_share(&parties, &xor));
+    assert_eq!(xor.id(), "xor");
+}
@@ -0,0 +1,52 @@
+use smol_mpc::math::matrix::Matrix;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+
+type Fp = Mersenne61;
+
+fn to_matrix(rows: Vec<Vec<u64>>) -> Matrix<Fp> {
+    Matrix::from_rows(
+        rows.into_iter()
+            .map(|row| row.into_iter().map(Fp::new).collect())
+            .collect(),
+    )
+}
+
+#[test]
+fn matrix_add() {
+    let a = to_matrix(vec![vec![1, 2], vec![3, 4]]);
+    let b = to_matrix(vec![vec![5, 6], vec![7, 8]]);
+
+    let sum = a.add(&b);
+    let values: Vec<u64> = sum.iter().map(|elem| elem.value()).collect();
+    assert_eq!(values, vec![6, 8, 10, 12]);
+}
+
+#[test]
+fn matrix_multiply() {
+    let a = to_matrix(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    let b = to_matrix(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+
+    let prod = a.multiply(&b);
+    assert_eq!(prod.rows(), 2);
+    assert_eq!(prod.cols(), 2);
+
+    let values: Vec<u64> = prod.iter().map(|elem| elem.value()).collect();
+    assert_eq!(values, vec![58, 64, 139, 154]);
+}
+
+#[test]
+#[should_panic]
+fn matrix_multiply_wrong_dimensions() {
+    let a = to_matrix(vec![vec![1, 2], vec![3, 4]]);
+    let b = to_matrix(vec![vec![1, 2, 3]]);
+
+    a.multiply(&b);
+}
+
+#[test]
+fn matrix_transpose() {
+    let m = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    let t = m.transpose();
+    assert_eq!((t.rows(), t.cols()), (3, 2));
+    assert_eq!(t.row(2), &[3, 6]);
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "sha2")]
+
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::utils::merkle::MerkleTree;
+
+type Fp = Mersenne61;
+
+#[test]
+fn proofs_for_every_size() {
+    for n_leaves in 1..10 {
+        let values: Vec<Vec<u8>> = (0..n_leaves).map(|i| vec![i as u8; i + 1]).collect();
+        let tree = MerkleTree::new(&values);
+        assert_eq!(tree.n_leaves(), n_leaves);
+
+        for (index, value) in values.iter().enumerate() {
+            let proof = tree.prove(index);
+            assert!(proof.verify(&tree.root(), value));
+            assert!(!proof.verify(&tree.root(), b"other"));
+        }
+    }
+}
+
+#[test]
+fn wrong_index_or_root() {
+    let values = ["a", "b", "c", "d", "e"];
+    let tree = MerkleTree::new(&values);
+    let other_tree = MerkleTree::new(&["a", "b", "c", "d", "f"]);
+    assert_ne!(tree.root(), other_tree.root());
+
+    let mut proof = tree.prove(2);
+    assert!(!proof.verify(&other_tree.root(), b"c"));
+    proof.index = 3;
+    assert!(!proof.verify(&tree.root(), b"c"));
+}
+
+#[test]
+fn field_elements() {
+    let elements: Vec<Fp> = (0..6).map(Fp::new).collect();
+    let tree = MerkleTree::from_field_elements(&elements);
+
+    let proof = tree.prove(4);
+    assert!(proof.verify_field_element(&tree.root(), &Fp::new(4)));
+    assert!(!proof.verify_field_element(&tree.root(), &Fp::new(5)));
+}
+
+#[test]
+#[should_panic(expected = "The tree does not have a leaf with index 3.")]
+fn prove_missing_leaf() {
+    MerkleTree::new(&["a", "b", "c"]).prove(3);
+}
@@ -81,3 +81,13 @@ fn mersenne61_prg() {
     let product = rand_mersenne.multiply(&rand_mersenne.inverse());
     assert_eq!(product.value, 1);
 }
+
+#[test]
+fn mersenne61_pow() {
+    let a = Mersenne61::new(3);
+    assert_eq!(a.pow(13).value, 1594323);
+    assert_eq!(a.pow(0).value, 1);
+
+    // Fermat's little theorem.
+    assert_eq!(a.pow(Mersenne61::ORDER - 1).value, 1);
+}
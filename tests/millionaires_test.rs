@@ -0,0 +1,85 @@
+use std::cmp::Ordering;
+
+use smol_mpc::apps::millionaires;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::network::Simulator;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn run() {
+    assert_eq!(millionaires::run(1_000_000, 2_500_000), Ordering::Less);
+    assert_eq!(millionaires::run(2_500_000, 1_000_000), Ordering::Greater);
+    assert_eq!(millionaires::run(7_000_000, 7_000_000), Ordering::Equal);
+    assert_eq!(millionaires::run(0, 0), Ordering::Equal);
+
+    let max = Fp::ORDER / 4 - 1;
+    assert_eq!(millionaires::run(max, 0), Ordering::Greater);
+    assert_eq!(millionaires::run(max - 1, max), Ordering::Less);
+}
+
+#[test]
+#[should_panic(expected = "The wealth of a millionaire must be smaller than")]
+fn run_value_too_large() {
+    millionaires::run(Fp::ORDER / 4, 0);
+}
+
+#[test]
+fn millionaires_protocol() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(10), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(20), &mut prg);
+    assert_eq!(
+        millionaires::millionaires_protocol(&mut parties, "a", "b", &mut prg),
+        Ordering::Less
+    );
+    assert_eq!(
+        millionaires::millionaires_protocol(&mut parties, "b", "a", &mut prg),
+        Ordering::Greater
+    );
+    assert_eq!(
+        millionaires::millionaires_protocol(&mut parties, "a", "a", &mut prg),
+        Ordering::Equal
+    );
+
+    // Only the inputs remain, and all the keys have been consumed.
+    assert_eq!(parties[0].shares().len(), 2);
+    assert_eq!(parties[0].preprocessing.remaining_comparison_keys(), 0);
+}
+
+#[test]
+fn dealer_masks() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(10), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(20), &mut prg);
+
+    // Two executions with fresh dealers open differently masked inputs.
+    let mut opened = Vec::new();
+    for _ in 0..2 {
+        Simulator::new(&parties).reset_stats();
+        let mut dealer = millionaires::dealer();
+        millionaires::millionaires_protocol(&mut parties, "a", "b", &mut dealer);
+        opened.push(Simulator::new(&parties).view("alice").opened);
+    }
+    assert!(!opened[0].is_empty());
+    assert_ne!(
+        opened[0]
+            .iter()
+            .map(|value| value.value())
+            .collect::<Vec<u64>>(),
+        opened[1]
+            .iter()
+            .map(|value| value.value())
+            .collect::<Vec<u64>>()
+    );
+}
@@ -0,0 +1,68 @@
+use smol_mpc::circuit::Backend;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mimc::{self, Mimc};
+use smol_mpc::mpc;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn mimc_parameters() {
+    let mut prg = Prg::new(None);
+    let cipher: Mimc<Fp> = Mimc::new(&mut prg);
+
+    // x^3 is not a permutation because 3 divides p - 1.
+    assert_eq!(cipher.exponent(), 17);
+    assert_eq!(cipher.n_rounds(), 15);
+    assert_eq!(cipher.constants()[0].value(), 0);
+
+    let circuit = cipher.circuit();
+    assert_eq!(circuit.gate_counts().mul, 5 * 15);
+    assert_eq!(circuit.multiplicative_depth(), 5 * 15);
+    assert_eq!(
+        circuit.estimate_cost(Backend::SecretSharing, 2).rounds,
+        5 * 15
+    );
+}
+
+#[test]
+fn mimc_encrypt() {
+    let mut prg = Prg::new(None);
+    let cipher: Mimc<Fp> = Mimc::new(&mut prg);
+
+    let key = Fp::random(&mut prg);
+    let a = cipher.encrypt(&key, &Fp::new(1));
+    let b = cipher.encrypt(&key, &Fp::new(2));
+    assert_ne!(a.value(), b.value());
+    assert_ne!(
+        a.value(),
+        cipher.encrypt(&key.add(&Fp::new(1)), &Fp::new(1)).value()
+    );
+    assert_eq!(
+        cipher.circuit().evaluate(&[key, Fp::new(1)])[0].value(),
+        a.value()
+    );
+}
+
+#[test]
+fn mimc_protocol() {
+    let mut prg = Prg::new(None);
+    let cipher: Mimc<Fp> = Mimc::new(&mut prg);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::simulate_random_dist("key", &mut parties, &Fp::new(1234), &mut prg);
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(42), &mut prg);
+    mimc::mimc_protocol(&mut parties, &cipher, "key", "x", "y", Some(&mut prg));
+
+    let expected = cipher.encrypt(&Fp::new(1234), &Fp::new(42));
+    assert_eq!(
+        mpc::reconstruct_share(&parties, "y").value(),
+        expected.value()
+    );
+    assert_eq!(parties[0].preprocessing.remaining_triples(), 0);
+}
@@ -1,22 +1,44 @@
 use std::vec;
 
+use smol_mpc::adversary::Behavior;
+use smol_mpc::circuit::CircuitBuilder;
+use smol_mpc::counter;
+use smol_mpc::math::fixed;
+use smol_mpc::math::matrix::Matrix;
 use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
-use smol_mpc::mpc;
+use smol_mpc::mpc::{self, NotInvertible, Share};
+use smol_mpc::network::MissingParty;
+use smol_mpc::preprocessing::TripleHandle;
 use smol_mpc::utils::prg::Prg;
 use smol_mpc::vm::VirtualMachine;
 
 type Fp = Mersenne61;
 
+fn triple_names(count: usize) -> Vec<(String, String, String)> {
+    (0..count)
+        .map(|i| (format!("a{i}"), format!("b{i}"), format!("c{i}")))
+        .collect()
+}
+
+fn as_triple_ids(names: &[(String, String, String)]) -> Vec<(&str, &str, &str)> {
+    names
+        .iter()
+        .map(|(a, b, c)| (a.as_str(), b.as_str(), c.as_str()))
+        .collect()
+}
+
+fn bit_names(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("bit{i}")).collect()
+}
+
 #[test]
 fn mpc_distribute_share() {
-    let mut prg = Prg::new(None);
-
     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
     alice.insert_priv_value("a", Fp::new(4));
 
-    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 
     let share_alice = alice.get_share("a");
     let share_bob = bob.get_share("a");
@@ -28,34 +50,30 @@ fn mpc_distribute_share() {
 
 #[test]
 fn reconstruct_share() {
-    let mut prg = Prg::new(None);
-
     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
     alice.insert_priv_value("a", Fp::new(4));
-    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 
-    let reconstructed_value = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "a");
+    let reconstructed_value = mpc::reconstruct_share(&[&mut alice, &mut bob], "a");
     assert_eq!(reconstructed_value.value, 4);
 }
 
 #[test]
 fn add_protocol() {
-    let mut prg = Prg::new(None);
-
     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
     alice.insert_priv_value("a", Fp::new(4));
-    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 
     bob.insert_priv_value("b", Fp::new(2));
-    mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob]);
 
-    mpc::add_protocol(&mut vec![&mut alice, &mut bob], "a", "b", "c");
+    mpc::add_protocol(&mut [&mut alice, &mut bob], "a", "b", "c");
 
-    let sum = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "c");
+    let sum = mpc::reconstruct_share(&[&mut alice, &mut bob], "c");
     assert_eq!(sum.value, 6);
 }
 
@@ -67,9 +85,9 @@ fn simulate_random_distribution() {
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
     let value = Fp::new(10);
-    mpc::simulate_random_dist("a", &mut vec![&mut alice, &mut bob], &value, &mut prg);
+    mpc::simulate_random_dist("a", &mut [&mut alice, &mut bob], &value, &mut prg);
 
-    let reconstruction = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "a");
+    let reconstruction = mpc::reconstruct_share(&[&mut alice, &mut bob], "a");
     assert_eq!(reconstruction.value(), 10);
 }
 
@@ -80,27 +98,25 @@ fn generate_triple() {
     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
-    mpc::generate_triple(&mut vec![&mut alice, &mut bob], ("a", "b", "c"), &mut prg);
-    let rec_a = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "a");
-    let rec_b = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "b");
-    let rec_c = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "c");
+    mpc::generate_triple(&mut [&mut alice, &mut bob], ("a", "b", "c"), &mut prg);
+    let rec_a = mpc::reconstruct_share(&[&mut alice, &mut bob], "a");
+    let rec_b = mpc::reconstruct_share(&[&mut alice, &mut bob], "b");
+    let rec_c = mpc::reconstruct_share(&[&mut alice, &mut bob], "c");
 
     assert_eq!(rec_a.multiply(&rec_b).value(), rec_c.value());
 }
 
 #[test]
 fn mult_by_const() {
-    let mut prg = Prg::new(None);
-
     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
     alice.insert_priv_value("a", Fp::new(4));
-    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 
     let pub_val = Fp::new(6);
-    mpc::multiply_by_const_protocol(&mut vec![&mut alice, &mut bob], &pub_val, "a", "m");
-    let reconst = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "m");
+    mpc::multiply_by_const_protocol(&mut [&mut alice, &mut bob], &pub_val, "a", "m");
+    let reconst = mpc::reconstruct_share(&[&mut alice, &mut bob], "m");
     assert_eq!(reconst.value, 24);
 }
 
@@ -111,47 +127,52 @@ fn multiplication() {
     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
-    mpc::generate_triple(
-        &mut vec![&mut alice, &mut bob],
-        ("x1", "x2", "x3"),
-        &mut prg,
-    );
+    mpc::generate_triple(&mut [&mut alice, &mut bob], ("x1", "x2", "x3"), &mut prg);
+    mpc::register_triple(&mut [&mut alice, &mut bob], ("x1", "x2", "x3"));
 
     alice.insert_priv_value("a", Fp::new(4));
-    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 
     bob.insert_priv_value("b", Fp::new(2));
-    mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob]);
 
-    mpc::mult_protocol(
-        &mut vec![&mut alice, &mut bob],
-        "a",
-        "b",
-        "prod",
-        ("x1", "x2", "x3"),
-    );
+    mpc::mult_protocol(&mut [&mut alice, &mut bob], "a", "b", "prod");
 
-    let mult_reconst = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "prod");
+    let mult_reconst = mpc::reconstruct_share(&[&mut alice, &mut bob], "prod");
 
-    assert_eq!(mult_reconst.value(), 8)
+    assert_eq!(mult_reconst.value(), 8);
+    assert_eq!(alice.preprocessing.remaining_triples(), 0);
+    assert!(!alice.contains_share("x1"));
 }
 
 #[test]
-fn subtract_protocol() {
+#[should_panic(expected = "Not enough triples")]
+fn multiplication_without_triples() {
     let mut prg = Prg::new(None);
 
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(3), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(5), &mut prg);
+    mpc::mult_protocol(&mut parties, "a", "b", "prod");
+}
+
+#[test]
+fn subtract_protocol() {
     let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
     alice.insert_priv_value("a", Fp::new(4));
-    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
 
     bob.insert_priv_value("b", Fp::new(6));
-    mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob], &mut prg);
+    mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob]);
 
-    mpc::subtract_protocol(&mut vec![&mut alice, &mut bob], "a", "b", "c");
+    mpc::subtract_protocol(&mut [&mut alice, &mut bob], "a", "b", "c");
 
-    let subs = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "c");
+    let subs = mpc::reconstruct_share(&[&mut alice, &mut bob], "c");
     assert_eq!(subs.value, Fp::ORDER - 2);
 }
 
@@ -161,8 +182,1384 @@ fn distribute_pub_value() {
     let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
 
     let value = Fp::new(100);
-    mpc::distribute_pub_value(&value, "v", &mut vec![&mut alice, &mut bob]);
+    mpc::distribute_pub_value(&value, "v", &mut [&mut alice, &mut bob]);
 
-    let rec_value = mpc::reconstruct_share(&mut vec![&mut alice, &mut bob], "v");
+    let rec_value = mpc::reconstruct_share(&[&mut alice, &mut bob], "v");
     assert_eq!(rec_value.value(), 100);
 }
+
+#[test]
+fn reshare() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let mut carol: VirtualMachine<Fp> = VirtualMachine::new("carol");
+    let mut dave: VirtualMachine<Fp> = VirtualMachine::new("dave");
+    let mut eve: VirtualMachine<Fp> = VirtualMachine::new("eve");
+
+    alice.insert_priv_value("a", Fp::new(4));
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
+
+    mpc::reshare(
+        &mut [&mut alice, &mut bob],
+        &mut [&mut carol, &mut dave, &mut eve],
+        "a",
+    );
+
+    assert!(!alice.contains_share("a"));
+    assert!(!bob.contains_share("a"));
+
+    let rec_value = mpc::reconstruct_share(&[&mut carol, &mut dave, &mut eve], "a");
+    assert_eq!(rec_value.value(), 4);
+}
+
+#[test]
+fn prss_random_share() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut carol: VirtualMachine<Fp> = VirtualMachine::new("carol");
+
+    mpc::prss_setup(&mut [&mut alice, &mut bob, &mut carol], 1);
+    assert_eq!(alice.prss_keys.len(), 2);
+
+    alice.prss_random_share("r");
+    bob.prss_random_share("r");
+    carol.prss_random_share("r");
+
+    alice.prss_random_share("s");
+    bob.prss_random_share("s");
+    carol.prss_random_share("s");
+
+    let rec_r = mpc::reconstruct_share(&[&mut alice, &mut bob, &mut carol], "r");
+    let rec_s = mpc::reconstruct_share(&[&mut alice, &mut bob, &mut carol], "s");
+    assert_ne!(rec_r.value(), 0);
+    assert_ne!(rec_r.value(), rec_s.value());
+}
+
+#[test]
+fn inner_product_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let ids_x = ["x1", "x2", "x3"];
+    let ids_y = ["y1", "y2", "y3"];
+    let triples = [("a1", "b1", "c1"), ("a2", "b2", "c2"), ("a3", "b3", "c3")];
+
+    for (i, id) in ids_x.iter().enumerate() {
+        alice.insert_priv_value(id, Fp::new(i as u64 + 1));
+        mpc::distribute_shares(id, "alice", vec![&mut alice, &mut bob]);
+    }
+
+    for (i, id) in ids_y.iter().enumerate() {
+        bob.insert_priv_value(id, Fp::new(i as u64 + 4));
+        mpc::distribute_shares(id, "bob", vec![&mut alice, &mut bob]);
+    }
+
+    for triple in triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], triple, &mut prg);
+    }
+
+    mpc::inner_product_protocol(&mut [&mut alice, &mut bob], &ids_x, &ids_y, "ip", &triples);
+
+    let rec_value = mpc::reconstruct_share(&[&mut alice, &mut bob], "ip");
+    assert_eq!(rec_value.value(), 32);
+}
+
+#[test]
+fn matmul_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let ids_x = Matrix::from_rows(vec![vec!["x11", "x12"], vec!["x21", "x22"]]);
+    let ids_y = Matrix::from_rows(vec![vec!["y11", "y12"], vec!["y21", "y22"]]);
+    let ids_z = Matrix::from_rows(vec![vec!["z11", "z12"], vec!["z21", "z22"]]);
+
+    let ids_a = Matrix::from_rows(vec![vec!["a11", "a12"], vec!["a21", "a22"]]);
+    let ids_b = Matrix::from_rows(vec![vec!["b11", "b12"], vec!["b21", "b22"]]);
+    let ids_c = Matrix::from_rows(vec![vec!["c11", "c12"], vec!["c21", "c22"]]);
+
+    for (i, id) in ids_x.iter().enumerate() {
+        alice.insert_priv_value(id, Fp::new(i as u64 + 1));
+        mpc::distribute_shares(id, "alice", vec![&mut alice, &mut bob]);
+    }
+
+    for (i, id) in ids_y.iter().enumerate() {
+        bob.insert_priv_value(id, Fp::new(i as u64 + 5));
+        mpc::distribute_shares(id, "bob", vec![&mut alice, &mut bob]);
+    }
+
+    mpc::generate_matrix_triple(
+        &mut [&mut alice, &mut bob],
+        (&ids_a, &ids_b, &ids_c),
+        &mut prg,
+    );
+
+    mpc::matmul_protocol(
+        &mut [&mut alice, &mut bob],
+        &ids_x,
+        &ids_y,
+        &ids_z,
+        (&ids_a, &ids_b, &ids_c),
+    );
+
+    let result: Vec<u64> = ids_z
+        .iter()
+        .map(|id| mpc::reconstruct_share(&[&mut alice, &mut bob], id).value())
+        .collect();
+    assert_eq!(result, vec![19, 22, 43, 50]);
+}
+
+#[test]
+fn product_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut carol: VirtualMachine<Fp> = VirtualMachine::new("carol");
+
+    let ids = ["v1", "v2", "v3", "v4", "v5"];
+    let triples = [
+        ("a1", "b1", "c1"),
+        ("a2", "b2", "c2"),
+        ("a3", "b3", "c3"),
+        ("a4", "b4", "c4"),
+    ];
+
+    for (i, id) in ids.iter().enumerate() {
+        alice.insert_priv_value(id, Fp::new(i as u64 + 1));
+        mpc::distribute_shares(id, "alice", vec![&mut alice, &mut bob, &mut carol]);
+    }
+
+    for triple in triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob, &mut carol], triple, &mut prg);
+    }
+
+    mpc::product_protocol(
+        &mut [&mut alice, &mut bob, &mut carol],
+        &ids,
+        "prod",
+        &triples,
+    );
+
+    let rec_value = mpc::reconstruct_share(&[&mut alice, &mut bob, &mut carol], "prod");
+    assert_eq!(rec_value.value(), 120);
+}
+
+#[test]
+fn pow_const_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let triples = [
+        ("a1", "b1", "c1"),
+        ("a2", "b2", "c2"),
+        ("a3", "b3", "c3"),
+        ("a4", "b4", "c4"),
+        ("a5", "b5", "c5"),
+    ];
+    for triple in triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], triple, &mut prg);
+    }
+
+    alice.insert_priv_value("x", Fp::new(3));
+    mpc::distribute_shares("x", "alice", vec![&mut alice, &mut bob]);
+
+    // 13 = 0b1101 needs three squarings and two multiplications.
+    mpc::pow_const_protocol(&mut [&mut alice, &mut bob], "x", 13, "pow", &triples);
+    mpc::pow_const_protocol(&mut [&mut alice, &mut bob], "x", 0, "one", &[]);
+
+    let rec_pow = mpc::reconstruct_share(&[&mut alice, &mut bob], "pow");
+    let rec_one = mpc::reconstruct_share(&[&mut alice, &mut bob], "one");
+    assert_eq!(rec_pow.value(), 1594323);
+    assert_eq!(rec_one.value(), 1);
+}
+
+#[test]
+fn inverse_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    mpc::generate_triple(&mut [&mut alice, &mut bob], ("a", "b", "c"), &mut prg);
+
+    alice.insert_priv_value("x", Fp::new(7));
+    mpc::distribute_shares("x", "alice", vec![&mut alice, &mut bob]);
+
+    mpc::inverse_protocol(&mut [&mut alice, &mut bob], "x", "inv", ("a", "b", "c")).unwrap();
+
+    let rec_inv = mpc::reconstruct_share(&[&mut alice, &mut bob], "inv");
+    assert_eq!(rec_inv.multiply(&Fp::new(7)).value(), 1);
+}
+
+#[test]
+fn inverse_protocol_zero() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    mpc::generate_triple(&mut [&mut alice, &mut bob], ("a", "b", "c"), &mut prg);
+
+    alice.insert_priv_value("x", Fp::new(0));
+    mpc::distribute_shares("x", "alice", vec![&mut alice, &mut bob]);
+
+    let result = mpc::inverse_protocol(&mut [&mut alice, &mut bob], "x", "inv", ("a", "b", "c"));
+    assert_eq!(result, Err(NotInvertible));
+    assert!(!alice.contains_share("inv"));
+}
+
+#[test]
+fn inverse_protocol_zero_mask() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    mpc::generate_triples(&mut [&mut alice, &mut bob], 1, &mut prg);
+
+    // The mask of the triple is zero, so the opened product is zero although
+    // the shared value is not.
+    let triple = [(3, 4), (5, Fp::ORDER - 5), (0, 0)];
+    for (id, (alice_share, bob_share)) in ["a", "b", "c"].into_iter().zip(triple) {
+        for (party, value) in [(&mut alice, alice_share), (&mut bob, bob_share)] {
+            let share = Share {
+                id: id.to_string(),
+                value: Fp::new(value),
+            };
+            party.insert_share(id, share);
+        }
+    }
+
+    alice.insert_priv_value("x", Fp::new(7));
+    mpc::distribute_shares("x", "alice", vec![&mut alice, &mut bob]);
+
+    mpc::inverse_protocol(&mut [&mut alice, &mut bob], "x", "inv", ("a", "b", "c")).unwrap();
+
+    // The parties retried with the triple of the preprocessing store.
+    assert_eq!(alice.preprocessing.remaining_triples(), 0);
+    let rec_inv = mpc::reconstruct_share(&[&mut alice, &mut bob], "inv");
+    assert_eq!(rec_inv.multiply(&Fp::new(7)).value(), 1);
+}
+
+#[test]
+fn div_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let triples = [("a1", "b1", "c1"), ("a2", "b2", "c2")];
+    for triple in triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], triple, &mut prg);
+    }
+
+    alice.insert_priv_value("num", Fp::new(42));
+    mpc::distribute_shares("num", "alice", vec![&mut alice, &mut bob]);
+
+    bob.insert_priv_value("den", Fp::new(6));
+    mpc::distribute_shares("den", "bob", vec![&mut alice, &mut bob]);
+
+    mpc::div_protocol(&mut [&mut alice, &mut bob], "num", "den", "quot", &triples).unwrap();
+
+    let rec_quot = mpc::reconstruct_share(&[&mut alice, &mut bob], "quot");
+    assert_eq!(rec_quot.value(), 7);
+}
+
+#[test]
+fn equals_protocol() {
+    let mut prg = Prg::new(None);
+
+    let names = triple_names(119);
+    let triples = as_triple_ids(&names);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    alice.insert_priv_value("x", Fp::new(5));
+    mpc::distribute_shares("x", "alice", vec![&mut alice, &mut bob]);
+
+    bob.insert_priv_value("y", Fp::new(5));
+    mpc::distribute_shares("y", "bob", vec![&mut alice, &mut bob]);
+
+    bob.insert_priv_value("z", Fp::new(6));
+    mpc::distribute_shares("z", "bob", vec![&mut alice, &mut bob]);
+
+    for triple in &triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], *triple, &mut prg);
+    }
+
+    mpc::equals_protocol(&mut [&mut alice, &mut bob], "x", "y", "eq", &triples);
+    mpc::equals_protocol(&mut [&mut alice, &mut bob], "x", "z", "neq", &triples);
+
+    let rec_eq = mpc::reconstruct_share(&[&mut alice, &mut bob], "eq");
+    let rec_neq = mpc::reconstruct_share(&[&mut alice, &mut bob], "neq");
+    assert_eq!(rec_eq.value(), 1);
+    assert_eq!(rec_neq.value(), 0);
+}
+
+#[test]
+fn generate_random_bit() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    mpc::generate_random_bit(&mut [&mut alice, &mut bob], "bit", &mut prg);
+
+    let rec_bit = mpc::reconstruct_share(&[&mut alice, &mut bob], "bit");
+    assert!(rec_bit.value() <= 1);
+}
+
+#[test]
+fn random_bit_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let mut bits = Vec::new();
+    for i in 0..64 {
+        let id = format!("bit{i}");
+        mpc::random_bit_protocol(&mut [&mut alice, &mut bob], &id, &mut prg);
+        let rec_bit = mpc::reconstruct_share(&[&mut alice, &mut bob], &id);
+        bits.push(rec_bit.value());
+    }
+
+    assert!(bits.iter().all(|&bit| bit <= 1));
+    assert!(bits.contains(&0) && bits.contains(&1));
+    // Each bit consumes exactly one square pair dealt by the PRG.
+    assert_eq!(alice.preprocessing.remaining_squares(), 0);
+}
+
+#[test]
+fn trunc_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    alice.insert_priv_value("a", fixed::encode(1.5, 16));
+    mpc::distribute_shares("a", "alice", vec![&mut alice, &mut bob]);
+
+    bob.insert_priv_value("b", fixed::encode(-2.25, 16));
+    mpc::distribute_shares("b", "bob", vec![&mut alice, &mut bob]);
+
+    mpc::generate_triple(&mut [&mut alice, &mut bob], ("x", "y", "z"), &mut prg);
+    mpc::register_triple(&mut [&mut alice, &mut bob], ("x", "y", "z"));
+    mpc::generate_trunc_pair(&mut [&mut alice, &mut bob], 16, ("r", "r_low"), &mut prg);
+
+    // The product has 32 fractional bits and is truncated back to 16 bits.
+    mpc::mult_protocol(&mut [&mut alice, &mut bob], "a", "b", "ab");
+    mpc::trunc_protocol(
+        &mut [&mut alice, &mut bob],
+        "ab",
+        16,
+        "prod",
+        ("r", "r_low"),
+    );
+
+    let rec_prod = mpc::reconstruct_share(&[&mut alice, &mut bob], "prod");
+    assert!((fixed::decode(&rec_prod, 16) + 3.375).abs() <= 1.0 / (1 << 16) as f64);
+}
+
+#[test]
+fn fixed_div_protocol() {
+    let mut prg = Prg::new(None);
+
+    let cost = mpc::fixed_div_cost(16);
+    let names = triple_names(cost);
+    let triples = as_triple_ids(&names);
+    let mask_names: Vec<(String, String)> = (0..cost)
+        .map(|i| (format!("r{i}"), format!("r_low{i}")))
+        .collect();
+    let masks: Vec<(&str, &str)> = mask_names
+        .iter()
+        .map(|(r, r_low)| (r.as_str(), r_low.as_str()))
+        .collect();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    for (triple, mask) in triples.iter().zip(&masks) {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], *triple, &mut prg);
+        mpc::generate_trunc_pair(&mut [&mut alice, &mut bob], 16, *mask, &mut prg);
+    }
+
+    alice.insert_priv_value("num", fixed::encode(0.3, 16));
+    mpc::distribute_shares("num", "alice", vec![&mut alice, &mut bob]);
+
+    bob.insert_priv_value("den", fixed::encode(0.75, 16));
+    mpc::distribute_shares("den", "bob", vec![&mut alice, &mut bob]);
+
+    mpc::fixed_div_protocol(
+        &mut [&mut alice, &mut bob],
+        "num",
+        "den",
+        "quot",
+        16,
+        &triples,
+        &masks,
+    );
+
+    let rec_quot = mpc::reconstruct_share(&[&mut alice, &mut bob], "quot");
+    assert!((fixed::decode(&rec_quot, 16) - 0.4).abs() < 1e-3);
+}
+
+#[test]
+fn select_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    alice.insert_priv_value("x", Fp::new(10));
+    mpc::distribute_shares("x", "alice", vec![&mut alice, &mut bob]);
+
+    bob.insert_priv_value("y", Fp::new(20));
+    mpc::distribute_shares("y", "bob", vec![&mut alice, &mut bob]);
+
+    alice.insert_priv_value("one", Fp::new(1));
+    mpc::distribute_shares("one", "alice", vec![&mut alice, &mut bob]);
+
+    alice.insert_priv_value("zero", Fp::new(0));
+    mpc::distribute_shares("zero", "alice", vec![&mut alice, &mut bob]);
+
+    mpc::generate_triple(&mut [&mut alice, &mut bob], ("a1", "b1", "c1"), &mut prg);
+    mpc::generate_triple(&mut [&mut alice, &mut bob], ("a2", "b2", "c2"), &mut prg);
+
+    mpc::select_protocol(
+        &mut [&mut alice, &mut bob],
+        "one",
+        "x",
+        "y",
+        "sel_x",
+        ("a1", "b1", "c1"),
+    );
+    mpc::select_protocol(
+        &mut [&mut alice, &mut bob],
+        "zero",
+        "x",
+        "y",
+        "sel_y",
+        ("a2", "b2", "c2"),
+    );
+
+    let rec_x = mpc::reconstruct_share(&[&mut alice, &mut bob], "sel_x");
+    let rec_y = mpc::reconstruct_share(&[&mut alice, &mut bob], "sel_y");
+    assert_eq!(rec_x.value(), 10);
+    assert_eq!(rec_y.value(), 20);
+}
+
+#[test]
+fn less_than_protocol() {
+    let mut prg = Prg::new(None);
+
+    let (n_triples, n_bits) = mpc::less_than_cost::<Fp>();
+    let names = triple_names(3 * n_triples);
+    let triples = as_triple_ids(&names);
+    let bit_names = bit_names(3 * n_bits);
+    let bits: Vec<&str> = bit_names.iter().map(|name| name.as_str()).collect();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    for triple in &triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], *triple, &mut prg);
+    }
+    for bit in &bits {
+        mpc::generate_random_bit(&mut [&mut alice, &mut bob], bit, &mut prg);
+    }
+
+    alice.insert_priv_value("x", Fp::new(3));
+    mpc::distribute_shares("x", "alice", vec![&mut alice, &mut bob]);
+
+    bob.insert_priv_value("y", Fp::new(5));
+    mpc::distribute_shares("y", "bob", vec![&mut alice, &mut bob]);
+
+    let chunk = |i: usize| {
+        (
+            &triples[i * n_triples..(i + 1) * n_triples],
+            &bits[i * n_bits..(i + 1) * n_bits],
+        )
+    };
+
+    let (t, b) = chunk(0);
+    mpc::less_than_protocol(&mut [&mut alice, &mut bob], "x", "y", "lt", t, b);
+    let (t, b) = chunk(1);
+    mpc::less_than_protocol(&mut [&mut alice, &mut bob], "y", "x", "gt", t, b);
+    let (t, b) = chunk(2);
+    mpc::less_than_protocol(&mut [&mut alice, &mut bob], "x", "x", "eq", t, b);
+
+    let rec_lt = mpc::reconstruct_share(&[&mut alice, &mut bob], "lt");
+    let rec_gt = mpc::reconstruct_share(&[&mut alice, &mut bob], "gt");
+    let rec_eq = mpc::reconstruct_share(&[&mut alice, &mut bob], "eq");
+    assert_eq!(rec_lt.value(), 1);
+    assert_eq!(rec_gt.value(), 0);
+    assert_eq!(rec_eq.value(), 0);
+}
+
+#[test]
+fn max_and_argmax_protocol() {
+    let mut prg = Prg::new(None);
+
+    let ids = ["v0", "v1", "v2", "v3", "v4"];
+    let (n_triples, n_bits) = mpc::less_than_cost::<Fp>();
+    let names = triple_names(2 * (ids.len() - 1) * (n_triples + 2));
+    let triples = as_triple_ids(&names);
+    let bit_names = bit_names(2 * (ids.len() - 1) * n_bits);
+    let bits: Vec<&str> = bit_names.iter().map(|name| name.as_str()).collect();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    for triple in &triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], *triple, &mut prg);
+    }
+    for bit in &bits {
+        mpc::generate_random_bit(&mut [&mut alice, &mut bob], bit, &mut prg);
+    }
+
+    for (id, value) in ids.iter().zip([3, 9, 4, 9, 7]) {
+        alice.insert_priv_value(id, Fp::new(value));
+        mpc::distribute_shares(id, "alice", vec![&mut alice, &mut bob]);
+    }
+
+    let (triples_max, triples_argmax) = triples.split_at(triples.len() / 2);
+    let (bits_max, bits_argmax) = bits.split_at(bits.len() / 2);
+    mpc::max_protocol(
+        &mut [&mut alice, &mut bob],
+        &ids,
+        "max",
+        triples_max,
+        bits_max,
+    );
+    mpc::argmax_protocol(
+        &mut [&mut alice, &mut bob],
+        &ids,
+        "argmax",
+        triples_argmax,
+        bits_argmax,
+    );
+
+    let rec_max = mpc::reconstruct_share(&[&mut alice, &mut bob], "max");
+    let rec_argmax = mpc::reconstruct_share(&[&mut alice, &mut bob], "argmax");
+    assert_eq!(rec_max.value(), 9);
+    assert_eq!(rec_argmax.value(), 1);
+}
+
+#[test]
+fn sort_protocol() {
+    let mut prg = Prg::new(None);
+
+    let ids_in = ["v0", "v1", "v2", "v3", "v4", "v5"];
+    let ids_out = ["s0", "s1", "s2", "s3", "s4", "s5"];
+    let (n_triples, n_bits) = mpc::sort_cost::<Fp>(ids_in.len());
+    let names = triple_names(n_triples);
+    let triples = as_triple_ids(&names);
+    let bit_names = bit_names(n_bits);
+    let bits: Vec<&str> = bit_names.iter().map(|name| name.as_str()).collect();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    for triple in &triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], *triple, &mut prg);
+    }
+    for bit in &bits {
+        mpc::generate_random_bit(&mut [&mut alice, &mut bob], bit, &mut prg);
+    }
+
+    for (id, value) in ids_in.iter().zip([8, 3, 12, 3, 0, 5]) {
+        bob.insert_priv_value(id, Fp::new(value));
+        mpc::distribute_shares(id, "bob", vec![&mut alice, &mut bob]);
+    }
+
+    mpc::sort_protocol(
+        &mut [&mut alice, &mut bob],
+        &ids_in,
+        &ids_out,
+        &triples,
+        &bits,
+    );
+
+    let sorted: Vec<u64> = ids_out
+        .iter()
+        .map(|id| mpc::reconstruct_share(&[&mut alice, &mut bob], id).value())
+        .collect();
+    assert_eq!(sorted, vec![0, 3, 3, 5, 8, 12]);
+}
+
+#[test]
+fn prefix_protocols() {
+    let mut prg = Prg::new(None);
+
+    let ids_bits = ["x0", "x1", "x2", "x3", "x4"];
+    let ids_values = ["v0", "v1", "v2", "v3", "v4"];
+    let ids_or = ["or0", "or1", "or2", "or3", "or4"];
+    let ids_and = ["and0", "and1", "and2", "and3", "and4"];
+    let ids_prod = ["prod0", "prod1", "prod2", "prod3", "prod4"];
+
+    let cost = mpc::prefix_cost(ids_bits.len());
+    let names = triple_names(3 * cost);
+    let triples = as_triple_ids(&names);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    for triple in &triples {
+        mpc::generate_triple(&mut [&mut alice, &mut bob], *triple, &mut prg);
+    }
+
+    for (id, bit) in ids_bits.iter().zip([0, 0, 1, 0, 1]) {
+        alice.insert_priv_value(id, Fp::new(bit));
+        mpc::distribute_shares(id, "alice", vec![&mut alice, &mut bob]);
+    }
+
+    for (id, value) in ids_values.iter().zip([1, 2, 3, 4, 5]) {
+        bob.insert_priv_value(id, Fp::new(value));
+        mpc::distribute_shares(id, "bob", vec![&mut alice, &mut bob]);
+    }
+
+    let mut parties = vec![&mut alice, &mut bob];
+    mpc::prefix_or_protocol(&mut parties, &ids_bits, &ids_or, &triples[..cost]);
+    mpc::prefix_and_protocol(&mut parties, &ids_or, &ids_and, &triples[cost..2 * cost]);
+    mpc::prefix_product_protocol(&mut parties, &ids_values, &ids_prod, &triples[2 * cost..]);
+
+    let open = |parties: &mut Vec<&mut VirtualMachine<Fp>>, ids: &[&str]| -> Vec<u64> {
+        ids.iter()
+            .map(|id| mpc::reconstruct_share(parties, id).value())
+            .collect()
+    };
+    assert_eq!(open(&mut parties, &ids_or), vec![0, 0, 1, 1, 1]);
+    assert_eq!(open(&mut parties, &ids_and), vec![0, 0, 0, 0, 0]);
+    assert_eq!(open(&mut parties, &ids_prod), vec![1, 2, 6, 24, 120]);
+}
+
+#[test]
+fn lsb_protocol() {
+    let mut prg = Prg::new(None);
+
+    let ids = ["x0", "x1", "x2", "x3"];
+    let values = [6, 13, 0, Fp::ORDER - 1];
+    let (n_triples, n_bits) = mpc::lsb_cost::<Fp>();
+    let names = triple_names(ids.len() * n_triples);
+    let triples = as_triple_ids(&names);
+    let bit_names = bit_names(ids.len() * n_bits);
+    let bits: Vec<&str> = bit_names.iter().map(|name| name.as_str()).collect();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut carol: VirtualMachine<Fp> = VirtualMachine::new("carol");
+    let mut parties = vec![&mut alice, &mut bob, &mut carol];
+
+    for triple in &triples {
+        mpc::generate_triple(&mut parties, *triple, &mut prg);
+    }
+    for bit in &bits {
+        mpc::generate_random_bit(&mut parties, bit, &mut prg);
+    }
+
+    for (i, (id, value)) in ids.iter().zip(values).enumerate() {
+        let id_lsb = ["lsb0", "lsb1", "lsb2", "lsb3"][i];
+        mpc::simulate_random_dist(id, &mut parties, &Fp::new(value), &mut prg);
+        mpc::lsb_protocol(
+            &mut parties,
+            id,
+            id_lsb,
+            &triples[i * n_triples..(i + 1) * n_triples],
+            &bits[i * n_bits..(i + 1) * n_bits],
+        );
+
+        let rec_lsb = mpc::reconstruct_share(&parties, id_lsb);
+        assert_eq!(rec_lsb.value(), value & 1);
+    }
+}
+
+#[test]
+fn lookup_protocol() {
+    let mut prg = Prg::new(None);
+
+    let table: Vec<Fp> = [7, 1, 4, 0, 9, 12].into_iter().map(Fp::new).collect();
+    let cost = mpc::lookup_cost(table.len());
+    let names = triple_names(table.len() * cost);
+    let triples = as_triple_ids(&names);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    for triple in &triples {
+        mpc::generate_triple(&mut parties, *triple, &mut prg);
+    }
+
+    let ids_index = ["i0", "i1", "i2", "i3", "i4", "i5"];
+    let ids_result = ["t0", "t1", "t2", "t3", "t4", "t5"];
+    for (i, (id_index, id_result)) in ids_index.iter().zip(ids_result).enumerate() {
+        mpc::simulate_random_dist(id_index, &mut parties, &Fp::new(i as u64), &mut prg);
+        mpc::lookup_protocol(
+            &mut parties,
+            &table,
+            id_index,
+            id_result,
+            &triples[i * cost..(i + 1) * cost],
+        );
+
+        let rec_value = mpc::reconstruct_share(&parties, id_result);
+        assert_eq!(rec_value.value(), table[i].value());
+    }
+}
+
+#[test]
+fn poly_eval_protocol() {
+    let mut prg = Prg::new(None);
+
+    // p(x) = 5 + 3x - 2x^3 + x^4
+    let coeffs: Vec<Fp> = vec![
+        Fp::new(5),
+        Fp::new(3),
+        Fp::new(0),
+        Fp::new(2).negate(),
+        Fp::new(1),
+    ];
+    let names = triple_names(3);
+    let triples = as_triple_ids(&names);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    for triple in &triples {
+        mpc::generate_triple(&mut parties, *triple, &mut prg);
+    }
+
+    let x = Fp::new(7);
+    mpc::simulate_random_dist("x", &mut parties, &x, &mut prg);
+    mpc::poly_eval_protocol(&mut parties, &coeffs, "x", "p", &triples);
+
+    let rec_value = mpc::reconstruct_share(&parties, "p");
+    let expected = 5 + 3 * 7 + 2401 - 2 * 343;
+    assert_eq!(rec_value.value(), expected);
+}
+
+#[test]
+fn linear_combination_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(10), &mut prg);
+    mpc::simulate_random_dist("y", &mut parties, &Fp::new(4), &mut prg);
+    mpc::simulate_random_dist("z", &mut parties, &Fp::new(7), &mut prg);
+
+    let terms = [
+        (Fp::new(3), "x"),
+        (Fp::new(2).negate(), "y"),
+        (Fp::new(1), "z"),
+    ];
+    mpc::linear_combination_protocol(&mut parties, &terms, Fp::new(100), "result");
+
+    let rec_value = mpc::reconstruct_share(&parties, "result");
+    assert_eq!(rec_value.value(), 3 * 10 + 7 + 100 - 2 * 4);
+    for party in &parties {
+        assert_eq!(party.memory_usage().shares, 4);
+    }
+}
+
+#[test]
+fn add_const_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(41), &mut prg);
+    mpc::add_const_protocol(&mut parties, &Fp::new(1), "x", "x_plus_one");
+
+    let rec_value = mpc::reconstruct_share(&parties, "x_plus_one");
+    assert_eq!(rec_value.value(), 42);
+}
+
+#[test]
+fn affine_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(12), &mut prg);
+    mpc::affine_protocol(&mut parties, &Fp::new(3), "x", &Fp::new(5), "y");
+
+    let rec_value = mpc::reconstruct_share(&parties, "y");
+    assert_eq!(rec_value.value(), 41);
+}
+
+#[test]
+fn distribute_shares_many() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+
+    let ids = ["v0", "v1", "v2"];
+    for (i, id) in ids.iter().enumerate() {
+        bob.insert_priv_value(id, Fp::new(10 * i as u64 + 3));
+    }
+
+    mpc::distribute_shares_many(&ids, "bob", vec![&mut alice, &mut bob, &mut charlie]);
+
+    let parties = vec![&mut alice, &mut bob, &mut charlie];
+    for (i, id) in ids.iter().enumerate() {
+        let rec_value = mpc::reconstruct_share(&parties, id);
+        assert_eq!(rec_value.value(), 10 * i as u64 + 3);
+    }
+}
+
+#[test]
+fn add_many_and_mult_many() {
+    let mut prg = Prg::new(None);
+
+    let names = triple_names(3);
+    let triples = as_triple_ids(&names);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    for triple in &triples {
+        mpc::generate_triple(&mut parties, *triple, &mut prg);
+    }
+
+    let ids_x = ["x0", "x1", "x2"];
+    let ids_y = ["y0", "y1", "y2"];
+    let xs = [4, 9, 15];
+    let ys = [7, 2, 11];
+    for i in 0..3 {
+        mpc::simulate_random_dist(ids_x[i], &mut parties, &Fp::new(xs[i]), &mut prg);
+        mpc::simulate_random_dist(ids_y[i], &mut parties, &Fp::new(ys[i]), &mut prg);
+    }
+
+    let ids_sum = ["s0", "s1", "s2"];
+    let ids_prod = ["p0", "p1", "p2"];
+    mpc::add_many(&mut parties, &ids_x, &ids_y, &ids_sum);
+    mpc::mult_many(&mut parties, &ids_x, &ids_y, &ids_prod, &triples);
+
+    for i in 0..3 {
+        let sum = mpc::reconstruct_share(&parties, ids_sum[i]);
+        let prod = mpc::reconstruct_share(&parties, ids_prod[i]);
+        assert_eq!(sum.value(), xs[i] + ys[i]);
+        assert_eq!(prod.value(), xs[i] * ys[i]);
+    }
+}
+
+#[test]
+fn reconstruct_to() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(73), &mut prg);
+    mpc::reconstruct_to(&mut parties, "x", "bob");
+
+    assert_eq!(bob.get_priv_value("x").value(), 73);
+    assert!(alice.memory_usage().private_values == 0);
+    assert!(charlie.memory_usage().private_values == 0);
+}
+
+#[test]
+fn generate_triples() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let handles = mpc::generate_triples(&mut parties, 3, &mut prg);
+    assert_eq!(
+        handles,
+        vec![TripleHandle(0), TripleHandle(1), TripleHandle(2)]
+    );
+    for party in &parties {
+        assert_eq!(party.preprocessing.remaining_triples(), 3);
+        assert!(party.memory_usage().shares == 0);
+    }
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(2), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(3), &mut prg);
+    mpc::mult_protocol(&mut parties, "a", "b", "ab");
+    mpc::mult_protocol(&mut parties, "ab", "ab", "ab2");
+    mpc::mult_protocol(&mut parties, "ab2", "a", "result");
+
+    let rec_value = mpc::reconstruct_share(&parties, "result");
+    assert_eq!(rec_value.value(), 72);
+    for party in &parties {
+        assert_eq!(party.preprocessing.remaining_triples(), 0);
+    }
+}
+
+#[test]
+fn square_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let handles = mpc::generate_square_pairs(&mut parties, 2, &mut prg);
+    assert_eq!(handles.len(), 2);
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(12), &mut prg);
+    mpc::square_protocol(&mut parties, "x", "x2");
+    mpc::square_protocol(&mut parties, "x2", "x4");
+
+    assert_eq!(mpc::reconstruct_share(&parties, "x2").value(), 144);
+    assert_eq!(mpc::reconstruct_share(&parties, "x4").value(), 20736);
+    for party in &parties {
+        assert_eq!(party.preprocessing.remaining_squares(), 0);
+    }
+}
+
+#[test]
+fn ot_triple_protocol() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::ot_triple_protocol(&mut parties, ("a", "b", "c"));
+
+    let a = mpc::reconstruct_share(&parties, "a");
+    let b = mpc::reconstruct_share(&parties, "b");
+    let c = mpc::reconstruct_share(&parties, "c");
+    assert_eq!(a.multiply(&b).value(), c.value());
+}
+
+#[test]
+fn he_triple_protocol() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::he_triple_protocol(&mut parties, ("a", "b", "c"));
+
+    let a = mpc::reconstruct_share(&parties, "a");
+    let b = mpc::reconstruct_share(&parties, "b");
+    let c = mpc::reconstruct_share(&parties, "c");
+    assert_eq!(a.multiply(&b).value(), c.value());
+}
+
+#[test]
+fn b2a_and_a2b_protocols() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::generate_dabits(&mut parties, 4, &mut prg);
+    mpc::generate_triples(&mut parties, 2, &mut prg);
+
+    mpc::simulate_bool_dist("x0", &mut parties, false, &mut prg);
+    mpc::simulate_bool_dist("x1", &mut parties, true, &mut prg);
+    mpc::b2a_protocol(&mut parties, "x0", "a0");
+    mpc::b2a_protocol(&mut parties, "x1", "a1");
+    assert_eq!(mpc::reconstruct_share(&parties, "a0").value(), 0);
+    assert_eq!(mpc::reconstruct_share(&parties, "a1").value(), 1);
+
+    mpc::a2b_protocol(&mut parties, "a0", "y0");
+    mpc::a2b_protocol(&mut parties, "a1", "y1");
+    assert!(!mpc::reconstruct_bool_share(&parties, "y0"));
+    assert!(mpc::reconstruct_bool_share(&parties, "y1"));
+
+    for party in &parties {
+        assert_eq!(party.preprocessing.remaining_dabits(), 0);
+        assert_eq!(party.preprocessing.remaining_triples(), 0);
+    }
+}
+
+#[test]
+fn trunc_edabit_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    let n_bits = (mpc::TRUNC_INPUT_BITS + mpc::TRUNC_STAT_SECURITY) as usize;
+    mpc::generate_edabits(&mut parties, 2, n_bits, &mut prg);
+
+    let x: Fp = fixed::encode(-7.3125, 16);
+    mpc::simulate_random_dist("x", &mut parties, &x, &mut prg);
+    mpc::trunc_edabit_protocol(&mut parties, "x", 8, "x8");
+    mpc::trunc_edabit_protocol(&mut parties, "x", 12, "x12");
+
+    let x8 = mpc::reconstruct_share(&parties, "x8");
+    let x12 = mpc::reconstruct_share(&parties, "x12");
+    assert!((fixed::decode(&x8, 8) + 7.3125).abs() <= 1.0 / (1 << 8) as f64);
+    assert!((fixed::decode(&x12, 4) + 7.3125).abs() <= 1.0 / (1 << 4) as f64);
+}
+
+#[test]
+fn less_than_edabit_protocol() {
+    let mut prg = Prg::new(None);
+
+    let (n_triples, _) = mpc::less_than_cost::<Fp>();
+    let names = triple_names(2 * n_triples);
+    let triples = as_triple_ids(&names);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    for triple in &triples {
+        mpc::generate_triple(&mut parties, *triple, &mut prg);
+    }
+    mpc::generate_edabits(&mut parties, 2, Fp::POWER as usize, &mut prg);
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(17), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(40), &mut prg);
+    mpc::less_than_edabit_protocol(&mut parties, "a", "b", "a_lt_b", &triples[..n_triples]);
+    mpc::less_than_edabit_protocol(&mut parties, "b", "a", "b_lt_a", &triples[n_triples..]);
+
+    assert_eq!(mpc::reconstruct_share(&parties, "a_lt_b").value(), 1);
+    assert_eq!(mpc::reconstruct_share(&parties, "b_lt_a").value(), 0);
+}
+
+#[test]
+fn less_than_fss_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    let handles = mpc::generate_comparison_keys(&mut parties, 3, &mut prg);
+    assert_eq!(handles.len(), 3);
+    assert_eq!(parties[0].preprocessing.remaining_comparison_keys(), 3);
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(17), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(40), &mut prg);
+    mpc::simulate_random_dist("c", &mut parties, &Fp::new(3).negate(), &mut prg);
+    let (_, counts) =
+        counter::measure(|| mpc::less_than_fss_protocol(&mut parties, "a", "b", "a_lt_b"));
+    mpc::less_than_fss_protocol(&mut parties, "b", "a", "b_lt_a");
+    mpc::less_than_fss_protocol(&mut parties, "c", "a", "c_lt_a");
+
+    assert_eq!(counts.triples, 0);
+    assert_eq!(counts.openings, 2);
+    assert_eq!(parties[1].preprocessing.remaining_comparison_keys(), 0);
+    assert_eq!(mpc::reconstruct_share(&parties, "a_lt_b").value(), 1);
+    assert_eq!(mpc::reconstruct_share(&parties, "b_lt_a").value(), 0);
+    assert_eq!(mpc::reconstruct_share(&parties, "c_lt_a").value(), 1);
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn coin_toss() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let coin = mpc::coin_toss(&mut parties, "coin");
+    for party in &parties {
+        assert_eq!(party.get_priv_value("coin").value(), coin.value());
+    }
+    let other = mpc::coin_toss(&mut parties, "other");
+    assert_ne!(coin.value(), other.value());
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+#[should_panic(expected = "The opening of a commitment is not valid.")]
+fn coin_toss_wrong_opening() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> =
+        VirtualMachine::new_cheating("bob", Behavior::WrongOpening(Fp::new(1)));
+    let mut parties = vec![&mut alice, &mut bob];
+
+    // Bob reveals a different contribution from the committed one.
+    mpc::coin_toss(&mut parties, "coin");
+}
+
+#[test]
+fn covert_triple_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("y", &mut parties, &Fp::new(7), &mut prg);
+    let handles = mpc::covert_triple_protocol(&mut parties, 3, 4, &mut prg);
+    assert_eq!(handles.len(), 3);
+    assert_eq!(parties[0].preprocessing.remaining_triples(), 3);
+
+    mpc::simulate_random_dist("z", &mut parties, &Fp::new(6), &mut prg);
+    mpc::mult_protocol(&mut parties, "z", "y", "zy");
+    assert_eq!(mpc::reconstruct_share(&parties, "zy").value(), 42);
+}
+
+#[test]
+#[should_panic(expected = "caught cheating")]
+fn covert_triple_protocol_catches_cheater() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob = VirtualMachine::new_cheating("bob", Behavior::ShareError(Fp::new(1)));
+    let mut parties = vec![&mut alice, &mut bob];
+
+    // The cheater escapes each check with probability 1/2, so it is caught in
+    // one of the 64 triples except with probability 2^-64.
+    mpc::covert_triple_protocol(&mut parties, 64, 2, &mut prg);
+}
+
+#[test]
+fn evaluate_circuit() {
+    let mut prg = Prg::new(None);
+
+    // Computes (x + y) * z * 3 + x * y.
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let z = builder.input();
+    let sum = builder.add(x, y);
+    let product = builder.mul(sum, z);
+    let three = builder.constant(Fp::new(3));
+    let scaled = builder.mul(product, three);
+    let xy = builder.mul(x, y);
+    let result = builder.add(scaled, xy);
+    builder.output(result);
+    builder.output(sum);
+    let circuit = builder.build();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(2), &mut prg);
+    mpc::simulate_random_dist("y", &mut parties, &Fp::new(5), &mut prg);
+    mpc::simulate_random_dist("z", &mut parties, &Fp::new(4), &mut prg);
+    mpc::evaluate_circuit(
+        &mut parties,
+        &circuit,
+        &["x", "y", "z"],
+        &["result", "sum"],
+        Some(&mut prg),
+    );
+
+    assert_eq!(mpc::reconstruct_share(&parties, "result").value(), 94);
+    assert_eq!(mpc::reconstruct_share(&parties, "sum").value(), 7);
+    assert_eq!(parties[0].preprocessing.remaining_triples(), 0);
+}
+
+#[test]
+fn evaluate_boolean_circuit() {
+    let mut prg = Prg::new(None);
+
+    // Computes (x AND y) XOR (NOT z) on bits shared over the field.
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let y = builder.input();
+    let z = builder.input();
+    let and = builder.and(x, y);
+    let not = builder.inv(z);
+    let result = builder.xor(and, not);
+    builder.output(result);
+    let circuit = builder.build();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::generate_triples(&mut parties, 2, &mut prg);
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(1), &mut prg);
+    mpc::simulate_random_dist("y", &mut parties, &Fp::new(1), &mut prg);
+    mpc::simulate_random_dist("z", &mut parties, &Fp::new(1), &mut prg);
+    mpc::evaluate_circuit(&mut parties, &circuit, &["x", "y", "z"], &["result"], None);
+
+    assert_eq!(mpc::reconstruct_share(&parties, "result").value(), 1);
+}
+
+#[test]
+#[should_panic]
+fn evaluate_circuit_without_triples() {
+    let mut prg = Prg::new(None);
+
+    let mut builder: CircuitBuilder<Fp> = CircuitBuilder::new();
+    let x = builder.input();
+    let square = builder.mul(x, x);
+    builder.output(square);
+    let circuit = builder.build();
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(3), &mut prg);
+    mpc::evaluate_circuit(&mut parties, &circuit, &["x"], &["square"], None);
+}
+
+#[test]
+fn subtract_with_reserved_id() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    // The intermediate ID of the subtraction does not collide with user IDs.
+    mpc::simulate_random_dist("subtraction", &mut parties, &Fp::new(9), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(4), &mut prg);
+    mpc::subtract_protocol(&mut parties, "subtraction", "b", "result");
+    assert_eq!(mpc::reconstruct_share(&parties, "result").value(), 5);
+    assert_eq!(mpc::reconstruct_share(&parties, "subtraction").value(), 9);
+}
+
+#[test]
+fn temp_id() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    bob.insert_priv_value("t#0", Fp::new(3));
+    let mut parties = vec![&mut alice, &mut bob];
+
+    // The ID is fresh for all the parties, not only for the first one.
+    let first = mpc::temp_id(&mut parties, "t");
+    assert_eq!(first, "t#1");
+    mpc::simulate_random_dist(&first, &mut parties, &Fp::new(2), &mut prg);
+    let second = mpc::temp_id(&mut parties, "t");
+    assert_ne!(first, second);
+}
+
+#[test]
+fn scoped_cleans_up_on_panic() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        mpc::scoped(&mut parties, "failing", |parties| {
+            mpc::simulate_random_dist("partial", parties, &Fp::new(1), &mut prg);
+            mpc::reconstruct_share(parties, "missing")
+        })
+    }));
+
+    assert!(result.is_err());
+    assert!(parties.iter().all(|party| party.current_scope().is_none()));
+    assert!(parties.iter().all(|party| !party.contains_id("partial")));
+}
+
+#[test]
+fn nested_subtractions() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(10), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(4), &mut prg);
+    mpc::simulate_random_dist("c", &mut parties, &Fp::new(1), &mut prg);
+    mpc::scoped(&mut parties, "outer", |parties| {
+        mpc::subtract_protocol(parties, "a", "b", "d");
+        mpc::subtract_protocol(parties, "d", "c", "e");
+        assert_eq!(mpc::reconstruct_share(parties, "e").value(), 5);
+    });
+}
+
+#[test]
+fn rewind_multiplication() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(3), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(5), &mut prg);
+    let snapshots = mpc::snapshot(&parties);
+
+    // Both continuations consume the same triple.
+    mpc::mult_protocol(&mut parties, "a", "b", "c");
+    assert_eq!(mpc::reconstruct_share(&parties, "c").value(), 15);
+    mpc::restore(&mut parties, &snapshots);
+    assert_eq!(parties[0].preprocessing.remaining_triples(), 1);
+    mpc::add_protocol(&mut parties, "a", "b", "c");
+    assert_eq!(mpc::reconstruct_share(&parties, "c").value(), 8);
+}
+
+#[test]
+fn pairwise_setup() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    mpc::pairwise_setup(&mut [&mut alice, &mut bob, &mut charlie]);
+
+    assert_eq!(
+        alice.pairwise_prg("bob").next(16),
+        bob.pairwise_prg("alice").next(16)
+    );
+    assert_eq!(
+        bob.pairwise_prg("charlie").next(16),
+        charlie.pairwise_prg("bob").next(16)
+    );
+    assert_ne!(
+        alice.pairwise_prg("charlie").next(16),
+        bob.pairwise_prg("charlie").next(16)
+    );
+}
+
+#[test]
+#[should_panic(expected = "There is no PRG shared with the party bob.")]
+fn pairwise_prg_without_setup() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.pairwise_prg("bob");
+}
+
+#[test]
+fn additive_reconstruction_with_dropout() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(8), &mut prg);
+    assert_eq!(
+        mpc::try_reconstruct_share(&parties, "x").unwrap().value(),
+        8
+    );
+
+    parties[2].mailbox.set_offline(true);
+    let error = mpc::try_reconstruct_share(&parties, "x").err().unwrap();
+    assert_eq!(error, MissingParty::new("charlie"));
+}
+
+#[test]
+#[should_panic(expected = "The party bob is offline.")]
+fn dropout_during_opening() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(8), &mut prg);
+
+    parties[1].mailbox.set_offline(true);
+    mpc::reconstruct_share(&parties, "x");
+}
@@ -0,0 +1,645 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::network::{self, measure, NetworkConfig, Payload, ResendPolicy, Simulator};
+use smol_mpc::spdz;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn send_and_recv() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let network = Simulator::new(&parties);
+    network.send(0, 2, Payload::Elements(vec![Fp::new(3)]));
+    network.send(1, 2, Payload::Bits(vec![true, false]));
+    assert_eq!(parties[2].mailbox.pending(), 0);
+
+    network.deliver();
+    assert_eq!(parties[2].mailbox.pending(), 2);
+    assert_eq!(network.recv_from(2, 1).payload.bits(), vec![true, false]);
+    let message = network.recv(2);
+    assert_eq!(message.from, "alice");
+    assert_eq!(message.to, "charlie");
+    assert_eq!(message.payload.elements()[0].value(), 3);
+}
+
+#[test]
+#[should_panic]
+fn recv_empty_inbox() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let parties = vec![&mut alice, &mut bob];
+
+    Simulator::new(&parties).recv(1);
+}
+
+#[test]
+fn open() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let parties = vec![&mut alice, &mut bob];
+
+    let network = Simulator::new(&parties);
+    let opened = network.open(&[vec![Fp::new(2), Fp::new(5)], vec![Fp::new(3), Fp::new(1)]]);
+    assert_eq!(
+        opened
+            .iter()
+            .map(|value| value.value())
+            .collect::<Vec<u64>>(),
+        vec![5, 6]
+    );
+    assert_eq!(network.open_bits(&[vec![true], vec![true]]), vec![false]);
+    assert!(parties.iter().all(|party| party.mailbox.pending() == 0));
+}
+
+#[test]
+fn protocols_empty_inboxes() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    let a = mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    let b = mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+    let product = mpc::mult_protocol(&mut parties, &a, &b, "product");
+    assert_eq!(mpc::reconstruct_share(&parties, &product).value(), 42);
+    assert!(parties.iter().all(|party| party.mailbox.pending() == 0));
+}
+
+#[test]
+fn stats() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    let a = mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    let b = mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+    Simulator::new(&parties).reset_stats();
+    mpc::mult_protocol(&mut parties, &a, &b, "product");
+
+    // Each party sends its shares of epsilon and delta to the other two.
+    for stats in Simulator::new(&parties).stats() {
+        assert_eq!(stats.messages_sent, 2);
+        assert_eq!(stats.bytes_sent, 32);
+        assert_eq!(stats.messages_received, 2);
+        assert_eq!(stats.bytes_received, 32);
+    }
+}
+
+#[test]
+fn stats_of_conversions_and_openings() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    // The masked bit of the conversion is opened through the network.
+    mpc::generate_dabits(&mut parties, 1, &mut prg);
+    mpc::simulate_bool_dist("x", &mut parties, true, &mut prg);
+    Simulator::new(&parties).reset_stats();
+    mpc::b2a_protocol(&mut parties, "x", "a");
+    for stats in Simulator::new(&parties).stats() {
+        assert_eq!(stats.messages_sent, 1);
+        assert_eq!(stats.messages_received, 1);
+    }
+
+    // The SPDZ opening sends a message to the other party, and the MAC check
+    // sends a commitment and its opening, or just the value without `sha2`.
+    spdz::setup(&mut parties, &mut prg);
+    parties[0].insert_priv_value("b", Fp::new(5));
+    spdz::distribute_shares("b", "alice", &mut parties, &mut prg);
+    Simulator::new(&parties).reset_stats();
+    spdz::reconstruct_share(&mut parties, "b", &mut prg);
+    let expected = if cfg!(feature = "sha2") { 3 } else { 2 };
+    for stats in Simulator::new(&parties).stats() {
+        assert_eq!(stats.messages_sent, expected);
+        assert_eq!(stats.messages_received, expected);
+    }
+}
+
+#[test]
+fn rounds() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    let ids: Vec<String> = (0..8).map(|i| format!("x{}", i)).collect();
+    for id in &ids {
+        mpc::simulate_random_dist(id, &mut parties, &Fp::new(2), &mut prg);
+    }
+    let triple_ids: Vec<(String, String, String)> = (0..14)
+        .map(|i| (format!("a{}", i), format!("b{}", i), format!("c{}", i)))
+        .collect();
+    let triples: Vec<(&str, &str, &str)> = triple_ids
+        .iter()
+        .map(|(a, b, c)| (a.as_str(), b.as_str(), c.as_str()))
+        .collect();
+    for triple in &triples {
+        mpc::generate_triple(&mut parties, *triple, &mut prg);
+    }
+    let ids: Vec<&str> = ids.iter().map(|id| id.as_str()).collect();
+
+    // The balanced tree needs a logarithmic number of rounds.
+    let (_, report) = measure(&mut parties, |parties| {
+        mpc::product_protocol(parties, &ids, "tree", &triples[..7]);
+    });
+    assert_eq!(report.rounds, 3);
+    assert_eq!(report.stats[0].messages_sent, 3);
+
+    // Multiplying the values one after the other needs a linear number.
+    let (_, report) = measure(&mut parties, |parties| {
+        let mut acc = ids[0].to_string();
+        for (i, (id, triple)) in ids[1..].iter().zip(&triples[7..]).enumerate() {
+            let result = format!("chain{}", i);
+            mpc::register_triple(parties, *triple);
+            mpc::mult_protocol(parties, acc.as_str(), *id, &result);
+            acc = result;
+        }
+    });
+    assert_eq!(report.rounds, 7);
+    assert_eq!(mpc::reconstruct_share(&parties, "tree").value(), 256);
+    assert_eq!(mpc::reconstruct_share(&parties, "chain6").value(), 256);
+}
+
+#[test]
+fn transcript() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    let (_, report) = measure(&mut parties, |parties| {
+        let a = mpc::simulate_random_dist("a", parties, &Fp::new(6), &mut prg);
+        let b = mpc::simulate_random_dist("b", parties, &Fp::new(7), &mut prg);
+        let product = mpc::mult_protocol(parties, &a, &b, "product");
+        mpc::reconstruct_share(parties, &product)
+    });
+
+    let transcript = report.transcript;
+    assert_eq!(transcript.records().len(), 12);
+    assert_eq!(transcript.with_tag("beaver").records().len(), 6);
+    assert!(transcript
+        .with_tag("beaver")
+        .records()
+        .iter()
+        .all(|record| record.round == 0));
+    assert!(transcript
+        .with_tag("open")
+        .records()
+        .iter()
+        .all(|record| record.round == 1));
+
+    let bob_view = transcript.for_party("bob");
+    assert_eq!(bob_view.records().len(), 8);
+    assert!(bob_view
+        .records()
+        .iter()
+        .all(|record| record.message.from == "bob" || record.message.to == "bob"));
+    assert!(transcript
+        .to_string()
+        .starts_with("round 0: alice -> bob [beaver] 16 bytes"));
+}
+
+#[test]
+fn transcript_diagrams() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    let (_, report) = measure(&mut parties, |parties| {
+        let a = mpc::simulate_random_dist("a", parties, &Fp::new(6), &mut prg);
+        let b = mpc::simulate_random_dist("b", parties, &Fp::new(7), &mut prg);
+        mpc::mult_protocol(parties, &a, &b, "product");
+    });
+
+    let mermaid = report.transcript.to_mermaid();
+    let lines: Vec<&str> = mermaid.lines().collect();
+    assert_eq!(lines[0], "sequenceDiagram");
+    assert_eq!(lines[1], "    participant alice");
+    assert_eq!(lines[2], "    participant bob");
+    assert_eq!(lines[3], "    Note over alice,bob: round 0");
+    assert_eq!(lines[4], "    alice->>bob: beaver (16 bytes)");
+    assert_eq!(lines.len(), 6);
+
+    let dot = report.transcript.to_dot();
+    assert!(dot.starts_with("digraph transcript {\n    \"alice\";\n    \"bob\";\n"));
+    assert!(dot.contains("    \"bob\" -> \"alice\" [label=\"round 0: beaver (16 bytes)\"];\n"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn king_reconstruction() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut dave: VirtualMachine<Fp> = VirtualMachine::new("dave");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie, &mut dave];
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(42), &mut prg);
+
+    let (all_to_all, direct) =
+        measure(&mut parties, |parties| mpc::reconstruct_share(parties, "x"));
+    let (via_king, star) = measure(&mut parties, |parties| {
+        mpc::reconstruct_via_king(parties, "x", "charlie")
+    });
+    assert_eq!(all_to_all.value(), 42);
+    assert_eq!(via_king.value(), 42);
+
+    assert_eq!(direct.rounds, 1);
+    assert_eq!(star.rounds, 2);
+    assert_eq!(direct.transcript.records().len(), 12);
+    assert_eq!(star.transcript.records().len(), 6);
+    assert!(star
+        .transcript
+        .records()
+        .iter()
+        .all(|record| record.message.from == "charlie" || record.message.to == "charlie"));
+    assert!(star.transcript.records()[..3]
+        .iter()
+        .all(|record| record.round == 0 && record.message.to == "charlie"));
+    assert_eq!(star.stats[2].messages_received, 3);
+    assert_eq!(star.stats[2].messages_sent, 3);
+    assert_eq!(Simulator::new(&parties).view("alice").opened[0].value(), 42);
+}
+
+#[test]
+fn view() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    let (_, report) = measure(&mut parties, |parties| {
+        let a = mpc::simulate_random_dist("a", parties, &Fp::new(6), &mut prg);
+        let b = mpc::simulate_random_dist("b", parties, &Fp::new(7), &mut prg);
+        let product = mpc::mult_protocol(parties, &a, &b, "product");
+        mpc::reconstruct_share(parties, &product)
+    });
+    assert_eq!(report.rounds, 2);
+
+    let view = Simulator::new(&parties).view("bob");
+    let ids: Vec<&str> = view.shares.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, vec!["a", "b", "product"]);
+    assert_eq!(view.received.len(), 2);
+    assert!(view
+        .received
+        .iter()
+        .all(|record| record.message.from == "alice"));
+    assert_eq!(
+        view.opened
+            .iter()
+            .map(|value| value.value())
+            .collect::<Vec<u64>>()[2],
+        42
+    );
+}
+
+#[test]
+fn view_of_opened_bits() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let parties = vec![&mut alice, &mut bob];
+
+    let network = Simulator::new(&parties);
+    network.open_bits(&[vec![true, false], vec![false, false]]);
+    assert_eq!(
+        network
+            .view("bob")
+            .opened
+            .iter()
+            .map(|value| value.value())
+            .collect::<Vec<u64>>(),
+        vec![1, 0]
+    );
+}
+
+#[test]
+fn simulate_add_view() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let a = mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    let b = mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+    Simulator::new(&parties).reset_stats();
+    let sum = mpc::add_protocol(&mut parties, &a, &b, "sum");
+    let output = mpc::reconstruct_share(&parties, &sum);
+    let real = Simulator::new(&parties).view("bob");
+
+    // The simulator only gets the inputs and the output of bob.
+    let inputs = [
+        ("a", parties[1].get_share("a").value.clone()),
+        ("b", parties[1].get_share("b").value.clone()),
+    ];
+    let simulated = network::simulate_add_view(
+        &["alice", "bob", "charlie"],
+        1,
+        &inputs,
+        "sum",
+        &output,
+        &mut prg,
+    );
+
+    assert_eq!(simulated.id, real.id);
+    let values = |view: &network::View<Fp>| -> Vec<(String, u64)> {
+        view.shares
+            .iter()
+            .map(|(id, share)| (id.clone(), share.value()))
+            .collect()
+    };
+    assert_eq!(values(&simulated), values(&real));
+    assert_eq!(simulated.opened[0].value(), real.opened[0].value());
+    assert_eq!(simulated.received.len(), real.received.len());
+    for view in [&real, &simulated] {
+        let total = view
+            .received
+            .iter()
+            .fold(view.shares[2].1.clone(), |acc, record| {
+                acc.add(&record.message.payload.elements()[0])
+            });
+        assert_eq!(total.value(), 13);
+    }
+    for (real, simulated) in real.received.iter().zip(&simulated.received) {
+        assert_eq!(real.round, simulated.round);
+        assert_eq!(real.n_bytes, simulated.n_bytes);
+        assert_eq!(real.message.from, simulated.message.from);
+        assert_eq!(real.message.tag, simulated.message.tag);
+    }
+}
+
+#[cfg(feature = "net")]
+#[test]
+fn wire_format() {
+    use smol_mpc::network::{tcp, Envelope, Message};
+
+    let envelope: Envelope<Fp> = Envelope {
+        round: 3,
+        from: 1,
+        active: true,
+        messages: vec![Message {
+            from: "bob".to_string(),
+            to: "alice".to_string(),
+            tag: "beaver".to_string(),
+            payload: Payload::Elements(vec![Fp::new(5), Fp::new(9)]),
+        }],
+    };
+    let decoded: Envelope<Fp> = tcp::decode(&tcp::encode(&envelope)).unwrap();
+    assert_eq!((decoded.round, decoded.from, decoded.active), (3, 1, true));
+    assert_eq!(decoded.messages[0].tag, "beaver");
+    let values: Vec<u64> = decoded.messages[0]
+        .payload
+        .elements()
+        .iter()
+        .map(|value| value.value())
+        .collect();
+    assert_eq!(values, vec![5, 9]);
+}
+
+#[cfg(feature = "net")]
+#[test]
+fn tcp_channel() {
+    use smol_mpc::network::tcp::TcpChannel;
+    use smol_mpc::protocol::{self, Mult, Reconstruct};
+    use std::net::TcpListener;
+
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+
+    let listeners: Vec<TcpListener> = (0..2)
+        .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+        .collect();
+    let addresses: Vec<_> = listeners
+        .iter()
+        .map(|listener| listener.local_addr().unwrap())
+        .collect();
+    let ids = vec!["alice".to_string(), "bob".to_string()];
+
+    let outputs: Vec<Fp> = std::thread::scope(|scope| {
+        let handles: Vec<_> = parties
+            .iter_mut()
+            .zip(listeners)
+            .enumerate()
+            .map(|(own, (vm, listener))| {
+                let (ids, addresses) = (&ids, &addresses);
+                scope.spawn(move || {
+                    let mut channel = TcpChannel::new(own, listener, addresses).unwrap();
+                    protocol::run_party(vm, &mut Mult::new("a", "b", "product"), ids, &mut channel);
+                    protocol::run_party(vm, &mut Reconstruct::new("product"), ids, &mut channel)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    assert!(outputs.iter().all(|value| value.value() == 42));
+}
+
+#[cfg(feature = "net")]
+#[test]
+fn wire_format_errors() {
+    use smol_mpc::network::{tcp, Envelope, Message};
+
+    let envelope: Envelope<Fp> = Envelope {
+        round: 0,
+        from: 0,
+        active: true,
+        messages: vec![Message {
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            tag: "open".to_string(),
+            payload: Payload::Bits(vec![true, false]),
+        }],
+    };
+    let bytes = tcp::encode(&envelope);
+
+    let truncated = tcp::decode::<Fp>(&bytes[..bytes.len() - 1]).err().unwrap();
+    assert_eq!(truncated.reason, "the envelope is too short");
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(tcp::decode::<Fp>(&trailing).is_err());
+    let mut kind = bytes.clone();
+    let position = kind.len() - 2 - 4 - 1;
+    kind[position] = 7;
+    let invalid = tcp::decode::<Fp>(&kind).err().unwrap();
+    assert_eq!(invalid.reason, "the payload kind 7 is not valid");
+}
+
+/// Connects a raw stream, announcing the provided position, to a channel in
+/// position 0 of two parties.
+#[cfg(feature = "net")]
+fn raw_peer(
+    position: u32,
+) -> (
+    Result<smol_mpc::network::tcp::TcpChannel<Fp>, smol_mpc::network::tcp::TcpError>,
+    std::net::TcpStream,
+) {
+    use smol_mpc::network::tcp::TcpChannel;
+    use std::io::Write;
+    use std::net::{TcpListener, TcpStream};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(&position.to_le_bytes()).unwrap();
+    (TcpChannel::new(0, listener, &[address, address]), stream)
+}
+
+#[cfg(feature = "net")]
+#[test]
+fn tcp_invalid_position() {
+    use smol_mpc::network::tcp::TcpError;
+
+    assert_eq!(raw_peer(0).0.err(), Some(TcpError::InvalidPosition(0)));
+    assert_eq!(raw_peer(2).0.err(), Some(TcpError::InvalidPosition(2)));
+}
+
+#[cfg(feature = "net")]
+#[test]
+#[should_panic(expected = "The party 1 sent a frame of 67108865 bytes, longer than 67108864.")]
+fn tcp_frame_too_long() {
+    use smol_mpc::network::{tcp, Channel};
+    use std::io::Write;
+
+    let (channel, mut stream) = raw_peer(1);
+    let mut channel = channel.unwrap();
+    let length = tcp::MAX_FRAME_LEN as u32 + 1;
+    stream.write_all(&length.to_le_bytes()).unwrap();
+    channel.recv();
+}
+
+#[cfg(feature = "net")]
+#[test]
+#[should_panic(expected = "The party 1 sent an envelope on behalf of the party 0.")]
+fn tcp_wrong_sender() {
+    use smol_mpc::network::{tcp, Channel, Envelope};
+    use std::io::Write;
+
+    let (channel, mut stream) = raw_peer(1);
+    let mut channel = channel.unwrap();
+    let envelope: Envelope<Fp> = Envelope {
+        round: 0,
+        from: 0,
+        active: true,
+        messages: vec![],
+    };
+    let bytes = tcp::encode(&envelope);
+    stream
+        .write_all(&(bytes.len() as u32).to_le_bytes())
+        .unwrap();
+    stream.write_all(&bytes).unwrap();
+    channel.recv();
+}
+
+#[cfg(feature = "net")]
+#[test]
+#[should_panic(expected = "The party 1 has left the execution.")]
+fn tcp_disconnected() {
+    use smol_mpc::network::Channel;
+    use std::io::Write;
+
+    let (channel, mut stream) = raw_peer(1);
+    let mut channel = channel.unwrap();
+    stream.write_all(&[16, 0, 0, 0, 1, 2]).unwrap();
+    drop(stream);
+    channel.recv();
+}
+
+#[cfg(feature = "net")]
+#[test]
+#[should_panic(expected = "All the other parties have left the execution.")]
+fn tcp_all_parties_left() {
+    use smol_mpc::network::Channel;
+
+    let (channel, stream) = raw_peer(1);
+    let mut channel = channel.unwrap();
+    drop(stream);
+    channel.recv();
+}
+
+/// Multiplies 6 and 7 and opens the product among three parties, which takes
+/// two rounds, under the provided network conditions.
+fn mult_under(config: &NetworkConfig) -> (u64, network::Report<Fp>) {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    let a = mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    let b = mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+    Simulator::new(&parties).configure(config, &mut prg);
+    let (product, report) = measure(&mut parties, |parties| {
+        let product = mpc::mult_protocol(parties, &a, &b, "product");
+        mpc::reconstruct_share(parties, &product)
+    });
+    (product.value(), report)
+}
+
+#[test]
+fn latency() {
+    let (product, report) = mult_under(&NetworkConfig::new().with_latency(50));
+    assert_eq!(product, 42);
+    assert_eq!(report.elapsed_ms, 100);
+
+    let config = NetworkConfig::new()
+        .with_latency(50)
+        .with_link_latency("alice", "charlie", 80);
+    let (_, report) = mult_under(&config);
+    assert_eq!(report.elapsed_ms, 160);
+}
+
+#[test]
+fn reordering() {
+    let (product, report) = mult_under(&NetworkConfig::new().with_reordering());
+    assert_eq!(product, 42);
+    assert_eq!(report.rounds, 2);
+}
+
+#[test]
+fn drops_with_resend() {
+    let resend = ResendPolicy::Retry {
+        max_retries: 64,
+        timeout_ms: 100,
+    };
+    let (product, report) = mult_under(
+        &NetworkConfig::new()
+            .with_latency(10)
+            .with_drops(0.5, resend),
+    );
+    assert_eq!(product, 42);
+    let resent: usize = report.stats.iter().map(|stats| stats.resent).sum();
+    assert!(resent > 0);
+    assert!(report.elapsed_ms > 20);
+    assert!(report.stats.iter().all(|stats| stats.lost == 0));
+}
+
+#[test]
+#[should_panic]
+fn drops_without_resend() {
+    mult_under(&NetworkConfig::new().with_drops(1.0, ResendPolicy::Never));
+}
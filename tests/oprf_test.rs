@@ -0,0 +1,72 @@
+#![cfg(feature = "sha2")]
+
+use smol_mpc::math::group::Group;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::oprf;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn hash_to_group() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let h = oprf::hash_to_group(&group, &Fp::new(1));
+    assert_eq!(h, oprf::hash_to_group(&group, &Fp::new(1)));
+    assert_ne!(h, oprf::hash_to_group(&group, &Fp::new(2)));
+    assert_eq!(group.exp(&h, &Fp::new(0)), group.identity());
+}
+
+#[test]
+fn oprf_protocol() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut client: VirtualMachine<Fp> = VirtualMachine::new("client");
+    let mut server: VirtualMachine<Fp> = VirtualMachine::new("server");
+    client.insert_priv_value("x", Fp::new(42));
+    server.insert_priv_value("k", Fp::random(&mut prg));
+    server.insert_priv_value("other", Fp::random(&mut prg));
+
+    let output = oprf::oprf_protocol(&mut client, &mut server, &group, "x", "k");
+    assert_eq!(output.len(), oprf::OUTPUT_LEN);
+    assert_eq!(
+        output,
+        oprf::evaluate(&group, server.get_priv_value("k"), &Fp::new(42))
+    );
+    assert_ne!(
+        output,
+        oprf::evaluate(&group, server.get_priv_value("k"), &Fp::new(43))
+    );
+    assert_ne!(
+        output,
+        oprf::oprf_protocol(&mut client, &mut server, &group, "x", "other")
+    );
+}
+
+#[test]
+fn oprf_batch_protocol() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut client: VirtualMachine<Fp> = VirtualMachine::new("client");
+    let mut server: VirtualMachine<Fp> = VirtualMachine::new("server");
+    for (id, value) in [("a", 1), ("b", 2), ("c", 1)] {
+        client.insert_priv_value(id, Fp::new(value));
+    }
+    server.insert_priv_value("k", Fp::random(&mut prg));
+
+    let outputs =
+        oprf::oprf_batch_protocol(&mut client, &mut server, &group, &["a", "b", "c"], "k");
+    assert_eq!(
+        outputs[0],
+        oprf::evaluate(&group, server.get_priv_value("k"), &Fp::new(1))
+    );
+    assert_eq!(
+        outputs[1],
+        oprf::evaluate(&group, server.get_priv_value("k"), &Fp::new(2))
+    );
+    assert_eq!(outputs[0], outputs[2]);
+}
@@ -0,0 +1,138 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::network::Simulator;
+use smol_mpc::utils::ot;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn ideal_ot() {
+    assert_eq!(ot::ideal_ot(&"zero", &"one", false), "zero");
+    assert_eq!(ot::ideal_ot(&"zero", &"one", true), "one");
+}
+
+#[test]
+fn ot_channel() {
+    let mut channel = ot::OtChannel::new();
+    channel.send(10, 20);
+    assert_eq!(channel.receive(true), 20);
+}
+
+#[test]
+fn ot_protocol() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    alice.insert_priv_value("m0", Fp::new(10));
+    alice.insert_priv_value("m1", Fp::new(20));
+    bob.insert_priv_value("c", Fp::new(0));
+
+    ot::ot_protocol(&mut alice, &mut bob, ("m0", "m1"), "c", "m");
+    assert_eq!(bob.get_priv_value("m").value(), 10);
+
+    // The sender answers the columns of the receiver with one masked pair.
+    let parties = vec![&mut alice, &mut bob];
+    let network = Simulator::new(&parties);
+    let stats = network.stats();
+    assert_eq!(stats[0].messages_sent, 1);
+    assert_eq!(stats[0].bytes_sent, 2 * 8);
+    assert!(stats[1].messages_sent > 0);
+    assert!(network
+        .transcript()
+        .records()
+        .iter()
+        .all(|record| record.message.tag == "iknp"));
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn dh_ot() {
+    use smol_mpc::math::group::Group;
+    use smol_mpc::utils::prg::Prg;
+
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    for choice in [false, true] {
+        let sender = ot::DhOtSender::new(&group, &mut prg);
+        let (receiver, message) =
+            ot::DhOtReceiver::new(&group, sender.first_message(), choice, &mut prg);
+        let ciphertexts = sender.send(&message, b"zero", b"one!");
+
+        let expected: &[u8] = if choice { b"one!" } else { b"zero" };
+        assert_eq!(receiver.receive(&ciphertexts), expected);
+    }
+}
+
+#[test]
+fn iknp_extension() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let messages: Vec<(Vec<u8>, Vec<u8>)> = (0..300u32)
+        .map(|j| (j.to_le_bytes().to_vec(), (j + 1000).to_le_bytes().to_vec()))
+        .collect();
+    let choices: Vec<bool> = (0..300).map(|j| j % 3 == 0).collect();
+
+    let received = ot::iknp_extension(&mut alice, &mut bob, &messages, &choices);
+    for ((m0, m1), (choice, message)) in messages.iter().zip(choices.iter().zip(received)) {
+        assert_eq!(&message, if *choice { m1 } else { m0 });
+    }
+}
+
+#[test]
+fn iknp_extension_messages() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let choices: Vec<bool> = (0..64).map(|j| j % 2 == 0).collect();
+    ot::random_ot_extension(&mut alice, &mut bob, &choices, 16);
+
+    // The receiver sends one column of 64 bits for each base OT.
+    let parties = vec![&mut alice, &mut bob];
+    let network = Simulator::new(&parties);
+    let stats = network.stats();
+    assert_eq!(stats[1].bytes_sent, ot::IKNP_BASE_OTS * 64 / 8);
+    assert_eq!(stats[0].bytes_received, stats[1].bytes_sent);
+    assert!(network
+        .transcript()
+        .records()
+        .iter()
+        .all(|record| record.message.tag == "iknp" && record.message.from == "bob"));
+}
+
+#[test]
+fn random_ot_extension() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let choices: Vec<bool> = (0..200).map(|j| j % 2 == 0).collect();
+    let (pairs, received) = ot::random_ot_extension(&mut alice, &mut bob, &choices, 16);
+    for ((x0, x1), (choice, x)) in pairs.iter().zip(choices.iter().zip(received)) {
+        assert_eq!(x.len(), 16);
+        assert_ne!(x0, x1);
+        assert_eq!(&x, if *choice { x1 } else { x0 });
+    }
+}
+
+#[test]
+fn correlated_ot_extension() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+
+    let deltas: Vec<Fp> = (0..200).map(|j| Fp::new(j * 7)).collect();
+    let choices: Vec<bool> = (0..200).map(|j| j % 3 == 0).collect();
+    let (sender, receiver) = ot::correlated_ot_extension(&mut alice, &mut bob, &deltas, &choices);
+    for j in 0..200 {
+        let expected = if choices[j] {
+            sender[j].add(&deltas[j])
+        } else {
+            sender[j].clone()
+        };
+        assert_eq!(receiver[j].value(), expected.value());
+    }
+
+    // The corrections are sent by the sender in a second round.
+    let parties = vec![&mut alice, &mut bob];
+    assert_eq!(Simulator::new(&parties).stats()[0].messages_sent, 1);
+    assert_eq!(Simulator::new(&parties).rounds(), 2);
+}
@@ -0,0 +1,44 @@
+use smol_mpc::utils::paillier::{self, BigUint};
+use smol_mpc::utils::prg::Prg;
+
+#[test]
+fn paillier_encrypt_decrypt() {
+    let mut prg = Prg::new(None);
+    let (public_key, secret_key) = paillier::keygen(128, &mut prg);
+
+    let message = BigUint::from(123456789u64);
+    let ciphertext = public_key.encrypt(&message, &mut prg);
+    assert_eq!(secret_key.decrypt(&ciphertext), message);
+}
+
+#[test]
+fn paillier_homomorphic_operations() {
+    let mut prg = Prg::new(None);
+    let (public_key, secret_key) = paillier::keygen(128, &mut prg);
+
+    let a = public_key.encrypt(&BigUint::from(20u32), &mut prg);
+    let b = public_key.encrypt(&BigUint::from(22u32), &mut prg);
+    let sum = public_key.add(&a, &b);
+    let scaled = public_key.mul_const(&a, &BigUint::from(5u32));
+
+    assert_eq!(secret_key.decrypt(&sum), BigUint::from(42u32));
+    assert_eq!(secret_key.decrypt(&scaled), BigUint::from(100u32));
+}
+
+#[test]
+fn paillier_sum() {
+    let mut prg = Prg::new(None);
+    let (public_key, secret_key) = paillier::keygen(128, &mut prg);
+
+    let ciphertexts: Vec<_> = (1..=4u32)
+        .map(|message| public_key.encrypt(&BigUint::from(message), &mut prg))
+        .collect();
+    assert_eq!(
+        secret_key.decrypt(&public_key.sum(&ciphertexts)),
+        BigUint::from(10u32)
+    );
+    assert_eq!(
+        secret_key.decrypt(&public_key.sum(&[])),
+        BigUint::from(0u32)
+    );
+}
@@ -0,0 +1,93 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::party::PartySet;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn protocols_on_party_set() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = PartySet::new(vec![&mut alice, &mut bob]);
+
+    parties.party("bob").insert_priv_value("b", Fp::new(6));
+    mpc::simulate_random_dist("a", parties.parties_mut(), &Fp::new(3), &mut prg);
+    mpc::generate_triples(parties.parties_mut(), 1, &mut prg);
+    mpc::mult_protocol(parties.parties_mut(), "a", "a", "c");
+    assert_eq!(mpc::reconstruct_share(&parties, "c").value(), 9);
+    assert_eq!(parties.position("bob"), 1);
+    assert_eq!(parties.ids(), vec!["alice", "bob"]);
+
+    mpc::distribute_shares("b", "bob", parties.into_inner());
+    assert_eq!(
+        alice
+            .get_share("b")
+            .value
+            .add(&bob.get_share("b").value)
+            .value(),
+        6
+    );
+}
+
+#[test]
+#[should_panic(expected = "The party alice appears more than once.")]
+fn duplicate_ids() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut other: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    PartySet::new(vec![&mut alice, &mut other]);
+}
+
+#[test]
+#[should_panic(expected = "Party with that id does not exist.")]
+fn missing_owner() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let parties = PartySet::new(vec![&mut alice]);
+    parties.position("bob");
+}
+
+#[test]
+fn threshold() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let parties = PartySet::new(vec![&mut alice, &mut bob, &mut charlie]);
+    assert!(parties.threshold().is_none());
+    assert!(!parties.is_qualified(&["alice", "bob"]));
+
+    let parties = parties.with_threshold(1);
+    assert_eq!(parties.threshold(), Some(1));
+    assert!(parties.is_qualified(&["alice", "charlie"]));
+    assert!(!parties.is_qualified(&["bob", "bob", "dave"]));
+    assert_eq!(
+        parties.replicated_subsets(),
+        vec![
+            vec!["alice", "bob"],
+            vec!["alice", "charlie"],
+            vec!["bob", "charlie"]
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "The threshold must be smaller than the number of parties.")]
+fn threshold_too_large() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    PartySet::new(vec![&mut alice]).with_threshold(1);
+}
+
+#[test]
+fn remove_party() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = PartySet::new(vec![&mut alice, &mut bob, &mut charlie]).with_threshold(1);
+
+    let removed = parties.remove("bob");
+    assert_eq!(removed.id, "bob");
+    assert_eq!(parties.ids(), vec!["alice", "charlie"]);
+    parties.set_threshold(0);
+    assert_eq!(parties.threshold(), Some(0));
+}
@@ -0,0 +1,41 @@
+use smol_mpc::math::group::Group;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::utils::pedersen::PedersenParams;
+use smol_mpc::utils::prg::Prg;
+
+type Fp = Mersenne61;
+
+#[test]
+fn group_exponents_are_field_elements() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+    let g = group.generator();
+
+    let a = Fp::random(&mut prg);
+    let b = Fp::random(&mut prg);
+    let product = group.mul(&group.exp(g, &a), &group.exp(g, &b));
+    assert_eq!(product, group.exp(g, &a.add(&b)));
+    assert_eq!(group.exp(g, &Fp::new(0)), group.identity());
+    assert_eq!(group.mul(g, &group.inverse(g)), group.identity());
+}
+
+#[test]
+fn pedersen_commit_and_verify() {
+    let mut prg = Prg::new(None);
+    let params = PedersenParams::new(Group::generate(128, &mut prg), &mut prg);
+
+    let (commitment, randomness) = params.commit_random(&Fp::new(42), &mut prg);
+    assert!(params.verify(&commitment, &Fp::new(42), &randomness));
+    assert!(!params.verify(&commitment, &Fp::new(43), &randomness));
+}
+
+#[test]
+fn pedersen_add() {
+    let mut prg = Prg::new(None);
+    let params = PedersenParams::new(Group::generate(128, &mut prg), &mut prg);
+
+    let (c1, r1) = params.commit_random(&Fp::new(20), &mut prg);
+    let (c2, r2) = params.commit_random(&Fp::new(22), &mut prg);
+    let sum = params.add(&c1, &c2);
+    assert!(params.verify(&sum, &Fp::new(42), &r1.add(&r2)));
+}
@@ -0,0 +1,41 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::preprocessing::{AndTripleHandle, Preprocessing, TripleHandle};
+
+type Fp = Mersenne61;
+
+#[test]
+fn preprocessing_triples_in_order() {
+    let mut store: Preprocessing<Fp> = Preprocessing::new();
+    let first = store.add_triple((Fp::new(1), Fp::new(2), Fp::new(3)));
+    let second = store.add_triple((Fp::new(4), Fp::new(5), Fp::new(6)));
+    assert_eq!(first, TripleHandle(0));
+    assert_eq!(second, TripleHandle(1));
+    assert_eq!(store.remaining_triples(), 2);
+
+    let (handle, (a, _, _)) = store.next_triple().unwrap();
+    assert_eq!(handle, first);
+    assert_eq!(a.value(), 1);
+
+    let (handle, (_, _, c)) = store.next_triple().unwrap();
+    assert_eq!(handle, second);
+    assert_eq!(c.value(), 6);
+
+    assert!(store.next_triple().is_none());
+    assert_eq!(store.remaining_triples(), 0);
+}
+
+#[test]
+fn preprocessing_and_triples() {
+    let mut store: Preprocessing<Fp> = Preprocessing::new();
+    store.add_triple((Fp::new(1), Fp::new(2), Fp::new(3)));
+    let handle = store.add_and_triple((true, false, false));
+    assert_eq!(handle, AndTripleHandle(0));
+    assert_eq!(store.remaining_and_triples(), 1);
+    assert_eq!(store.remaining_triples(), 1);
+
+    assert_eq!(
+        store.next_and_triple(),
+        Some((handle, (true, false, false)))
+    );
+    assert!(store.next_and_triple().is_none());
+}
@@ -1,4 +1,13 @@
-use smol_mpc::utils::prg::Prg;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+#[cfg(all(feature = "aes", feature = "chacha20"))]
+use smol_mpc::utils::prg::AesPrg;
+#[cfg(feature = "chacha20")]
+use smol_mpc::utils::prg::ChaChaPrg;
+use smol_mpc::utils::prg::{Prg, PseudoRandomGenerator, RngAdapter};
+
+type Fp = Mersenne61;
 
 #[test]
 fn create_prg_default() {
@@ -28,3 +37,141 @@ fn create_prg_autocomplete() {
 
     assert_eq!(random_stream, random_stream_real);
 }
+
+#[test]
+fn prg_consecutive_calls_differ() {
+    let mut prg = Prg::new(None);
+    let first = prg.next(8);
+    let second = prg.next(8);
+
+    assert_ne!(first, second);
+}
+
+#[test]
+fn random_from_trait_object() {
+    let mut prg = Prg::new(Some(vec![7]));
+    let mut same_prg = Prg::new(Some(vec![7]));
+    let generator: &mut dyn PseudoRandomGenerator = &mut same_prg;
+
+    assert_eq!(Fp::random(&mut prg).value(), Fp::random(generator).value());
+    assert_eq!(generator.counter(), 1);
+}
+
+#[cfg(feature = "chacha20")]
+#[test]
+fn chacha_prg() {
+    let mut prg = ChaChaPrg::new(Some(vec![1; 40]));
+    let mut same_prg = ChaChaPrg::new(Some(vec![1; 32]));
+    let first = prg.next(100);
+    assert_eq!(first, same_prg.next(100));
+    assert_eq!(prg.counter(), 2);
+    assert_eq!(prg.block_len(), 64);
+
+    let second = prg.next(8);
+    prg.reset();
+    assert_eq!(prg.next(128)[..100], first[..]);
+    assert_ne!(second[..], first[..8]);
+}
+
+#[cfg(all(feature = "aes", feature = "chacha20"))]
+#[test]
+fn generators_differ() {
+    let mut aes = AesPrg::new(None);
+    let mut chacha = ChaChaPrg::new(None);
+    assert_ne!(aes.next(32), chacha.next(32));
+}
+
+#[test]
+fn rand_traits() {
+    let mut prg = Prg::from_seed([3; 32]);
+    let mut same_prg = Prg::new(Some(vec![3; 32]));
+    assert_eq!(prg.next_u64().to_le_bytes().to_vec(), same_prg.next(8));
+
+    let mut bytes = [0; 20];
+    prg.fill_bytes(&mut bytes);
+    assert_eq!(bytes.to_vec(), same_prg.next(20));
+
+    let roll: u32 = prg.gen_range(1..=6);
+    assert!((1..=6).contains(&roll));
+}
+
+#[test]
+fn rng_adapter() {
+    let mut rng = RngAdapter::new(StdRng::seed_from_u64(1));
+    let mut same_rng = StdRng::seed_from_u64(1);
+
+    let value = Fp::random(&mut rng);
+    assert_eq!(value.value(), Fp::new(same_rng.next_u64()).value());
+    assert_eq!(rng.next(9).len(), 9);
+    assert_eq!(rng.counter(), 3);
+    rng.reset();
+    assert_eq!(rng.counter(), 0);
+}
+
+#[test]
+fn short_draws_share_a_block() {
+    let mut prg = Prg::new(Some(vec![5]));
+    let mut same_prg = Prg::new(Some(vec![5]));
+
+    let mut bytes = [0; 8];
+    prg.fill_bytes(&mut bytes);
+    let value = prg.next_u64();
+    assert_eq!(prg.counter(), 1);
+
+    let stream = same_prg.next(16);
+    assert_eq!(bytes.to_vec(), stream[..8].to_vec());
+    assert_eq!(value.to_le_bytes().to_vec(), stream[8..].to_vec());
+
+    let block = prg.next_block();
+    assert_eq!(block.to_vec(), same_prg.next(block.len()));
+    assert_eq!(
+        prg.next_u128(),
+        u128::from_le_bytes(same_prg.next(16).try_into().unwrap())
+    );
+}
+
+#[test]
+fn fork() {
+    let mut prg = Prg::new(Some(vec![9; 32]));
+    let child = prg.fork("alice");
+    prg.next(100);
+
+    assert_eq!(prg.fork("alice").next(32), child.clone().next(32));
+    assert_ne!(prg.fork("bob").next(32), child.clone().next(32));
+    assert_ne!(
+        prg.fork("alice").fork("bob").next(32),
+        child.clone().next(32)
+    );
+    assert_ne!(prg.fork("").next(32), Prg::new(Some(vec![9; 32])).next(32));
+    assert_ne!(
+        Prg::new(None).fork("alice").next(32),
+        child.clone().next(32)
+    );
+}
+
+#[test]
+fn nonces_and_positions() {
+    let mut prg = Prg::with_nonce(Some(vec![4]), 1);
+    let mut same_prg = Prg::with_nonce(Some(vec![4]), 1);
+    assert_eq!(prg.nonce(), 1);
+    assert_ne!(prg.clone().next(16), Prg::new(Some(vec![4])).next(16));
+
+    prg.next(2 * prg.block_len() + 8);
+    same_prg.skip_blocks(2);
+    assert_eq!(prg.counter(), 3);
+    same_prg.set_counter(prg.counter());
+    prg.set_counter(3);
+    assert_eq!(prg.next(16), same_prg.next(16));
+}
+
+#[test]
+#[should_panic(expected = "is reserved to fork generators.")]
+fn reserved_nonce() {
+    Prg::with_nonce(None, u64::MAX);
+}
+
+#[cfg(feature = "getrandom")]
+#[test]
+fn from_entropy() {
+    assert_ne!(Prg::from_entropy().next(16), Prg::from_entropy().next(16));
+}
@@ -0,0 +1,178 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc;
+use smol_mpc::network::measure;
+use smol_mpc::protocol::{self, Add, Debugger, DistributeShares, Mult, Reconstruct};
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn distribute_shares() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    bob.insert_priv_value("x", Fp::new(12));
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    let machines = (0..3).map(|_| DistributeShares::new("x", "bob")).collect();
+    let (handles, report) = measure(&mut parties, |parties| protocol::execute(parties, machines));
+    assert_eq!(report.rounds, 1);
+    assert_eq!(report.stats[1].messages_sent, 2);
+    assert_eq!(mpc::reconstruct_share(&parties, &handles[0]).value(), 12);
+}
+
+#[test]
+fn add() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(3), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(4), &mut prg);
+    let (_, report) = measure(&mut parties, |parties| {
+        protocol::execute(parties, vec![Add::new("a", "b", "sum"); 2])
+    });
+    assert_eq!(report.rounds, 0);
+    assert_eq!(mpc::reconstruct_share(&parties, "sum").value(), 7);
+}
+
+#[test]
+fn mult() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+    let (_, report) = measure(&mut parties, |parties| {
+        protocol::execute(parties, vec![Mult::new("a", "b", "product"); 3])
+    });
+    assert_eq!(report.rounds, 1);
+    assert!(report.stats.iter().all(|stats| stats.bytes_sent == 32));
+    assert_eq!(mpc::reconstruct_share(&parties, "product").value(), 42);
+}
+
+#[test]
+fn reconstruct() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(9), &mut prg);
+    let outputs = protocol::execute(&mut parties, vec![Reconstruct::new("a"); 2]);
+    assert!(outputs.iter().all(|value| value.value() == 9));
+}
+
+#[test]
+#[should_panic]
+fn missing_machine() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    protocol::execute(&mut parties, vec![Reconstruct::<Fp>::new("a")]);
+}
+
+#[test]
+fn debugger_rounds() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+
+    let mut debugger = Debugger::new(&mut parties, vec![Mult::new("a", "b", "product"); 2]);
+    assert!(debugger.step_round());
+    assert_eq!(debugger.round(), 1);
+    assert_eq!(debugger.pending().len(), 2);
+    assert!(!debugger.party("alice").contains_share("product"));
+
+    assert!(debugger.step_round());
+    assert!(debugger.is_finished());
+    assert!(debugger.pending().is_empty());
+    assert!(debugger.party("alice").contains_share("product"));
+    assert!(!debugger.step_round());
+    drop(debugger);
+
+    assert_eq!(mpc::reconstruct_share(&parties, "product").value(), 42);
+}
+
+#[test]
+fn debugger_messages() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(5), &mut prg);
+    let mut debugger = Debugger::new(&mut parties, vec![Reconstruct::new("a"); 3]);
+    assert!(debugger.step_message().is_none());
+    assert_eq!(debugger.pending().len(), 6);
+
+    let message = debugger.step_message().unwrap();
+    assert_eq!(message.from, "alice");
+    assert_eq!(message.to, "bob");
+    assert_eq!(debugger.inbox("bob").len(), 1);
+    assert_eq!(debugger.pending().len(), 5);
+
+    let outputs = debugger.run();
+    assert!(outputs.iter().all(|value| value.value() == 5));
+}
+
+#[test]
+fn execute_threaded() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    alice.insert_priv_value("x", Fp::new(6));
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    mpc::simulate_random_dist("y", &mut parties, &Fp::new(7), &mut prg);
+    let machines = (0..3)
+        .map(|_| DistributeShares::new("x", "alice"))
+        .collect();
+    protocol::execute_threaded(&mut parties, machines);
+
+    let (outputs, report) = measure(&mut parties, |parties| {
+        protocol::execute_threaded(parties, vec![Mult::new("x", "y", "product"); 3]);
+        protocol::execute_threaded(parties, vec![Reconstruct::new("product"); 3])
+    });
+    assert!(outputs.iter().all(|value| value.value() == 42));
+    assert_eq!(report.rounds, 2);
+    assert!(report
+        .stats
+        .iter()
+        .all(|stats| stats.messages_sent == 4 && stats.messages_received == 4));
+    assert_eq!(report.transcript.with_tag("beaver").records().len(), 6);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn execute_async() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+    mpc::generate_triples(&mut parties, 1, &mut prg);
+    mpc::simulate_random_dist("a", &mut parties, &Fp::new(6), &mut prg);
+    mpc::simulate_random_dist("b", &mut parties, &Fp::new(7), &mut prg);
+
+    let results =
+        protocol::execute_async(vec![alice, bob], vec![Mult::new("a", "b", "product"); 2]).await;
+    let parties: Vec<VirtualMachine<Fp>> = results.into_iter().map(|(party, _)| party).collect();
+    let outputs = protocol::execute_async(parties, vec![Reconstruct::new("product"); 2]).await;
+    assert!(outputs.iter().all(|(_, value)| value.value() == 42));
+    assert_eq!(outputs[0].0.mailbox.stats().messages_sent, 2);
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "sha2")]
+
+use smol_mpc::apps::psi;
+use smol_mpc::math::group::Group;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::network::Simulator;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+fn parties(
+    receiver: &[u64],
+    sender: &[u64],
+) -> (
+    VirtualMachine<Fp>,
+    VirtualMachine<Fp>,
+    Vec<String>,
+    Vec<String>,
+) {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let ids_receiver: Vec<String> = (0..receiver.len()).map(|i| format!("x{}", i)).collect();
+    let ids_sender: Vec<String> = (0..sender.len()).map(|i| format!("y{}", i)).collect();
+    for (id, value) in ids_receiver.iter().zip(receiver) {
+        alice.insert_priv_value(id, Fp::new(*value));
+    }
+    for (id, value) in ids_sender.iter().zip(sender) {
+        bob.insert_priv_value(id, Fp::new(*value));
+    }
+    (alice, bob, ids_receiver, ids_sender)
+}
+
+#[test]
+fn psi_protocol() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let (mut alice, mut bob, ids_receiver, ids_sender) =
+        parties(&[7, 3, 10, 42, 8], &[1, 42, 8, 9, 7, 100]);
+    let ids_receiver: Vec<&str> = ids_receiver.iter().map(String::as_str).collect();
+    let ids_sender: Vec<&str> = ids_sender.iter().map(String::as_str).collect();
+
+    let intersection = psi::psi_protocol(&mut alice, &mut bob, &group, &ids_receiver, &ids_sender);
+    let values: Vec<u64> = intersection.iter().map(|value| value.value()).collect();
+    assert_eq!(values, vec![7, 42, 8]);
+    assert_eq!(
+        psi::psi_cardinality_protocol(&mut alice, &mut bob, &group, &ids_receiver, &ids_sender),
+        3
+    );
+
+    // The key of the sender is not kept.
+    assert_eq!(bob.priv_values().len(), ids_sender.len());
+}
+
+#[test]
+fn psi_protocol_disjoint() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let (mut alice, mut bob, ids_receiver, ids_sender) = parties(&[1, 2, 3], &[4, 5]);
+    let ids_receiver: Vec<&str> = ids_receiver.iter().map(String::as_str).collect();
+    let ids_sender: Vec<&str> = ids_sender.iter().map(String::as_str).collect();
+
+    assert!(psi::psi_protocol(&mut alice, &mut bob, &group, &ids_receiver, &ids_sender).is_empty());
+    assert!(psi::psi_protocol(&mut alice, &mut bob, &group, &ids_receiver, &[]).is_empty());
+}
+
+#[test]
+fn psi_protocol_messages() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let (mut alice, mut bob, ids_receiver, ids_sender) = parties(&[1, 2], &[2, 3, 4]);
+    let ids_receiver: Vec<&str> = ids_receiver.iter().map(String::as_str).collect();
+    let ids_sender: Vec<&str> = ids_sender.iter().map(String::as_str).collect();
+
+    psi::psi_protocol(&mut alice, &mut bob, &group, &ids_receiver, &ids_sender);
+
+    // The evaluated set of the sender goes through the network to the receiver.
+    let stats = Simulator::new(&[&mut alice, &mut bob]).stats();
+    assert_eq!(stats[0].messages_received, 1);
+    assert_eq!(stats[1].messages_sent, 1);
+}
+
+#[test]
+fn psi_cardinality_protocol() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let (mut alice, mut bob, ids_receiver, ids_sender) =
+        parties(&[7, 3, 10, 42, 8], &[1, 42, 8, 9, 7, 100]);
+    let ids_receiver: Vec<&str> = ids_receiver.iter().map(String::as_str).collect();
+    let ids_sender: Vec<&str> = ids_sender.iter().map(String::as_str).collect();
+
+    assert_eq!(
+        psi::psi_cardinality_protocol(&mut alice, &mut bob, &group, &ids_receiver, &ids_sender),
+        3
+    );
+    assert_eq!(
+        psi::psi_cardinality_protocol(&mut alice, &mut bob, &group, &ids_receiver, &[]),
+        0
+    );
+
+    // The receiver only gets group elements, which do not reveal the
+    // intersection, and the sender keeps no key.
+    assert_eq!(bob.priv_values().len(), ids_sender.len());
+    let parties = [&mut alice, &mut bob];
+    let network = Simulator::new(&parties);
+    assert!(network
+        .transcript()
+        .records()
+        .iter()
+        .all(|record| record.message.tag == "psi_ca"));
+    assert_eq!(network.stats()[0].messages_received, 4);
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "sha2")]
+
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::utils::ro::RandomOracle;
+
+type Fp = Mersenne61;
+
+#[test]
+fn deterministic() {
+    let mut oracle = RandomOracle::new("test");
+    let mut same_oracle = RandomOracle::new("test");
+    oracle.absorb(b"data");
+    same_oracle.absorb(b"data");
+
+    assert_eq!(oracle.squeeze(40), same_oracle.squeeze(40));
+    assert_eq!(
+        oracle.squeeze_field_element::<Fp>().value(),
+        same_oracle.squeeze_field_element::<Fp>().value()
+    );
+}
+
+#[test]
+fn domain_separation() {
+    let squeeze = |domain: &str, data: &[&[u8]]| {
+        let mut oracle = RandomOracle::new(domain);
+        for chunk in data {
+            oracle.absorb(chunk);
+        }
+        oracle.squeeze(32)
+    };
+
+    assert_ne!(squeeze("a", &[b"data"]), squeeze("b", &[b"data"]));
+    assert_ne!(squeeze("a", &[b"ab", b"c"]), squeeze("a", &[b"a", b"bc"]));
+    assert_ne!(squeeze("a", &[b"abc"]), squeeze("a", &[b"ab", b"c"]));
+    assert_ne!(squeeze("ab", &[b"c"]), squeeze("a", &[b"bc"]));
+}
+
+#[test]
+fn squeezes_differ() {
+    let mut oracle = RandomOracle::new("test");
+    oracle.absorb_field_element(&Fp::new(7));
+
+    let first = oracle.squeeze(16);
+    let second = oracle.squeeze(16);
+    assert_ne!(first, second);
+
+    for _ in 0..100 {
+        assert!(oracle.squeeze_field_element::<Fp>().value() < Fp::ORDER);
+    }
+}
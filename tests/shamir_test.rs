@@ -0,0 +1,100 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::shamir;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn share_and_reconstruct() {
+    let mut prg = Prg::new(None);
+
+    let shares = shamir::share(&Fp::new(1234), 2, 5, &mut prg);
+    assert_eq!(shares.len(), 5);
+    assert_eq!(shamir::reconstruct(&shares).value(), 1234);
+    assert_eq!(shamir::reconstruct(&shares[..3]).value(), 1234);
+}
+
+#[test]
+fn double_sharing_protocol() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+
+    shamir::double_sharing_protocol(&mut parties, 1, ("r_t", "r_2t"));
+
+    let shares_t: Vec<Fp> = parties
+        .iter()
+        .map(|party| party.get_share("r_t").value.clone())
+        .collect();
+    let r_t = shamir::reconstruct_share(&parties, "r_t");
+    let r_2t = shamir::reconstruct_share(&parties, "r_2t");
+    assert_eq!(r_t.value(), r_2t.value());
+
+    // Two shares are enough for a sharing of degree one.
+    assert_eq!(shamir::reconstruct(&shares_t[..2]).value(), r_t.value());
+}
+
+#[test]
+fn mult_protocol() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut dave: VirtualMachine<Fp> = VirtualMachine::new("dave");
+    let mut eve: VirtualMachine<Fp> = VirtualMachine::new("eve");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie, &mut dave, &mut eve];
+
+    shamir::simulate_shamir_dist("x", &mut parties, &Fp::new(21), 2, &mut prg);
+    shamir::simulate_shamir_dist("y", &mut parties, &Fp::new(2), 2, &mut prg);
+    shamir::double_sharing_protocol(&mut parties, 2, ("r_t", "r_2t"));
+    shamir::mult_protocol(&mut parties, "x", "y", "xy", ("r_t", "r_2t"));
+
+    let shares: Vec<Fp> = parties
+        .iter()
+        .map(|party| party.get_share("xy").value.clone())
+        .collect();
+    assert_eq!(shamir::reconstruct(&shares[..3]).value(), 42);
+    for party in &parties {
+        assert!(!party.contains_share("r_t"));
+    }
+}
+
+#[test]
+fn reconstruct_with_dropouts() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut dave: VirtualMachine<Fp> = VirtualMachine::new("dave");
+    let mut eve: VirtualMachine<Fp> = VirtualMachine::new("eve");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie, &mut dave, &mut eve];
+    shamir::simulate_shamir_dist("x", &mut parties, &Fp::new(77), 2, &mut prg);
+
+    parties[1].mailbox.set_offline(true);
+    parties[3].mailbox.set_offline(true);
+    assert_eq!(shamir::reconstruct_share(&parties, "x").value(), 77);
+    assert_eq!(
+        shamir::try_reconstruct_share(&parties, "x", 2)
+            .unwrap()
+            .value(),
+        77
+    );
+
+    parties[4].mailbox.set_offline(true);
+    let error = shamir::try_reconstruct_share(&parties, "x", 2)
+        .err()
+        .unwrap();
+    assert_eq!(error.id, "bob");
+    assert_eq!(error.to_string(), "The party bob is offline.");
+}
+
+#[test]
+fn interpolate_at_points() {
+    let mut prg = Prg::new(None);
+    let shares = shamir::share(&Fp::new(99), 1, 4, &mut prg);
+    let value = shamir::interpolate_at_zero(&[2, 4], &[shares[1].clone(), shares[3].clone()]);
+    assert_eq!(value.value(), 99);
+}
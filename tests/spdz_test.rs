@@ -0,0 +1,79 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::spdz;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn spdz_add_and_mult() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+
+    alice.insert_priv_value("a", Fp::new(6));
+    bob.insert_priv_value("b", Fp::new(7));
+
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+    spdz::setup(&mut parties, &mut prg);
+    spdz::distribute_shares("a", "alice", &mut parties, &mut prg);
+    spdz::distribute_shares("b", "bob", &mut parties, &mut prg);
+    spdz::generate_triple(&mut parties, ("x", "y", "z"), &mut prg);
+
+    spdz::add_protocol(&mut parties, "a", "b", "sum");
+    spdz::mult_protocol(&mut parties, "a", "b", "prod", ("x", "y", "z"));
+
+    assert_eq!(
+        spdz::reconstruct_share(&mut parties, "sum", &mut prg).value(),
+        13
+    );
+    assert_eq!(
+        spdz::reconstruct_share(&mut parties, "prod", &mut prg).value(),
+        42
+    );
+    for party in &parties {
+        assert!(party.spdz.opened.is_empty());
+    }
+}
+
+#[test]
+#[should_panic(expected = "MAC check failed")]
+fn spdz_detects_tampering() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    spdz::setup(&mut parties, &mut prg);
+    spdz::simulate_auth_dist("x", &mut parties, &Fp::new(10), &mut prg);
+
+    // Bob adds one to its share without updating the MAC.
+    let share = parties[1].spdz.shares.get_mut("x").unwrap();
+    share.value = share.value.add(&Fp::new(1));
+
+    spdz::reconstruct_share(&mut parties, "x", &mut prg);
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+#[should_panic(expected = "The opening of a commitment is not valid.")]
+fn spdz_commits_to_the_mac_check() {
+    use smol_mpc::adversary::Behavior;
+
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> =
+        VirtualMachine::new_cheating("bob", Behavior::WrongOpening(Fp::new(1)));
+    let mut parties = vec![&mut alice, &mut bob];
+
+    spdz::setup(&mut parties, &mut prg);
+    spdz::simulate_auth_dist("x", &mut parties, &Fp::new(10), &mut prg);
+
+    // Bob opens a wrong share of x, and then tries to reveal a different
+    // value from the one it committed to in the MAC check.
+    spdz::reconstruct_share(&mut parties, "x", &mut prg);
+}
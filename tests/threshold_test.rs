@@ -0,0 +1,219 @@
+use smol_mpc::adversary::Behavior;
+use smol_mpc::math::group::Group;
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::network::Simulator;
+use smol_mpc::shamir;
+use smol_mpc::threshold;
+use smol_mpc::utils::elgamal;
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::VirtualMachine;
+
+type Fp = Mersenne61;
+
+#[test]
+fn threshold_decrypt_protocol() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+    let (public_key, secret_key) = elgamal::keygen(&group, &mut prg);
+
+    let mut machines: Vec<VirtualMachine<Fp>> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|id| VirtualMachine::new(id))
+        .collect();
+    let mut parties: Vec<&mut VirtualMachine<Fp>> = machines.iter_mut().collect();
+    shamir::simulate_shamir_dist("key", &mut parties, secret_key.exponent(), 2, &mut prg);
+
+    let message = group.random_element(&mut prg);
+    let ciphertext = public_key.encrypt(&message, &mut prg);
+    assert_eq!(
+        threshold::threshold_decrypt_protocol(&parties, "key", &public_key, &ciphertext),
+        message
+    );
+
+    // Every party sends its partial decryption to the other four.
+    let network = Simulator::new(&parties);
+    assert_eq!(network.transcript().records().len(), 5 * 4);
+    assert!(network
+        .transcript()
+        .records()
+        .iter()
+        .all(|record| record.message.tag == "shared_exp"));
+
+    // Any three parties are enough for a key shared with degree two.
+    parties[0].mailbox.set_offline(true);
+    parties[2].mailbox.set_offline(true);
+    assert_eq!(
+        threshold::threshold_decrypt_protocol(&parties, "key", &public_key, &ciphertext),
+        message
+    );
+
+    // Two parties are not.
+    parties[4].mailbox.set_offline(true);
+    assert_ne!(
+        threshold::threshold_decrypt_protocol(&parties, "key", &public_key, &ciphertext),
+        message
+    );
+}
+
+#[test]
+fn feldman_share() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let (shares, commitment) = threshold::feldman_share(&group, &Fp::new(42), 2, 5, &mut prg);
+    assert_eq!(shamir::reconstruct(&shares).value(), 42);
+    assert_eq!(
+        commitment.constant(),
+        &group.exp(group.generator(), &Fp::new(42))
+    );
+    for (j, share) in shares.iter().enumerate() {
+        assert!(commitment.verify(&group, j as u64 + 1, share));
+        assert!(!commitment.verify(&group, j as u64 + 1, &share.add(&Fp::new(1))));
+    }
+}
+
+#[test]
+fn dkg_protocol() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut machines: Vec<VirtualMachine<Fp>> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|id| VirtualMachine::new(id))
+        .collect();
+    let mut parties: Vec<&mut VirtualMachine<Fp>> = machines.iter_mut().collect();
+    let dkg = threshold::dkg_protocol(&mut parties, &group, 2, "key");
+    assert_eq!(dkg.qualified, vec![0, 1, 2, 3, 4]);
+
+    // Each dealer sends a commitment and a share to the other four parties,
+    // which answer with their complaints.
+    let network = Simulator::new(&parties);
+    assert_eq!(network.transcript().records().len(), 5 * 4 * 3);
+    assert_eq!(network.rounds(), 2);
+
+    let key = shamir::reconstruct_share(&parties, "key");
+    assert_eq!(
+        dkg.public_key.element(),
+        &group.exp(group.generator(), &key)
+    );
+    for (party, verification_key) in parties.iter().zip(&dkg.verification_keys) {
+        assert_eq!(
+            verification_key,
+            &group.exp(group.generator(), &party.get_share("key").value)
+        );
+    }
+
+    let message = group.random_element(&mut prg);
+    let ciphertext = dkg.public_key.encrypt(&message, &mut prg);
+    assert_eq!(
+        threshold::threshold_decrypt_protocol(&parties, "key", &dkg.public_key, &ciphertext),
+        message
+    );
+}
+
+#[test]
+fn dkg_protocol_cheating_dealer() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob = VirtualMachine::new_cheating("bob", Behavior::Inconsistent(Fp::new(1)));
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut dave: VirtualMachine<Fp> = VirtualMachine::new("dave");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie, &mut dave];
+    parties[3].mailbox.set_offline(true);
+
+    // Bob is disqualified and Dave does not deal.
+    let dkg = threshold::dkg_protocol(&mut parties, &group, 1, "key");
+    assert_eq!(dkg.qualified, vec![0, 2]);
+    assert!(!parties[3].contains_share("key"));
+
+    let message = group.random_element(&mut prg);
+    let ciphertext = dkg.public_key.encrypt(&message, &mut prg);
+    assert_eq!(
+        threshold::threshold_decrypt_protocol(&parties, "key", &dkg.public_key, &ciphertext),
+        message
+    );
+}
+
+#[test]
+#[should_panic(expected = "The threshold must be smaller than the number of parties.")]
+fn dkg_protocol_threshold_too_large() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+    threshold::dkg_protocol(&mut parties, &group, 2, "key");
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn threshold_sign_protocol() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut machines: Vec<VirtualMachine<Fp>> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|id| VirtualMachine::new(id))
+        .collect();
+    let mut parties: Vec<&mut VirtualMachine<Fp>> = machines.iter_mut().collect();
+    let dkg = threshold::dkg_protocol(&mut parties, &group, 2, "key");
+
+    Simulator::new(&parties).reset_stats();
+    let signature = threshold::threshold_sign_protocol(&mut parties, "key", &dkg, b"hello");
+    assert!(signature.verify(&dkg.public_key, b"hello"));
+    assert!(!signature.verify(&dkg.public_key, b"goodbye"));
+
+    // The commitments go to the other four signers, and the signature shares
+    // to the first signer, in two rounds.
+    let network = Simulator::new(&parties);
+    assert_eq!(network.transcript().records().len(), 5 * 4 + 4);
+    assert_eq!(network.rounds(), 2);
+
+    // Any three parties can sign.
+    parties[1].mailbox.set_offline(true);
+    parties[4].mailbox.set_offline(true);
+    let signature = threshold::threshold_sign_protocol(&mut parties, "key", &dkg, b"goodbye");
+    assert!(signature.verify(&dkg.public_key, b"goodbye"));
+
+    let other = threshold::dkg_protocol(&mut parties, &group, 2, "other");
+    assert!(!signature.verify(&other.public_key, b"goodbye"));
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+#[should_panic(expected = "Signing needs at least 3 online parties.")]
+fn threshold_sign_protocol_not_enough_parties() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut machines: Vec<VirtualMachine<Fp>> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|id| VirtualMachine::new(id))
+        .collect();
+    let mut parties: Vec<&mut VirtualMachine<Fp>> = machines.iter_mut().collect();
+    let dkg = threshold::dkg_protocol(&mut parties, &group, 2, "key");
+
+    parties[0].mailbox.set_offline(true);
+    parties[1].mailbox.set_offline(true);
+    parties[2].mailbox.set_offline(true);
+    threshold::threshold_sign_protocol(&mut parties, "key", &dkg, b"hello");
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+#[should_panic(expected = "The signature share of party bob is invalid.")]
+fn threshold_sign_protocol_cheating_signer() {
+    let mut prg = Prg::new(None);
+    let group: Group<Fp> = Group::generate(128, &mut prg);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob = VirtualMachine::new_cheating("bob", Behavior::WrongOpening(Fp::new(1)));
+    let mut charlie: VirtualMachine<Fp> = VirtualMachine::new("charlie");
+    let mut parties = vec![&mut alice, &mut bob, &mut charlie];
+    let dkg = threshold::dkg_protocol(&mut parties, &group, 1, "key");
+
+    threshold::threshold_sign_protocol(&mut parties, "key", &dkg, b"hello");
+}
@@ -0,0 +1,274 @@
+use smol_mpc::math::mersenne::{Mersenne61, MersenneField};
+use smol_mpc::mpc::{self, Share};
+use smol_mpc::utils::prg::Prg;
+use smol_mpc::vm::{InsertPolicy, MemoryUsage, VirtualMachine};
+
+type Fp = Mersenne61;
+
+fn share(value: u64) -> Share<Fp> {
+    Share {
+        id: "x".to_string(),
+        value: Fp::new(value),
+    }
+}
+
+#[test]
+fn runtime_ids() {
+    let mut prg = Prg::new(None);
+
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    for i in 0..4 {
+        let id = format!("x_{}", i);
+        mpc::simulate_random_dist(&id, &mut parties, &Fp::new(i), &mut prg);
+    }
+    for i in 1..4 {
+        mpc::add_protocol(
+            &mut parties,
+            &format!("x_{}", i - 1),
+            &format!("x_{}", i),
+            &format!("sum_{}", i),
+        );
+    }
+
+    assert_eq!(mpc::reconstruct_share(&parties, "sum_3").value(), 5);
+    assert_eq!(parties[0].get_share("sum_3").id, "sum_3");
+}
+
+#[test]
+fn scopes() {
+    let mut prg = Prg::new(None);
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut parties = vec![&mut alice, &mut bob];
+
+    mpc::simulate_random_dist("x", &mut parties, &Fp::new(5), &mut prg);
+    let doubled = mpc::scoped(&mut parties, "double", |parties| {
+        assert_eq!(parties[0].current_scope(), Some("double".to_string()));
+        mpc::simulate_random_dist("x", parties, &Fp::new(1), &mut prg);
+        mpc::add_protocol(parties, "x", "x", "y");
+        mpc::reconstruct_share(parties, "y")
+    });
+
+    // The value of the scope shadowed the outer one and was erased.
+    assert_eq!(doubled.value(), 2);
+    assert_eq!(mpc::reconstruct_share(&parties, "x").value(), 5);
+    assert!(parties[0].current_scope().is_none());
+    assert!(!parties[0].contains_share("y"));
+}
+
+#[test]
+fn nested_scopes() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_priv_value("x", Fp::new(1));
+    alice.enter_scope("outer");
+    alice.insert_priv_value("y", Fp::new(2));
+    alice.enter_scope("inner");
+    assert_eq!(alice.current_scope(), Some("outer/inner".to_string()));
+    assert_eq!(alice.get_priv_value("x").value(), 1);
+    assert_eq!(alice.get_priv_value("y").value(), 2);
+
+    alice.exit_scope();
+    alice.exit_scope();
+    assert!(!alice.contains_priv_value("y"));
+}
+
+#[test]
+#[should_panic]
+fn exit_without_scope() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.exit_scope();
+}
+
+#[test]
+fn fresh_id() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_priv_value("tmp#1", Fp::new(1));
+    assert_eq!(alice.fresh_id("tmp"), "tmp#0");
+    // The IDs already in the memory are skipped.
+    assert_eq!(alice.fresh_id("tmp"), "tmp#2");
+    assert!(alice.contains_id("tmp#1"));
+    assert!(!alice.contains_id("tmp#2"));
+}
+
+#[test]
+fn insert_overwrite() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_policy = InsertPolicy::Overwrite;
+    assert!(alice.insert_share("x", share(1)).is_none());
+    let previous = alice.insert_share("x", share(2)).unwrap();
+    assert_eq!(previous.value.value(), 1);
+    assert_eq!(alice.get_share("x").value.value(), 2);
+    assert_eq!(alice.insert_bool_share("b", true), None);
+    assert_eq!(alice.insert_bool_share("b", false), Some(true));
+}
+
+#[test]
+fn insert_versioned() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    for i in 0..3 {
+        alice.insert_share_with("x", share(i), InsertPolicy::Versioned);
+    }
+    assert_eq!(alice.get_share("x").value.value(), 2);
+    assert_eq!(alice.get_share("x@0").value.value(), 0);
+    assert_eq!(alice.get_share("x@1").value.value(), 1);
+    assert_eq!(alice.get_share("x").id, "x");
+    assert_eq!(alice.get_share("x@0").id, "x@0");
+    assert_eq!(alice.get_share("x@1").id, "x@1");
+}
+
+#[test]
+fn insert_priv_value_overwrite() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_policy = InsertPolicy::Overwrite;
+    assert!(alice.insert_priv_value("x", Fp::new(1)).is_none());
+    let previous = alice.insert_priv_value("x", Fp::new(2)).unwrap();
+    assert_eq!(previous.value(), 1);
+    assert_eq!(alice.get_priv_value("x").value(), 2);
+}
+
+#[test]
+fn insert_priv_value_versioned() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    for i in 0..3 {
+        alice.insert_priv_value_with("x", Fp::new(i), InsertPolicy::Versioned);
+    }
+    assert_eq!(alice.get_priv_value("x").value(), 2);
+    assert_eq!(alice.get_priv_value("x@0").value(), 0);
+    assert_eq!(alice.get_priv_value("x@1").value(), 1);
+}
+
+#[test]
+#[should_panic(expected = "There exists a private value with this id.")]
+fn insert_priv_value_error() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_priv_value("x", Fp::new(1));
+    alice.insert_priv_value("x", Fp::new(2));
+}
+
+#[test]
+#[should_panic(expected = "There exists a share with this id.")]
+fn insert_error() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_share("x", share(1));
+    alice.insert_share("x", share(2));
+}
+
+#[test]
+fn remove_values() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_priv_value("a", Fp::new(1));
+    alice.insert_share("x", share(2));
+    alice.insert_bool_share("b", true);
+
+    assert_eq!(alice.remove_priv_value("a").unwrap().value(), 1);
+    assert_eq!(alice.remove_share("x").unwrap().value.value(), 2);
+    assert_eq!(alice.remove_bool_share("b"), Some(true));
+    assert!(alice.remove_share("x").is_none());
+    assert_eq!(alice.memory_usage(), MemoryUsage::default());
+}
+
+#[test]
+fn clear_and_retain() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_priv_value("a", Fp::new(1));
+    alice.insert_priv_value("tmp", Fp::new(1));
+    alice.insert_share("x", share(2));
+    alice.enter_scope("inner");
+    alice.insert_share("y", share(3));
+    alice.insert_bool_share("b", false);
+
+    let ids: Vec<&str> = alice.shares().into_iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec!["x", "y"]);
+
+    alice.retain_shares(|id, _| id != "x");
+    alice.retain_priv_values(|id, _| id != "tmp");
+    assert!(!alice.contains_share("x"));
+    assert!(alice.contains_share("y"));
+    assert!(!alice.contains_priv_value("tmp"));
+
+    alice.clear_shares();
+    let usage = alice.memory_usage();
+    assert_eq!(usage.private_values, 1);
+    assert_eq!(usage.shares, 0);
+    assert_eq!(usage.bool_shares, 0);
+    assert_eq!(usage.scopes, 1);
+}
+
+#[test]
+fn snapshot_restore() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    alice.insert_share("x", share(1));
+    let snapshot = alice.snapshot();
+
+    alice.remove_share("x");
+    alice.enter_scope("later");
+    alice.insert_priv_value("y", Fp::new(2));
+    alice.restore(&snapshot);
+    assert_eq!(alice.get_share("x").value.value(), 1);
+    assert!(!alice.contains_priv_value("y"));
+    assert!(alice.current_scope().is_none());
+}
+
+#[test]
+#[should_panic(expected = "The snapshot was taken from the virtual machine alice.")]
+fn restore_other_machine() {
+    let alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    bob.restore(&alice.snapshot());
+}
+
+#[test]
+fn own_prg() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    assert_ne!(alice.prg.next(16), bob.prg.next(16));
+
+    // The snapshot rewinds the PRG as well.
+    let snapshot = alice.snapshot();
+    let first = alice.prg.next(16);
+    alice.restore(&snapshot);
+    assert_eq!(alice.prg.next(16), first);
+}
+
+#[test]
+fn seeded_prg() {
+    // Machines with the same ID do not share their randomness.
+    let mut first: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut second: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    assert_ne!(first.prg.next(16), second.prg.next(16));
+
+    // A seed makes the PRG reproducible, and the ID separates the machines.
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::with_seed("alice", b"seed");
+    let mut again: VirtualMachine<Fp> = VirtualMachine::with_seed("alice", b"seed");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::with_seed("bob", b"seed");
+    let first = alice.prg.next(16);
+    assert_eq!(again.prg.next(16), first);
+    assert_ne!(bob.prg.next(16), first);
+    assert_ne!(Prg::new(Some(b"alice".to_vec())).next(16), first);
+}
+
+#[test]
+fn snapshot_correlated_randomness() {
+    let mut alice: VirtualMachine<Fp> = VirtualMachine::new("alice");
+    let mut bob: VirtualMachine<Fp> = VirtualMachine::new("bob");
+    let mut carol: VirtualMachine<Fp> = VirtualMachine::new("carol");
+    mpc::prss_setup(&mut [&mut alice, &mut bob, &mut carol], 1);
+    mpc::pairwise_setup(&mut [&mut alice, &mut bob]);
+
+    alice.prss_random_share("before");
+    let snapshot = alice.snapshot();
+    alice.prss_random_share("after");
+    let share = alice.get_share("after").value.clone();
+    let pairwise = alice.pairwise_prg("bob").next(16);
+
+    // The PRSS keys and the pairwise PRGs are rewound with the snapshot, so
+    // replaying gives the same correlated randomness.
+    alice.restore(&snapshot);
+    assert!(!alice.contains_share("after"));
+    alice.prss_random_share("after");
+    assert_eq!(alice.get_share("after").value.value(), share.value());
+    assert_eq!(alice.pairwise_prg("bob").next(16), pairwise);
+}